@@ -1,21 +1,15 @@
 //! Fetch the detail of supported currencies from RESTful API.
 //!
 //! ```bash
-//! cargo run --example get_currencies
+//! cargo run --example get_currencies --features client-surf
 //! ```
 
-use maicoin_max::v2::rest::{CurrencyInfo, GetCurrencies};
+use maicoin_max::prelude::*;
 
 #[async_std::main]
-async fn main() -> Result<(), http_types::Error> {
+async fn main() -> MaxResult<()> {
     let client = surf::Client::new();
-    let params = GetCurrencies {};
-    let resp = client
-        .send(params.to_request())
-        .await
-        .expect("Error while sending request");
-    let result = GetCurrencies::read_response(resp.into()).await;
-    let currencies: Vec<CurrencyInfo> = result.expect("failed to parse result");
+    let currencies: Vec<CurrencyInfo> = GetCurrencies {}.fetch(&client).await?;
 
     println!("List of support currencies:");
     for info in currencies {
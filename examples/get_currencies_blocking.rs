@@ -0,0 +1,18 @@
+//! Fetch the detail of supported currencies from RESTful API, without async runtime.
+//!
+//! ```bash
+//! cargo run --example get_currencies_blocking --features blocking
+//! ```
+
+use maicoin_max::prelude::*;
+
+fn main() -> MaxResult<()> {
+    let client = BlockingClient::new();
+    let currencies: Vec<CurrencyInfo> = client.call(&GetCurrencies {})?;
+
+    println!("List of support currencies:");
+    for info in currencies {
+        println!("- {}", info.id);
+    }
+    Ok(())
+}
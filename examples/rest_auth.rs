@@ -4,11 +4,10 @@
 //! cargo run --example rest_auth <currency> <api_key> <api_secret>
 //! ```
 
-use maicoin_max::v2::rest::{GetAccountOfCurrency, RespAccountCurrencyInfo};
-use maicoin_max::Credentials;
+use maicoin_max::prelude::*;
 
 #[async_std::main]
-async fn main() -> Result<(), http_types::Error> {
+async fn main() -> MaxResult<()> {
     let mut args = std::env::args().collect::<Vec<String>>().into_iter();
     args.next();
     let currency = args.next().unwrap();
@@ -18,15 +17,12 @@ async fn main() -> Result<(), http_types::Error> {
     let credentials = Credentials::new(api_key, api_secret);
 
     let client = surf::Client::new();
-    let params = GetAccountOfCurrency {
-        path_currency: currency.clone(),
-    };
-    let resp = client
-        .send(params.to_request(&credentials))
-        .await
-        .expect("Error while sending request");
-    let result = GetAccountOfCurrency::read_response(resp.into()).await;
-    let info: RespAccountCurrencyInfo = result.expect("failed to parse result");
+    let params = GetAccountOfCurrency::new(currency.as_str());
+    // the reader only parses the response of `GetAccountOfCurrency`
+    let (req, reader) = params.prepare_typed(Some(&credentials))?.into_parts();
+    // failures of sending are converted into `Error::Transport`
+    let resp = client.send(req).await?;
+    let info: RespAccountCurrencyInfo = reader.read(resp.into()).await?;
 
     println!("My {} balance is {}", currency, info.balance);
     Ok(())
@@ -3,6 +3,8 @@
 //! ```bash
 //! cargo run --example ws_auth <api_key> <api_secret>
 //! ```
+//!
+//! Set `MAX_WS_URL` to connect with another server, e.g. `MAX_WS_URL=ws://localhost:8080/ws`.
 
 use std::time::Duration;
 
@@ -15,8 +17,7 @@ use env_logger::{Builder as EnvLoggerBuilder, Env as EnvLoggerEnv};
 use futures::{pin_mut, select, sink::SinkExt, stream::StreamExt};
 use log::*;
 
-use maicoin_max::v2::ws::{AuthRequest, PrivFeedType, ServerPushEvent, BASE_URL};
-use maicoin_max::Credentials;
+use maicoin_max::prelude::*;
 
 fn init_log() {
     let env = EnvLoggerEnv::new()
@@ -53,11 +54,13 @@ fn main() -> Result<()> {
 
     task::block_on(async {
         // Connect to the server.
-        let mut stream = connect_async(BASE_URL).await?.0.fuse();
+        let host = WsHost::from_env("MAX_WS_URL")?;
+        let mut stream = connect_async(host.as_str()).await?.0.fuse();
 
         // subscribe
         let req = {
-            let auth_req = AuthRequest::new(&credentials, None, Some(vec![PrivFeedType::Trade]));
+            let filter = PrivFeedFilter::all().with(PrivFeedType::Trade);
+            let auth_req = AuthRequest::with_filter(&credentials, None, filter);
             serde_json::to_string(&auth_req)?
         };
         stream.send(Message::text(req)).await?;
@@ -3,6 +3,8 @@
 //! ```bash
 //! cargo run --example ws_client <market_name>
 //! ```
+//!
+//! Set `MAX_WS_URL` to connect with another server, e.g. `MAX_WS_URL=ws://localhost:8080/ws`.
 
 use std::time::Duration;
 
@@ -15,7 +17,7 @@ use env_logger::{Builder as EnvLoggerBuilder, Env as EnvLoggerEnv};
 use futures::{pin_mut, select, sink::SinkExt, stream::StreamExt};
 use log::*;
 
-use maicoin_max::v2::ws::{ServerPushEvent, SubRequest, BASE_URL};
+use maicoin_max::prelude::*;
 
 fn init_log() {
     let env = EnvLoggerEnv::new()
@@ -44,7 +46,8 @@ fn main() -> Result<()> {
         .expect("usage: ws_client <market_name>");
     task::block_on(async {
         // Connect to the server.
-        let mut stream = connect_async(BASE_URL).await?.0.fuse();
+        let host = WsHost::from_env("MAX_WS_URL")?;
+        let mut stream = connect_async(host.as_str()).await?.0.fuse();
 
         // subscribe
         let req = {
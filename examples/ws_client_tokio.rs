@@ -0,0 +1,97 @@
+//! A websocket ticker client based on `tokio-tungstenite` and `tokio`, the counterpart of `ws_client.rs`.
+//!
+//! ```bash
+//! cargo run --example ws_client_tokio <market_name>
+//! ```
+//!
+//! Set `MAX_WS_URL` to connect with another server, e.g. `MAX_WS_URL=ws://localhost:8080/ws`.
+
+use std::time::Duration;
+
+use anyhow::{bail, Result};
+use env_logger::{Builder as EnvLoggerBuilder, Env as EnvLoggerEnv};
+use futures::{sink::SinkExt, stream::StreamExt};
+use log::*;
+use tokio::time::interval;
+use tokio_tungstenite::connect_async;
+use tokio_tungstenite::tungstenite::Message;
+
+use maicoin_max::prelude::*;
+
+fn init_log() {
+    let env = EnvLoggerEnv::new()
+        .filter_or("LOG_LEVEL", "info")
+        .write_style("LOG_STYLE");
+    EnvLoggerBuilder::from_env(env).init();
+}
+
+fn handle_push(raw: String) {
+    if let Ok(event) = serde_json::from_str::<ServerPushEvent>(raw.as_str()) {
+        match event {
+            ServerPushEvent::Error(err) => error!("error while receiving feed: {:?}", err),
+            ServerPushEvent::PubTickerFeed(feed) => println!("{:?}", feed),
+            event => error!("unexpected feed: {:?}", event),
+        }
+    } else {
+        error!("failed to parse server event: {}", raw);
+    }
+}
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    init_log();
+
+    let market = std::env::args()
+        .nth(1)
+        .expect("usage: ws_client_tokio <market_name>");
+
+    // Connect to the server.
+    let host = WsHost::from_env("MAX_WS_URL")?;
+    let mut stream = connect_async(host.as_str()).await?.0;
+
+    // subscribe
+    let req = {
+        let mut sub = SubRequest::new_sub(String::new());
+        sub.subset().insert_ticker(market);
+        serde_json::to_string(&sub)?
+    };
+    stream.send(Message::text(req)).await?;
+    if let Some(Ok(Message::Text(resp))) = stream.next().await {
+        match serde_json::from_str::<ServerPushEvent>(resp.as_str())? {
+            ServerPushEvent::Error(err) => bail!("error while submitting ticker: {:?}", err),
+            ServerPushEvent::SubResp(_) => {}
+            event => bail!("unexpected response: {:?}", event),
+        };
+    } else {
+        bail!("fail to get response for ticker submition");
+    };
+
+    // heartbeat ticker, the first tick completes immediately
+    let mut ticker = interval(Duration::from_secs(30));
+    ticker.tick().await;
+
+    loop {
+        tokio::select! {
+            _ = ticker.tick() => {
+                if let Err(err) = stream.send(Message::Ping("heartbeat".into())).await {
+                    error!("error while sending heartbeat: {:?}", err);
+                } else {
+                    debug!("sending heartbeat to server");
+                }
+            }
+            recv = stream.next() => {
+                if let Some(Ok(recv_entry)) = recv {
+                    match recv_entry {
+                        Message::Text(feed) => handle_push(feed),
+                        Message::Pong(_) => {}, // ignore heartbeat
+                        x => error!("receiving unexpected push: {:?}", x),
+                    }
+                } else {
+                    info!("stream terminated");
+                    break;
+                }
+            }
+        };
+    }
+    Ok(())
+}
@@ -20,6 +20,27 @@ pub enum Error {
     #[error("Unable read response")]
     ReadResponse(Box<anyhow::Error>),
 
+    /// Error while sending request or receiving response via HTTP client.
+    // See `ReadResponse` for why not wrapping http_types::Error directly
+    #[error("HTTP transport error")]
+    Transport(Box<anyhow::Error>),
+
+    /// Request or response which can not be converted between HTTP libraries.
+    #[error("Invalid HTTP message: {0}")]
+    InvalidHttpMessage(String),
+
+    /// API server is under maintenance. Clients are expected to back off and retry later.
+    #[error("API server under maintenance (HTTP {0})")]
+    Maintenance(u16),
+
+    /// Malformed URL, e.g. an invalid API host, or a dynamic API path with `..` segments.
+    #[error("Invalid URL: {0}")]
+    InvalidUrl(String),
+
+    /// Request parameters which can not be serialized into query string or body.
+    #[error("Invalid request parameters: {0}")]
+    InvalidParams(String),
+
     /// Invalid content in websocket request/response body.
     #[error("Invalid value: {0}")]
     WsInvalidValue(String),
@@ -29,6 +50,29 @@ pub enum Error {
     WsApiParse(serde_json::Error),
 }
 
+// Error codes of MAX API, see "Error Codes" in official document.
+pub(crate) const API_ERROR_CANCEL_FAILED: u64 = 2003;
+pub(crate) const API_ERROR_ORDER_NOT_FOUND: u64 = 2004;
+pub(crate) const API_ERROR_NONCE_USED: u64 = 2006;
+pub(crate) const API_ERROR_NONCE_INVALID: u64 = 2007;
+
+impl Error {
+    /// `true` if the server rejected the nonce of an authenticated request, which is either used or out of the
+    /// allowed time window. Such request can be sent again after rebuilt with a new nonce.
+    pub fn is_nonce_error(&self) -> bool {
+        matches!(
+            self,
+            Error::RestApi(API_ERROR_NONCE_USED, _) | Error::RestApi(API_ERROR_NONCE_INVALID, _)
+        )
+    }
+
+    /// `true` if the order to operate on does not exist, e.g. cancelling an order with unknown id. Cancelling logic
+    /// which is meant to be idempotent may treat it as success.
+    pub fn is_order_not_found(&self) -> bool {
+        matches!(self, Error::RestApi(API_ERROR_ORDER_NOT_FOUND, _))
+    }
+}
+
 #[derive(Deserialize, Debug)]
 struct ApiErrorDetail {
     code: u64,
@@ -46,8 +90,180 @@ pub(crate) struct ApiErrorWrapper {
     error: ApiErrorDetail,
 }
 
+/// Failures of sending requests by [`http_types`] compatible clients, e.g. `surf::Client::send`, are transport errors.
+impl From<http_types::Error> for Error {
+    fn from(err: http_types::Error) -> Self {
+        Error::Transport(Box::new(err.into_inner()))
+    }
+}
+
 impl From<ApiErrorWrapper> for Error {
     fn from(err: ApiErrorWrapper) -> Self {
         Error::RestApi(err.error.code, err.error.message)
     }
 }
+
+/// Error of parsing an enum from string, see the `FromStr` implementations of the enums in this crate.
+#[derive(Clone, Debug, Eq, PartialEq, Error)]
+#[error("invalid {type_name}: {value}")]
+pub struct ParseEnumError {
+    /// Name of the enum type.
+    pub type_name: &'static str,
+    /// The string failed to parse.
+    pub value: String,
+}
+
+impl From<ParseEnumError> for Error {
+    fn from(err: ParseEnumError) -> Self {
+        Error::InvalidParams(err.to_string())
+    }
+}
+
+/// Error of parsing a [`crate::common::Symbol`] from an empty or whitespace-only string.
+#[derive(Clone, Debug, Eq, PartialEq, Error)]
+#[error("invalid market symbol: {value:?}")]
+pub struct ParseSymbolError {
+    /// The string failed to parse.
+    pub value: String,
+}
+
+impl From<ParseSymbolError> for Error {
+    fn from(err: ParseSymbolError) -> Self {
+        Error::InvalidParams(err.to_string())
+    }
+}
+
+/// Error of parsing a [`crate::common::Currency`] from an empty or whitespace-only string.
+#[derive(Clone, Debug, Eq, PartialEq, Error)]
+#[error("invalid currency: {value:?}")]
+pub struct ParseCurrencyError {
+    /// The string failed to parse.
+    pub value: String,
+}
+
+impl From<ParseCurrencyError> for Error {
+    fn from(err: ParseCurrencyError) -> Self {
+        Error::InvalidParams(err.to_string())
+    }
+}
+
+/// Error of [`crate::common::PageParams`] out of the documented bounds.
+#[derive(Clone, Debug, Eq, PartialEq, Error)]
+pub enum PageParamsError {
+    /// Page number is 0.
+    #[error("invalid page {0}, page number starts from 1")]
+    InvalidPage(u64),
+    /// Limit is 0 or greater than [`crate::common::PageParams::MAX_LIMIT`].
+    #[error("invalid limit {0}, expect 1 to 1000")]
+    InvalidLimit(u64),
+}
+
+impl From<PageParamsError> for Error {
+    fn from(err: PageParamsError) -> Self {
+        Error::InvalidParams(err.to_string())
+    }
+}
+
+/// Error of [`crate::common::TimeRange`] starting later than its end.
+#[derive(Clone, Debug, Eq, PartialEq, Error)]
+#[error("invalid time range: {from} is later than {to}")]
+pub struct TimeRangeError {
+    /// Start of the range.
+    pub from: chrono::DateTime<chrono::Utc>,
+    /// End of the range.
+    pub to: chrono::DateTime<chrono::Utc>,
+}
+
+impl From<TimeRangeError> for Error {
+    fn from(err: TimeRangeError) -> Self {
+        Error::InvalidParams(err.to_string())
+    }
+}
+
+/// Error of rounding prices and volumes to the precision of markets, see [`crate::common::precision`].
+#[derive(Clone, Debug, Eq, PartialEq, Error)]
+pub enum PrecisionError {
+    /// Prices and volumes must be positive.
+    #[error("{0} is not positive")]
+    NotPositive(rust_decimal::Decimal),
+    /// The value is rounded to zero, i.e. it is less than one tick.
+    #[error("{value} is below one tick of {places} decimal places")]
+    BelowTick {
+        /// The value failed to round.
+        value: rust_decimal::Decimal,
+        /// Decimal places of the precision.
+        places: u32,
+    },
+    /// The value has more decimal places than the precision allows.
+    #[error("{value} has more than {places} decimal places")]
+    TooPrecise {
+        /// The value failed to fit.
+        value: rust_decimal::Decimal,
+        /// Decimal places of the precision.
+        places: u32,
+    },
+    /// Negative precision given by the market.
+    #[error("invalid precision {0}")]
+    InvalidPrecision(i8),
+}
+
+impl From<PrecisionError> for Error {
+    fn from(err: PrecisionError) -> Self {
+        Error::InvalidParams(err.to_string())
+    }
+}
+
+/// Error of validating and resampling K lines, see [`crate::v2::rest::ohlc`].
+#[derive(Clone, Debug, Eq, PartialEq, Error)]
+pub enum ResampleError {
+    /// The target period must be positive.
+    #[error("invalid target period of {0} seconds")]
+    InvalidPeriod(i64),
+    /// The target period must be a whole number of the candles.
+    #[error("target period of {target} seconds is not a multiple of the candle spacing of {spacing} seconds")]
+    NotMultiple {
+        /// Target period in seconds.
+        target: i64,
+        /// Spacing of the given candles in seconds.
+        spacing: i64,
+    },
+    /// Candles must be in time order without duplicates.
+    #[error("candle at {0} is not later than the previous one")]
+    NotMonotonic(chrono::DateTime<chrono::Utc>),
+    /// Highest price of the candle is below the lowest.
+    #[error("candle at {0} has the high below the low")]
+    HighBelowLow(chrono::DateTime<chrono::Utc>),
+}
+
+impl From<ResampleError> for Error {
+    fn from(err: ResampleError) -> Self {
+        Error::InvalidParams(err.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn transport_error() {
+        let err = http_types::Error::from_str(
+            http_types::StatusCode::ServiceUnavailable,
+            "connection refused",
+        );
+        match Error::from(err) {
+            Error::Transport(inner) => assert_eq!(inner.to_string(), "connection refused"),
+            other => panic!("expect transport error, got {:?}", other),
+        }
+
+        // `?` converts the errors of http_types into the unified error
+        fn send() -> Result<()> {
+            Err(http_types::Error::from_str(
+                http_types::StatusCode::BadGateway,
+                "connection reset",
+            ))?
+        }
+        assert!(matches!(send(), Err(Error::Transport(_))));
+        assert_eq!(send().unwrap_err().to_string(), "HTTP transport error");
+    }
+}
@@ -10,7 +10,10 @@
 //! - Get list of exchange supported currencies via public REST API: `examples/get_currencies.rs`
 //! - Private REST API authentication: `examples/rest_auth.rs`
 //! - Receiving tickers from public websocket API: `examples/ws_client.rs`
+//! - Receiving tickers on tokio runtime with `tokio-tungstenite`: `examples/ws_client_tokio.rs`
 //! - Websocket authentication and channel filtering: `examples/ws_auth.rs`
+//!
+//! Commonly used types are gathered in [`prelude`] to be glob-imported.
 
 #![deny(
     rustdoc::broken_intra_doc_links,
@@ -21,17 +24,65 @@
 use std::env::var as env_var;
 use std::ffi::OsStr;
 use std::sync::atomic::{AtomicU64, Ordering};
-use std::time::{SystemTime, UNIX_EPOCH};
+
+// Implement `as_str`, `Display` and `FromStr` of enums by their wire strings, which must be identical to the serialized
+// values. Parsing is case-insensitive.
+macro_rules! impl_wire_str {
+    ($ty:ident { $($variant:ident => $wire:literal),+ $(,)? }) => {
+        impl $ty {
+            /// All the variants.
+            pub const VARIANTS: &'static [$ty] = &[$($ty::$variant),+];
+
+            /// The string used by API server.
+            pub fn as_str(&self) -> &'static str {
+                match *self {
+                    $($ty::$variant => $wire),+
+                }
+            }
+        }
+
+        impl std::fmt::Display for $ty {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                f.write_str(self.as_str())
+            }
+        }
+
+        impl std::str::FromStr for $ty {
+            type Err = $crate::error::ParseEnumError;
+
+            fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+                Self::VARIANTS
+                    .iter()
+                    .find(|v| v.as_str().eq_ignore_ascii_case(s))
+                    .cloned()
+                    .ok_or_else(|| $crate::error::ParseEnumError {
+                        type_name: stringify!($ty),
+                        value: s.to_owned(),
+                    })
+            }
+        }
+    };
+}
 
 pub mod error;
-pub(crate) mod util;
+pub mod prelude;
+pub mod util;
 pub mod v2;
+pub mod v3;
 
+#[cfg(not(all(target_arch = "wasm32", target_os = "unknown")))]
 fn clock() -> u64 {
+    use std::time::{SystemTime, UNIX_EPOCH};
     let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap();
     now.as_secs() * 1000 + now.subsec_millis() as u64
 }
 
+// `SystemTime::now()` panics on wasm32-unknown-unknown, read the clock of JS runtime instead
+#[cfg(all(target_arch = "wasm32", target_os = "unknown"))]
+fn clock() -> u64 {
+    js_sys::Date::now() as u64
+}
+
 /// Credentials to access private API. It internally maintains an atomic monotonic clock for payload signing. This
 /// implies that the data created from [`Credentials`] must be sent to server as soon as possible.
 #[derive(Debug)]
@@ -51,6 +102,17 @@ impl Credentials {
         }
     }
 
+    /// Create credential by tokens, trimming the surrounding whitespace pasted along with them. Returns
+    /// [`error::Error::InvalidParams`] if either key is empty, contains whitespace or non-ASCII characters, or is
+    /// longer than 128 characters. The length limit is a generous sanity bound against pasting the wrong thing, not
+    /// the length of the tokens issued by MAX, which are typically 40 characters.
+    pub fn new_checked(access_key: &str, secret_key: &str) -> error::Result<Self> {
+        Ok(Self::new(
+            check_key("access key", access_key)?,
+            check_key("secret key", secret_key)?,
+        ))
+    }
+
     /// Given environment variable names, create credentials from their values.
     pub fn from_env(access_var: impl AsRef<OsStr>, secret_var: impl AsRef<OsStr>) -> Self {
         Self {
@@ -60,40 +122,271 @@ impl Credentials {
         }
     }
 
-    pub(crate) fn nonce(&self) -> u64 {
+    /// Allocate the next nonce, which is increasing and no earlier than the current time in milliseconds. Requests
+    /// built by `to_request` take one each, while it could be allocated ahead for `try_to_request_with_nonce`.
+    pub fn nonce(&self) -> u64 {
+        // `fetch_update` returns the replaced value, which may be long before now
+        let mut next = 0;
         self.nonce
             .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |t| {
-                Some((t + 1).max(clock()))
+                next = (t + 1).max(clock());
+                Some(next)
             })
-            .unwrap()
+            .unwrap();
+        next
+    }
+
+    /// Milliseconds elapsed since the time of the last allocated nonce, or since creation if none is allocated yet.
+    /// It tells how long a request built by the latest nonce has been waiting, and the server rejects the request once
+    /// its nonce is out of the allowed time window.
+    pub fn age_of_last_nonce_ms(&self) -> u64 {
+        Self::age_of_nonce_ms(self.nonce.load(Ordering::SeqCst))
+    }
+
+    /// Milliseconds elapsed since the time of the nonce, `0` for nonces allocated ahead of the clock.
+    pub fn age_of_nonce_ms(nonce: u64) -> u64 {
+        clock().saturating_sub(nonce)
     }
 }
 
+// Sanity bound of the key length, well above the 40 characters of the tokens issued by MAX.
+const MAX_KEY_LEN: usize = 128;
+
+fn check_key(name: &str, key: &str) -> error::Result<String> {
+    let key = key.trim();
+    if key.is_empty() {
+        return Err(error::Error::InvalidParams(format!("{} is empty", name)));
+    }
+    if key.len() > MAX_KEY_LEN {
+        return Err(error::Error::InvalidParams(format!(
+            "{} is too long: {} characters",
+            name,
+            key.len()
+        )));
+    }
+    if !key.chars().all(|c| c.is_ascii_graphic()) {
+        return Err(error::Error::InvalidParams(format!(
+            "{} contains whitespace or non-ASCII characters",
+            name
+        )));
+    }
+    Ok(key.to_owned())
+}
+
 // =====================
 // API common components
 // =====================
 
 /// Common type definition.
 pub mod common {
+    use std::borrow::{Borrow, Cow};
+    use std::convert::TryFrom;
+    use std::fmt;
+    use std::ops::Deref;
+    use std::str::FromStr;
+
     use chrono::{DateTime as ChronoDateTime, Utc};
-    use serde::{Deserialize, Serialize};
+    use serde::{Deserialize, Deserializer, Serialize};
+
+    use crate::error::{PageParamsError, ParseCurrencyError, ParseSymbolError, TimeRangeError};
+
+    // Implement construction, conversions and serde of lowercase id newtypes over `Cow<'static, str>`. `new` and
+    // `FromStr` reject empty ids, while `From` only normalizes, as ids from the server are trusted.
+    macro_rules! impl_lowercase_id {
+        ($ty:ident, $err:ident) => {
+            impl $ty {
+                /// Normalize the id, or fail if it is empty or only whitespace.
+                pub fn new(id: impl Into<String>) -> Result<Self, $err> {
+                    let id = id.into();
+                    let trimmed = id.trim();
+                    if trimmed.is_empty() {
+                        return Err($err { value: id });
+                    }
+                    Ok(Self(Cow::Owned(trimmed.to_lowercase())))
+                }
+
+                pub fn as_str(&self) -> &str {
+                    &self.0
+                }
+
+                pub fn into_string(self) -> String {
+                    self.0.into_owned()
+                }
+            }
+
+            impl Deref for $ty {
+                type Target = str;
+
+                fn deref(&self) -> &str {
+                    &self.0
+                }
+            }
+
+            impl AsRef<str> for $ty {
+                fn as_ref(&self) -> &str {
+                    &self.0
+                }
+            }
+
+            impl Borrow<str> for $ty {
+                fn borrow(&self) -> &str {
+                    &self.0
+                }
+            }
+
+            impl fmt::Display for $ty {
+                fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                    f.write_str(&self.0)
+                }
+            }
+
+            impl fmt::Debug for $ty {
+                fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                    fmt::Debug::fmt(&*self.0, f)
+                }
+            }
+
+            impl FromStr for $ty {
+                type Err = $err;
+
+                fn from_str(s: &str) -> Result<Self, Self::Err> {
+                    Self::new(s)
+                }
+            }
+
+            impl From<String> for $ty {
+                fn from(id: String) -> Self {
+                    Self(Cow::Owned(id.trim().to_lowercase()))
+                }
+            }
+
+            impl From<&str> for $ty {
+                fn from(id: &str) -> Self {
+                    Self(Cow::Owned(id.trim().to_lowercase()))
+                }
+            }
+
+            impl From<&$ty> for $ty {
+                fn from(id: &$ty) -> Self {
+                    id.clone()
+                }
+            }
+
+            impl From<$ty> for String {
+                fn from(id: $ty) -> Self {
+                    id.into_string()
+                }
+            }
+
+            impl PartialEq<str> for $ty {
+                fn eq(&self, other: &str) -> bool {
+                    *self.0 == *other
+                }
+            }
+
+            impl PartialEq<&str> for $ty {
+                fn eq(&self, other: &&str) -> bool {
+                    *self.0 == **other
+                }
+            }
+
+            impl PartialEq<String> for $ty {
+                fn eq(&self, other: &String) -> bool {
+                    *self.0 == **other
+                }
+            }
+
+            impl<'de> Deserialize<'de> for $ty {
+                fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                    String::deserialize(deserializer).map(Self::from)
+                }
+            }
+        };
+    }
 
     /// Unique market id, check /api/v2/markets for available markets.
-    pub type Symbol = String;
+    ///
+    /// Market ids are lowercase on the wire, e.g. `btctwd`, so symbols are lowercased on construction. [`Symbol::new`]
+    /// and [`FromStr`] also reject empty ids, while `From<String>` and `From<&str>` only normalize the case, to keep
+    /// code written for the former `String` alias working. Symbols dereference to `str` and can be looked up by `&str`
+    /// in maps keyed by symbols.
+    #[derive(Serialize, Clone, Default, Hash, Eq, PartialEq, Ord, PartialOrd)]
+    #[serde(transparent)]
+    pub struct Symbol(Cow<'static, str>);
+
+    impl_lowercase_id!(Symbol, ParseSymbolError);
+
+    /// Unique currency id, check /api/v2/currencies for available currencies.
+    ///
+    /// Normalized to lowercase like [`Symbol`], e.g. `Currency::from("USDT") == Currency::USDT`.
+    #[derive(Serialize, Clone, Default, Hash, Eq, PartialEq, Ord, PartialOrd)]
+    #[serde(transparent)]
+    pub struct Currency(Cow<'static, str>);
+
+    impl_lowercase_id!(Currency, ParseCurrencyError);
+
+    impl Currency {
+        pub const TWD: Currency = Currency(Cow::Borrowed("twd"));
+        pub const USDT: Currency = Currency(Cow::Borrowed("usdt"));
+        pub const BTC: Currency = Currency(Cow::Borrowed("btc"));
+        pub const ETH: Currency = Currency(Cow::Borrowed("eth"));
+        pub const MAX: Currency = Currency(Cow::Borrowed("max"));
+    }
+
+    /// Group order id, to operate orders of the same group together.
+    #[derive(Serialize, Deserialize, Copy, Clone, Hash, Eq, PartialEq, Ord, PartialOrd, Debug)]
+    #[serde(transparent)]
+    pub struct GroupId(pub u64);
+
+    impl From<u64> for GroupId {
+        fn from(id: u64) -> Self {
+            Self(id)
+        }
+    }
+
+    impl From<GroupId> for u64 {
+        fn from(id: GroupId) -> Self {
+            id.0
+        }
+    }
+
+    impl fmt::Display for GroupId {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            self.0.fmt(f)
+        }
+    }
 
     /// Data type to represent time points. Identical to `chrono::DateTime<Utc>`.
     pub type DateTime = ChronoDateTime<Utc>;
 
     /// Options for sort list in created time.
-    #[derive(Serialize, Copy, Clone, Eq, PartialEq, Debug)]
+    #[derive(Serialize, Deserialize, Copy, Clone, Eq, PartialEq, Debug)]
     #[serde(rename_all = "lowercase")]
     pub enum OrderBy {
         Asc,
         Desc,
     }
 
+    impl_wire_str!(OrderBy {
+        Asc => "asc",
+        Desc => "desc",
+    });
+
+    impl OrderBy {
+        /// The opposite order.
+        pub fn reverse(self) -> Self {
+            match self {
+                Self::Asc => Self::Desc,
+                Self::Desc => Self::Asc,
+            }
+        }
+    }
+
     /// Parameters for pagination.
-    #[derive(Serialize, Debug)]
+    ///
+    /// Constructing by the public fields skips validation, prefer [`PageParams::new`] or [`PageParams::first_page`] for
+    /// values from user input.
+    #[derive(Serialize, Copy, Clone, Eq, PartialEq, Debug)]
     pub struct PageParams {
         /// Page number, applied for pagination (default 1)
         pub page: u64,
@@ -107,45 +400,809 @@ pub mod common {
         }
     }
 
+    impl PageParams {
+        /// Max records returned per page.
+        pub const MAX_LIMIT: u64 = 1000;
+
+        /// Validate the page number (at least 1) and the limit (1 to [`PageParams::MAX_LIMIT`]).
+        pub fn new(page: u64, limit: u64) -> Result<Self, PageParamsError> {
+            if page == 0 {
+                return Err(PageParamsError::InvalidPage(page));
+            }
+            if limit == 0 || limit > Self::MAX_LIMIT {
+                return Err(PageParamsError::InvalidLimit(limit));
+            }
+            Ok(Self { page, limit })
+        }
+
+        /// The first page with the given limit.
+        pub fn first_page(limit: u64) -> Result<Self, PageParamsError> {
+            Self::new(1, limit)
+        }
+
+        /// The page after this one, with the same limit.
+        pub fn next(&self) -> Self {
+            Self {
+                page: self.page.saturating_add(1),
+                limit: self.limit,
+            }
+        }
+    }
+
+    /// Target period of history queries, serialized as `from` and `to` in epoch seconds. Both ends are inclusive.
+    ///
+    /// Constructing by the public fields skips validation, see [`TimeRange::validate`].
+    #[derive(Serialize, Copy, Clone, Eq, PartialEq, Debug, Default)]
+    pub struct TimeRange {
+        /// Target period start (Epoch time in seconds).
+        #[serde(
+            skip_serializing_if = "Option::is_none",
+            with = "chrono::serde::ts_seconds_option"
+        )]
+        pub from: Option<DateTime>,
+        /// Target period end (Epoch time in seconds).
+        #[serde(
+            skip_serializing_if = "Option::is_none",
+            with = "chrono::serde::ts_seconds_option"
+        )]
+        pub to: Option<DateTime>,
+    }
+
+    impl TimeRange {
+        /// From the given time on.
+        pub fn since(from: DateTime) -> Self {
+            Self {
+                from: Some(from),
+                to: None,
+            }
+        }
+
+        /// Up to the given time.
+        pub fn until(to: DateTime) -> Self {
+            Self {
+                from: None,
+                to: Some(to),
+            }
+        }
+
+        /// Between the given times, or fail if `from` is later than `to`.
+        pub fn between(from: DateTime, to: DateTime) -> Result<Self, TimeRangeError> {
+            Self {
+                from: Some(from),
+                to: Some(to),
+            }
+            .validate()
+        }
+
+        /// The last `hours` hours until now, leaving the end open.
+        pub fn last_hours(hours: u32) -> Self {
+            Self::since(Utc::now() - chrono::Duration::hours(hours.into()))
+        }
+
+        /// Check that the start is not later than the end.
+        pub fn validate(self) -> Result<Self, TimeRangeError> {
+            match (self.from, self.to) {
+                (Some(from), Some(to)) if from > to => Err(TimeRangeError { from, to }),
+                _ => Ok(self),
+            }
+        }
+    }
+
+    /// Time range and paging parameters shared by history queries.
+    ///
+    /// ```
+    /// # use chrono::{TimeZone, Utc};
+    /// # use maicoin_max::common::{PageParams, TimeRangeQuery};
+    /// let range = TimeRangeQuery::default()
+    ///     .since(Utc.timestamp_opt(1637316000, 0).unwrap())
+    ///     .with_page(PageParams { page: 2, limit: 10 });
+    /// assert_eq!(range.pagination, Some(true));
+    /// ```
+    #[derive(Serialize, Copy, Clone, Eq, PartialEq, Debug, Default)]
+    pub struct TimeRangeQuery {
+        /// Target period, see [`TimeRange`].
+        #[serde(flatten)]
+        pub time: TimeRange,
+        /// Do pagination & return metadata in header (default differs by endpoint).
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub pagination: Option<bool>,
+        /// Pagination parameters, see [`PageParams`].
+        #[serde(flatten, skip_serializing_if = "Option::is_none")]
+        pub page_params: Option<PageParams>,
+        /// Records to skip, not applied for pagination (default `0`).
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub offset: Option<u64>,
+    }
+
+    impl TimeRangeQuery {
+        /// Start of the target period.
+        pub fn since(mut self, from: DateTime) -> Self {
+            self.time.from = Some(from);
+            self
+        }
+
+        /// End of the target period.
+        pub fn until(mut self, to: DateTime) -> Self {
+            self.time.to = Some(to);
+            self
+        }
+
+        /// Replace the target period.
+        pub fn with_time(mut self, time: TimeRange) -> Self {
+            self.time = time;
+            self
+        }
+
+        /// Query the given page, which turns pagination on.
+        pub fn with_page(mut self, page_params: PageParams) -> Self {
+            self.pagination = Some(true);
+            self.page_params = Some(page_params);
+            self
+        }
+
+        /// Turn pagination on or off explicitly.
+        pub fn with_pagination(mut self, pagination: bool) -> Self {
+            self.pagination = Some(pagination);
+            self
+        }
+
+        /// Records to skip, not applied for pagination.
+        pub fn with_offset(mut self, offset: u64) -> Self {
+            self.offset = Some(offset);
+            self
+        }
+    }
+
     /// Side information used in orders.
-    #[derive(Serialize, Deserialize, Copy, Clone, Eq, PartialEq, Debug)]
+    #[derive(Serialize, Deserialize, Copy, Clone, Eq, PartialEq, Debug, Default)]
     #[serde(rename_all = "lowercase")]
     pub enum OrderSide {
         Sell,
         Buy,
+        #[default]
         Unknown,
     }
 
+    impl_wire_str!(OrderSide {
+        Sell => "sell",
+        Buy => "buy",
+        Unknown => "unknown",
+    });
+
     impl OrderSide {
         pub fn is_unknown(&self) -> bool {
             self == &Self::Unknown
         }
+
+        /// Side of the counterparty. The opposite of `Unknown` is `Unknown`.
+        pub fn opposite(self) -> Self {
+            match self {
+                Self::Sell => Self::Buy,
+                Self::Buy => Self::Sell,
+                Self::Unknown => Self::Unknown,
+            }
+        }
+
+        /// `1` for buy, `-1` for sell and `0` for unknown, e.g. to sign the traded volume in PnL calculation.
+        pub fn sign(self) -> i8 {
+            match self {
+                Self::Sell => -1,
+                Self::Buy => 1,
+                Self::Unknown => 0,
+            }
+        }
     }
 
-    impl Default for OrderSide {
-        fn default() -> Self {
-            Self::Unknown
+    /// Buy orders are matched as bids, and sell orders as asks.
+    impl From<OrderSide> for TradeSide {
+        fn from(side: OrderSide) -> Self {
+            match side {
+                OrderSide::Sell => TradeSide::Ask,
+                OrderSide::Buy => TradeSide::Bid,
+                OrderSide::Unknown => TradeSide::Unknown,
+            }
+        }
+    }
+
+    /// Bids come from buy orders, and asks from sell orders. `Unknown` is returned back as the error.
+    impl TryFrom<TradeSide> for OrderSide {
+        type Error = TradeSide;
+
+        fn try_from(side: TradeSide) -> Result<Self, Self::Error> {
+            match side {
+                TradeSide::Ask => Ok(OrderSide::Sell),
+                TradeSide::Bid => Ok(OrderSide::Buy),
+                TradeSide::Unknown => Err(side),
+            }
         }
     }
 
     /// Side information used in trade records.
-    #[derive(Deserialize, Copy, Clone, Eq, PartialEq, Debug)]
+    #[derive(Serialize, Deserialize, Copy, Clone, Eq, PartialEq, Debug, Default)]
     #[serde(rename_all = "lowercase")]
     pub enum TradeSide {
         Ask,
         Bid,
+        #[default]
         Unknown,
     }
 
+    impl_wire_str!(TradeSide {
+        Ask => "ask",
+        Bid => "bid",
+        Unknown => "unknown",
+    });
+
     impl TradeSide {
         pub fn is_unknown(&self) -> bool {
             self == &Self::Unknown
         }
+
+        /// Side of the counterparty. The opposite of `Unknown` is `Unknown`.
+        pub fn opposite(self) -> Self {
+            match self {
+                Self::Ask => Self::Bid,
+                Self::Bid => Self::Ask,
+                Self::Unknown => Self::Unknown,
+            }
+        }
+
+        /// `1` for bid, `-1` for ask and `0` for unknown, e.g. to sign the traded volume in PnL calculation.
+        pub fn sign(self) -> i8 {
+            match self {
+                Self::Ask => -1,
+                Self::Bid => 1,
+                Self::Unknown => 0,
+            }
+        }
     }
 
-    impl Default for TradeSide {
-        fn default() -> Self {
-            Self::Unknown
+    /// Rounding prices and volumes to the precision of markets.
+    ///
+    /// ```
+    /// use maicoin_max::common::precision::{round_price, round_volume, RoundingMode};
+    /// use maicoin_max::common::OrderSide;
+    /// use maicoin_max::v2::rest::MarketInfo;
+    /// use rust_decimal::Decimal;
+    ///
+    /// let market = MarketInfo {
+    ///     base_unit_precision: 2,
+    ///     quote_unit_precision: 1,
+    ///     ..Default::default()
+    /// };
+    /// let price: Decimal = "1234.56".parse().unwrap();
+    /// let volume: Decimal = "0.129".parse().unwrap();
+    /// let bid = round_price(price, &market, OrderSide::Buy, RoundingMode::Passive).unwrap();
+    /// let ask = round_price(price, &market, OrderSide::Sell, RoundingMode::Passive).unwrap();
+    /// assert_eq!((bid.to_string().as_str(), ask.to_string().as_str()), ("1234.5", "1234.6"));
+    /// let volume = round_volume(volume, &market, OrderSide::Buy, RoundingMode::Passive).unwrap();
+    /// assert_eq!(volume.to_string(), "0.12");
+    /// ```
+    pub mod precision {
+        use std::convert::TryFrom;
+
+        use rust_decimal::{Decimal, RoundingStrategy};
+
+        use super::OrderSide;
+        pub use crate::error::PrecisionError;
+        use crate::v2::rest::MarketInfo;
+
+        /// How to round values to the precision.
+        #[derive(Copy, Clone, Eq, PartialEq, Hash, Debug, Default)]
+        pub enum RoundingMode {
+            /// Round so that the order never trades at a worse price or more than the given volume: buy prices down,
+            /// sell prices up, and volumes toward zero. Without a side (e.g. [`quantize`] and unknown sides), round
+            /// toward zero.
+            #[default]
+            Passive,
+            /// Round toward negative infinity.
+            Floor,
+            /// Round toward positive infinity.
+            Ceiling,
+            /// Round to the nearest, and ties to even (banker's rounding).
+            HalfEven,
+            /// Round to the nearest, and ties away from zero.
+            HalfUp,
+        }
+
+        impl RoundingMode {
+            fn strategy(self, passive: RoundingStrategy) -> RoundingStrategy {
+                match self {
+                    Self::Passive => passive,
+                    Self::Floor => RoundingStrategy::ToNegativeInfinity,
+                    Self::Ceiling => RoundingStrategy::ToPositiveInfinity,
+                    Self::HalfEven => RoundingStrategy::MidpointNearestEven,
+                    Self::HalfUp => RoundingStrategy::MidpointAwayFromZero,
+                }
+            }
+        }
+
+        /// Round the value to at most `places` decimal places. Any value, including negative ones, can be rounded.
+        pub fn quantize(value: Decimal, places: u8, mode: RoundingMode) -> Decimal {
+            value.round_dp_with_strategy(places.into(), mode.strategy(RoundingStrategy::ToZero))
+        }
+
+        /// Round the price to the quote unit precision of the market. Fails if the price is not positive or is
+        /// rounded to zero.
+        pub fn round_price(
+            price: Decimal,
+            market: &MarketInfo,
+            side: OrderSide,
+            mode: RoundingMode,
+        ) -> Result<Decimal, PrecisionError> {
+            let passive = match side {
+                OrderSide::Buy => RoundingStrategy::ToNegativeInfinity,
+                OrderSide::Sell => RoundingStrategy::ToPositiveInfinity,
+                OrderSide::Unknown => RoundingStrategy::ToZero,
+            };
+            round_positive(price, market.quote_unit_precision, mode.strategy(passive))
+        }
+
+        /// Round the volume to the base unit precision of the market. Fails if the volume is not positive or is
+        /// rounded to zero.
+        ///
+        /// Volumes are rounded the same for both sides, the side is taken for symmetry with [`round_price`].
+        pub fn round_volume(
+            volume: Decimal,
+            market: &MarketInfo,
+            _side: OrderSide,
+            mode: RoundingMode,
+        ) -> Result<Decimal, PrecisionError> {
+            round_positive(
+                volume,
+                market.base_unit_precision,
+                mode.strategy(RoundingStrategy::ToZero),
+            )
+        }
+
+        /// Check that the value is positive and fits the precision as is.
+        pub(crate) fn check(value: Decimal, precision: i8) -> Result<Decimal, PrecisionError> {
+            let places = places(precision)?;
+            if value <= Decimal::ZERO {
+                return Err(PrecisionError::NotPositive(value));
+            }
+            if value.normalize().scale() > places {
+                return Err(PrecisionError::TooPrecise { value, places });
+            }
+            Ok(value)
+        }
+
+        fn round_positive(
+            value: Decimal,
+            precision: i8,
+            strategy: RoundingStrategy,
+        ) -> Result<Decimal, PrecisionError> {
+            let places = places(precision)?;
+            if value <= Decimal::ZERO {
+                return Err(PrecisionError::NotPositive(value));
+            }
+            let rounded = value.round_dp_with_strategy(places, strategy);
+            if rounded.is_zero() {
+                return Err(PrecisionError::BelowTick { value, places });
+            }
+            Ok(rounded)
+        }
+
+        fn places(precision: i8) -> Result<u32, PrecisionError> {
+            u32::try_from(precision).map_err(|_| PrecisionError::InvalidPrecision(precision))
+        }
+
+        #[cfg(test)]
+        mod tests {
+            use super::*;
+            use rust_decimal_macros::dec;
+
+            fn market(base: i8, quote: i8) -> MarketInfo {
+                MarketInfo {
+                    id: "btctwd".into(),
+                    base_unit_precision: base,
+                    quote_unit_precision: quote,
+                    ..Default::default()
+                }
+            }
+
+            #[test]
+            fn quantize_modes() {
+                use RoundingMode::*;
+
+                let cases = [
+                    // value, places, passive, floor, ceiling, half even, half up
+                    (
+                        dec!(2.345),
+                        2,
+                        dec!(2.34),
+                        dec!(2.34),
+                        dec!(2.35),
+                        dec!(2.34),
+                        dec!(2.35),
+                    ),
+                    (
+                        dec!(2.355),
+                        2,
+                        dec!(2.35),
+                        dec!(2.35),
+                        dec!(2.36),
+                        dec!(2.36),
+                        dec!(2.36),
+                    ),
+                    (
+                        dec!(2.3451),
+                        2,
+                        dec!(2.34),
+                        dec!(2.34),
+                        dec!(2.35),
+                        dec!(2.35),
+                        dec!(2.35),
+                    ),
+                    (
+                        dec!(-2.345),
+                        2,
+                        dec!(-2.34),
+                        dec!(-2.35),
+                        dec!(-2.34),
+                        dec!(-2.34),
+                        dec!(-2.35),
+                    ),
+                    (dec!(2.5), 0, dec!(2), dec!(2), dec!(3), dec!(2), dec!(3)),
+                    (dec!(3.5), 0, dec!(3), dec!(3), dec!(4), dec!(4), dec!(4)),
+                    (dec!(0), 2, dec!(0), dec!(0), dec!(0), dec!(0), dec!(0)),
+                    (
+                        dec!(1.2),
+                        4,
+                        dec!(1.2),
+                        dec!(1.2),
+                        dec!(1.2),
+                        dec!(1.2),
+                        dec!(1.2),
+                    ),
+                ];
+                for (value, places, passive, floor, ceiling, half_even, half_up) in cases {
+                    let rounded = [Passive, Floor, Ceiling, HalfEven, HalfUp]
+                        .map(|mode| quantize(value, places, mode));
+                    assert_eq!(
+                        rounded,
+                        [passive, floor, ceiling, half_even, half_up],
+                        "{} to {} places",
+                        value,
+                        places
+                    );
+                }
+            }
+
+            #[test]
+            fn round_price_by_side() {
+                let market = market(8, 1);
+                let price = dec!(1738000.15);
+                let round = |side, mode| round_price(price, &market, side, mode).unwrap();
+                assert_eq!(
+                    round(OrderSide::Buy, RoundingMode::Passive),
+                    dec!(1738000.1)
+                );
+                assert_eq!(
+                    round(OrderSide::Sell, RoundingMode::Passive),
+                    dec!(1738000.2)
+                );
+                assert_eq!(
+                    round(OrderSide::Unknown, RoundingMode::Passive),
+                    dec!(1738000.1)
+                );
+                // explicit modes ignore the side
+                for side in OrderSide::VARIANTS.iter().copied() {
+                    assert_eq!(round(side, RoundingMode::HalfEven), dec!(1738000.2));
+                    assert_eq!(round(side, RoundingMode::Floor), dec!(1738000.1));
+                }
+                // already fits the precision
+                assert_eq!(
+                    round_price(
+                        dec!(1738000),
+                        &market,
+                        OrderSide::Sell,
+                        RoundingMode::Passive
+                    ),
+                    Ok(dec!(1738000))
+                );
+            }
+
+            #[test]
+            fn round_volume_toward_zero() {
+                let market = market(2, 1);
+                for side in OrderSide::VARIANTS.iter().copied() {
+                    assert_eq!(
+                        round_volume(dec!(0.129), &market, side, RoundingMode::Passive),
+                        Ok(dec!(0.12))
+                    );
+                }
+                assert_eq!(
+                    round_volume(dec!(0.125), &market, OrderSide::Buy, RoundingMode::HalfEven),
+                    Ok(dec!(0.12))
+                );
+                assert_eq!(
+                    round_volume(dec!(0.125), &market, OrderSide::Buy, RoundingMode::HalfUp),
+                    Ok(dec!(0.13))
+                );
+            }
+
+            #[test]
+            fn precision_errors() {
+                let market = market(2, 1);
+                for value in [dec!(0), dec!(-1), dec!(-0.001)] {
+                    for side in OrderSide::VARIANTS.iter().copied() {
+                        assert_eq!(
+                            round_price(value, &market, side, RoundingMode::Ceiling),
+                            Err(PrecisionError::NotPositive(value))
+                        );
+                        assert_eq!(
+                            round_volume(value, &market, side, RoundingMode::Ceiling),
+                            Err(PrecisionError::NotPositive(value))
+                        );
+                    }
+                }
+
+                // below one tick, unless rounded up
+                let volume = dec!(0.004);
+                assert_eq!(
+                    round_volume(volume, &market, OrderSide::Sell, RoundingMode::Passive),
+                    Err(PrecisionError::BelowTick {
+                        value: volume,
+                        places: 2
+                    })
+                );
+                assert_eq!(
+                    round_volume(volume, &market, OrderSide::Sell, RoundingMode::HalfEven),
+                    Err(PrecisionError::BelowTick {
+                        value: volume,
+                        places: 2
+                    })
+                );
+                assert_eq!(
+                    round_volume(volume, &market, OrderSide::Sell, RoundingMode::Ceiling),
+                    Ok(dec!(0.01))
+                );
+                assert_eq!(
+                    round_price(dec!(0.04), &market, OrderSide::Sell, RoundingMode::Passive),
+                    Ok(dec!(0.1))
+                );
+                assert_eq!(
+                    round_price(dec!(0.04), &market, OrderSide::Buy, RoundingMode::Passive),
+                    Err(PrecisionError::BelowTick {
+                        value: dec!(0.04),
+                        places: 1
+                    })
+                );
+
+                let market = self::market(-1, 2);
+                assert_eq!(
+                    round_volume(dec!(1), &market, OrderSide::Buy, RoundingMode::Passive),
+                    Err(PrecisionError::InvalidPrecision(-1))
+                );
+            }
+
+            #[test]
+            fn check_precision() {
+                assert_eq!(check(dec!(1.20), 1), Ok(dec!(1.20)));
+                assert_eq!(check(dec!(5), 0), Ok(dec!(5)));
+                assert_eq!(
+                    check(dec!(1.25), 1),
+                    Err(PrecisionError::TooPrecise {
+                        value: dec!(1.25),
+                        places: 1
+                    })
+                );
+                assert_eq!(check(dec!(0), 1), Err(PrecisionError::NotPositive(dec!(0))));
+                assert_eq!(
+                    check(dec!(1), -2),
+                    Err(PrecisionError::InvalidPrecision(-2))
+                );
+            }
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+        use crate::util::test_util::assert_wire_str;
+        use chrono::TimeZone;
+
+        #[test]
+        fn enum_wire_str() {
+            assert_wire_str(OrderBy::VARIANTS);
+            assert_wire_str(OrderSide::VARIANTS);
+            assert_wire_str(TradeSide::VARIANTS);
+        }
+
+        #[test]
+        fn order_and_trade_sides() {
+            let pairs = [
+                (OrderSide::Buy, TradeSide::Bid),
+                (OrderSide::Sell, TradeSide::Ask),
+            ];
+            for &(order_side, trade_side) in pairs.iter() {
+                assert_eq!(TradeSide::from(order_side), trade_side);
+                assert_eq!(OrderSide::try_from(trade_side), Ok(order_side));
+                assert_eq!(
+                    TradeSide::from(order_side.opposite()),
+                    trade_side.opposite()
+                );
+                assert_eq!(order_side.sign(), trade_side.sign());
+                assert_eq!(order_side.opposite().sign(), -order_side.sign());
+                assert_eq!(order_side.opposite().opposite(), order_side);
+                assert_eq!(trade_side.opposite().opposite(), trade_side);
+            }
+            assert_eq!(OrderSide::Buy.sign(), 1);
+            assert_eq!(TradeSide::Ask.sign(), -1);
+
+            assert_eq!(TradeSide::from(OrderSide::Unknown), TradeSide::Unknown);
+            assert_eq!(
+                OrderSide::try_from(TradeSide::Unknown),
+                Err(TradeSide::Unknown)
+            );
+            assert_eq!(OrderSide::Unknown.opposite(), OrderSide::Unknown);
+            assert_eq!(TradeSide::Unknown.opposite(), TradeSide::Unknown);
+            assert_eq!(OrderSide::Unknown.sign(), 0);
+            assert_eq!(TradeSide::Unknown.sign(), 0);
+        }
+
+        #[test]
+        fn order_by_parse() {
+            assert_eq!("DESC".parse::<OrderBy>(), Ok(OrderBy::Desc));
+            assert!("descending".parse::<OrderBy>().is_err());
+            assert_eq!(OrderBy::Asc.reverse(), OrderBy::Desc);
+            assert_eq!(OrderBy::Desc.reverse().to_string(), "asc");
+
+            #[derive(Deserialize)]
+            struct QueryConfig {
+                order_by: OrderBy,
+            }
+            let config: QueryConfig = serde_json::from_str(r#"{"order_by": "desc"}"#).unwrap();
+            assert_eq!(config.order_by, OrderBy::Desc);
+            assert_eq!(
+                serde_json::to_string(&config.order_by.reverse()).unwrap(),
+                r#""asc""#
+            );
+        }
+
+        #[test]
+        fn symbol_normalization() {
+            assert_eq!(Symbol::new("BTCTWD").unwrap(), "btctwd");
+            assert_eq!(" MaxUsdt ".parse::<Symbol>().unwrap().as_str(), "maxusdt");
+            assert_eq!(Symbol::from("EthTwd"), Symbol::from(String::from("ethtwd")));
+            assert_eq!(
+                Symbol::new(" \t"),
+                Err(ParseSymbolError {
+                    value: " \t".into()
+                })
+            );
+            assert!("".parse::<Symbol>().is_err());
+
+            let symbol: Symbol = serde_json::from_str(r#""BTCUSDT""#).unwrap();
+            assert_eq!(symbol, "btcusdt");
+            assert_eq!(serde_json::to_string(&symbol).unwrap(), r#""btcusdt""#);
+            assert_eq!(symbol.to_string(), "btcusdt");
+            assert!(symbol.ends_with("usdt"));
+        }
+
+        #[test]
+        fn currency_normalization() {
+            assert_eq!(Currency::from("USDT"), Currency::USDT);
+            assert_eq!(Currency::from(" Btc"), "btc");
+            assert_eq!("MAX".parse::<Currency>().unwrap(), Currency::MAX);
+            assert_eq!(Currency::TWD.to_string(), "twd");
+            assert!(Currency::new("").is_err());
+
+            let currency: Currency = serde_json::from_str(r#""ETH""#).unwrap();
+            assert_eq!(currency, Currency::ETH);
+            assert_eq!(serde_json::to_string(&currency).unwrap(), r#""eth""#);
+        }
+
+        #[test]
+        fn page_params_bounds() {
+            assert_eq!(PageParams::new(1, 1), Ok(PageParams { page: 1, limit: 1 }));
+            assert_eq!(
+                PageParams::new(u64::MAX, 1000),
+                Ok(PageParams {
+                    page: u64::MAX,
+                    limit: 1000
+                })
+            );
+            assert_eq!(PageParams::new(0, 50), Err(PageParamsError::InvalidPage(0)));
+            assert_eq!(PageParams::new(1, 0), Err(PageParamsError::InvalidLimit(0)));
+            assert_eq!(
+                PageParams::first_page(1001),
+                Err(PageParamsError::InvalidLimit(1001))
+            );
+            assert_eq!(
+                PageParamsError::InvalidPage(0).to_string(),
+                "invalid page 0, page number starts from 1"
+            );
+            assert_eq!(
+                PageParamsError::InvalidLimit(1001).to_string(),
+                "invalid limit 1001, expect 1 to 1000"
+            );
+
+            let page = PageParams::first_page(20).unwrap();
+            assert_eq!(page.next(), PageParams { page: 2, limit: 20 });
+            assert_eq!(page.next().next().page, 3);
+        }
+
+        #[test]
+        fn time_range() {
+            let from = Utc.timestamp_opt(1637316000, 0).unwrap();
+            let to = Utc.timestamp_opt(1637402400, 0).unwrap();
+            let range = TimeRange::between(from, to).unwrap();
+            assert_eq!(
+                serde_json::to_value(range).unwrap(),
+                serde_json::json!({"from": 1637316000, "to": 1637402400})
+            );
+            assert_eq!(
+                serde_json::to_value(TimeRange::since(from)).unwrap(),
+                serde_json::json!({"from": 1637316000})
+            );
+            assert_eq!(
+                serde_json::to_value(TimeRange::default()).unwrap(),
+                serde_json::json!({})
+            );
+            assert_eq!(TimeRange::between(from, from).unwrap().to, Some(from));
+            assert_eq!(
+                TimeRange::between(to, from),
+                Err(TimeRangeError { from: to, to: from })
+            );
+
+            let range = TimeRange::last_hours(24);
+            assert_eq!(range.to, None);
+            assert!(range.from.unwrap() < Utc::now() - chrono::Duration::hours(23));
+        }
+
+        #[test]
+        fn group_id_round_trip() {
+            let id = GroupId::from(12);
+            assert_eq!(serde_json::to_string(&id).unwrap(), "12");
+            assert_eq!(serde_json::from_str::<GroupId>("12").unwrap(), id);
+            assert_eq!(
+                serde_json::from_str::<Option<GroupId>>("null").unwrap(),
+                None
+            );
+            assert_eq!(u64::from(id), 12);
+            assert_eq!(id.to_string(), "12");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn credentials_checked() {
+        let credentials = Credentials::new_checked(
+            " 1EwwEEvjsbZhk6kRTrIUSOfv42xQqXz5k94xKjRW\n",
+            "\tJ6X8j4RRgIaMxm7MxtZXnuMGfbEUBxE4pH3Asbpi ",
+        )
+        .unwrap();
+        assert_eq!(
+            credentials.access_key,
+            "1EwwEEvjsbZhk6kRTrIUSOfv42xQqXz5k94xKjRW"
+        );
+        assert_eq!(
+            credentials.secret_key,
+            "J6X8j4RRgIaMxm7MxtZXnuMGfbEUBxE4pH3Asbpi"
+        );
+
+        let long = "k".repeat(MAX_KEY_LEN + 1);
+        for (access, secret) in [
+            ("  ", "secret"),
+            ("access", ""),
+            ("acc ess", "secret"),
+            ("access", "sécret"),
+            (long.as_str(), "secret"),
+        ] {
+            assert!(
+                matches!(
+                    Credentials::new_checked(access, secret),
+                    Err(error::Error::InvalidParams(_))
+                ),
+                "{:?}",
+                (access, secret)
+            );
         }
     }
 }
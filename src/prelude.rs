@@ -0,0 +1,69 @@
+//! Commonly used types, to be glob-imported by `use maicoin_max::prelude::*;`.
+//!
+//! The result type is re-exported as [`MaxResult`], so that the glob import does not shadow `std::result::Result`.
+//! Less common requests and responses are still available in [`crate::v2::rest`] and [`crate::v2::ws`].
+//!
+//! ```
+//! use maicoin_max::prelude::*;
+//!
+//! fn parse_market(raw: &str) -> MaxResult<Symbol> {
+//!     Ok(Symbol::new(raw)?)
+//! }
+//!
+//! let credentials = Credentials::new("access key".into(), "secret key".into());
+//! let order = CreateOrder {
+//!     market: "btctwd".into(),
+//!     side: OrderSide::Buy,
+//!     volume: Decimal::new(1, 2),
+//!     price: Some(Decimal::new(1_000_000, 0)),
+//!     client_oid: None,
+//!     stop_price: None,
+//!     ord_type: OrderType::Limit,
+//!     group_id: None,
+//! };
+//! let _req = order.to_request(&credentials);
+//! let orders: GetOrders = GetOrders::open("btctwd");
+//! assert_eq!(orders.market, "btctwd");
+//!
+//! let _tickers = GetTickers {};
+//! let mut sub = SubRequest::new_sub(String::new());
+//! sub.subset().insert_ticker("btctwd");
+//! let _key = ChannelKey::new(PublicChannel::Ticker, "btctwd");
+//! let _host = WsHost::default();
+//!
+//! let _now: DateTime = Utc::now();
+//! let _currency = Currency::TWD;
+//! let _err: Option<Error> = None;
+//! let _ticker: Option<RespTickerInfo> = None;
+//! let _trade: Option<TradeRecord> = None;
+//! let _feed: Option<PubTickerFeed> = None;
+//! assert!(parse_market(" ").is_err());
+//! ```
+
+pub use chrono::Utc;
+pub use rust_decimal::Decimal;
+
+pub use crate::common::*;
+pub use crate::error::{Error, Result as MaxResult};
+pub use crate::Credentials;
+
+#[cfg(feature = "blocking")]
+pub use crate::v2::rest::BlockingClient;
+pub use crate::v2::rest::{
+    ApiHost, AuthEndpoint, ClearOrders, CreateOrder, CurrencyInfo, DeleteOrder, Endpoint,
+    GetAccountOfCurrency, GetCurrencies, GetDepth, GetMarkets, GetMyTrades, GetOrder, GetOrders,
+    GetProfileAndAccount, GetTickers, GetTickersOfMarket, GetTimestamp, MarketInfo, OrderState,
+    OrderType, PublicEndpoint, RespAccountCurrencyInfo, RespDepth, RespOrder, RespTickerInfo,
+    TradeRecord,
+};
+#[cfg(feature = "client-surf")]
+pub use crate::v2::rest::{MaxRestClient, SurfAuthExt, SurfPublicExt};
+
+pub use crate::v2::ws::feed::{
+    PrivBalanceFeed, PrivOrderBookFeed, PrivTradeFeed, PubOrderBookFeed, PubTickerFeed,
+    PubTradeFeed,
+};
+pub use crate::v2::ws::{
+    AuthRequest, ChannelKey, PrivFeedFilter, PrivFeedType, PublicChannel, ServerPushEvent,
+    SubRequest, SubscribeChannelSet, WsHost,
+};
@@ -1,6 +1,77 @@
-pub(crate) mod serde {
-    use serde::de;
-    use serde::{Deserialize, Deserializer};
+//! Client side utilities.
+
+pub mod ratelimit;
+
+/// Monotonic time point used by the utilities. Identical to `std::time::Instant`, except on wasm32-unknown-unknown
+/// where `instant::Instant` is used since the standard one is unsupported.
+#[cfg(all(target_arch = "wasm32", target_os = "unknown"))]
+pub use instant::Instant;
+/// Monotonic time point used by the utilities. Identical to `std::time::Instant`, except on wasm32-unknown-unknown
+/// where `instant::Instant` is used since the standard one is unsupported.
+#[cfg(not(all(target_arch = "wasm32", target_os = "unknown")))]
+pub use std::time::Instant;
+
+/// Serde helpers for the irregular data replied by MAX API.
+pub mod serde {
+    use rust_decimal::Decimal;
+    use serde::de::{self, DeserializeOwned};
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    /// Deserialize either a single value or an array of values into a `Vec`, for replies which are sometimes a bare
+    /// object and a one-element array at other times.
+    ///
+    /// ```
+    /// use maicoin_max::util::serde::one_or_many;
+    /// use serde::Deserialize;
+    ///
+    /// #[derive(Deserialize)]
+    /// struct Reply {
+    ///     #[serde(deserialize_with = "one_or_many")]
+    ///     ids: Vec<u64>,
+    /// }
+    ///
+    /// let reply: Reply = serde_json::from_str(r#"{"ids": 1}"#).unwrap();
+    /// assert_eq!(reply.ids, vec![1]);
+    /// let reply: Reply = serde_json::from_str(r#"{"ids": [1, 2]}"#).unwrap();
+    /// assert_eq!(reply.ids, vec![1, 2]);
+    /// ```
+    pub fn one_or_many<'de, D, T>(deserializer: D) -> Result<Vec<T>, D::Error>
+    where
+        D: Deserializer<'de>,
+        T: DeserializeOwned,
+    {
+        OneOrMany::deserialize(deserializer).map(|values| values.0)
+    }
+
+    /// `Vec` deserialized by [`one_or_many`], and serialized as an array.
+    #[derive(Serialize, Clone, Eq, PartialEq, Default, Debug)]
+    #[serde(transparent)]
+    pub struct OneOrMany<T>(pub Vec<T>);
+
+    impl<'de, T: DeserializeOwned> Deserialize<'de> for OneOrMany<T> {
+        fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+        where
+            D: Deserializer<'de>,
+        {
+            #[derive(Deserialize)]
+            #[serde(untagged, bound = "T: DeserializeOwned")]
+            enum Raw<T> {
+                Many(Vec<T>),
+                One(T),
+            }
+
+            match Raw::deserialize(deserializer)? {
+                Raw::Many(values) => Ok(Self(values)),
+                Raw::One(value) => Ok(Self(vec![value])),
+            }
+        }
+    }
+
+    impl<T> From<OneOrMany<T>> for Vec<T> {
+        fn from(values: OneOrMany<T>) -> Self {
+            values.0
+        }
+    }
 
     pub(crate) fn bool_from_onoff<'de, D>(deserializer: D) -> Result<bool, D::Error>
     where
@@ -15,31 +86,329 @@ pub(crate) mod serde {
             )),
         }
     }
+
+    // Parse enums by their case-insensitive `FromStr`, and fall back to the default (unknown) variant for values not
+    // known yet, so that new values from the server do not fail the whole message.
+    pub(crate) fn from_str_or_default<'de, D, T>(deserializer: D) -> Result<T, D::Error>
+    where
+        D: Deserializer<'de>,
+        T: std::str::FromStr + Default,
+    {
+        Ok(String::deserialize(deserializer)?
+            .parse()
+            .unwrap_or_default())
+    }
+
+    pub(crate) fn bool_to_onoff<S>(value: &bool, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(if *value { "ON" } else { "OFF" })
+    }
+
+    /// Deserialize an optional decimal, taking an empty string as `None` like `null` and missing fields, since the
+    /// server sometimes replies `""` for unset prices. Numeric strings and numbers are accepted, other strings are
+    /// still errors. Missing fields require `#[serde(default)]` on the field or the struct.
+    ///
+    /// ```
+    /// use maicoin_max::util::serde::lenient_decimal_option;
+    /// use rust_decimal::Decimal;
+    /// use serde::Deserialize;
+    ///
+    /// #[derive(Deserialize)]
+    /// struct Reply {
+    ///     #[serde(default, deserialize_with = "lenient_decimal_option")]
+    ///     price: Option<Decimal>,
+    /// }
+    ///
+    /// let reply: Reply = serde_json::from_str(r#"{"price": ""}"#).unwrap();
+    /// assert_eq!(reply.price, None);
+    /// let reply: Reply = serde_json::from_str(r#"{"price": "1.5"}"#).unwrap();
+    /// assert_eq!(reply.price, Some(Decimal::new(15, 1)));
+    /// ```
+    pub fn lenient_decimal_option<'de, D>(deserializer: D) -> Result<Option<Decimal>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum Raw {
+            Str(String),
+            Num(Decimal),
+        }
+
+        match Option::<Raw>::deserialize(deserializer)? {
+            None => Ok(None),
+            Some(Raw::Str(s)) if s.trim().is_empty() => Ok(None),
+            Some(Raw::Str(s)) => <Decimal as Deserialize>::deserialize(
+                de::value::StrDeserializer::<D::Error>::new(&s),
+            )
+            .map(Some),
+            Some(Raw::Num(value)) => Ok(Some(value)),
+        }
+    }
+
+    /// Timestamp helpers to be used with `#[serde(with = "...")]`, for structs mirroring the MAX payloads.
+    ///
+    /// ```
+    /// use maicoin_max::common::DateTime;
+    /// use serde::Deserialize;
+    ///
+    /// #[derive(Deserialize)]
+    /// struct Record {
+    ///     #[serde(with = "maicoin_max::util::serde::time::ts_seconds_or_milliseconds")]
+    ///     created_at: DateTime,
+    ///     #[serde(default, with = "maicoin_max::util::serde::time::ts_seconds_or_milliseconds_option")]
+    ///     done_at: Option<DateTime>,
+    /// }
+    ///
+    /// let secs: Record = serde_json::from_str(r#"{"created_at": 1635853116, "done_at": 0}"#).unwrap();
+    /// let millis: Record = serde_json::from_str(r#"{"created_at": 1635853116000}"#).unwrap();
+    /// assert_eq!(secs.created_at, millis.created_at);
+    /// assert_eq!(secs.done_at, None);
+    /// ```
+    pub mod time {
+        use chrono::{TimeZone, Utc};
+        use serde::de::{self, Deserialize, Deserializer};
+
+        use crate::common::DateTime;
+
+        /// Helpers of chrono used by the crate: `ts_seconds` for most REST replies, and `ts_milliseconds` for the
+        /// websocket events and the `*_in_ms` fields. The `_option` ones take `null` or missing fields as `None`,
+        /// which requires `#[serde(default)]` on the field or the struct.
+        pub use chrono::serde::{
+            ts_milliseconds, ts_milliseconds_option, ts_seconds, ts_seconds_option,
+        };
+
+        // Timestamps not less than this are taken as milliseconds, i.e. seconds after year 5138 are not supported.
+        const MILLISECONDS_THRESHOLD: i64 = 100_000_000_000;
+
+        fn from_seconds_or_milliseconds<E: de::Error>(ts: i64) -> Result<DateTime, E> {
+            let time = if ts.unsigned_abs() >= MILLISECONDS_THRESHOLD as u64 {
+                Utc.timestamp_millis_opt(ts)
+            } else {
+                Utc.timestamp_opt(ts, 0)
+            };
+            time.single()
+                .ok_or_else(|| E::custom(format!("timestamp out of range: {}", ts)))
+        }
+
+        /// Timestamp in either seconds or milliseconds, told apart by the magnitude. Serialized in seconds.
+        pub mod ts_seconds_or_milliseconds {
+            use super::*;
+
+            pub use chrono::serde::ts_seconds::serialize;
+
+            pub fn deserialize<'de, D>(deserializer: D) -> Result<DateTime, D::Error>
+            where
+                D: Deserializer<'de>,
+            {
+                from_seconds_or_milliseconds(i64::deserialize(deserializer)?)
+            }
+        }
+
+        /// Optional timestamp in either seconds or milliseconds, where `0` means `None` as well as `null`. Serialized
+        /// in seconds.
+        pub mod ts_seconds_or_milliseconds_option {
+            use super::*;
+
+            pub use chrono::serde::ts_seconds_option::serialize;
+
+            pub fn deserialize<'de, D>(deserializer: D) -> Result<Option<DateTime>, D::Error>
+            where
+                D: Deserializer<'de>,
+            {
+                match Option::<i64>::deserialize(deserializer)? {
+                    None | Some(0) => Ok(None),
+                    Some(ts) => from_seconds_or_milliseconds(ts).map(Some),
+                }
+            }
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+        use serde_json::json;
+
+        #[derive(Deserialize, PartialEq, Debug)]
+        struct Item {
+            id: u64,
+        }
+
+        #[test]
+        fn one_or_many_object_or_array() {
+            let values: OneOrMany<Item> = serde_json::from_value(json!({"id": 1})).unwrap();
+            assert_eq!(values.0, vec![Item { id: 1 }]);
+
+            let values: OneOrMany<Item> =
+                serde_json::from_value(json!([{"id": 1}, {"id": 2}])).unwrap();
+            assert_eq!(values.0, vec![Item { id: 1 }, Item { id: 2 }]);
+
+            let values: OneOrMany<Item> = serde_json::from_value(json!([])).unwrap();
+            assert!(values.0.is_empty());
+
+            assert!(serde_json::from_value::<OneOrMany<Item>>(json!("1")).is_err());
+        }
+
+        #[derive(Deserialize, PartialEq, Debug)]
+        struct Record {
+            #[serde(with = "time::ts_seconds_or_milliseconds")]
+            created_at: crate::common::DateTime,
+            #[serde(default, with = "time::ts_seconds_or_milliseconds_option")]
+            updated_at: Option<crate::common::DateTime>,
+        }
+
+        #[test]
+        fn timestamp_seconds_or_milliseconds() {
+            use chrono::{TimeZone, Utc};
+
+            let created_at = Utc.timestamp_opt(1635853116, 0).unwrap();
+            let updated_at = Utc.timestamp_millis_opt(1635853634047).unwrap();
+            let expected = Record {
+                created_at,
+                updated_at: Some(updated_at),
+            };
+            let record: Record = serde_json::from_value(
+                json!({"created_at": 1635853116, "updated_at": 1635853634047i64}),
+            )
+            .unwrap();
+            assert_eq!(record, expected);
+            let record: Record = serde_json::from_value(
+                json!({"created_at": 1635853116000i64, "updated_at": 1635853634047i64}),
+            )
+            .unwrap();
+            assert_eq!(record, expected);
+
+            for updated_at in [json!(0), json!(null)] {
+                let record: Record = serde_json::from_value(
+                    json!({"created_at": 1635853116, "updated_at": updated_at}),
+                )
+                .unwrap();
+                assert_eq!(record.updated_at, None);
+            }
+            let record: Record = serde_json::from_value(json!({"created_at": 0})).unwrap();
+            assert_eq!(record.created_at.timestamp(), 0);
+            assert_eq!(record.updated_at, None);
+
+            assert!(serde_json::from_value::<Record>(json!({"created_at": "1635853116"})).is_err());
+            assert!(serde_json::from_value::<Record>(json!({"created_at": i64::MAX})).is_err());
+            assert!(serde_json::from_value::<Record>(json!({"created_at": i64::MIN})).is_err());
+        }
+    }
 }
 
-#[cfg(test)]
-pub(crate) mod test_util {
+/// Testing helpers to replay recorded API sessions, enabled by `test-util` feature.
+///
+/// ```ignore
+/// // record with `VcrMode::Record` once, then replay in the tests afterwards
+/// let client = create_test_recording_client(VcrMode::Replay, "tests/cassettes/get_orders.yaml").await;
+/// let orders = GetOrders { ... }.fetch(&client, &credentials).await?;
+/// ```
+#[cfg(any(test, feature = "test-util"))]
+pub mod test_util {
     use std::borrow::Borrow;
+    use std::convert::TryFrom;
+    #[cfg(test)]
     use std::path::PathBuf;
 
+    #[cfg(test)]
     use lazy_static::lazy_static;
     use serde_json::{json, Value};
-    use surf_vcr::{Body as VcrBody, VcrMiddleware, VcrMode};
+    pub use surf_vcr::{Body as VcrBody, VcrMiddleware, VcrMode, VcrRequest, VcrResponse};
 
+    #[cfg(test)]
     use crate::Credentials;
 
+    /// Canned response bodies, taken from real responses of MAX API, except the hand-written
+    /// [`ERROR_ORDER_NOT_FOUND`](fixtures::ERROR_ORDER_NOT_FOUND).
+    pub mod fixtures {
+        /// Response of `GET /api/v2/order`, a filled buy order of `dotusdt`.
+        pub const ORDER: &str = r#"{"avg_price":"52.0","client_oid":null,"created_at":1635853116,"created_at_in_ms":1635853116171,"executed_volume":"3.14","group_id":null,"id":1545763894,"market":"dotusdt","ord_type":"limit","price":"52.0","remaining_volume":"0.0","side":"buy","state":"done","stop_price":null,"trades_count":1,"updated_at":1635853634,"updated_at_in_ms":1635853634047,"volume":"3.14"}"#;
+
+        /// Response of `GET /api/v2/trades/my/of_order`, the trade of [`ORDER`].
+        pub const TRADES: &str = r#"[{"created_at":1635853634,"created_at_in_ms":1635853634052,"fee":"0.08908907","fee_currency":"max","funds":"163.28","id":29009013,"market":"dotusdt","market_name":"DOT/USDT","price":"52.0","side":"bid","volume":"3.14"}]"#;
+
+        /// Response of `GET /api/v2/tickers/btctwd`.
+        pub const TICKER: &str = r#"{"at":1636258205,"buy":"1737000.0","sell":"1738000.0","open":"1708337.2","low":"1682500.0","high":"1739517.2","last":"1738000.0","vol":"23.70350862","vol_in_btc":"23.70350862"}"#;
+
+        /// Response of `GET /api/v2/depth` of `btctwd`, with 2 levels on each side.
+        pub const DEPTH: &str = r#"{"timestamp":1636258205,"last_update_version":1635855699255,"last_update_id":4870357,"asks":[["1739999.0","0.00278039"],["1739716.7","0.00487816"]],"bids":[["1737000.0","0.2567111"],["1734381.3","0.001639"]]}"#;
+
+        /// Error response of `POST /api/v2/order/delete` with an unknown order ID, replied with status 404. Written by
+        /// hand after the documented error format.
+        pub const ERROR_ORDER_NOT_FOUND: &str =
+            r#"{"error":{"code":2004,"message":"Order#1 not found."}}"#;
+    }
+
+    /// Create a response with the given status and JSON body, e.g. to reply from a mocked
+    /// [`crate::v2::rest::HttpExecutor`].
+    pub fn canned_response(status: u16, body: &str) -> http_types::Response {
+        let status = http_types::StatusCode::try_from(status).expect("invalid HTTP status code");
+        let mut resp = http_types::Response::new(status);
+        resp.set_content_type(http_types::mime::JSON);
+        resp.set_body(body);
+        resp
+    }
+
     /// Get path of testing resource
+    #[cfg(test)]
     pub(crate) fn test_resource_path() -> PathBuf {
         [env!("CARGO_MANIFEST_DIR"), "resource", "test"]
             .iter()
             .collect()
     }
 
-    /// Create a Surf HTTP client with the surf-vcr middleware.
-    pub(crate) async fn create_test_recording_client(
-        mode: VcrMode,
-        cassette: &str,
-    ) -> surf::Client {
+    /// Hide credentials of the recorded request: authentication headers and nonce, which vary in each session.
+    pub fn redact_request(req: &mut VcrRequest) {
+        req.headers
+            .entry(crate::v2::rest::internal::HEADER_AUTH_ACCESS_KEY.to_lowercase())
+            .and_modify(|val| *val = vec!["(auth key)".into()]);
+        req.headers
+            .entry(crate::v2::rest::internal::HEADER_AUTH_PAYLOAD.to_lowercase())
+            .and_modify(|val| *val = vec!["(auth payload)".into()]);
+        req.headers
+            .entry(crate::v2::rest::internal::HEADER_AUTH_SIGNATURE.to_lowercase())
+            .and_modify(|val| *val = vec!["(auth signature)".into()]);
+
+        let url_copy = req.url.clone();
+        let query: Vec<_> = url_copy
+            .query_pairs()
+            .map(|(key, val)| {
+                let val = if key == "nonce" {
+                    std::borrow::Cow::from("(nonce)")
+                } else {
+                    val
+                };
+                (key, val)
+            })
+            .collect();
+        if !query.is_empty() {
+            req.url.query_pairs_mut().clear();
+            for (k, v) in query {
+                req.url
+                    .query_pairs_mut()
+                    .append_pair(k.borrow(), v.borrow());
+            }
+        }
+
+        match req.body {
+            VcrBody::Str(ref mut body) if !body.is_empty() => {
+                // leave non-JSON bodies as they are
+                if let Ok(mut parsed) = serde_json::from_str::<Value>(body) {
+                    if let Value::Object(ref mut obj) = parsed {
+                        obj.entry("nonce").and_modify(|val| *val = json!(0));
+                    }
+                    *body = parsed.to_string();
+                }
+            }
+            _ => {}
+        };
+    }
+
+    /// Hide private information of the recorded response: cookies, and long strings which are roughly treated as
+    /// wallet addresses.
+    pub fn redact_response(resp: &mut VcrResponse) {
         fn hide_address(obj: &mut serde_json::map::Map<String, Value>) {
             // roughly treat all long strings as wallet address
             for (key, val) in obj.iter_mut() {
@@ -49,85 +418,136 @@ pub(crate) mod test_util {
                     }
                 }
             }
-            println!("modified {:?}", obj);
         }
 
-        let vcr = VcrMiddleware::new(mode, cassette)
-            .await
-            .expect("Failed to create VCR middleware")
-            .with_modify_request(|req| {
-                req.headers
-                    .entry(crate::v2::rest::internal::HEADER_AUTH_ACCESS_KEY.to_lowercase())
-                    .and_modify(|val| *val = vec!["(auth key)".into()]);
-                req.headers
-                    .entry(crate::v2::rest::internal::HEADER_AUTH_PAYLOAD.to_lowercase())
-                    .and_modify(|val| *val = vec!["(auth payload)".into()]);
-                req.headers
-                    .entry(crate::v2::rest::internal::HEADER_AUTH_SIGNATURE.to_lowercase())
-                    .and_modify(|val| *val = vec!["(auth signature)".into()]);
-
-                let url_copy = req.url.clone();
-                let query: Vec<_> = url_copy
-                    .query_pairs()
-                    .map(|(key, val)| {
-                        let val = if key == "nonce" {
-                            std::borrow::Cow::from("(nonce)")
-                        } else {
-                            val
-                        };
-                        (key, val)
-                    })
-                    .collect();
-                if !query.is_empty() {
-                    req.url.query_pairs_mut().clear();
-                    for (k, v) in query {
-                        req.url
-                            .query_pairs_mut()
-                            .append_pair(k.borrow(), v.borrow());
-                    }
-                }
+        resp.headers
+            .entry("set-cookie".into())
+            .and_modify(|val| *val = vec!["(cookies)".into()]);
 
-                match req.body {
-                    VcrBody::Str(ref mut body) if !body.is_empty() => {
-                        let mut parsed: Value = serde_json::from_str(body).unwrap();
-                        if let serde_json::Value::Object(ref mut obj) = parsed {
-                            obj.entry("nonce").and_modify(|val| *val = json!(0));
-                        }
-                        *body = serde_json::to_string(&parsed).unwrap();
-                    }
-                    _ => {}
-                };
-            })
-            .with_modify_response(|resp| {
-                resp.headers
-                    .entry("set-cookie".into())
-                    .and_modify(|val| *val = vec!["(cookies)".into()]);
-
-                match resp.body {
-                    VcrBody::Str(ref mut body) => {
-                        println!("raw {:?}", body);
-                        let mut parsed: Value = serde_json::from_str(body).unwrap();
-                        match parsed {
-                            serde_json::Value::Object(ref mut obj) => hide_address(obj),
-                            serde_json::Value::Array(ref mut obj_list) => {
-                                for item in obj_list.iter_mut() {
-                                    if let serde_json::Value::Object(ref mut obj) = item {
-                                        hide_address(obj);
-                                    }
+        match resp.body {
+            VcrBody::Str(ref mut body) => {
+                // leave non-JSON bodies, e.g. the HTML maintenance page, as they are
+                if let Ok(mut parsed) = serde_json::from_str::<Value>(body) {
+                    match parsed {
+                        Value::Object(ref mut obj) => hide_address(obj),
+                        Value::Array(ref mut obj_list) => {
+                            for item in obj_list.iter_mut() {
+                                if let Value::Object(ref mut obj) = item {
+                                    hide_address(obj);
                                 }
                             }
-                            _ => {}
                         }
-                        *body = serde_json::to_string(&parsed).unwrap();
+                        _ => {}
                     }
-                    VcrBody::Bytes(_) => {}
+                    *body = parsed.to_string();
                 }
-            });
+            }
+            VcrBody::Bytes(_) => {}
+        }
+    }
+
+    /// Create a Surf HTTP client with the surf-vcr middleware, which redacts the recorded sessions with
+    /// [`redact_request`] and [`redact_response`].
+    pub async fn create_test_recording_client(mode: VcrMode, cassette: &str) -> surf::Client {
+        let vcr = VcrMiddleware::new(mode, cassette)
+            .await
+            .expect("Failed to create VCR middleware")
+            .with_modify_request(redact_request)
+            .with_modify_response(redact_response);
         surf::Client::new().with(vcr)
     }
 
+    #[cfg(test)]
     lazy_static! {
         pub static ref TEST_CREDENTIALS: Credentials =
             Credentials::from_env("MAX_TEST_ACCESS_KEY", "MAX_TEST_SECRET_KEY");
     }
+
+    /// Check that every variant is displayed as its serialized value, and parsed back case-insensitively.
+    #[cfg(test)]
+    pub(crate) fn assert_wire_str<T>(variants: &[T])
+    where
+        T: std::fmt::Display + std::str::FromStr + serde::Serialize + PartialEq + std::fmt::Debug,
+        T::Err: std::fmt::Debug,
+    {
+        assert!(!variants.is_empty());
+        for variant in variants {
+            let wire = variant.to_string();
+            assert_eq!(
+                serde_json::to_value(variant).unwrap(),
+                Value::String(wire.clone())
+            );
+            assert_eq!(wire.parse::<T>().unwrap(), *variant);
+            assert_eq!(wire.to_uppercase().parse::<T>().unwrap(), *variant);
+            assert_eq!(wire.to_lowercase().parse::<T>().unwrap(), *variant);
+        }
+        assert!("no-such-variant".parse::<T>().is_err());
+    }
+
+    /// Fields of a captured tracing event, formatted by `Debug`. The message is stored as "message".
+    #[cfg(all(test, feature = "tracing"))]
+    pub(crate) type TracedFields = std::collections::HashMap<String, String>;
+
+    /// Run the closure and capture the tracing events emitted in the current thread.
+    #[cfg(all(test, feature = "tracing"))]
+    pub(crate) fn capture_tracing_events<R>(f: impl FnOnce() -> R) -> (R, Vec<TracedFields>) {
+        use std::sync::{Arc, Mutex};
+        use tracing::field::{Field, Visit};
+        use tracing_subscriber::layer::{Context, SubscriberExt};
+
+        struct FieldVisitor<'a>(&'a mut TracedFields);
+        impl Visit for FieldVisitor<'_> {
+            fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+                self.0
+                    .insert(field.name().to_owned(), format!("{:?}", value));
+            }
+            fn record_str(&mut self, field: &Field, value: &str) {
+                self.0.insert(field.name().to_owned(), value.to_owned());
+            }
+        }
+
+        #[derive(Clone, Default)]
+        struct CaptureLayer(Arc<Mutex<Vec<TracedFields>>>);
+        impl<S: tracing::Subscriber> tracing_subscriber::Layer<S> for CaptureLayer {
+            fn on_event(&self, event: &tracing::Event<'_>, _ctx: Context<'_, S>) {
+                let mut fields = TracedFields::new();
+                event.record(&mut FieldVisitor(&mut fields));
+                self.0.lock().unwrap().push(fields);
+            }
+        }
+
+        let layer = CaptureLayer::default();
+        let subscriber = tracing_subscriber::registry().with(layer.clone());
+        let result = tracing::subscriber::with_default(subscriber, f);
+        let events = std::mem::take(&mut *layer.0.lock().unwrap());
+        (result, events)
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        #[test]
+        fn redact_non_json_body() {
+            let html = "<html><body>Under maintenance</body></html>";
+            let mut resp = VcrResponse {
+                status: http_types::StatusCode::ServiceUnavailable,
+                version: None,
+                headers: Default::default(),
+                body: VcrBody::Str(html.into()),
+            };
+            redact_response(&mut resp);
+            assert_eq!(resp.body, VcrBody::Str(html.into()));
+
+            let mut resp = VcrResponse {
+                body: VcrBody::Str(r#"{"address":"0x0123456789abcdef0123"}"#.into()),
+                ..resp
+            };
+            redact_response(&mut resp);
+            assert_eq!(
+                resp.body,
+                VcrBody::Str(r#"{"address":"(test erased address)"}"#.into())
+            );
+        }
+    }
 }
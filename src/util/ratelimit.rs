@@ -0,0 +1,302 @@
+//! Client side rate limiter, to keep requests within the quotas of MAX API.
+//!
+//! The limiter never sleeps by itself. When the budget runs out, it tells how long to wait so the caller can sleep on
+//! any runtime. Quotas are configured by the caller, according to the limits announced in the official API document.
+//!
+//! ```
+//! use std::time::Duration;
+//! use maicoin_max::util::ratelimit::{Quota, RateLimiter};
+//! use maicoin_max::v2::rest::GetTimestamp;
+//!
+//! let mut limiter = RateLimiter::new(
+//!     Quota::new(2, Duration::from_secs(1)),
+//!     Quota::new(1, Duration::from_secs(1)),
+//! );
+//! let params = GetTimestamp {};
+//! assert!(limiter.try_acquire_for(&params).is_ok());
+//! assert!(limiter.try_acquire_for(&params).is_ok());
+//! let wait = limiter.try_acquire_for(&params).unwrap_err();
+//! assert!(wait.0 <= Duration::from_millis(500));
+//! ```
+
+use std::time::Duration;
+
+use crate::util::Instant;
+use crate::v2::rest::Endpoint;
+
+/// Kind of API calls, which are limited separately.
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub enum EndpointKind {
+    /// Public endpoints.
+    Public,
+    /// Endpoints require authentication.
+    Private,
+}
+
+impl EndpointKind {
+    /// Classify the endpoint.
+    pub fn of<E: Endpoint>() -> Self {
+        if E::AUTH {
+            Self::Private
+        } else {
+            Self::Public
+        }
+    }
+}
+
+/// Time to wait before the next request is allowed.
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub struct WaitHint(pub Duration);
+
+/// Allowed number of requests in a period.
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub struct Quota {
+    /// Number of requests, also the max burst size.
+    pub requests: u32,
+    /// Length of the period.
+    pub per: Duration,
+}
+
+impl Quota {
+    /// Allow `requests` requests every `per`.
+    pub fn new(requests: u32, per: Duration) -> Self {
+        Self { requests, per }
+    }
+}
+
+/// Source of current time, replaceable for testing.
+pub trait Clock {
+    fn now(&self) -> Instant;
+}
+
+/// [`Clock`] based on [`Instant::now`].
+#[derive(Copy, Clone, Default, Debug)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> Instant {
+        Instant::now()
+    }
+}
+
+/// Token bucket rate limiter, with separate quotas for public and private endpoints.
+///
+/// Each quota starts full, and is refilled continuously at the rate of `requests / per`.
+#[derive(Debug)]
+pub struct RateLimiter<C: Clock = SystemClock> {
+    public: TokenBucket,
+    private: TokenBucket,
+    clock: C,
+}
+
+impl RateLimiter<SystemClock> {
+    /// Create the limiter with the quotas of the public and private endpoints.
+    pub fn new(public: Quota, private: Quota) -> Self {
+        Self::with_clock(public, private, SystemClock)
+    }
+}
+
+impl<C: Clock> RateLimiter<C> {
+    /// Create the limiter with the given clock.
+    pub fn with_clock(public: Quota, private: Quota, clock: C) -> Self {
+        let now = clock.now();
+        Self {
+            public: TokenBucket::new(public, now),
+            private: TokenBucket::new(private, now),
+            clock,
+        }
+    }
+
+    /// Take one request from the budget of the kind, or tell how long to wait if it is used up.
+    pub fn try_acquire(&mut self, kind: EndpointKind) -> Result<(), WaitHint> {
+        let now = self.clock.now();
+        match kind {
+            EndpointKind::Public => self.public.try_acquire(now),
+            EndpointKind::Private => self.private.try_acquire(now),
+        }
+    }
+
+    /// Same as [`RateLimiter::try_acquire`], with kind classified from the endpoint.
+    pub fn try_acquire_for<E: Endpoint>(&mut self, _params: &E) -> Result<(), WaitHint> {
+        self.try_acquire(EndpointKind::of::<E>())
+    }
+}
+
+#[derive(Debug)]
+struct TokenBucket {
+    quota: Quota,
+    tokens: f64,
+    updated_at: Instant,
+}
+
+impl TokenBucket {
+    fn new(quota: Quota, now: Instant) -> Self {
+        Self {
+            quota,
+            tokens: quota.requests as f64,
+            updated_at: now,
+        }
+    }
+
+    // tokens refilled per second
+    fn rate(&self) -> f64 {
+        self.quota.requests as f64 / self.quota.per.as_secs_f64()
+    }
+
+    fn try_acquire(&mut self, now: Instant) -> Result<(), WaitHint> {
+        if self.quota.requests == 0 {
+            return Err(WaitHint(self.quota.per));
+        }
+        let elapsed = now.saturating_duration_since(self.updated_at);
+        self.tokens =
+            (self.tokens + elapsed.as_secs_f64() * self.rate()).min(self.quota.requests as f64);
+        self.updated_at = now;
+        if self.tokens >= 1.0 {
+            self.tokens -= 1.0;
+            Ok(())
+        } else {
+            // a token takes at most `per` to refill, which also covers waits too long for `Duration`
+            let wait = Duration::try_from_secs_f64((1.0 - self.tokens) / self.rate())
+                .unwrap_or(self.quota.per);
+            Err(WaitHint(wait))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::v2::rest::{GetOrder, GetTimestamp};
+    use std::cell::Cell;
+
+    struct TestClock {
+        start: Instant,
+        offset: Cell<Duration>,
+    }
+
+    impl TestClock {
+        fn new() -> Self {
+            Self {
+                start: Instant::now(),
+                offset: Cell::new(Duration::ZERO),
+            }
+        }
+    }
+
+    impl Clock for &TestClock {
+        fn now(&self) -> Instant {
+            self.start + self.offset.get()
+        }
+    }
+
+    impl TestClock {
+        fn advance(&self, duration: Duration) {
+            self.offset.set(self.offset.get() + duration);
+        }
+    }
+
+    fn assert_wait(result: Result<(), WaitHint>, expected: Duration) {
+        let WaitHint(wait) = result.expect_err("expect to wait");
+        let diff = wait.abs_diff(expected);
+        assert!(
+            diff < Duration::from_micros(1),
+            "wait {:?}, expected {:?}",
+            wait,
+            expected
+        );
+    }
+
+    #[test]
+    fn classify_endpoints() {
+        assert_eq!(EndpointKind::of::<GetTimestamp>(), EndpointKind::Public);
+        assert_eq!(EndpointKind::of::<GetOrder>(), EndpointKind::Private);
+    }
+
+    #[test]
+    fn burst_and_refill() {
+        let clock = TestClock::new();
+        let mut limiter = RateLimiter::with_clock(
+            Quota::new(4, Duration::from_secs(2)),
+            Quota::new(1, Duration::from_secs(1)),
+            &clock,
+        );
+
+        // full budget at the beginning
+        for _ in 0..4 {
+            assert!(limiter.try_acquire(EndpointKind::Public).is_ok());
+        }
+        assert_wait(
+            limiter.try_acquire(EndpointKind::Public),
+            Duration::from_millis(500),
+        );
+
+        // partially refilled
+        clock.advance(Duration::from_millis(200));
+        assert_wait(
+            limiter.try_acquire(EndpointKind::Public),
+            Duration::from_millis(300),
+        );
+        clock.advance(Duration::from_millis(300));
+        assert!(limiter.try_acquire(EndpointKind::Public).is_ok());
+        assert!(limiter.try_acquire(EndpointKind::Public).is_err());
+
+        // refill never exceeds the quota
+        clock.advance(Duration::from_secs(60));
+        for _ in 0..4 {
+            assert!(limiter.try_acquire(EndpointKind::Public).is_ok());
+        }
+        assert!(limiter.try_acquire(EndpointKind::Public).is_err());
+    }
+
+    #[test]
+    fn separated_budgets() {
+        let clock = TestClock::new();
+        let mut limiter = RateLimiter::with_clock(
+            Quota::new(1, Duration::from_secs(1)),
+            Quota::new(2, Duration::from_secs(10)),
+            &clock,
+        );
+        let public = GetTimestamp {};
+        let private = GetOrder {
+            id: Some(1),
+            client_oid: None,
+        };
+
+        assert!(limiter.try_acquire_for(&public).is_ok());
+        assert_wait(limiter.try_acquire_for(&public), Duration::from_secs(1));
+        assert!(limiter.try_acquire_for(&private).is_ok());
+        assert!(limiter.try_acquire_for(&private).is_ok());
+        assert_wait(limiter.try_acquire_for(&private), Duration::from_secs(5));
+
+        clock.advance(Duration::from_secs(1));
+        assert!(limiter.try_acquire_for(&public).is_ok());
+        assert_wait(limiter.try_acquire_for(&private), Duration::from_secs(4));
+    }
+
+    #[test]
+    fn zero_quota() {
+        let mut limiter = RateLimiter::new(
+            Quota::new(0, Duration::from_secs(1)),
+            Quota::new(1, Duration::from_secs(1)),
+        );
+        assert_eq!(
+            limiter.try_acquire(EndpointKind::Public),
+            Err(WaitHint(Duration::from_secs(1)))
+        );
+    }
+
+    #[test]
+    fn huge_period() {
+        let clock = TestClock::new();
+        let mut limiter = RateLimiter::with_clock(
+            Quota::new(1, Duration::MAX),
+            Quota::new(1, Duration::from_secs(1)),
+            &clock,
+        );
+        assert!(limiter.try_acquire(EndpointKind::Public).is_ok());
+        assert_eq!(
+            limiter.try_acquire(EndpointKind::Public),
+            Err(WaitHint(Duration::MAX))
+        );
+    }
+}
@@ -0,0 +1,468 @@
+//! Tracking an order from the REST placement to its end by the websocket pushes.
+//!
+//! [`OrderLifecycle`] is seeded with the [`CreateOrder`] params before sending, so that the pushes can be matched by
+//! the client order id even if they arrive before the REST response. It does no I/O: feed it the REST response and the
+//! server pushes, and handle the returned [`LifecycleEvent`]s.
+//!
+//! ```
+//! use maicoin_max::prelude::*;
+//! use maicoin_max::v2::lifecycle::{OrderLifecycle, OrderTransition};
+//!
+//! let mut lifecycle = OrderLifecycle::new(CreateOrder {
+//!     market: "btctwd".into(),
+//!     side: OrderSide::Buy,
+//!     volume: Decimal::new(1, 2),
+//!     price: Some(Decimal::new(1_000_000, 0)),
+//!     client_oid: None,
+//!     stop_price: None,
+//!     ord_type: OrderType::Limit,
+//!     group_id: None,
+//! });
+//! // send `lifecycle.order()`, which carries a generated client_oid
+//! assert!(lifecycle.order().client_oid.is_some());
+//!
+//! let raw = r#"{"c":"user","e":"order_update","T":1700000000000,"o":[{"i":1,"sd":"bid","ot":"limit",
+//!     "p":"1000000","S":"wait","M":"btctwd","T":1700000000000,"v":"0.01","rv":"0.01","ev":"0",
+//!     "ci":"CLIENT_OID"}]}"#;
+//! let raw = raw.replace("CLIENT_OID", lifecycle.client_oid());
+//! let event: ServerPushEvent = serde_json::from_str(&raw).unwrap();
+//! let events = lifecycle.on_push(&event);
+//! assert_eq!(events[0].transition, OrderTransition::Acknowledged);
+//! assert_eq!(lifecycle.id(), Some(1));
+//! assert!(!lifecycle.is_terminal());
+//! ```
+
+use std::collections::HashSet;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use rust_decimal::Decimal;
+
+use crate::common::*;
+use crate::v2::rest::{CreateOrder, OrderState, RespOrder};
+use crate::v2::ws::feed::{PrivOrderBookRec, PrivTradeRec};
+use crate::v2::ws::ServerPushEvent;
+
+/// State machine of a placed order, see the [module document](self).
+///
+/// Fills are counted from both the executed volume of the order pushes and the trade pushes, deduplicated by trade id,
+/// so either feed alone is enough. Once terminal, further pushes of the order are ignored.
+#[derive(Debug)]
+pub struct OrderLifecycle {
+    order: CreateOrder,
+    id: Option<u64>,
+    acknowledged: bool,
+    filled: Decimal,
+    traded: Decimal,
+    trade_ids: HashSet<u64>,
+    // trades of unknown orders kept until the order id is known
+    pending_trades: Vec<PrivTradeRec>,
+    outcome: Option<OrderTransition>,
+}
+
+/// Transition of an order, emitted by [`OrderLifecycle`].
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub enum OrderTransition {
+    /// Accepted by the server.
+    Acknowledged,
+    /// Executed volume increased, and the order is still open.
+    PartiallyFilled {
+        /// Total executed volume so far.
+        filled: Decimal,
+        /// Volume left to be executed.
+        remaining: Decimal,
+    },
+    /// Fully executed.
+    Filled,
+    /// Cancelled, possibly after partial executions.
+    Cancelled,
+    /// Refused by the server.
+    Rejected,
+}
+
+impl OrderTransition {
+    /// `true` if no more transitions follow.
+    pub fn is_terminal(&self) -> bool {
+        matches!(self, Self::Filled | Self::Cancelled | Self::Rejected)
+    }
+}
+
+/// Transition of an order with the time reported by the server.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct LifecycleEvent {
+    /// Time reported by the server in the push, trade or REST reply which caused the transition.
+    pub time: DateTime,
+    /// The transition itself.
+    pub transition: OrderTransition,
+}
+
+impl OrderLifecycle {
+    /// Track the order, generating a client order id if absent.
+    pub fn new(mut order: CreateOrder) -> Self {
+        if order.client_oid.is_none() {
+            order.client_oid = Some(generate_client_oid());
+        }
+        Self {
+            order,
+            id: None,
+            acknowledged: false,
+            filled: Decimal::ZERO,
+            traded: Decimal::ZERO,
+            trade_ids: HashSet::new(),
+            pending_trades: Vec::new(),
+            outcome: None,
+        }
+    }
+
+    /// Params to be sent, with the client order id set.
+    pub fn order(&self) -> &CreateOrder {
+        &self.order
+    }
+
+    /// Client order id to match the pushes.
+    pub fn client_oid(&self) -> &str {
+        self.order.client_oid.as_deref().unwrap_or_default()
+    }
+
+    /// Order id, known from the REST response or the first matched push.
+    pub fn id(&self) -> Option<u64> {
+        self.id
+    }
+
+    /// Executed volume so far.
+    pub fn filled(&self) -> Decimal {
+        self.filled
+    }
+
+    /// Final transition, if the order is closed.
+    pub fn outcome(&self) -> Option<OrderTransition> {
+        self.outcome
+    }
+
+    /// `true` if the order is filled, cancelled or rejected.
+    pub fn is_terminal(&self) -> bool {
+        self.outcome.is_some()
+    }
+
+    /// Apply the response of [`CreateOrder`]. The time is taken from the update time of the order, or the creation
+    /// time if absent.
+    pub fn on_response(&mut self, resp: &RespOrder) -> Vec<LifecycleEvent> {
+        let mut events = Vec::new();
+        if self.id.is_none() {
+            self.id = resp.id;
+        }
+        let time = resp
+            .updated_at_in_ms
+            .or(resp.updated_at)
+            .or(resp.created_at_in_ms)
+            .or(resp.created_at)
+            .unwrap_or_default();
+        let executed = resp.executed_volume.or_else(|| {
+            resp.volume
+                .zip(resp.remaining_volume)
+                .map(|(volume, remaining)| volume - remaining)
+        });
+        self.advance(time, resp.state, executed, &mut events);
+        self.replay_pending(&mut events);
+        events
+    }
+
+    /// Apply a server push. Pushes other than the private orders and trades are ignored.
+    pub fn on_push(&mut self, event: &ServerPushEvent) -> Vec<LifecycleEvent> {
+        let mut events = Vec::new();
+        match event {
+            ServerPushEvent::PrivOrderbookFeed(feed) => {
+                for rec in feed.orders.iter() {
+                    if !self.owns_order(rec) {
+                        continue;
+                    }
+                    if self.id.is_none() {
+                        self.id = Some(rec.oid);
+                    }
+                    let executed = rec
+                        .executed_volume
+                        .or_else(|| rec.remaining_volume.map(|remaining| rec.volume - remaining));
+                    self.advance(feed.time, rec.state, executed, &mut events);
+                }
+                self.replay_pending(&mut events);
+            }
+            ServerPushEvent::PrivTradeFeed(feed) => {
+                for rec in feed.trades.iter() {
+                    self.on_trade(rec, &mut events);
+                }
+            }
+            _ => {}
+        }
+        events
+    }
+
+    fn owns_order(&self, rec: &PrivOrderBookRec) -> bool {
+        Some(rec.oid) == self.id
+            || (rec.client_oid.is_some() && rec.client_oid == self.order.client_oid)
+    }
+
+    fn on_trade(&mut self, rec: &PrivTradeRec, events: &mut Vec<LifecycleEvent>) {
+        let owned = match (rec.oid, self.id) {
+            (Some(oid), Some(id)) => oid == id,
+            _ => rec.client_oid.is_some() && rec.client_oid == self.order.client_oid,
+        };
+        if !owned {
+            if self.id.is_none() && rec.oid.is_some() && rec.market == self.order.market {
+                self.pending_trades.push(rec.clone());
+            }
+            return;
+        }
+        if self.id.is_none() {
+            self.id = rec.oid;
+        }
+        if self.is_terminal() || !self.trade_ids.insert(rec.tid) {
+            return;
+        }
+        self.traded += rec.volume;
+        let traded = self.traded;
+        self.advance(rec.create_time, OrderState::Wait, Some(traded), events);
+    }
+
+    fn replay_pending(&mut self, events: &mut Vec<LifecycleEvent>) {
+        if self.id.is_none() || self.pending_trades.is_empty() {
+            return;
+        }
+        for rec in std::mem::take(&mut self.pending_trades) {
+            self.on_trade(&rec, events);
+        }
+    }
+
+    fn advance(
+        &mut self,
+        time: DateTime,
+        state: OrderState,
+        executed: Option<Decimal>,
+        events: &mut Vec<LifecycleEvent>,
+    ) {
+        if self.is_terminal() {
+            return;
+        }
+        let mut emit = |transition| events.push(LifecycleEvent { time, transition });
+        if state == OrderState::Failed {
+            self.outcome = Some(OrderTransition::Rejected);
+            emit(OrderTransition::Rejected);
+            return;
+        }
+        if !self.acknowledged {
+            self.acknowledged = true;
+            emit(OrderTransition::Acknowledged);
+        }
+        if let Some(executed) = executed.filter(|executed| *executed > self.filled) {
+            self.filled = executed;
+            if executed < self.order.volume && state != OrderState::Done {
+                emit(OrderTransition::PartiallyFilled {
+                    filled: executed,
+                    remaining: self.order.volume - executed,
+                });
+            } else {
+                self.outcome = Some(OrderTransition::Filled);
+            }
+        }
+        match state {
+            OrderState::Done => self.outcome = Some(OrderTransition::Filled),
+            OrderState::Cancel => self.outcome = Some(OrderTransition::Cancelled),
+            _ => {}
+        }
+        if let Some(outcome) = self.outcome {
+            emit(outcome);
+            self.pending_trades.clear();
+        }
+    }
+}
+
+// Generate client order id from the current time and a process-wide sequence number, within the limit of 36 chars.
+fn generate_client_oid() -> String {
+    static SEQ: AtomicU64 = AtomicU64::new(0);
+    format!(
+        "max-rs-{}-{}",
+        crate::clock(),
+        SEQ.fetch_add(1, Ordering::Relaxed)
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::v2::rest::OrderType;
+    use rust_decimal_macros::dec;
+    use serde_json::json;
+
+    fn lifecycle() -> OrderLifecycle {
+        OrderLifecycle::new(CreateOrder {
+            market: "ethtwd".into(),
+            side: OrderSide::Buy,
+            volume: dec!(1.0),
+            price: Some(dec!(50000)),
+            client_oid: Some("lifecycle-1".into()),
+            stop_price: None,
+            ord_type: OrderType::Limit,
+            group_id: None,
+        })
+    }
+
+    fn order_push(state: &str, executed: &str, time: i64) -> ServerPushEvent {
+        serde_json::from_value(json!({
+            "c": "user",
+            "e": "order_update",
+            "o": [{
+                "i": 87, "sd": "bid", "ot": "limit", "p": "50000", "S": state, "M": "ethtwd",
+                "T": 1700000000000_i64, "v": "1.0", "ev": executed, "ci": "lifecycle-1"
+            }],
+            "T": time
+        }))
+        .unwrap()
+    }
+
+    fn trade_push(tid: u64, oid: Option<u64>, volume: &str, time: i64) -> ServerPushEvent {
+        serde_json::from_value(json!({
+            "c": "user",
+            "e": "trade_update",
+            "t": [{
+                "i": tid, "p": "50000", "v": volume, "M": "ethtwd", "T": time, "sd": "bid",
+                "f": "0.001", "fc": "eth", "m": true, "oi": oid
+            }],
+            "T": time
+        }))
+        .unwrap()
+    }
+
+    fn response(state: OrderState, executed: Decimal) -> RespOrder {
+        RespOrder {
+            id: Some(87),
+            client_oid: Some("lifecycle-1".into()),
+            state,
+            market: "ethtwd".into(),
+            volume: Some(dec!(1.0)),
+            executed_volume: Some(executed),
+            created_at_in_ms: Some(at(1700000000000)),
+            ..Default::default()
+        }
+    }
+
+    fn at(ms: i64) -> DateTime {
+        use chrono::TimeZone;
+        chrono::Utc.timestamp_millis_opt(ms).unwrap()
+    }
+
+    fn transitions(events: Vec<LifecycleEvent>) -> Vec<OrderTransition> {
+        events.into_iter().map(|event| event.transition).collect()
+    }
+
+    #[test]
+    fn generated_client_oid() {
+        let first = OrderLifecycle::new(CreateOrder {
+            client_oid: None,
+            ..lifecycle().order
+        });
+        let second = OrderLifecycle::new(CreateOrder {
+            client_oid: None,
+            ..lifecycle().order
+        });
+        assert!(first.client_oid().len() <= 36);
+        assert_ne!(first.client_oid(), second.client_oid());
+        assert_eq!(lifecycle().client_oid(), "lifecycle-1");
+    }
+
+    #[test]
+    fn rest_then_pushes() {
+        let mut lifecycle = lifecycle();
+        let events = lifecycle.on_response(&response(OrderState::Wait, dec!(0)));
+        assert_eq!(
+            events,
+            vec![LifecycleEvent {
+                time: at(1700000000000),
+                transition: OrderTransition::Acknowledged
+            }]
+        );
+        assert_eq!(lifecycle.id(), Some(87));
+
+        // the acknowledgement by push is not repeated
+        assert!(lifecycle
+            .on_push(&order_push("wait", "0", 1700000000100))
+            .is_empty());
+        let events = lifecycle.on_push(&trade_push(1, Some(87), "0.4", 1700000000200));
+        assert_eq!(
+            events,
+            vec![LifecycleEvent {
+                time: at(1700000000200),
+                transition: OrderTransition::PartiallyFilled {
+                    filled: dec!(0.4),
+                    remaining: dec!(0.6)
+                }
+            }]
+        );
+        // the order push of the same fill, and the trade pushed again
+        assert!(lifecycle
+            .on_push(&order_push("wait", "0.4", 1700000000300))
+            .is_empty());
+        assert!(lifecycle
+            .on_push(&trade_push(1, Some(87), "0.4", 1700000000200))
+            .is_empty());
+        // trades of other orders
+        assert!(lifecycle
+            .on_push(&trade_push(2, Some(88), "0.4", 1700000000400))
+            .is_empty());
+        assert_eq!(lifecycle.filled(), dec!(0.4));
+
+        let events = lifecycle.on_push(&trade_push(3, Some(87), "0.6", 1700000000500));
+        assert_eq!(transitions(events), vec![OrderTransition::Filled]);
+        assert!(lifecycle.is_terminal());
+        assert!(lifecycle
+            .on_push(&order_push("done", "1.0", 1700000000600))
+            .is_empty());
+    }
+
+    #[test]
+    fn push_before_rest() {
+        let mut lifecycle = lifecycle();
+        // a trade of the order before knowing its id is kept until the order is matched
+        assert!(lifecycle
+            .on_push(&trade_push(1, Some(87), "0.5", 1700000000050))
+            .is_empty());
+        let events = lifecycle.on_push(&order_push("wait", "0", 1700000000100));
+        assert_eq!(
+            transitions(events),
+            vec![
+                OrderTransition::Acknowledged,
+                OrderTransition::PartiallyFilled {
+                    filled: dec!(0.5),
+                    remaining: dec!(0.5)
+                }
+            ]
+        );
+        assert_eq!(lifecycle.id(), Some(87));
+        assert!(lifecycle
+            .on_response(&response(OrderState::Wait, dec!(0)))
+            .is_empty());
+
+        let events = lifecycle.on_push(&order_push("cancel", "0.5", 1700000000300));
+        assert_eq!(
+            events,
+            vec![LifecycleEvent {
+                time: at(1700000000300),
+                transition: OrderTransition::Cancelled
+            }]
+        );
+        assert_eq!(lifecycle.outcome(), Some(OrderTransition::Cancelled));
+    }
+
+    #[test]
+    fn closed_by_response() {
+        let mut lifecycle = lifecycle();
+        let events = lifecycle.on_response(&response(OrderState::Done, dec!(1.0)));
+        assert_eq!(
+            transitions(events),
+            vec![OrderTransition::Acknowledged, OrderTransition::Filled]
+        );
+
+        let mut lifecycle = self::lifecycle();
+        let events = lifecycle.on_response(&response(OrderState::Failed, dec!(0)));
+        assert_eq!(transitions(events), vec![OrderTransition::Rejected]);
+        assert!(lifecycle.is_terminal());
+        assert!(lifecycle.outcome().unwrap().is_terminal());
+    }
+}
@@ -4,5 +4,8 @@
 //! - [v2 REST API Endpoints](https://max.maicoin.com/documents/api_list/v2) |
 //! - [v2 Websocket API Documentation](https://maicoin.github.io/max-websocket-docs/)
 
+pub mod lifecycle;
+pub mod portfolio;
+pub mod position;
 pub mod rest;
 pub mod ws;
@@ -0,0 +1,318 @@
+//! Valuation of balances in a quote currency by the tickers.
+//!
+//! [`PortfolioValuator`] prices each currency by the last price of the markets, routing through intermediate markets
+//! if there is no direct one, e.g. DOT to TWD via `dotusdt` then `usdttwd`.
+//!
+//! ```
+//! use std::collections::HashMap;
+//! use maicoin_max::prelude::*;
+//! use maicoin_max::v2::portfolio::PortfolioValuator;
+//! use maicoin_max::v2::rest::MarketTable;
+//!
+//! let markets: MarketTable = vec![MarketInfo {
+//!     id: "btctwd".into(),
+//!     base_unit: "btc".into(),
+//!     quote_unit: "twd".into(),
+//!     ..Default::default()
+//! }]
+//! .into();
+//! let ticker: RespTickerInfo = serde_json::from_str(r#"{"at":1636258205,"buy":"1737000.0","sell":"1738000.0",
+//!     "open":"1708337.2","low":"1682500.0","high":"1739517.2","last":"1738000.0","vol":"23.70350862",
+//!     "vol_in_btc":"23.70350862"}"#).unwrap();
+//! let tickers: HashMap<Symbol, RespTickerInfo> = vec![("btctwd".into(), ticker)].into_iter().collect();
+//!
+//! let valuator = PortfolioValuator::new("twd", &markets, &tickers);
+//! let valuation = valuator.value(vec![("btc", Decimal::new(1, 2)), ("doge", Decimal::ONE)]);
+//! assert_eq!(valuation.total, Decimal::new(17380, 0));
+//! assert_eq!(valuation.unpriced().count(), 1);
+//! ```
+
+use std::collections::{HashMap, HashSet, VecDeque};
+
+use rust_decimal::Decimal;
+
+use crate::common::*;
+use crate::v2::rest::{MarketTable, RespAccountCurrencyInfo, RespTickerInfo};
+use crate::v2::ws::feed::PrivBalanceItem;
+
+/// Prices currencies in the quote currency, see the [module document](self).
+#[derive(Clone, Debug)]
+pub struct PortfolioValuator {
+    quote: String,
+    // currency => [(currency, rate, market)], converting 1 unit of the currency into `rate` units of the other
+    edges: HashMap<String, Vec<(String, Decimal, Symbol)>>,
+}
+
+/// Valuation of all the balances, see [`PortfolioValuator::value`].
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub struct Valuation {
+    /// Quote currency of the values.
+    pub quote: String,
+    /// Valuation of each currency, in the order of the balances.
+    pub currencies: Vec<CurrencyValuation>,
+    /// Sum of the priced values.
+    pub total: Decimal,
+}
+
+impl Valuation {
+    /// Currencies without any price path to the quote currency, which are excluded from the total.
+    pub fn unpriced(&self) -> impl Iterator<Item = &CurrencyValuation> {
+        self.currencies
+            .iter()
+            .filter(|valuation| valuation.value.is_none())
+    }
+}
+
+/// Valuation of the balance of a currency.
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub struct CurrencyValuation {
+    /// Currency id in lowercase.
+    pub currency: String,
+    /// Total amount, including the locked.
+    pub amount: Decimal,
+    /// Price of 1 unit in the quote currency, `None` if unpriced.
+    pub price: Option<Decimal>,
+    /// Value in the quote currency, `None` if unpriced.
+    pub value: Option<Decimal>,
+    /// Markets used to price the currency, empty for the quote currency itself.
+    pub route: Vec<Symbol>,
+}
+
+impl PortfolioValuator {
+    /// Valuator of the quote currency (e.g. `twd` or `usdt`) by the last price of the tickers, e.g. from
+    /// [`GetTickers`](crate::v2::rest::GetTickers). Tickers of markets not in the table, or of zero price, are ignored.
+    pub fn new(
+        quote: impl Into<String>,
+        markets: &MarketTable,
+        tickers: &HashMap<Symbol, RespTickerInfo>,
+    ) -> Self {
+        let mut edges: HashMap<String, Vec<(String, Decimal, Symbol)>> = HashMap::new();
+        for (symbol, ticker) in tickers.iter() {
+            let (base, quote) = match markets.split_symbol(symbol) {
+                Some((base, quote)) if !ticker.last_price.is_zero() => {
+                    (base.to_lowercase(), quote.to_lowercase())
+                }
+                _ => continue,
+            };
+            edges.entry(base.clone()).or_default().push((
+                quote.clone(),
+                ticker.last_price,
+                symbol.clone(),
+            ));
+            edges.entry(quote).or_default().push((
+                base,
+                Decimal::ONE / ticker.last_price,
+                symbol.clone(),
+            ));
+        }
+        // prefer the same route among the equally short ones regardless of the map order
+        for targets in edges.values_mut() {
+            targets.sort_by(|lhs, rhs| (&lhs.0, &lhs.2).cmp(&(&rhs.0, &rhs.2)));
+        }
+        Self {
+            quote: quote.into().to_lowercase(),
+            edges,
+        }
+    }
+
+    /// Price of 1 unit of the currency in the quote currency by the fewest markets, with the markets of the route.
+    /// `None` if there is no price path.
+    pub fn price_of(&self, currency: &str) -> Option<(Decimal, Vec<Symbol>)> {
+        let currency = currency.to_lowercase();
+        if currency == self.quote {
+            return Some((Decimal::ONE, Vec::new()));
+        }
+        let mut visited: HashSet<&str> = HashSet::new();
+        let mut queue: VecDeque<(&str, Decimal, Vec<Symbol>)> = VecDeque::new();
+        visited.insert(&currency);
+        queue.push_back((&currency, Decimal::ONE, Vec::new()));
+        while let Some((from, price, route)) = queue.pop_front() {
+            for (to, rate, market) in self.edges.get(from).into_iter().flatten() {
+                if !visited.insert(to) {
+                    continue;
+                }
+                let mut route = route.clone();
+                route.push(market.clone());
+                if to == &self.quote {
+                    return Some((price * rate, route));
+                }
+                queue.push_back((to, price * rate, route));
+            }
+        }
+        None
+    }
+
+    /// Value the balances of `(currency, amount)`. Balances of the same currency are valued separately.
+    pub fn value<C: AsRef<str>>(
+        &self,
+        balances: impl IntoIterator<Item = (C, Decimal)>,
+    ) -> Valuation {
+        let currencies: Vec<_> = balances
+            .into_iter()
+            .map(|(currency, amount)| {
+                let currency = currency.as_ref().to_lowercase();
+                let (price, route) = match self.price_of(&currency) {
+                    Some((price, route)) => (Some(price), route),
+                    None => (None, Vec::new()),
+                };
+                CurrencyValuation {
+                    value: price.map(|price| price * amount),
+                    currency,
+                    amount,
+                    price,
+                    route,
+                }
+            })
+            .collect();
+        Valuation {
+            quote: self.quote.clone(),
+            total: currencies
+                .iter()
+                .filter_map(|valuation| valuation.value)
+                .sum(),
+            currencies,
+        }
+    }
+
+    /// Value the accounts from the REST API, including the locked funds.
+    pub fn value_accounts(&self, accounts: &[RespAccountCurrencyInfo]) -> Valuation {
+        self.value(
+            accounts
+                .iter()
+                .map(|account| (account.currency.as_str(), account.balance + account.locked)),
+        )
+    }
+
+    /// Value the balances from the websocket push, including the locked funds.
+    pub fn value_balances(&self, balances: &[PrivBalanceItem]) -> Valuation {
+        self.value(balances.iter().map(|balance| {
+            (
+                balance.currency.as_str(),
+                balance.available + balance.locked,
+            )
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::test_util::fixtures;
+    use crate::v2::rest::MarketInfo;
+    use rust_decimal_macros::dec;
+
+    fn market(id: &str, base: &str, quote: &str) -> MarketInfo {
+        MarketInfo {
+            id: id.into(),
+            base_unit: base.into(),
+            quote_unit: quote.into(),
+            ..Default::default()
+        }
+    }
+
+    fn ticker(last: Decimal) -> RespTickerInfo {
+        RespTickerInfo {
+            last_price: last,
+            ..serde_json::from_str(fixtures::TICKER).unwrap()
+        }
+    }
+
+    fn valuator(quote: &str) -> PortfolioValuator {
+        let markets = MarketTable::from(vec![
+            market("btctwd", "btc", "twd"),
+            market("usdttwd", "usdt", "twd"),
+            market("dotusdt", "dot", "usdt"),
+            market("maxtwd", "max", "twd"),
+        ]);
+        let tickers: HashMap<Symbol, RespTickerInfo> = vec![
+            (
+                "btctwd".into(),
+                serde_json::from_str(fixtures::TICKER).unwrap(),
+            ),
+            ("usdttwd".into(), ticker(dec!(25))),
+            ("dotusdt".into(), ticker(dec!(5))),
+            // zero price is not usable
+            ("maxtwd".into(), ticker(dec!(0))),
+            // unknown market
+            ("xyztwd".into(), ticker(dec!(1))),
+        ]
+        .into_iter()
+        .collect();
+        PortfolioValuator::new(quote, &markets, &tickers)
+    }
+
+    fn accounts() -> Vec<RespAccountCurrencyInfo> {
+        [
+            ("twd", dec!(900), dec!(100)),
+            ("btc", dec!(0.01), dec!(0)),
+            ("usdt", dec!(60), dec!(40)),
+            ("dot", dec!(10), dec!(0)),
+            ("max", dec!(3), dec!(0)),
+            ("xyz", dec!(5), dec!(0)),
+        ]
+        .iter()
+        .map(|(currency, balance, locked)| RespAccountCurrencyInfo {
+            currency: currency.to_string(),
+            balance: *balance,
+            locked: *locked,
+            ..Default::default()
+        })
+        .collect()
+    }
+
+    #[test]
+    fn value_in_twd() {
+        let valuation = valuator("TWD").value_accounts(&accounts());
+        assert_eq!(valuation.quote, "twd");
+        let values: Vec<_> = valuation
+            .currencies
+            .iter()
+            .map(|valuation| (valuation.currency.as_str(), valuation.value))
+            .collect();
+        assert_eq!(
+            values,
+            [
+                ("twd", Some(dec!(1000))),
+                ("btc", Some(dec!(17380))),
+                ("usdt", Some(dec!(2500))),
+                ("dot", Some(dec!(1250))),
+                ("max", None),
+                ("xyz", None),
+            ]
+        );
+        assert_eq!(valuation.total, dec!(22130));
+        let unpriced: Vec<_> = valuation
+            .unpriced()
+            .map(|valuation| valuation.currency.as_str())
+            .collect();
+        assert_eq!(unpriced, ["max", "xyz"]);
+
+        let dot = &valuation.currencies[3];
+        assert_eq!(dot.price, Some(dec!(125)));
+        assert_eq!(
+            dot.route,
+            [Symbol::from("dotusdt"), Symbol::from("usdttwd")]
+        );
+        assert!(valuation.currencies[0].route.is_empty());
+    }
+
+    #[test]
+    fn value_in_usdt() {
+        let balances: Vec<PrivBalanceItem> = serde_json::from_str(
+            r#"[{"cu":"twd","av":"1000","l":"0"},{"cu":"btc","av":"0.005","l":"0.005"},{"cu":"dot","av":"10","l":"0"}]"#,
+        )
+        .unwrap();
+        let valuation = valuator("usdt").value_balances(&balances);
+        // through the inverse of usdttwd
+        assert_eq!(valuation.currencies[0].price, Some(dec!(0.04)));
+        assert_eq!(valuation.currencies[0].value, Some(dec!(40)));
+        assert_eq!(
+            valuation.currencies[1].route,
+            [Symbol::from("btctwd"), Symbol::from("usdttwd")]
+        );
+        assert_eq!(valuation.currencies[1].value, Some(dec!(695.2)));
+        assert_eq!(valuation.currencies[2].value, Some(dec!(50)));
+        assert_eq!(valuation.total, dec!(785.2));
+        assert_eq!(valuation.unpriced().count(), 0);
+    }
+}
@@ -0,0 +1,306 @@
+//! Positions and PnL per market computed from your trades.
+//!
+//! [`PositionTracker`] takes your trades from the REST API ([`TradeRecord`]) and/or the websocket pushes
+//! ([`PrivTradeRec`]); trades known from both are counted once by the trade id. Prices and PnL are in the quote
+//! currency of the market.
+//!
+//! ```
+//! use maicoin_max::prelude::*;
+//! use maicoin_max::v2::position::PositionTracker;
+//! use maicoin_max::v2::rest::MarketTable;
+//!
+//! let mut tracker = PositionTracker::new(MarketTable::default());
+//! let buy: TradeRecord = serde_json::from_str(r#"{"id":1,"price":"100","volume":"2","funds":"200",
+//!     "market":"btcusdt","market_name":"BTC/USDT","created_at":1700000000,
+//!     "created_at_in_ms":1700000000000,"side":"bid","fee":null,"fee_currency":null,"order_id":1}"#).unwrap();
+//! assert!(tracker.ingest(&buy));
+//! assert!(!tracker.ingest(&buy));
+//! assert_eq!(tracker.unrealized_pnl("btcusdt", Decimal::new(110, 0)), Some(Decimal::new(20, 0)));
+//! ```
+
+use std::collections::{HashMap, HashSet};
+
+use rust_decimal::Decimal;
+
+use crate::common::*;
+use crate::v2::rest::{MarketTable, TradeRecord};
+use crate::v2::ws::feed::PrivTradeRec;
+
+/// Tracker of the positions of all traded markets, see the [module document](self).
+#[derive(Clone, Debug, Default)]
+pub struct PositionTracker {
+    markets: MarketTable,
+    positions: HashMap<Symbol, Position>,
+    trade_ids: HashSet<u64>,
+}
+
+/// Position of a market.
+///
+/// Fees do not change the position size, even if charged in the base currency.
+#[derive(Clone, Eq, PartialEq, Debug, Default)]
+pub struct Position {
+    /// Position size in the base currency, positive for long and negative for short.
+    pub size: Decimal,
+    /// Volume weighted entry price of the open position, zero if flat.
+    pub avg_entry_price: Decimal,
+    /// PnL realized by the reducing trades, excluding fees.
+    pub realized_pnl: Decimal,
+    /// Fees charged in the base or quote currency, in the quote currency.
+    pub fees_in_quote: Decimal,
+    /// Fees charged in the other currencies (e.g. MAX token), by currency.
+    pub other_fees: HashMap<String, Decimal>,
+}
+
+impl Position {
+    /// PnL of the open position at the mark price.
+    pub fn unrealized_pnl(&self, mark_price: Decimal) -> Decimal {
+        self.size * (mark_price - self.avg_entry_price)
+    }
+
+    /// `true` if no position is open.
+    pub fn is_flat(&self) -> bool {
+        self.size.is_zero()
+    }
+
+    fn fill(&mut self, side: TradeSide, price: Decimal, volume: Decimal) {
+        let signed = match side {
+            TradeSide::Bid => volume,
+            TradeSide::Ask => -volume,
+            // self-trades buy and sell the same volume
+            TradeSide::Unknown => return,
+        };
+        // nothing filled, and a flat position would divide by zero below
+        if volume.is_zero() {
+            return;
+        }
+        if self.size.is_zero() || self.size.is_sign_positive() == signed.is_sign_positive() {
+            let size = self.size.abs() + volume;
+            self.avg_entry_price = (self.size.abs() * self.avg_entry_price + volume * price) / size;
+            self.size += signed;
+            return;
+        }
+
+        let closed = self.size.abs().min(volume);
+        let direction = if self.size.is_sign_positive() {
+            Decimal::ONE
+        } else {
+            -Decimal::ONE
+        };
+        self.realized_pnl += closed * (price - self.avg_entry_price) * direction;
+        self.size -= closed * direction;
+        let opened = volume - closed;
+        if !opened.is_zero() {
+            // flipped through zero, the rest opens a position of the other side
+            self.size = opened * -direction;
+            self.avg_entry_price = price;
+        } else if self.size.is_zero() {
+            self.avg_entry_price = Decimal::ZERO;
+        }
+    }
+
+    fn charge(&mut self, fee: Decimal, currency: &str, price: Decimal, pair: Option<(&str, &str)>) {
+        match pair {
+            Some((_, quote)) if quote.eq_ignore_ascii_case(currency) => self.fees_in_quote += fee,
+            Some((base, _)) if base.eq_ignore_ascii_case(currency) => {
+                self.fees_in_quote += fee * price
+            }
+            _ => *self.other_fees.entry(currency.to_lowercase()).or_default() += fee,
+        }
+    }
+}
+
+impl PositionTracker {
+    /// Track positions, with the markets to convert fees in the base currency. Fees of markets not in the table are
+    /// kept in [`Position::other_fees`].
+    pub fn new(markets: MarketTable) -> Self {
+        Self {
+            markets,
+            ..Default::default()
+        }
+    }
+
+    /// Apply one of your trades from the REST API. Returns `false` if the trade was applied already, or lacks the price
+    /// or volume.
+    ///
+    /// Self-trades (side [`TradeSide::Unknown`]) leave the position unchanged, only the fee is counted.
+    pub fn ingest(&mut self, trade: &TradeRecord) -> bool {
+        match (trade.price, trade.volume) {
+            (Some(price), Some(volume)) => self.apply(
+                trade.id,
+                &trade.market,
+                trade.side,
+                price,
+                volume,
+                trade.fee.zip(trade.fee_currency.as_deref()),
+            ),
+            _ => false,
+        }
+    }
+
+    /// Apply one of your trades from the websocket push. Returns `false` if the trade was applied already.
+    pub fn ingest_push(&mut self, trade: &PrivTradeRec) -> bool {
+        self.apply(
+            trade.tid,
+            &trade.market,
+            trade.side.parse().unwrap_or_default(),
+            trade.price,
+            trade.volume,
+            Some((trade.fee, trade.fee_currency.as_str())),
+        )
+    }
+
+    fn apply(
+        &mut self,
+        id: u64,
+        market: &Symbol,
+        side: TradeSide,
+        price: Decimal,
+        volume: Decimal,
+        fee: Option<(Decimal, &str)>,
+    ) -> bool {
+        if !self.trade_ids.insert(id) {
+            return false;
+        }
+        let pair = self.markets.split_symbol(market);
+        let position = self.positions.entry(market.clone()).or_default();
+        position.fill(side, price, volume);
+        if let Some((fee, currency)) = fee.filter(|(fee, _)| !fee.is_zero()) {
+            position.charge(fee, currency, price, pair);
+        }
+        true
+    }
+
+    /// Position of the market, `None` if never traded.
+    pub fn position(&self, market: &str) -> Option<&Position> {
+        self.positions.get(market)
+    }
+
+    /// Positions of all traded markets.
+    pub fn positions(&self) -> impl Iterator<Item = (&Symbol, &Position)> {
+        self.positions.iter()
+    }
+
+    /// PnL of the open position of the market at the mark price, `None` if never traded.
+    pub fn unrealized_pnl(&self, market: &str, mark_price: Decimal) -> Option<Decimal> {
+        self.position(market)
+            .map(|position| position.unrealized_pnl(mark_price))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::v2::rest::MarketInfo;
+    use rust_decimal_macros::dec;
+    use serde_json::json;
+
+    fn tracker() -> PositionTracker {
+        PositionTracker::new(MarketTable::from(vec![MarketInfo {
+            id: "btcusdt".into(),
+            base_unit: "btc".into(),
+            quote_unit: "usdt".into(),
+            ..Default::default()
+        }]))
+    }
+
+    fn trade(id: u64, side: &str, price: &str, volume: &str, fee: (&str, &str)) -> TradeRecord {
+        serde_json::from_value(json!({
+            "id": id,
+            "price": price,
+            "volume": volume,
+            "funds": null,
+            "market": "btcusdt",
+            "market_name": "BTC/USDT",
+            "created_at": 1700000000,
+            "created_at_in_ms": 1700000000000_i64,
+            "side": side,
+            "fee": fee.0,
+            "fee_currency": fee.1,
+            "order_id": id,
+        }))
+        .unwrap()
+    }
+
+    fn push(tid: u64, side: &str, price: &str, volume: &str) -> PrivTradeRec {
+        serde_json::from_value(json!({
+            "i": tid, "p": price, "v": volume, "M": "btcusdt", "T": 1700000000000_i64, "sd": side,
+            "f": "0", "fc": "usdt", "m": false
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn long_position() {
+        let mut tracker = tracker();
+        assert!(tracker.ingest(&trade(1, "bid", "100", "1", ("0", "btc"))));
+        assert!(tracker.ingest(&trade(2, "bid", "200", "1", ("0", "btc"))));
+        let position = tracker.position("btcusdt").unwrap();
+        assert_eq!(position.size, dec!(2));
+        assert_eq!(position.avg_entry_price, dec!(150));
+
+        // reducing keeps the entry price
+        assert!(tracker.ingest_push(&push(3, "ask", "210", "0.5")));
+        let position = tracker.position("btcusdt").unwrap();
+        assert_eq!(position.size, dec!(1.5));
+        assert_eq!(position.avg_entry_price, dec!(150));
+        assert_eq!(position.realized_pnl, dec!(30));
+        assert_eq!(tracker.unrealized_pnl("btcusdt", dec!(160)), Some(dec!(15)));
+        assert_eq!(tracker.unrealized_pnl("ethusdt", dec!(160)), None);
+
+        // the same trade from both REST and websocket
+        assert!(!tracker.ingest(&trade(3, "ask", "210", "0.5", ("0", "usdt"))));
+        assert_eq!(tracker.position("btcusdt").unwrap().size, dec!(1.5));
+    }
+
+    #[test]
+    fn flip_through_zero() {
+        let mut tracker = tracker();
+        tracker.ingest(&trade(1, "bid", "150", "1.5", ("0", "btc")));
+        // close 1.5 at a loss of 10 each, then open a 0.5 short
+        tracker.ingest(&trade(2, "ask", "140", "2", ("0", "usdt")));
+        let position = tracker.position("btcusdt").unwrap();
+        assert_eq!(position.realized_pnl, dec!(-15));
+        assert_eq!(position.size, dec!(-0.5));
+        assert_eq!(position.avg_entry_price, dec!(140));
+        assert_eq!(position.unrealized_pnl(dec!(130)), dec!(5));
+
+        // add to the short, then cover it all
+        tracker.ingest(&trade(3, "ask", "110", "0.5", ("0", "usdt")));
+        let position = tracker.position("btcusdt").unwrap();
+        assert_eq!(position.size, dec!(-1));
+        assert_eq!(position.avg_entry_price, dec!(125));
+        tracker.ingest(&trade(4, "bid", "120", "1", ("0", "btc")));
+        let position = tracker.position("btcusdt").unwrap();
+        assert_eq!(position.realized_pnl, dec!(-10));
+        assert!(position.is_flat());
+        assert_eq!(position.avg_entry_price, dec!(0));
+
+        // zero volume leaves the flat position as is
+        assert!(tracker.ingest(&trade(5, "bid", "120", "0", ("0", "btc"))));
+        let position = tracker.position("btcusdt").unwrap();
+        assert!(position.is_flat());
+        assert_eq!(position.avg_entry_price, dec!(0));
+    }
+
+    #[test]
+    fn fees_and_self_trades() {
+        let mut tracker = tracker();
+        tracker.ingest(&trade(1, "bid", "100", "1", ("0.001", "btc")));
+        tracker.ingest(&trade(2, "ask", "120", "0.5", ("0.12", "usdt")));
+        tracker.ingest(&trade(3, "ask", "120", "0.2", ("5", "MAX")));
+        // self-trades only pay the fee
+        tracker.ingest(&trade(4, "self-trade", "130", "0.3", ("0.039", "usdt")));
+        let position = tracker.position("btcusdt").unwrap();
+        assert_eq!(position.size, dec!(0.3));
+        assert_eq!(position.realized_pnl, dec!(14));
+        assert_eq!(position.fees_in_quote, dec!(0.259));
+        assert_eq!(position.other_fees.get("max"), Some(&dec!(5)));
+
+        // fees of unknown markets are not converted
+        let mut tracker = PositionTracker::default();
+        tracker.ingest(&trade(1, "bid", "100", "1", ("0.001", "btc")));
+        let position = tracker.position("btcusdt").unwrap();
+        assert_eq!(position.fees_in_quote, dec!(0));
+        assert_eq!(position.other_fees.get("btc"), Some(&dec!(0.001)));
+    }
+}
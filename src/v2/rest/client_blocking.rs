@@ -0,0 +1,251 @@
+//! Synchronous client built on [ureq](https://crates.io/crates/ureq), enabled by `blocking` feature.
+//!
+//! Requests are created and responses are parsed by the same code as the asynchronous API, which are driven to
+//! completion in place. No async runtime is required.
+//!
+//! ```ignore
+//! let client = BlockingClient::new();
+//! let currencies: Vec<CurrencyInfo> = client.call(&GetCurrencies {})?;
+//! let orders: Vec<RespOrder> = client.call_auth(&GetOrders::for_market("btctwd").build(), &credentials)?;
+//! ```
+
+use std::convert::TryFrom;
+use std::io::Read;
+
+use futures_executor::block_on;
+use http_types::{Request as HTTPRequest, Response as HTTPResponse, StatusCode};
+
+use crate::error::*;
+use crate::v2::rest::{ApiHost, AuthEndpoint, Endpoint, PublicEndpoint};
+use crate::Credentials;
+
+fn transport_error(err: impl Into<anyhow::Error>) -> Error {
+    Error::Transport(Box::new(err.into()))
+}
+
+/// Synchronous HTTP client to call API endpoints.
+#[derive(Debug)]
+pub struct BlockingClient {
+    agent: ureq::Agent,
+    host: ApiHost,
+}
+
+impl Default for BlockingClient {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl BlockingClient {
+    /// Create the client to [`crate::v2::rest::BASE_URL`].
+    pub fn new() -> Self {
+        Self::with_agent(ureq::Agent::new())
+    }
+
+    /// Create the client with the given agent, e.g. one with timeouts or proxy configured.
+    pub fn with_agent(agent: ureq::Agent) -> Self {
+        Self {
+            agent,
+            host: ApiHost::default(),
+        }
+    }
+
+    /// Send requests to the given API host.
+    pub fn with_host(mut self, host: ApiHost) -> Self {
+        self.host = host;
+        self
+    }
+
+    /// Send the request of public endpoint and parse the response.
+    pub fn call<E: PublicEndpoint>(&self, params: &E) -> Result<E::Response> {
+        self.send::<E>(params.try_prepare_with(&self.host, None)?)
+    }
+
+    /// Sign and send the request of authenticated endpoint, then parse the response.
+    pub fn call_auth<E: AuthEndpoint>(
+        &self,
+        params: &E,
+        credentials: &Credentials,
+    ) -> Result<E::Response> {
+        self.send::<E>(params.try_prepare_with(&self.host, Some(credentials))?)
+    }
+
+    /// Send the request and return the response regardless of its status.
+    pub fn execute(&self, mut req: HTTPRequest) -> Result<HTTPResponse> {
+        let mut builder = self
+            .agent
+            .request(req.method().as_ref(), req.url().as_str());
+        for (name, values) in req.iter() {
+            for value in values.iter() {
+                builder = builder.set(name.as_str(), value.as_str());
+            }
+        }
+        let body = block_on(req.take_body().into_bytes())
+            .map_err(|err| transport_error(err.into_inner()))?;
+
+        // error statuses are replied as is, to be parsed as API errors
+        let resp = match builder.send_bytes(&body) {
+            Ok(resp) | Err(ureq::Error::Status(_, resp)) => resp,
+            Err(err) => return Err(transport_error(err)),
+        };
+        from_ureq_response(resp)
+    }
+
+    fn send<E: Endpoint>(&self, req: HTTPRequest) -> Result<E::Response> {
+        let resp = self.execute(req)?;
+        block_on(E::read_response(resp))
+    }
+}
+
+fn from_ureq_response(resp: ureq::Response) -> Result<HTTPResponse> {
+    let status =
+        StatusCode::try_from(resp.status()).map_err(|err| transport_error(err.into_inner()))?;
+    let headers: Vec<(String, String)> = resp
+        .headers_names()
+        .into_iter()
+        .flat_map(|name| {
+            resp.all(&name)
+                .into_iter()
+                .map(|value| (name.clone(), value.to_owned()))
+                .collect::<Vec<_>>()
+        })
+        .collect();
+    let mut body = Vec::new();
+    resp.into_reader()
+        .read_to_end(&mut body)
+        .map_err(transport_error)?;
+
+    let mut converted = HTTPResponse::new(status);
+    // setting body overrides Content-Type, which is restored with other headers below
+    converted.set_body(body);
+    converted.remove_header("Content-Type");
+    for (name, value) in headers.iter() {
+        converted.append_header(name.as_str(), value.as_str());
+    }
+    Ok(converted)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::test_util::fixtures;
+    use crate::v2::rest::internal::{
+        HEADER_AUTH_ACCESS_KEY, HEADER_AUTH_PAYLOAD, HEADER_AUTH_SIGNATURE,
+    };
+    use crate::v2::rest::{DeleteOrder, GetOrder, GetTimestamp};
+    use hmac::{Hmac, Mac, NewMac};
+    use sha2::Sha256;
+    use std::collections::HashMap;
+    use std::io::{BufRead, BufReader, Write};
+    use std::net::TcpListener;
+    use std::thread::{self, JoinHandle};
+
+    // Received request line, headers (in lowercase names) and body.
+    type Received = (String, HashMap<String, String>, Vec<u8>);
+
+    // Serve a single request with the canned response on a local port.
+    fn mock_server(status: u16, body: &'static str) -> (ApiHost, JoinHandle<Received>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let host = ApiHost::parse(&format!("http://{}", listener.local_addr().unwrap())).unwrap();
+        let handle = thread::spawn(move || {
+            let (stream, _) = listener.accept().unwrap();
+            let mut reader = BufReader::new(stream.try_clone().unwrap());
+            let mut request_line = String::new();
+            reader.read_line(&mut request_line).unwrap();
+            let mut headers = HashMap::new();
+            loop {
+                let mut line = String::new();
+                reader.read_line(&mut line).unwrap();
+                match line.trim_end().split_once(": ") {
+                    Some((name, value)) => {
+                        headers.insert(name.to_lowercase(), value.to_owned());
+                    }
+                    None => break,
+                }
+            }
+            let len = headers
+                .get("content-length")
+                .map_or(0, |len| len.parse().unwrap());
+            let mut req_body = vec![0; len];
+            reader.read_exact(&mut req_body).unwrap();
+
+            let mut stream = stream;
+            write!(
+                stream,
+                "HTTP/1.1 {} MOCK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                status,
+                body.len(),
+                body
+            )
+            .unwrap();
+            (request_line.trim_end().to_owned(), headers, req_body)
+        });
+        (host, handle)
+    }
+
+    #[test]
+    fn call_public() {
+        let (host, server) = mock_server(200, "1636258261");
+        let client = BlockingClient::new().with_host(host);
+        let ts = client.call(&GetTimestamp {}).unwrap();
+        assert_eq!(ts.0, 1636258261);
+
+        let (request_line, _, _) = server.join().unwrap();
+        assert_eq!(request_line, "GET /api/v2/timestamp HTTP/1.1");
+    }
+
+    #[test]
+    fn call_auth_get() {
+        let (host, server) = mock_server(200, fixtures::ORDER);
+        let client = BlockingClient::new().with_host(host);
+        let credentials = Credentials::new("api key".into(), "api secret".into());
+        let params = GetOrder {
+            id: Some(1545763894),
+            client_oid: None,
+        };
+        let order = client.call_auth(&params, &credentials).unwrap();
+        assert_eq!(order.id, Some(1545763894));
+
+        // signed like the asynchronous API: the query carries the parameters and nonce of the signed payload
+        let (request_line, headers, _) = server.join().unwrap();
+        assert_eq!(headers[&HEADER_AUTH_ACCESS_KEY.to_lowercase()], "api key");
+        let payload = &headers[&HEADER_AUTH_PAYLOAD.to_lowercase()];
+        let signed: serde_json::Value =
+            serde_json::from_slice(&base64::decode(payload).unwrap()).unwrap();
+        assert_eq!(signed["path"], "/api/v2/order");
+        assert_eq!(signed["id"], 1545763894);
+        assert_eq!(
+            request_line,
+            format!(
+                "GET /api/v2/order?id=1545763894&nonce={} HTTP/1.1",
+                signed["nonce"]
+            )
+        );
+
+        let mut hmac = Hmac::<Sha256>::new_from_slice(b"api secret").unwrap();
+        hmac.update(payload.as_bytes());
+        assert_eq!(
+            headers[&HEADER_AUTH_SIGNATURE.to_lowercase()],
+            format!("{:x}", hmac.finalize().into_bytes())
+        );
+    }
+
+    #[test]
+    fn call_auth_error() {
+        let (host, server) = mock_server(404, fixtures::ERROR_ORDER_NOT_FOUND);
+        let client = BlockingClient::new().with_host(host);
+        let credentials = Credentials::new("api key".into(), "api secret".into());
+        let params = DeleteOrder {
+            id: Some(1),
+            client_oid: None,
+        };
+        let err = client.call_auth(&params, &credentials).unwrap_err();
+        assert!(err.is_order_not_found());
+
+        let (request_line, headers, body) = server.join().unwrap();
+        assert_eq!(request_line, "POST /api/v2/order/delete HTTP/1.1");
+        let body: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(body["id"], 1);
+        assert!(headers.contains_key(&HEADER_AUTH_SIGNATURE.to_lowercase()));
+    }
+}
@@ -0,0 +1,159 @@
+//! Conversions between `http_types` and [reqwest](https://crates.io/crates/reqwest), enabled by `reqwest` feature.
+//!
+//! ```ignore
+//! let client = reqwest::Client::new();
+//! let req = to_reqwest(&client, GetOrders::for_market("btctwd").build().to_request(&credentials)).await?;
+//! let resp = from_reqwest_response(client.execute(req).await?).await?;
+//! let orders: Vec<RespOrder> = GetOrders::read_response(resp).await?;
+//! ```
+
+use std::convert::TryFrom;
+
+use http_types::{Request as HTTPRequest, Response as HTTPResponse, StatusCode};
+
+use crate::error::*;
+
+fn transport_error(err: impl Into<anyhow::Error>) -> Error {
+    Error::Transport(Box::new(err.into()))
+}
+
+/// Convert a prepared request into `reqwest::Request`, keeping method, URL, headers and body as-is.
+pub async fn to_reqwest(
+    client: &reqwest::Client,
+    mut req: HTTPRequest,
+) -> Result<reqwest::Request> {
+    let method = reqwest::Method::from_bytes(req.method().to_string().as_bytes())
+        .map_err(transport_error)?;
+    let mut builder = client.request(method, req.url().as_str());
+    for (name, values) in req.iter() {
+        for value in values.iter() {
+            builder = builder.header(name.as_str(), value.as_str());
+        }
+    }
+    let body = req
+        .take_body()
+        .into_bytes()
+        .await
+        .map_err(|err| transport_error(err.into_inner()))?;
+    if !body.is_empty() {
+        builder = builder.body(body);
+    }
+    builder.build().map_err(transport_error)
+}
+
+/// Convert a `reqwest::Response` into `http_types::Response`, so `read_response` of endpoints can parse it.
+pub async fn from_reqwest_response(resp: reqwest::Response) -> Result<HTTPResponse> {
+    let status = StatusCode::try_from(resp.status().as_u16())
+        .map_err(|err| transport_error(err.into_inner()))?;
+    let headers = resp.headers().clone();
+    let body = resp.bytes().await.map_err(transport_error)?;
+
+    let mut converted = HTTPResponse::new(status);
+    // setting body overrides Content-Type, which is restored with other headers below
+    converted.set_body(body.to_vec());
+    converted.remove_header("Content-Type");
+    for (name, value) in headers.iter() {
+        let value = value.to_str().map_err(transport_error)?;
+        converted.append_header(name.as_str(), value);
+    }
+    Ok(converted)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::OrderSide;
+    use crate::util::test_util::TEST_CREDENTIALS;
+    use crate::v2::rest::internal::{
+        HEADER_AUTH_ACCESS_KEY, HEADER_AUTH_PAYLOAD, HEADER_AUTH_SIGNATURE,
+    };
+    use crate::v2::rest::{CreateOrder, GetTimestamp, OrderType, RespTimestamp};
+    use rust_decimal_macros::dec;
+    use serde_json::Value as JsonValue;
+
+    #[tokio::test]
+    async fn signed_post_round_trip() {
+        let params = CreateOrder {
+            market: "maxtwd".into(),
+            side: OrderSide::Buy,
+            volume: dec!(100),
+            price: Some(dec!(9.5)),
+            client_oid: None,
+            stop_price: None,
+            ord_type: OrderType::Limit,
+            group_id: None,
+        };
+        let orig = params.to_request(&TEST_CREDENTIALS);
+        let orig_url = orig.url().clone();
+        let orig_headers: Vec<(&str, Vec<u8>)> = [
+            HEADER_AUTH_ACCESS_KEY,
+            HEADER_AUTH_PAYLOAD,
+            HEADER_AUTH_SIGNATURE,
+            "Content-Type",
+        ]
+        .iter()
+        .map(|name| (*name, orig[*name].as_str().as_bytes().to_vec()))
+        .collect();
+
+        let converted = to_reqwest(&reqwest::Client::new(), orig).await.unwrap();
+        assert_eq!(converted.method(), reqwest::Method::POST);
+        assert_eq!(converted.url().as_str(), orig_url.as_str());
+        for (name, value) in orig_headers.iter() {
+            let converted_values: Vec<&[u8]> = converted
+                .headers()
+                .get_all(*name)
+                .iter()
+                .map(|v| v.as_bytes())
+                .collect();
+            assert_eq!(converted_values, vec![value.as_slice()], "header {}", name);
+        }
+
+        // body is the signed payload without path
+        let mut payload: JsonValue =
+            serde_json::from_slice(&base64::decode(&orig_headers[1].1).unwrap()).unwrap();
+        payload.as_object_mut().unwrap().remove("path");
+        let body: JsonValue =
+            serde_json::from_slice(converted.body().unwrap().as_bytes().unwrap()).unwrap();
+        assert_eq!(body, payload);
+    }
+
+    #[tokio::test]
+    async fn response_round_trip() {
+        let raw = http::Response::builder()
+            .status(200)
+            .header("Content-Type", "application/json; charset=utf-8")
+            .header("X-Request-Id", "1-618751d5-082d0fed2b7e78345969f0f9")
+            .body("1636258261")
+            .unwrap();
+        let resp = from_reqwest_response(reqwest::Response::from(raw))
+            .await
+            .unwrap();
+        assert_eq!(resp.status(), StatusCode::Ok);
+        assert_eq!(
+            resp["Content-Type"].as_str(),
+            "application/json; charset=utf-8"
+        );
+        assert_eq!(
+            resp["X-Request-Id"].as_str(),
+            "1-618751d5-082d0fed2b7e78345969f0f9"
+        );
+        let ts: RespTimestamp = GetTimestamp::read_response(resp).await.unwrap();
+        assert_eq!(ts.0, 1636258261);
+    }
+
+    #[tokio::test]
+    async fn maintenance_response() {
+        let raw = http::Response::builder()
+            .status(503)
+            .header("Content-Type", "text/html")
+            .body("<html>maintenance</html>")
+            .unwrap();
+        let resp = from_reqwest_response(reqwest::Response::from(raw))
+            .await
+            .unwrap();
+        match GetTimestamp::read_response(resp).await {
+            Err(Error::Maintenance(status)) => assert_eq!(status, 503),
+            other => panic!("expect maintenance error, got {:?}", other),
+        }
+    }
+}
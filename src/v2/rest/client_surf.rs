@@ -0,0 +1,135 @@
+//! Extension traits to call API endpoints with [Surf](https://crates.io/crates/surf), enabled by `client-surf` feature.
+
+use std::future::Future;
+use std::pin::Pin;
+
+use crate::error::*;
+use crate::v2::rest::{
+    execute, execute_auth, AuthEndpoint, ExecuteFuture, HttpExecutor, PublicEndpoint,
+};
+use crate::Credentials;
+
+/// Future of calling an API endpoint, see [`SurfPublicExt`] and [`SurfAuthExt`].
+pub type FetchFuture<'a, T> = Pin<Box<dyn Future<Output = Result<T>> + Send + 'a>>;
+
+/// Call public endpoints with a Surf client.
+///
+/// ```ignore
+/// let currencies: Vec<CurrencyInfo> = GetCurrencies {}.fetch(&client).await?;
+/// ```
+pub trait SurfPublicExt: PublicEndpoint {
+    /// Send the request to [`crate::v2::rest::BASE_URL`] and parse the response.
+    fn fetch<'a>(&'a self, client: &'a surf::Client) -> FetchFuture<'a, Self::Response>;
+}
+
+/// Call authenticated endpoints with a Surf client.
+///
+/// ```ignore
+/// let orders: Vec<RespOrder> = GetOrders::for_market("btctwd").build().fetch(&client, &credentials).await?;
+/// ```
+pub trait SurfAuthExt: AuthEndpoint {
+    /// Sign and send the request to [`crate::v2::rest::BASE_URL`], then parse the response.
+    fn fetch<'a>(
+        &'a self,
+        client: &'a surf::Client,
+        credentials: &'a Credentials,
+    ) -> FetchFuture<'a, Self::Response>;
+}
+
+impl<E> SurfPublicExt for E
+where
+    E: PublicEndpoint + Sync,
+    E::Response: Send,
+{
+    fn fetch<'a>(&'a self, client: &'a surf::Client) -> FetchFuture<'a, Self::Response> {
+        Box::pin(execute(client, self))
+    }
+}
+
+impl<E> SurfAuthExt for E
+where
+    E: AuthEndpoint + Sync,
+    E::Response: Send,
+{
+    fn fetch<'a>(
+        &'a self,
+        client: &'a surf::Client,
+        credentials: &'a Credentials,
+    ) -> FetchFuture<'a, Self::Response> {
+        Box::pin(execute_auth(client, self, credentials))
+    }
+}
+
+impl HttpExecutor for surf::Client {
+    fn execute(&self, req: http_types::Request) -> ExecuteFuture<'_> {
+        Box::pin(async move {
+            let resp = self.send(req).await?;
+            Ok(resp.into())
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::OrderSide;
+    use crate::util::test_util::*;
+    use crate::v2::rest::{
+        CurrencyInfo, GetCurrencies, GetMyTrades, GetOrder, GetTimestamp, RespOrder, TradeRecord,
+    };
+    use chrono::{TimeZone, Utc};
+    use surf_vcr::VcrMode;
+
+    async fn create_client(cassette: &str) -> surf::Client {
+        let mut path_builder = test_resource_path();
+        path_builder.extend(cassette.split('/'));
+        create_test_recording_client(VcrMode::Replay, path_builder.as_path().to_str().unwrap())
+            .await
+    }
+
+    #[async_std::test]
+    async fn fetch_public() {
+        let client = create_client("rest/public/misc/get_currencies.yaml").await;
+        let currencies: Vec<CurrencyInfo> = GetCurrencies {}.fetch(&client).await.unwrap();
+        assert_eq!(currencies[0].id, "twd");
+
+        let client = create_client("rest/public/misc/get_timestamp.yaml").await;
+        let ts = GetTimestamp {}.fetch(&client).await.unwrap();
+        assert_eq!(ts.0, 1636258261);
+    }
+
+    #[async_std::test]
+    async fn fetch_auth() {
+        let client = create_client("rest/private/order/get_single_order.yaml").await;
+        let params = GetOrder {
+            id: Some(1545763894),
+            client_oid: None,
+        };
+        let order: RespOrder = params.fetch(&client, &TEST_CREDENTIALS).await.unwrap();
+        assert_eq!(order.id, Some(1545763894));
+        assert_eq!(order.side, OrderSide::Buy);
+
+        let client = create_client("rest/private/trade/get_all_trades.yaml").await;
+        let params = GetMyTrades {
+            market: "dotusdt".into(),
+            timestamp_before: Some(Utc.timestamp_opt(1635854000, 0).unwrap()),
+            after_order_id: Some(29009000),
+            before_order_id: None,
+            order_by: None,
+            pagination: None,
+            page_params: None,
+            offset: None,
+        };
+        let trades: Vec<TradeRecord> = params.fetch(&client, &TEST_CREDENTIALS).await.unwrap();
+        assert_eq!(trades.len(), 1);
+    }
+
+    #[async_std::test]
+    async fn fetch_maintenance() {
+        let client = create_client("rest/public/misc/get_timestamp_maintenance.yaml").await;
+        match (GetTimestamp {}).fetch(&client).await {
+            Err(Error::Maintenance(status)) => assert_eq!(status, 503),
+            other => panic!("expect maintenance error, got {:?}", other),
+        }
+    }
+}
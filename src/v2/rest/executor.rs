@@ -0,0 +1,434 @@
+//! Abstraction over HTTP clients, to call API endpoints without depending on a specific client crate.
+//!
+//! ```ignore
+//! struct MyClient(...);
+//!
+//! impl HttpExecutor for MyClient {
+//!     fn execute(&self, req: http_types::Request) -> ExecuteFuture<'_> {
+//!         Box::pin(async move { ...(send the request)... })
+//!     }
+//! }
+//!
+//! let currencies: Vec<CurrencyInfo> = execute(&client, &GetCurrencies {}).await?;
+//! let orders: Vec<RespOrder> = execute_auth(&client, &GetOrders::for_market("btctwd").build(), &credentials).await?;
+//! ```
+
+use std::future::Future;
+use std::pin::Pin;
+
+use http_types::{Request as HTTPRequest, Response as HTTPResponse};
+
+use crate::error::*;
+use crate::v2::rest::internal::signed_nonce;
+use crate::v2::rest::{AuthEndpoint, Endpoint, PublicEndpoint};
+#[cfg(feature = "timer")]
+use crate::v2::rest::{CreateOrder, GetOrder, RespOrder};
+use crate::Credentials;
+
+/// Authenticated requests waiting longer than this before sending are warned with the `tracing` feature, as their
+/// nonces are getting close to the time window allowed by the server.
+pub const STALE_REQUEST_MS: u64 = 5_000;
+
+/// Future of sending a request, see [`HttpExecutor::execute`].
+pub type ExecuteFuture<'a> = Pin<Box<dyn Future<Output = Result<HTTPResponse>> + Send + 'a>>;
+
+/// HTTP client which sends `http_types` requests. Implemented for `surf::Client` with `client-surf` feature.
+pub trait HttpExecutor {
+    /// Send the request and return the response regardless of its status. Failures of sending should be reported as
+    /// [`Error::Transport`].
+    fn execute(&self, req: HTTPRequest) -> ExecuteFuture<'_>;
+}
+
+impl<T: HttpExecutor + ?Sized> HttpExecutor for &T {
+    fn execute(&self, req: HTTPRequest) -> ExecuteFuture<'_> {
+        (**self).execute(req)
+    }
+}
+
+/// Send the request of public endpoint to [`crate::v2::rest::BASE_URL`] and parse the response.
+pub async fn execute<X, E>(executor: &X, params: &E) -> Result<E::Response>
+where
+    X: HttpExecutor + ?Sized,
+    E: PublicEndpoint,
+{
+    send_request::<X, E>(executor, params.try_prepare(None)?).await
+}
+
+/// Sign and send the request of authenticated endpoint to [`crate::v2::rest::BASE_URL`], then parse the response.
+pub async fn execute_auth<X, E>(
+    executor: &X,
+    params: &E,
+    credentials: &Credentials,
+) -> Result<E::Response>
+where
+    X: HttpExecutor + ?Sized,
+    E: AuthEndpoint,
+{
+    send_request::<X, E>(executor, params.try_prepare(Some(credentials))?).await
+}
+
+/// Milliseconds since the nonce of the authenticated request was allocated, or `None` for requests without a signed
+/// nonce. Requests should be sent as soon as they are built, check this before sending ones that were queued.
+pub fn request_age_ms(req: &HTTPRequest) -> Option<u64> {
+    signed_nonce(req).map(Credentials::age_of_nonce_ms)
+}
+
+/// Place the order, then poll it every `poll_interval` until it is closed, see [`OrderState::is_terminal`]. Enabled
+/// by `timer` feature.
+///
+/// The order is polled at most `max_polls` times. The last polled order is returned even if it is still open, so check
+/// its state to tell whether it is closed in time, e.g. to cancel it otherwise.
+///
+/// ```ignore
+/// let order = place_and_await(&client, &credentials, &order, Duration::from_secs(1), 30).await?;
+/// if !order.state.is_terminal() {
+///     execute_auth(&client, &DeleteOrder::by_id(order.id.unwrap()), &credentials).await?;
+/// }
+/// ```
+///
+/// [`OrderState::is_terminal`]: crate::v2::rest::OrderState::is_terminal
+#[cfg(feature = "timer")]
+pub async fn place_and_await<X>(
+    executor: &X,
+    credentials: &Credentials,
+    order: &CreateOrder,
+    poll_interval: std::time::Duration,
+    max_polls: u32,
+) -> Result<RespOrder>
+where
+    X: HttpExecutor + ?Sized,
+{
+    let mut placed = execute_auth(executor, order, credentials).await?;
+    let query = match (placed.id, &order.client_oid) {
+        (Some(id), _) => GetOrder {
+            id: Some(id),
+            client_oid: None,
+        },
+        (None, Some(client_oid)) => GetOrder {
+            id: None,
+            client_oid: Some(client_oid.clone()),
+        },
+        (None, None) => {
+            return Err(Error::ReadResponse(Box::new(anyhow::anyhow!(
+                "response of the placed order has no id to poll"
+            ))))
+        }
+    };
+    for _ in 0..max_polls {
+        if placed.state.is_terminal() {
+            break;
+        }
+        futures_timer::Delay::new(poll_interval).await;
+        placed = execute_auth(executor, &query, credentials).await?;
+    }
+    Ok(placed)
+}
+
+async fn send_request<X, E>(executor: &X, req: HTTPRequest) -> Result<E::Response>
+where
+    X: HttpExecutor + ?Sized,
+    E: Endpoint,
+{
+    #[cfg(feature = "tracing")]
+    warn_if_stale::<E>(&req);
+    let resp = executor.execute(req).await?;
+    E::read_response(resp).await
+}
+
+#[cfg(feature = "tracing")]
+fn warn_if_stale<E>(req: &HTTPRequest) {
+    if let Some(age_ms) = request_age_ms(req).filter(|age_ms| *age_ms > STALE_REQUEST_MS) {
+        tracing::warn!(
+            endpoint = std::any::type_name::<E>(),
+            age_ms,
+            "sending stale request"
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::v2::rest::internal::{HEADER_AUTH_ACCESS_KEY, HEADER_AUTH_SIGNATURE};
+    use crate::v2::rest::{GetOrder, GetTimestamp};
+    use http_types::{Method as HTTPMethod, StatusCode};
+    use std::sync::Mutex;
+
+    // Reply the canned response, and keep the requests for inspection.
+    struct MockExecutor {
+        status: StatusCode,
+        body: &'static str,
+        requests: Mutex<Vec<HTTPRequest>>,
+    }
+
+    impl MockExecutor {
+        fn new(status: StatusCode, body: &'static str) -> Self {
+            Self {
+                status,
+                body,
+                requests: Mutex::new(Vec::new()),
+            }
+        }
+    }
+
+    impl HttpExecutor for MockExecutor {
+        fn execute(&self, req: HTTPRequest) -> ExecuteFuture<'_> {
+            self.requests.lock().unwrap().push(req);
+            let mut resp = HTTPResponse::new(self.status);
+            resp.set_body(self.body);
+            Box::pin(async move { Ok(resp) })
+        }
+    }
+
+    #[async_std::test]
+    async fn execute_public() {
+        let executor = MockExecutor::new(StatusCode::Ok, "1636258261");
+        let ts = execute(&executor, &GetTimestamp {}).await.unwrap();
+        assert_eq!(ts.0, 1636258261);
+
+        let requests = executor.requests.lock().unwrap();
+        assert_eq!(requests.len(), 1);
+        assert_eq!(requests[0].method(), HTTPMethod::Get);
+        assert_eq!(requests[0].url().path(), "/api/v2/timestamp");
+        assert!(requests[0].header(HEADER_AUTH_SIGNATURE).is_none());
+    }
+
+    #[async_std::test]
+    async fn execute_signed() {
+        let executor = MockExecutor::new(
+            StatusCode::NotFound,
+            r#"{"error":{"code":2004,"message":"Order#1 not found."}}"#,
+        );
+        let credentials = Credentials::new("api key".into(), "api secret".into());
+        let params = GetOrder {
+            id: Some(1),
+            client_oid: None,
+        };
+        let err = execute_auth(&&executor, &params, &credentials)
+            .await
+            .unwrap_err();
+        assert!(err.is_order_not_found());
+
+        let requests = executor.requests.lock().unwrap();
+        assert_eq!(requests[0].url().path(), "/api/v2/order");
+        assert_eq!(requests[0][HEADER_AUTH_ACCESS_KEY].as_str(), "api key");
+        assert!(requests[0].header(HEADER_AUTH_SIGNATURE).is_some());
+    }
+
+    #[async_std::test]
+    async fn execute_transport_error() {
+        struct Unreachable;
+        impl HttpExecutor for Unreachable {
+            fn execute(&self, _req: HTTPRequest) -> ExecuteFuture<'_> {
+                Box::pin(async {
+                    Err(Error::Transport(Box::new(anyhow::anyhow!(
+                        "connection refused"
+                    ))))
+                })
+            }
+        }
+
+        match execute(&Unreachable, &GetTimestamp {}).await {
+            Err(Error::Transport(_)) => {}
+            other => panic!("expect transport error, got {:?}", other),
+        }
+    }
+
+    #[async_std::test]
+    async fn request_age() {
+        let credentials = Credentials::new("api key".into(), "api secret".into());
+        let params = GetOrder {
+            id: Some(1),
+            client_oid: None,
+        };
+        let req = params.to_request(&credentials);
+        assert!(request_age_ms(&req).unwrap() < STALE_REQUEST_MS);
+        assert_eq!(request_age_ms(&GetTimestamp {}.to_request()), None);
+
+        // the request is delayed before sending
+        async_std::task::sleep(std::time::Duration::from_millis(30)).await;
+        assert!(request_age_ms(&req).unwrap() >= 30);
+        assert!(credentials.age_of_last_nonce_ms() >= 30);
+        let fresh = params.to_request(&credentials);
+        assert!(request_age_ms(&fresh).unwrap() < request_age_ms(&req).unwrap());
+        assert!(credentials.age_of_last_nonce_ms() < 30);
+
+        let stale_nonce = chrono::Utc::now().timestamp_millis() as u64 - 60_000;
+        let stale = params
+            .try_to_request_with_nonce(&Default::default(), &credentials, stale_nonce)
+            .unwrap();
+        assert!(request_age_ms(&stale).unwrap() >= 60_000);
+    }
+
+    #[cfg(feature = "tracing")]
+    #[test]
+    fn warn_stale_request() {
+        use crate::util::test_util::capture_tracing_events;
+
+        let credentials = Credentials::new("api key".into(), "api secret".into());
+        let params = GetOrder {
+            id: Some(1),
+            client_oid: None,
+        };
+        let fresh = params.to_request(&credentials);
+        let stale = params
+            .try_to_request_with_nonce(
+                &Default::default(),
+                &credentials,
+                credentials.nonce() - 60_000,
+            )
+            .unwrap();
+        let (_, events) = capture_tracing_events(|| {
+            warn_if_stale::<GetOrder>(&fresh);
+            warn_if_stale::<GetOrder>(&stale);
+        });
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0]["message"], "sending stale request");
+    }
+
+    #[cfg(feature = "timer")]
+    mod await_order {
+        use super::*;
+        use crate::common::OrderSide;
+        use crate::util::test_util::{test_resource_path, TEST_CREDENTIALS};
+        use crate::v2::rest::{CreateOrder, OrderType};
+        use rust_decimal_macros::dec;
+        use std::collections::VecDeque;
+        use std::convert::TryFrom;
+        use std::time::Duration;
+
+        // Reply the responses of the cassette in the recorded order, unlike surf-vcr which always replies the first
+        // matching one, so that polling the same order gets its updates.
+        struct SequentialReplay {
+            responses: Mutex<VecDeque<HTTPResponse>>,
+            requests: Mutex<Vec<HTTPRequest>>,
+        }
+
+        impl SequentialReplay {
+            fn new(cassette: &str) -> Self {
+                let mut path = test_resource_path();
+                path.extend(["rest", "private", "order", cassette]);
+                let entries: Vec<serde_yaml::Mapping> =
+                    serde_yaml::from_str(&std::fs::read_to_string(path).unwrap()).unwrap();
+                let responses = entries
+                    .iter()
+                    .filter_map(|entry| entry.get(&"Response".into()))
+                    .map(|recorded| {
+                        let status = recorded["status"].as_u64().unwrap() as u16;
+                        let mut resp = HTTPResponse::new(StatusCode::try_from(status).unwrap());
+                        resp.set_body(recorded["body"].as_str().unwrap());
+                        resp
+                    })
+                    .collect();
+                Self {
+                    responses: Mutex::new(responses),
+                    requests: Mutex::new(Vec::new()),
+                }
+            }
+        }
+
+        impl HttpExecutor for SequentialReplay {
+            fn execute(&self, req: HTTPRequest) -> ExecuteFuture<'_> {
+                self.requests.lock().unwrap().push(req);
+                let resp = self.responses.lock().unwrap().pop_front();
+                Box::pin(async move { Ok(resp.expect("no more recorded responses")) })
+            }
+        }
+
+        fn sell_order() -> CreateOrder {
+            CreateOrder {
+                market: "maxusdt".into(),
+                side: OrderSide::Sell,
+                volume: dec!(23.4),
+                price: Some(dec!(1.0)),
+                client_oid: Some("max_rs_api_case_create_order".into()),
+                stop_price: None,
+                ord_type: OrderType::Limit,
+                group_id: None,
+            }
+        }
+
+        #[async_std::test]
+        async fn place_and_await_done() {
+            let executor = SequentialReplay::new("place_and_await.yaml");
+            let order = place_and_await(
+                &executor,
+                &TEST_CREDENTIALS,
+                &sell_order(),
+                Duration::from_millis(1),
+                10,
+            )
+            .await
+            .unwrap();
+            assert!(order.state.is_done());
+            assert_eq!(order.executed_volume, Some(dec!(23.4)));
+
+            // placed, then polled by id until done: wait -> wait -> done
+            let requests = executor.requests.lock().unwrap();
+            let calls: Vec<_> = requests
+                .iter()
+                .map(|req| (req.method(), req.url().path()))
+                .collect();
+            assert_eq!(
+                calls,
+                [
+                    (HTTPMethod::Post, "/api/v2/orders"),
+                    (HTTPMethod::Get, "/api/v2/order"),
+                    (HTTPMethod::Get, "/api/v2/order"),
+                ]
+            );
+            assert!(requests[1]
+                .url()
+                .query_pairs()
+                .any(|(key, value)| key == "id" && value == "1601376421"));
+        }
+
+        #[async_std::test]
+        async fn place_and_await_gives_up() {
+            let executor = SequentialReplay::new("place_and_await.yaml");
+            let order = place_and_await(
+                &executor,
+                &TEST_CREDENTIALS,
+                &sell_order(),
+                Duration::from_millis(1),
+                1,
+            )
+            .await
+            .unwrap();
+            // still open after the only poll
+            assert!(order.state.is_wait());
+            assert_eq!(order.trades_count, Some(1));
+            assert_eq!(executor.requests.lock().unwrap().len(), 2);
+        }
+
+        #[async_std::test]
+        async fn place_and_await_without_id() {
+            let mut resp = HTTPResponse::new(StatusCode::Ok);
+            resp.set_body(
+                r#"{"side":"sell","ord_type":"limit","state":"wait","market":"maxusdt"}"#,
+            );
+            let executor = SequentialReplay {
+                responses: Mutex::new(vec![resp].into()),
+                requests: Mutex::new(Vec::new()),
+            };
+            let order = CreateOrder {
+                client_oid: None,
+                ..sell_order()
+            };
+            let result = place_and_await(
+                &executor,
+                &TEST_CREDENTIALS,
+                &order,
+                Duration::from_millis(1),
+                10,
+            )
+            .await;
+            assert!(
+                matches!(result, Err(Error::ReadResponse(_))),
+                "{:?}",
+                result
+            );
+            assert_eq!(executor.requests.lock().unwrap().len(), 1);
+        }
+    }
+}
@@ -0,0 +1,263 @@
+//! Fee calculation by VIP levels, to estimate fees before placing orders and to check the fees charged afterwards.
+//!
+//! ```ignore
+//! let levels = GetVIPLevels {}.fetch(&client).await?;
+//! let schedule = FeeSchedule::from(&levels[0]);
+//! let fee = schedule.taker_fee_for(dec!(10000));
+//! // monthly fees of each level, to tell whether staking for a higher level is worth it
+//! for cost in compare_levels(&levels, dec!(1000000), dec!(2000000)) {
+//!     println!("VIP {}: {} (stake {} MAX)", cost.level, cost.fee, cost.minimum_staking_volume);
+//! }
+//! ```
+
+use rust_decimal::Decimal;
+
+use crate::common::*;
+use crate::v2::rest::{RespAccountVIPInfo, RespVIPLevel, TradeRecord};
+
+/// Maker and taker fee rates of a VIP level. Negative rates mean rebates.
+#[derive(Copy, Clone, Eq, PartialEq, Default, Debug)]
+pub struct FeeSchedule {
+    /// VIP level of the rates.
+    pub level: u8,
+    /// Fee rate of maker orders.
+    pub maker_rate: Decimal,
+    /// Fee rate of taker orders.
+    pub taker_rate: Decimal,
+}
+
+impl From<&RespVIPLevel> for FeeSchedule {
+    fn from(level: &RespVIPLevel) -> Self {
+        Self {
+            level: level.level,
+            maker_rate: level.maker_fee,
+            taker_rate: level.taker_fee,
+        }
+    }
+}
+
+/// Rates of the current VIP level.
+impl From<&RespAccountVIPInfo> for FeeSchedule {
+    fn from(info: &RespAccountVIPInfo) -> Self {
+        Self::from(info.current())
+    }
+}
+
+impl FeeSchedule {
+    /// Expected fee of a maker order trading the notional amount, in the quote currency.
+    pub fn maker_fee_for(&self, notional: Decimal) -> Decimal {
+        notional * self.maker_rate
+    }
+
+    /// Expected fee of a taker order trading the notional amount, in the quote currency.
+    pub fn taker_fee_for(&self, notional: Decimal) -> Decimal {
+        notional * self.taker_rate
+    }
+
+    /// Total fee of trading the notional amounts as maker and as taker, in the quote currency.
+    pub fn cost_for(&self, maker_notional: Decimal, taker_notional: Decimal) -> Decimal {
+        self.maker_fee_for(maker_notional) + self.taker_fee_for(taker_notional)
+    }
+
+    /// Expected fee of your trade, along with the fee charged by the server.
+    ///
+    /// The fee is charged in the received currency, i.e. the base currency for bids and the quote currency for asks,
+    /// or in MAX token if you choose to. Fees in MAX token are converted from the quote currency by `max_price`, the
+    /// price of MAX in the quote currency, and left `None` without it. Discounts of paying in MAX token are not
+    /// applied.
+    ///
+    /// Returns `None` if the trade lacks the maker info (e.g. public trades) or the traded amount.
+    pub fn fee_for_trade(
+        &self,
+        trade: &TradeRecord,
+        max_price: Option<Decimal>,
+    ) -> Option<TradeFee> {
+        let is_maker = trade.info.as_ref()?.side()? == trade.side;
+        let volume = trade.volume?;
+        let funds = trade.funds.or_else(|| Some(trade.price? * volume))?;
+        let rate = if is_maker {
+            self.maker_rate
+        } else {
+            self.taker_rate
+        };
+        let expected_in_quote = funds * rate;
+
+        let in_max_token = trade
+            .fee_currency
+            .as_deref()
+            .is_some_and(|currency| currency.eq_ignore_ascii_case(Currency::MAX.as_str()));
+        let expected = match trade.side {
+            // MAX is the base currency of its own markets
+            TradeSide::Bid if !in_max_token || trade.market.starts_with(Currency::MAX.as_str()) => {
+                Some(volume * rate)
+            }
+            _ if in_max_token => max_price
+                .filter(|price| !price.is_zero())
+                .map(|price| expected_in_quote / price),
+            _ => Some(expected_in_quote),
+        };
+
+        Some(TradeFee {
+            is_maker,
+            rate,
+            expected_in_quote,
+            expected,
+            charged: trade.fee,
+            fee_currency: trade.fee_currency.clone(),
+        })
+    }
+}
+
+/// Fee breakdown of a trade, see [`FeeSchedule::fee_for_trade`].
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub struct TradeFee {
+    /// `true` if your order is the maker.
+    pub is_maker: bool,
+    /// Fee rate applied.
+    pub rate: Decimal,
+    /// Expected fee in the quote currency.
+    pub expected_in_quote: Decimal,
+    /// Expected fee in `fee_currency`, `None` if it can not be converted.
+    pub expected: Option<Decimal>,
+    /// Fee charged by the server.
+    pub charged: Option<Decimal>,
+    /// Currency of the charged fee.
+    pub fee_currency: Option<String>,
+}
+
+impl TradeFee {
+    /// Charged fee minus the expected one in the same currency, `None` if either is unknown.
+    pub fn difference(&self) -> Option<Decimal> {
+        Some(self.charged? - self.expected?)
+    }
+}
+
+/// Fee of trading at a VIP level, see [`compare_levels`].
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub struct LevelCost {
+    /// VIP level.
+    pub level: u8,
+    /// Trading volume required for the level.
+    pub minimum_trading_volume: Decimal,
+    /// MAX token staking required for the level.
+    pub minimum_staking_volume: Decimal,
+    /// Total fee of the given trading amounts, in the quote currency.
+    pub fee: Decimal,
+}
+
+/// Fees of trading the notional amounts at each VIP level, e.g. to tell whether staking MAX token for a higher level
+/// saves more than it costs.
+pub fn compare_levels(
+    levels: &[RespVIPLevel],
+    maker_notional: Decimal,
+    taker_notional: Decimal,
+) -> Vec<LevelCost> {
+    levels
+        .iter()
+        .map(|level| LevelCost {
+            level: level.level,
+            minimum_trading_volume: level.minimum_trading_volume,
+            minimum_staking_volume: level.minimum_staking_volume,
+            fee: FeeSchedule::from(level).cost_for(maker_notional, taker_notional),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::test_util::fixtures;
+    use crate::v2::rest::{TradeMakerInfo, TradeMakerType};
+    use rust_decimal_macros::dec;
+
+    // levels 0, 1 and 5 of GET /api/v2/vip_levels, with the float noise in the maker fee of level 1 rounded
+    const VIP_LEVELS: &str = r#"[{"level":0,"minimum_trading_volume":0,"minimum_staking_volume":0,"maker_fee":0.00045,"taker_fee":0.0015},{"level":1,"minimum_trading_volume":3000000,"minimum_staking_volume":500,"maker_fee":0.00036,"taker_fee":0.00135},{"level":5,"minimum_trading_volume":300000000,"minimum_staking_volume":10000,"maker_fee":-8.0e-05,"taker_fee":0.00075}]"#;
+
+    fn levels() -> Vec<RespVIPLevel> {
+        serde_json::from_str(VIP_LEVELS).unwrap()
+    }
+
+    fn my_trade(side: TradeSide, maker: Option<TradeSide>, fee_currency: &str) -> TradeRecord {
+        let mut trades: Vec<TradeRecord> = serde_json::from_str(fixtures::TRADES).unwrap();
+        let mut trade = trades.remove(0);
+        trade.side = side;
+        trade.fee_currency = Some(fee_currency.into());
+        trade.info = maker.map(|maker| {
+            let info = TradeMakerInfo {
+                fee: dec!(0),
+                fee_currency: fee_currency.into(),
+                order_id: 1,
+            };
+            match maker {
+                TradeSide::Ask => TradeMakerType::Ask { ask: info },
+                _ => TradeMakerType::Bid { bid: info },
+            }
+        });
+        trade
+    }
+
+    #[test]
+    fn expected_fees() {
+        let levels = levels();
+        let vip0 = FeeSchedule::from(&levels[0]);
+        assert_eq!(vip0.maker_fee_for(dec!(10000)), dec!(4.5));
+        assert_eq!(vip0.taker_fee_for(dec!(10000)), dec!(15));
+        assert_eq!(vip0.cost_for(dec!(10000), dec!(10000)), dec!(19.5));
+        assert_eq!(
+            vip0.taker_fee_for(dec!(10000)),
+            levels[0].expected_fee(dec!(10000), false)
+        );
+
+        // maker rebate
+        let vip5 = FeeSchedule::from(&levels[2]);
+        assert_eq!(vip5.maker_fee_for(dec!(10000)), dec!(-0.8));
+    }
+
+    #[test]
+    fn fee_of_trades() {
+        let vip0 = FeeSchedule::from(&levels()[0]);
+
+        // 3.14 DOT bought at 52 USDT as taker, fee paid in MAX token at 0.5 USDT
+        let trade = my_trade(TradeSide::Bid, Some(TradeSide::Ask), "max");
+        let fee = vip0.fee_for_trade(&trade, Some(dec!(0.5))).unwrap();
+        assert!(!fee.is_maker);
+        assert_eq!(fee.rate, dec!(0.0015));
+        assert_eq!(fee.expected_in_quote, dec!(0.24492));
+        assert_eq!(fee.expected, Some(dec!(0.48984)));
+        assert_eq!(fee.charged, Some(dec!(0.08908907)));
+        assert_eq!(fee.difference(), Some(dec!(-0.40075093)));
+        // unable to convert without MAX price
+        let fee = vip0.fee_for_trade(&trade, None).unwrap();
+        assert_eq!(fee.expected, None);
+        assert_eq!(fee.difference(), None);
+
+        // bid fee paid in the base currency
+        let trade = my_trade(TradeSide::Bid, Some(TradeSide::Bid), "dot");
+        let fee = vip0.fee_for_trade(&trade, None).unwrap();
+        assert!(fee.is_maker);
+        assert_eq!(fee.expected, Some(dec!(0.001413)));
+        assert_eq!(fee.expected_in_quote, dec!(0.073476));
+
+        // ask fee paid in the quote currency
+        let trade = my_trade(TradeSide::Ask, Some(TradeSide::Ask), "usdt");
+        let fee = vip0.fee_for_trade(&trade, None).unwrap();
+        assert!(fee.is_maker);
+        assert_eq!(fee.expected, Some(dec!(0.073476)));
+
+        // maker unknown
+        assert_eq!(
+            vip0.fee_for_trade(&my_trade(TradeSide::Bid, None, "max"), None),
+            None
+        );
+    }
+
+    #[test]
+    fn compare_vip_levels() {
+        let costs = compare_levels(&levels(), dec!(1000000), dec!(2000000));
+        let fees: Vec<_> = costs.iter().map(|cost| (cost.level, cost.fee)).collect();
+        assert_eq!(fees, [(0, dec!(3450)), (1, dec!(3060)), (5, dec!(1420))]);
+        // VIP 1 saves 390 USDT for staking 500 MAX
+        assert_eq!(costs[0].fee - costs[1].fee, dec!(390));
+        assert_eq!(costs[1].minimum_staking_volume, dec!(500));
+    }
+}
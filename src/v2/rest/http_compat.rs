@@ -0,0 +1,189 @@
+//! Conversions between `http_types` and the [http](https://crates.io/crates/http) crate, enabled by `http` feature.
+//!
+//! This allows working with hyper, tower and other libraries built upon the `http` crate.
+//!
+//! ```ignore
+//! let req = into_http_request(GetOrders::for_market("btctwd").build().to_request(&credentials)).await?;
+//! let resp: http::Response<Vec<u8>> = send_somehow(req).await;
+//! let orders: Vec<RespOrder> = GetOrders::read_response(from_http_response(resp)?).await?;
+//! ```
+
+use std::convert::TryFrom;
+
+use http_types::{Request as HTTPRequest, Response as HTTPResponse, StatusCode};
+
+use crate::error::*;
+
+fn conversion_error(err: impl ToString) -> Error {
+    Error::InvalidHttpMessage(err.to_string())
+}
+
+/// Convert a prepared request into `http::Request`, keeping method, full URL, headers and body as-is.
+pub async fn into_http_request(mut req: HTTPRequest) -> Result<http::Request<Vec<u8>>> {
+    let mut builder = http::Request::builder()
+        .method(req.method().to_string().as_str())
+        .uri(req.url().as_str());
+    for (name, values) in req.iter() {
+        for value in values.iter() {
+            builder = builder.header(name.as_str(), value.as_str());
+        }
+    }
+    let body = req
+        .take_body()
+        .into_bytes()
+        .await
+        .map_err(conversion_error)?;
+    builder.body(body).map_err(conversion_error)
+}
+
+/// Convert a `http::Response` into `http_types::Response`, so `read_response` of endpoints can parse it.
+pub fn from_http_response(resp: http::Response<Vec<u8>>) -> Result<HTTPResponse> {
+    let (parts, body) = resp.into_parts();
+    let status = StatusCode::try_from(parts.status.as_u16()).map_err(conversion_error)?;
+
+    let mut converted = HTTPResponse::new(status);
+    // setting body overrides Content-Type, which is restored with other headers below
+    converted.set_body(body);
+    converted.remove_header("Content-Type");
+    for (name, value) in parts.headers.iter() {
+        let value = value.to_str().map_err(conversion_error)?;
+        converted.append_header(name.as_str(), value);
+    }
+    Ok(converted)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::OrderSide;
+    use crate::v2::rest::internal::{
+        HEADER_AUTH_ACCESS_KEY, HEADER_AUTH_PAYLOAD, HEADER_AUTH_SIGNATURE,
+    };
+    use crate::v2::rest::{
+        CreateOrder, GetOrders, GetTimestamp, OrderState, OrderType, RespTimestamp,
+    };
+    use crate::Credentials;
+    use rust_decimal_macros::dec;
+    use serde_json::Value as JsonValue;
+
+    const SIGNED_HEADERS: [&str; 3] = [
+        HEADER_AUTH_ACCESS_KEY,
+        HEADER_AUTH_PAYLOAD,
+        HEADER_AUTH_SIGNATURE,
+    ];
+
+    fn test_credentials() -> Credentials {
+        Credentials::new("api key".into(), "api secret".into())
+    }
+
+    // Snapshot of request headers, taken before the conversion consumes the request.
+    fn header_snapshot(req: &HTTPRequest) -> Vec<(String, Vec<String>)> {
+        req.iter()
+            .map(|(name, values)| {
+                let values = values.iter().map(|v| v.as_str().to_owned()).collect();
+                (name.as_str().to_owned(), values)
+            })
+            .collect()
+    }
+
+    fn assert_same_headers(orig: &[(String, Vec<String>)], converted: &http::Request<Vec<u8>>) {
+        let orig_len: usize = orig.iter().map(|(_, values)| values.len()).sum();
+        assert_eq!(converted.headers().len(), orig_len);
+        for (name, values) in orig.iter() {
+            let converted_values: Vec<&[u8]> = converted
+                .headers()
+                .get_all(name.as_str())
+                .iter()
+                .map(|v| v.as_bytes())
+                .collect();
+            let orig_values: Vec<&[u8]> = values.iter().map(|v| v.as_bytes()).collect();
+            assert_eq!(converted_values, orig_values, "header {}", name);
+        }
+    }
+
+    #[async_std::test]
+    async fn signed_get_with_query() {
+        let params = GetOrders {
+            market: "maxtwd".into(),
+            state: vec![OrderState::Wait, OrderState::Done],
+            order_by: None,
+            group_id: None,
+            pagination: Some(true),
+            page_params: None,
+            offset: None,
+        };
+        let orig = params.to_request(&test_credentials());
+        let orig_url = orig.url().clone();
+        let orig_headers = header_snapshot(&orig);
+
+        let converted = into_http_request(orig).await.unwrap();
+        assert_eq!(converted.method(), http::Method::GET);
+        assert_eq!(converted.uri().to_string(), orig_url.as_str());
+        assert_eq!(converted.uri().scheme_str(), Some("https"));
+        assert_eq!(converted.uri().host(), Some("max-api.maicoin.com"));
+        assert_eq!(converted.uri().query(), orig_url.query());
+        assert!(converted
+            .uri()
+            .query()
+            .unwrap()
+            .contains("state%5B%5D=wait&state%5B%5D=done"));
+        assert_same_headers(&orig_headers, &converted);
+        for name in SIGNED_HEADERS.iter() {
+            assert!(converted.headers().contains_key(*name));
+        }
+        assert!(converted.body().is_empty());
+    }
+
+    #[async_std::test]
+    async fn signed_post_with_json_body() {
+        let params = CreateOrder {
+            market: "maxtwd".into(),
+            side: OrderSide::Sell,
+            volume: dec!(100),
+            price: Some(dec!(9.5)),
+            client_oid: Some("my-order".into()),
+            stop_price: None,
+            ord_type: OrderType::Limit,
+            group_id: None,
+        };
+        let orig = params.to_request(&test_credentials());
+        let orig_url = orig.url().clone();
+        let orig_headers = header_snapshot(&orig);
+
+        let converted = into_http_request(orig).await.unwrap();
+        assert_eq!(converted.method(), http::Method::POST);
+        assert_eq!(converted.uri().to_string(), orig_url.as_str());
+        assert_same_headers(&orig_headers, &converted);
+        assert_eq!(
+            converted.headers()["Content-Type"].as_bytes(),
+            b"application/json"
+        );
+
+        // body is the signed payload without path
+        let payload = converted.headers()[HEADER_AUTH_PAYLOAD].to_str().unwrap();
+        let mut payload: JsonValue =
+            serde_json::from_slice(&base64::decode(payload).unwrap()).unwrap();
+        payload.as_object_mut().unwrap().remove("path");
+        let body: JsonValue = serde_json::from_slice(converted.body()).unwrap();
+        assert_eq!(body, payload);
+    }
+
+    #[async_std::test]
+    async fn response_conversion() {
+        let raw = http::Response::builder()
+            .status(200)
+            .header("Content-Type", "application/json")
+            .header("X-Request-Id", "1-618751d5-082d0fed2b7e78345969f0f9")
+            .body(b"1636258261".to_vec())
+            .unwrap();
+        let resp = from_http_response(raw).unwrap();
+        assert_eq!(resp.status(), StatusCode::Ok);
+        assert_eq!(resp["Content-Type"].as_str(), "application/json");
+        assert_eq!(
+            resp["X-Request-Id"].as_str(),
+            "1-618751d5-082d0fed2b7e78345969f0f9"
+        );
+        let ts: RespTimestamp = GetTimestamp::read_response(resp).await.unwrap();
+        assert_eq!(ts.0, 1636258261);
+    }
+}
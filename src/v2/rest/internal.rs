@@ -1,15 +1,16 @@
 use base64::encode as b64_encode;
 use hmac::{Hmac, Mac, NewMac};
 use http_types::{
-    Body as HTTPBody, Request as HTTPRequest, Response as HTTPResponse, Url as HTTPURL,
+    Body as HTTPBody, Method as HTTPMethod, Request as HTTPRequest, Response as HTTPResponse,
+    StatusCode, Url as HTTPURL,
 };
 use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
+use serde_json::Value as JsonValue;
 use sha2::Sha256;
-use std::future::Future;
-use std::pin::Pin;
 
 use crate::error::*;
+use crate::v2::rest::{ApiHost, Envelope, ResponseFuture};
 use crate::Credentials;
 
 pub(crate) const HEADER_AUTH_ACCESS_KEY: &str = "X-MAX-ACCESSKEY";
@@ -47,23 +48,30 @@ where
 }
 
 impl<'path, 'params, P: Serialize> AuthParamsOuterWrapper<'path, 'params, P> {
-    pub(super) fn signed_payload(&self, credentials: &Credentials) -> (String, String) {
-        let payload = b64_encode(serde_json::to_string(&self).unwrap().as_bytes());
-        let mut hmac = Hmac::<Sha256>::new_from_slice(credentials.secret_key.as_bytes()).unwrap();
+    pub(super) fn signed_payload(&self, credentials: &Credentials) -> Result<(String, String)> {
+        let payload = serde_json::to_string(&self)
+            .map_err(|err| Error::InvalidParams(format!("unable to sign payload: {}", err)))?;
+        let payload = b64_encode(payload.as_bytes());
+        let mut hmac = Hmac::<Sha256>::new_from_slice(credentials.secret_key.as_bytes())
+            .map_err(|err| Error::InvalidParams(format!("unusable secret key: {}", err)))?;
         hmac.update(payload.as_bytes());
         let signature = format!("{:x}", hmac.finalize().into_bytes());
-        (payload, signature)
+        Ok((payload, signature))
     }
 }
 
 pub(crate) trait RestApiBase: Sized + Serialize {
-    fn get_url(&self) -> HTTPURL;
+    /// API path of the endpoint, which is also the path to be signed.
+    fn get_path(&self) -> String;
 
-    type Response: DeserializeOwned;
+    fn get_url(&self, host: &ApiHost) -> Result<HTTPURL> {
+        host.try_endpoint_url(&self.get_path())
+    }
+
+    type Response: DeserializeOwned + Send + 'static;
+    type Body: DeserializeOwned + Into<Self::Response>;
     // async fn fn read_response(mut HTTPResponse) -> Self::Response
-    fn read_response(
-        mut resp: HTTPResponse,
-    ) -> Pin<Box<dyn Future<Output = Result<Self::Response>>>> {
+    fn read_response(mut resp: HTTPResponse) -> ResponseFuture<Self::Response> {
         #[derive(Deserialize)]
         #[serde(untagged)]
         enum BodyWrapper<Content> {
@@ -72,70 +80,641 @@ pub(crate) trait RestApiBase: Sized + Serialize {
         }
 
         let fut_result = async move {
-            resp.body_json::<BodyWrapper<Self::Response>>()
+            if is_maintenance(&resp) {
+                #[cfg(feature = "tracing")]
+                tracing::warn!(
+                    endpoint = std::any::type_name::<Self>(),
+                    status = u16::from(resp.status()),
+                    "API server under maintenance"
+                );
+                return Err(Error::Maintenance(resp.status().into()));
+            }
+            let body = resp
+                .body_bytes()
                 .await
-                .map_err(|parse_err| Error::ReadResponse(Box::new(parse_err.into_inner())))
+                .map_err(|read_err| Error::ReadResponse(Box::new(read_err.into_inner())))?;
+
+            #[cfg(feature = "tracing")]
+            let parse_started = crate::util::Instant::now();
+            let result = serde_json::from_slice::<BodyWrapper<Self::Body>>(&body)
+                .map_err(|parse_err| Error::ReadResponse(Box::new(parse_err.into())))
                 .and_then(|parsed| match parsed {
-                    BodyWrapper::Ok(result) => Result::Ok(result),
+                    BodyWrapper::Ok(result) => Result::Ok(result.into()),
                     BodyWrapper::Err(err_wrapper) => Result::Err(err_wrapper.into()),
-                })
+                });
+
+            // only sizes and error codes are recorded, the body may contain private information
+            #[cfg(feature = "tracing")]
+            tracing::debug!(
+                endpoint = std::any::type_name::<Self>(),
+                status = u16::from(resp.status()),
+                body_len = body.len(),
+                parse_us = parse_started.elapsed().as_micros() as u64,
+                error_code = match &result {
+                    Err(Error::RestApi(code, _)) => Some(*code),
+                    _ => None,
+                },
+                parsed = !matches!(&result, Err(Error::ReadResponse(_))),
+                "response read"
+            );
+            result
         };
         Box::pin(fut_result)
     }
+
+    // Parse the response, along with the untouched JSON body for auditing.
+    fn read_response_raw(mut resp: HTTPResponse) -> ResponseFuture<(Self::Response, JsonValue)> {
+        Box::pin(async move {
+            let body = resp
+                .body_bytes()
+                .await
+                .map_err(|read_err| Error::ReadResponse(Box::new(read_err.into_inner())))?;
+
+            // replay the body to the typed reader, so maintenance and API errors are handled the same way
+            let mut replay = HTTPResponse::new(resp.status());
+            replay.set_body(body.clone());
+            replay.remove_header("Content-Type");
+            for (name, values) in resp.iter() {
+                for value in values.iter() {
+                    replay.append_header(name, value.as_str());
+                }
+            }
+            let parsed = Self::read_response(replay).await?;
+            let raw = serde_json::from_slice(&body)
+                .map_err(|parse_err| Error::ReadResponse(Box::new(parse_err.into())))?;
+            Ok((parsed, raw))
+        })
+    }
+
+    fn read_response_enveloped(resp: HTTPResponse) -> ResponseFuture<Envelope<Self::Response>> {
+        Box::pin(async move {
+            let Envelope {
+                status, headers, ..
+            } = Envelope::capture(&resp, ());
+            let body = Self::read_response(resp).await?;
+            Ok(Envelope {
+                body,
+                status,
+                headers,
+            })
+        })
+    }
 }
 
-pub(crate) fn make_unauth_get(params: &impl RestApiBase) -> HTTPRequest {
-    let mut req = HTTPRequest::get(params.get_url());
-    req.set_query(params)
-        .expect("failed to serialize parameters");
-    req
+// MAX replies 503 during maintenance, sometimes as a HTML page from the CDN instead of the JSON API error.
+fn is_maintenance(resp: &HTTPResponse) -> bool {
+    let is_html = resp
+        .content_type()
+        .is_some_and(|mime| mime.essence() == "text/html");
+    resp.status() == StatusCode::ServiceUnavailable || (resp.status().is_server_error() && is_html)
 }
 
-pub(crate) fn make_auth_get(params: &impl RestApiBase, credentials: &Credentials) -> HTTPRequest {
-    let (url, header_payload, header_signature) = {
-        let mut url = params.get_url();
-        let params = AuthParamsOuterWrapper {
-            path: url.path(),
-            inner: AuthParamsInnerWrapper {
-                nonce: credentials.nonce(),
-                params,
-            },
-        };
-        let qs = serde_qs::to_string(&params.inner).expect("auth parameter serialization failed");
-        let (payload, signature) = params.signed_payload(credentials);
+// Percent-encode a dynamic path segment, so it can be safely interpolated into API path. Only unreserved characters
+// (RFC 3986) are kept as-is.
+pub(crate) fn encode_path_segment(segment: &str) -> String {
+    let mut encoded = String::with_capacity(segment.len());
+    for byte in segment.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => {
+                encoded.push(byte as char)
+            }
+            _ => encoded.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+    encoded
+}
 
-        url.set_query(Some(&qs));
-        (url, payload, signature)
-    };
+// Serialize parameters into query string. Sequences are rendered as repeated `field[]=` pairs in order, instead of the
+// indexed `field[0]=` pairs of serde_qs. Empty sequences are omitted, hence fields of sequence should be skipped when
+// empty (`skip_serializing_if = "Vec::is_empty"`) to keep the signed payload identical.
+pub(crate) fn to_query_string(params: &impl Serialize) -> Result<String> {
+    let qs = serde_qs::to_string(params).map_err(|err| Error::InvalidParams(err.to_string()))?;
+    let pairs: Vec<_> = qs
+        .split('&')
+        .map(|pair| {
+            let (key, val) = pair.split_at(pair.find('=').unwrap_or(pair.len()));
+            match key.strip_suffix(']').and_then(|key| key.rsplit_once('[')) {
+                Some((field, index))
+                    if !index.is_empty() && index.bytes().all(|b| b.is_ascii_digit()) =>
+                {
+                    format!("{}%5B%5D{}", field, val)
+                }
+                _ => pair.to_owned(),
+            }
+        })
+        .collect();
+    Ok(pairs.join("&"))
+}
+
+pub(crate) fn make_unauth_get(params: &impl RestApiBase, host: &ApiHost) -> Result<HTTPRequest> {
+    let mut url = params.get_url(host)?;
+    url.set_query(Some(&to_query_string(params)?));
+    Ok(HTTPRequest::get(url))
+}
+
+pub(crate) fn make_auth_get(
+    params: &impl RestApiBase,
+    host: &ApiHost,
+    credentials: &Credentials,
+    nonce: u64,
+) -> Result<HTTPRequest> {
+    make_auth_with_query(HTTPMethod::Get, params, host, credentials, nonce)
+}
+
+pub(crate) fn make_auth_delete(
+    params: &impl RestApiBase,
+    host: &ApiHost,
+    credentials: &Credentials,
+    nonce: u64,
+) -> Result<HTTPRequest> {
+    make_auth_with_query(HTTPMethod::Delete, params, host, credentials, nonce)
+}
+
+pub(crate) fn make_auth_post(
+    params: &impl RestApiBase,
+    host: &ApiHost,
+    credentials: &Credentials,
+    nonce: u64,
+) -> Result<HTTPRequest> {
+    make_auth_with_body(HTTPMethod::Post, params, host, credentials, nonce)
+}
+
+// No endpoint uses PUT yet, only the test request type below. Drop `cfg(test)` along with the first one.
+#[cfg(test)]
+pub(crate) fn make_auth_put(
+    params: &impl RestApiBase,
+    host: &ApiHost,
+    credentials: &Credentials,
+    nonce: u64,
+) -> Result<HTTPRequest> {
+    make_auth_with_body(HTTPMethod::Put, params, host, credentials, nonce)
+}
 
-    let mut req = HTTPRequest::get(url);
+// Nonce signed in the payload header of an authenticated request.
+pub(crate) fn signed_nonce(req: &HTTPRequest) -> Option<u64> {
+    let payload = base64::decode(req.header(HEADER_AUTH_PAYLOAD)?.as_str()).ok()?;
+    serde_json::from_slice::<JsonValue>(&payload)
+        .ok()?
+        .get("nonce")?
+        .as_u64()
+}
+
+// Signed request carrying parameters in query string (GET, DELETE).
+fn make_auth_with_query(
+    method: HTTPMethod,
+    params: &impl RestApiBase,
+    host: &ApiHost,
+    credentials: &Credentials,
+    nonce: u64,
+) -> Result<HTTPRequest> {
+    let (url, header_payload, header_signature) = signed_query(params, host, credentials, nonce)?;
+
+    #[cfg(feature = "tracing")]
+    trace_signed_request(&method, &url, header_payload.len(), 0);
+
+    let mut req = HTTPRequest::new(method, url);
     req.insert_header(HEADER_AUTH_ACCESS_KEY, &credentials.access_key);
     req.insert_header(HEADER_AUTH_PAYLOAD, header_payload);
     req.insert_header(HEADER_AUTH_SIGNATURE, header_signature);
     req.insert_header("Content-Type", "application/json");
-    req
+    Ok(req)
+}
+
+// URL with signed query string, the payload and its signature.
+pub(crate) fn signed_query(
+    params: &impl RestApiBase,
+    host: &ApiHost,
+    credentials: &Credentials,
+    nonce: u64,
+) -> Result<(HTTPURL, String, String)> {
+    let mut url = params.get_url(host)?;
+    let path = params.get_path();
+    let params = AuthParamsOuterWrapper {
+        path: &path,
+        inner: AuthParamsInnerWrapper { nonce, params },
+    };
+    let qs = to_query_string(&params.inner)?;
+    let (payload, signature) = params.signed_payload(credentials)?;
+
+    url.set_query(Some(&qs));
+    Ok((url, payload, signature))
 }
 
-pub(crate) fn make_auth_post(params: &impl RestApiBase, credentials: &Credentials) -> HTTPRequest {
-    let url = params.get_url();
+// Signed request carrying parameters in JSON body (POST, PUT).
+fn make_auth_with_body(
+    method: HTTPMethod,
+    params: &impl RestApiBase,
+    host: &ApiHost,
+    credentials: &Credentials,
+    nonce: u64,
+) -> Result<HTTPRequest> {
+    let url = params.get_url(host)?;
     let (body, header_payload, header_signature) = {
+        let path = params.get_path();
         let params = AuthParamsOuterWrapper {
-            path: url.path(),
-            inner: AuthParamsInnerWrapper {
-                nonce: credentials.nonce(),
-                params,
-            },
+            path: &path,
+            inner: AuthParamsInnerWrapper { nonce, params },
         };
-        let (payload, signature) = params.signed_payload(credentials);
-        let body = HTTPBody::from_json(&params.inner).expect("auth parameter serialization failed");
+        let (payload, signature) = params.signed_payload(credentials)?;
+        let body = HTTPBody::from_json(&params.inner)
+            .map_err(|err| Error::InvalidParams(err.to_string()))?;
         (body, payload, signature)
     };
 
-    let mut req = HTTPRequest::post(url);
+    #[cfg(feature = "tracing")]
+    trace_signed_request(
+        &method,
+        &url,
+        header_payload.len(),
+        body.len().unwrap_or_default(),
+    );
+
+    let mut req = HTTPRequest::new(method, url);
     req.insert_header(HEADER_AUTH_ACCESS_KEY, &credentials.access_key);
     req.insert_header(HEADER_AUTH_PAYLOAD, header_payload);
     req.insert_header(HEADER_AUTH_SIGNATURE, header_signature);
     req.insert_header("Content-Type", "application/json");
     req.set_body(body);
-    req
+    Ok(req)
+}
+
+// Never record credentials, payload, signature or query string: only the path and sizes.
+#[cfg(feature = "tracing")]
+fn trace_signed_request(method: &HTTPMethod, url: &HTTPURL, payload_len: usize, body_len: usize) {
+    tracing::debug!(
+        method = %method,
+        path = url.path(),
+        payload_len,
+        body_len,
+        "signed request built"
+    );
+}
+
+#[cfg(test)]
+#[allow(dead_code)] // read_response of the endpoints below are not exercised
+mod tests {
+    use super::*;
+    use crate::v2::rest::api_impl::*;
+    use crate::v2::rest::AuthGetDebug;
+    use serde_json::{json, Value as JsonValue};
+
+    #[derive(Serialize, Debug)]
+    struct TestGetApi {
+        market: String,
+    }
+    impl_api!(TestGetApi => JsonValue : auth GET, "/api/v2/test/get");
+
+    #[derive(Serialize, Debug)]
+    struct TestPostApi {
+        market: String,
+    }
+    impl_api!(TestPostApi => JsonValue : auth POST, "/api/v2/test/post");
+
+    #[derive(Serialize, Debug)]
+    struct TestDeleteApi {
+        #[serde(skip)]
+        id: u64,
+        market: String,
+    }
+    impl_api!(TestDeleteApi => JsonValue : auth DELETE, dynamic params {
+        format!("/api/v2/test/delete/{}", params.id)
+    });
+
+    #[derive(Serialize, Debug)]
+    struct TestPutApi {
+        market: String,
+    }
+    impl_api!(TestPutApi => JsonValue : auth PUT, "/api/v2/test/put");
+
+    // Payload which can not be signed, since JSON object keys must be strings.
+    #[derive(Serialize, Debug)]
+    struct TestUnsignablePostApi {
+        weights: std::collections::HashMap<(u8, u8), u64>,
+    }
+    impl_api!(TestUnsignablePostApi => JsonValue : auth POST, "/api/v2/test/post");
+
+    fn test_credentials() -> Credentials {
+        Credentials::new("api key".into(), "api secret".into())
+    }
+
+    // Check the signature headers against the decoded payload, and return the payload.
+    fn verify_signature(req: &HTTPRequest) -> JsonValue {
+        assert_eq!(req[HEADER_AUTH_ACCESS_KEY].as_str(), "api key");
+        let payload = req[HEADER_AUTH_PAYLOAD].as_str();
+        let mut hmac = Hmac::<Sha256>::new_from_slice(b"api secret").unwrap();
+        hmac.update(payload.as_bytes());
+        assert_eq!(
+            req[HEADER_AUTH_SIGNATURE].as_str(),
+            format!("{:x}", hmac.finalize().into_bytes())
+        );
+        serde_json::from_slice(&base64::decode(payload).expect("payload is not base64"))
+            .expect("payload is not JSON")
+    }
+
+    fn query_params(req: &HTTPRequest) -> JsonValue {
+        req.url()
+            .query_pairs()
+            .map(|(k, v)| match v.parse::<u64>() {
+                Ok(n) => (k.into_owned(), json!(n)),
+                Err(_) => (k.into_owned(), json!(v)),
+            })
+            .collect::<serde_json::Map<_, _>>()
+            .into()
+    }
+
+    #[test]
+    fn auth_get_signature() {
+        let req = TestGetApi {
+            market: "btctwd".into(),
+        }
+        .to_request(&test_credentials());
+        assert_eq!(req.method(), HTTPMethod::Get);
+        assert_eq!(req.url().path(), "/api/v2/test/get");
+
+        let payload = verify_signature(&req);
+        let nonce = payload["nonce"].as_u64().expect("nonce must be signed");
+        assert_eq!(
+            payload,
+            json!({"path": "/api/v2/test/get", "market": "btctwd", "nonce": nonce})
+        );
+        assert_eq!(
+            query_params(&req),
+            json!({"market": "btctwd", "nonce": nonce})
+        );
+    }
+
+    #[test]
+    fn auth_get_debug_stable_signature() {
+        let params = TestGetApi {
+            market: "btctwd".into(),
+        };
+        let host = ApiHost::default();
+        let (url, payload, signature) = params
+            .signed_parts_with(&host, &test_credentials(), 1637671372000)
+            .unwrap();
+        assert_eq!(
+            url.as_str(),
+            "https://max-api.maicoin.com/api/v2/test/get?market=btctwd&nonce=1637671372000"
+        );
+        assert_eq!(
+            payload,
+            "eyJtYXJrZXQiOiJidGN0d2QiLCJub25jZSI6MTYzNzY3MTM3MjAwMCwicGF0aCI6Ii9hcGkvdjIvdGVzdC9nZXQifQ=="
+        );
+        assert_eq!(
+            signature,
+            "279cdfb4d162a7754919bf4975562e031b0ed306513369f9baeafe86a2f5d3fb"
+        );
+        // another credentials instance, same nonce
+        assert_eq!(
+            params
+                .signed_parts_with(&host, &test_credentials(), 1637671372000)
+                .unwrap(),
+            (url, payload, signature)
+        );
+
+        // the request built afterwards carries the same kind of payload with a fresh nonce
+        let credentials = test_credentials();
+        let (_, payload, signature) = params.signed_parts(&credentials).unwrap();
+        let req = params.to_request(&credentials);
+        assert_ne!(req[HEADER_AUTH_PAYLOAD].as_str(), payload);
+        assert_ne!(req[HEADER_AUTH_SIGNATURE].as_str(), signature);
+        verify_signature(&req);
+    }
+
+    #[test]
+    fn query_string_arrays() {
+        #[derive(Serialize)]
+        struct ArrayParams {
+            #[serde(skip_serializing_if = "Vec::is_empty")]
+            states: Vec<&'static str>,
+            market: &'static str,
+            ids: Vec<u64>,
+        }
+
+        let qs = |states, ids| {
+            to_query_string(&ArrayParams {
+                states,
+                market: "a[0]=b",
+                ids,
+            })
+            .unwrap()
+        };
+        assert_eq!(qs(vec![], vec![]), "market=a%5B0%5D%3Db");
+        assert_eq!(
+            qs(vec!["wait"], vec![7]),
+            "states%5B%5D=wait&market=a%5B0%5D%3Db&ids%5B%5D=7"
+        );
+        assert_eq!(
+            qs(vec!["wait", "done", "cancel"], vec![3, 1, 2]),
+            "states%5B%5D=wait&states%5B%5D=done&states%5B%5D=cancel&market=a%5B0%5D%3Db&ids%5B%5D=3&ids%5B%5D=1&ids%5B%5D=2"
+        );
+    }
+
+    #[test]
+    fn auth_get_array_in_order() {
+        #[derive(Serialize, Debug)]
+        struct TestArrayApi {
+            #[serde(skip_serializing_if = "Vec::is_empty")]
+            state: Vec<&'static str>,
+        }
+        impl_api!(TestArrayApi => JsonValue : auth GET, "/api/v2/test/array");
+
+        for state in [vec![], vec!["done"], vec!["wait", "done", "cancel"]] {
+            let req = TestArrayApi {
+                state: state.clone(),
+            }
+            .to_request(&test_credentials());
+            let payload = verify_signature(&req);
+            let queried: Vec<_> = req
+                .url()
+                .query_pairs()
+                .filter(|(key, _)| key == "state[]")
+                .map(|(_, val)| val.into_owned())
+                .collect();
+            assert_eq!(queried, state);
+            match payload.get("state") {
+                Some(signed) => assert_eq!(signed, &json!(state)),
+                None => assert!(state.is_empty()),
+            }
+        }
+    }
+
+    #[test]
+    fn path_segment_encoding() {
+        assert_eq!(encode_path_segment("btctwd"), "btctwd");
+        assert_eq!(encode_path_segment("a-b.c_d~"), "a-b.c_d~");
+        assert_eq!(encode_path_segment("btc twd/../x"), "btc%20twd%2F..%2Fx");
+        assert_eq!(encode_path_segment("?#%"), "%3F%23%25");
+        assert_eq!(encode_path_segment("幣"), "%E5%B9%A3");
+    }
+
+    #[test]
+    fn auth_delete_signature() {
+        let req = TestDeleteApi {
+            id: 42,
+            market: "btctwd".into(),
+        }
+        .to_request(&test_credentials());
+        assert_eq!(req.method(), HTTPMethod::Delete);
+        assert_eq!(req.url().path(), "/api/v2/test/delete/42");
+
+        let payload = verify_signature(&req);
+        let nonce = payload["nonce"].as_u64().expect("nonce must be signed");
+        assert_eq!(
+            payload,
+            json!({"path": "/api/v2/test/delete/42", "market": "btctwd", "nonce": nonce})
+        );
+        assert_eq!(
+            query_params(&req),
+            json!({"market": "btctwd", "nonce": nonce})
+        );
+    }
+
+    #[async_std::test]
+    async fn auth_post_signature() {
+        let mut req = TestPostApi {
+            market: "btctwd".into(),
+        }
+        .to_request(&test_credentials());
+        assert_eq!(req.method(), HTTPMethod::Post);
+        assert_eq!(req.url().path(), "/api/v2/test/post");
+        assert_eq!(req.url().query(), None);
+
+        let payload = verify_signature(&req);
+        let nonce = payload["nonce"].as_u64().expect("nonce must be signed");
+        assert_eq!(
+            payload,
+            json!({"path": "/api/v2/test/post", "market": "btctwd", "nonce": nonce})
+        );
+        let body: JsonValue = req.body_json().await.expect("body is not JSON");
+        assert_eq!(body, json!({"market": "btctwd", "nonce": nonce}));
+    }
+
+    #[async_std::test]
+    async fn auth_put_signature() {
+        let mut req = TestPutApi {
+            market: "btctwd".into(),
+        }
+        .to_request(&test_credentials());
+        assert_eq!(req.method(), HTTPMethod::Put);
+        assert_eq!(req.url().path(), "/api/v2/test/put");
+        assert_eq!(req.url().query(), None);
+
+        let payload = verify_signature(&req);
+        let nonce = payload["nonce"].as_u64().expect("nonce must be signed");
+        assert_eq!(
+            payload,
+            json!({"path": "/api/v2/test/put", "market": "btctwd", "nonce": nonce})
+        );
+        let body: JsonValue = req.body_json().await.expect("body is not JSON");
+        assert_eq!(body, json!({"market": "btctwd", "nonce": nonce}));
+    }
+
+    #[test]
+    fn auth_signature_with_custom_host() {
+        let host = ApiHost::parse("http://127.0.0.1:8080").unwrap();
+        let req = TestGetApi {
+            market: "btctwd".into(),
+        }
+        .to_request_with(&host, &test_credentials());
+        assert_eq!(
+            req.url().origin().ascii_serialization(),
+            "http://127.0.0.1:8080"
+        );
+        assert_eq!(req.url().path(), "/api/v2/test/get");
+        assert_eq!(verify_signature(&req)["path"], "/api/v2/test/get");
+
+        let host = ApiHost::parse("http://127.0.0.1:8080/proxy/max/").unwrap();
+        let req = TestDeleteApi {
+            id: 42,
+            market: "btctwd".into(),
+        }
+        .to_request_with(&host, &test_credentials());
+        assert_eq!(
+            req.url().origin().ascii_serialization(),
+            "http://127.0.0.1:8080"
+        );
+        assert_eq!(req.url().path(), "/proxy/max/api/v2/test/delete/42");
+        assert_eq!(verify_signature(&req)["path"], "/api/v2/test/delete/42");
+    }
+
+    #[cfg(feature = "tracing")]
+    #[test]
+    fn tracing_signed_requests() {
+        use crate::util::test_util::capture_tracing_events;
+
+        let credentials = test_credentials();
+        let (reqs, events) = capture_tracing_events(|| {
+            let get = TestGetApi {
+                market: "btctwd".into(),
+            }
+            .to_request(&credentials);
+            let post = TestPostApi {
+                market: "btctwd".into(),
+            }
+            .to_request(&credentials);
+            vec![get, post]
+        });
+        assert_eq!(events.len(), 2);
+        assert_eq!(events[0]["method"], "GET");
+        assert_eq!(events[0]["path"], "/api/v2/test/get");
+        assert_eq!(events[0]["body_len"], "0");
+        assert_eq!(events[1]["method"], "POST");
+        assert_eq!(events[1]["path"], "/api/v2/test/post");
+        assert_ne!(events[1]["body_len"], "0");
+
+        for (req, event) in reqs.iter().zip(events.iter()) {
+            let payload = req[HEADER_AUTH_PAYLOAD].as_str();
+            let signature = req[HEADER_AUTH_SIGNATURE].as_str();
+            assert_eq!(event["payload_len"], payload.len().to_string());
+            for value in event.values() {
+                assert!(!value.contains(&credentials.access_key));
+                assert!(!value.contains(payload));
+                assert!(!value.contains(signature));
+                assert!(!value.contains("btctwd"));
+            }
+        }
+    }
+
+    #[cfg(feature = "tracing")]
+    #[test]
+    fn tracing_read_response() {
+        use crate::util::test_util::capture_tracing_events;
+
+        let body = r#"{"error":{"code":2004,"message":"Order#1 not found."}}"#;
+        let (result, events) = capture_tracing_events(|| {
+            let mut resp = HTTPResponse::new(StatusCode::NotFound);
+            resp.set_body(body);
+            async_std::task::block_on(TestGetApi::read_response(resp))
+        });
+        assert!(result.unwrap_err().is_order_not_found());
+        assert_eq!(events.len(), 1);
+        let event = &events[0];
+        assert!(event["endpoint"].ends_with("TestGetApi"));
+        assert_eq!(event["status"], "404");
+        assert_eq!(event["body_len"], body.len().to_string());
+        assert_eq!(event["error_code"], "2004");
+        assert_eq!(event["parsed"], "true");
+        assert!(event.contains_key("parse_us"));
+        assert!(event.values().all(|value| !value.contains("not found")));
+    }
+
+    #[test]
+    fn invalid_api_host() {
+        assert!(matches!(
+            ApiHost::parse("127.0.0.1:8080"),
+            Err(Error::InvalidUrl(_))
+        ));
+    }
+
+    #[test]
+    fn unsignable_payload() {
+        let params = TestUnsignablePostApi {
+            weights: vec![((1, 2), 3)].into_iter().collect(),
+        };
+        match params.try_to_request(&test_credentials()) {
+            Err(Error::InvalidParams(msg)) => assert!(msg.starts_with("unable to sign payload")),
+            other => panic!("expect invalid params, got {:?}", other),
+        }
+    }
 }
@@ -0,0 +1,265 @@
+//! A batteries-included REST client built on [Surf](https://crates.io/crates/surf), enabled by `client-surf` feature.
+//!
+//! [`MaxRestClient`] is a thin wrapper over the request structures: it owns the HTTP client and credentials, builds
+//! and signs the requests, waits for the rate limiter, retries failed calls, and parses the responses. Scripts may use
+//! it directly, while the request structures remain the primary API for fine-grained control.
+//!
+//! ```ignore
+//! let max = MaxRestClient::new(Credentials::from_env("MAX_ACCESS_KEY", "MAX_SECRET_KEY"))
+//!     .with_retry(RetryPlan::new(5));
+//! let accounts = max.accounts().await?;
+//! let order = max.create_order(CreateOrder { ... }).await?;
+//! ```
+
+use std::sync::Mutex;
+use std::time::Duration;
+
+use futures_timer::Delay;
+
+use crate::error::*;
+use crate::util::ratelimit::RateLimiter;
+use crate::v2::rest::*;
+use crate::Credentials;
+
+/// REST client owning an HTTP client, credentials, API host, and optional rate limiter and retry policy.
+#[derive(Debug)]
+pub struct MaxRestClient {
+    client: surf::Client,
+    credentials: Credentials,
+    host: ApiHost,
+    options: Option<RequestOptions>,
+    limiter: Option<Mutex<RateLimiter>>,
+    retry: Option<RetryPlan>,
+}
+
+impl MaxRestClient {
+    /// Create the client to [`BASE_URL`], without rate limiting and retrying.
+    pub fn new(credentials: Credentials) -> Self {
+        Self {
+            client: surf::Client::new(),
+            credentials,
+            host: ApiHost::default(),
+            options: None,
+            limiter: None,
+            retry: None,
+        }
+    }
+
+    /// Send requests with the given Surf client, e.g. one with middlewares.
+    pub fn with_client(mut self, client: surf::Client) -> Self {
+        self.client = client;
+        self
+    }
+
+    /// Send requests to the given API host.
+    pub fn with_host(mut self, host: ApiHost) -> Self {
+        self.host = host;
+        self
+    }
+
+    /// Send requests with the `User-Agent` and extra headers of the options.
+    pub fn with_options(mut self, options: RequestOptions) -> Self {
+        self.options = Some(options);
+        self
+    }
+
+    /// Wait for the budget of the rate limiter before sending each request.
+    pub fn with_rate_limiter(mut self, limiter: RateLimiter) -> Self {
+        self.limiter = Some(Mutex::new(limiter));
+        self
+    }
+
+    /// Retry failed calls by the plan, which is started over for each call.
+    pub fn with_retry(mut self, plan: RetryPlan) -> Self {
+        self.retry = Some(plan);
+        self
+    }
+
+    /// Call any endpoint, and parse the response.
+    pub async fn call<E: Endpoint>(&self, params: &E) -> Result<E::Response> {
+        let mut plan = self.retry.clone();
+        if let Some(plan) = plan.as_mut() {
+            plan.reset();
+        }
+        loop {
+            let err = match self.call_once(params).await {
+                Ok(resp) => return Ok(resp),
+                Err(err) => err,
+            };
+            match plan.as_mut().map(|plan| plan.decide(params, &err)) {
+                Some(Retry::After(delay)) | Some(Retry::AfterRebuild(delay)) => sleep(delay).await,
+                Some(Retry::No) | None => return Err(err),
+            }
+        }
+    }
+
+    async fn call_once<E: Endpoint>(&self, params: &E) -> Result<E::Response> {
+        while let Some(wait) = self.acquire(params) {
+            sleep(wait).await;
+        }
+        let req = match &self.options {
+            Some(options) => {
+                params.try_prepare_with_options(&self.host, Some(&self.credentials), options)?
+            }
+            None => params.try_prepare_with(&self.host, Some(&self.credentials))?,
+        };
+        let resp = self.client.execute(req).await?;
+        E::read_response(resp).await
+    }
+
+    // time to wait if the budget is used up
+    fn acquire<E: Endpoint>(&self, params: &E) -> Option<Duration> {
+        let limiter = self.limiter.as_ref()?;
+        let mut limiter = limiter
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        limiter.try_acquire_for(params).err().map(|wait| wait.0)
+    }
+
+    /// All accounts of the user, from [`GetProfileAndAccount`].
+    pub async fn accounts(&self) -> Result<Vec<RespAccountCurrencyInfo>> {
+        let profile = self.get_profile_and_account().await?;
+        Ok(profile.accounts.unwrap_or_default())
+    }
+}
+
+async fn sleep(duration: Duration) {
+    if !duration.is_zero() {
+        Delay::new(duration).await;
+    }
+}
+
+// One method per request type. Request types without fields are called without parameters.
+macro_rules! client_methods {
+    ($($name:ident => $api:ident $({})? $(($params:ident))?;)+) => {
+        impl MaxRestClient {
+            $(
+                client_methods!(@method $name => $api $(($params))?);
+            )+
+        }
+    };
+    (@method $name:ident => $api:ident) => {
+        #[doc = concat!("Call [`", stringify!($api), "`].")]
+        pub async fn $name(&self) -> Result<<$api as Endpoint>::Response> {
+            self.call(&$api {}).await
+        }
+    };
+    (@method $name:ident => $api:ident ($params:ident)) => {
+        #[doc = concat!("Call [`", stringify!($api), "`].")]
+        pub async fn $name(&self, $params: $api) -> Result<<$api as Endpoint>::Response> {
+            self.call(&$params).await
+        }
+    };
+}
+
+client_methods! {
+    // public
+    get_vip_levels => GetVIPLevels {};
+    get_vip_by_level => GetVIPByLevel (params);
+    get_currencies => GetCurrencies {};
+    get_timestamp => GetTimestamp {};
+    get_withdrawal_constraints => GetWithdrawalConstraints (params);
+    get_ohlc => GetOHLC (params);
+    get_depth => GetDepth (params);
+    get_public_trades => GetPublicTrades (params);
+    get_markets => GetMarkets {};
+    get_markets_summary => GetMarketsSummary {};
+    get_tickers => GetTickers {};
+    get_tickers_of_market => GetTickersOfMarket (params);
+    // orders and trades
+    get_order => GetOrder (params);
+    get_orders => GetOrders (params);
+    create_order => CreateOrder (params);
+    delete_order => DeleteOrder (params);
+    clear_orders => ClearOrders (params);
+    get_my_trades_of_order => GetMyTradesOfOrder (params);
+    get_my_trades => GetMyTrades (params);
+    // withdrawals
+    get_withdrawal => GetWithdrawal (params);
+    get_withdrawals => GetWithdrawals (params);
+    create_withdrawal => CreateWithdrawal (params);
+    get_withdraw_addresses => GetWithdrawAddresses (params);
+    // deposits
+    get_deposits => GetDeposits (params);
+    get_deposit_detail => GetDepositDetail (params);
+    get_deposit_addresses => GetDepositAddresses (params);
+    create_deposit_address => CreateDepositAddress (params);
+    // members
+    get_profile => GetProfile {};
+    get_profile_and_account => GetProfileAndAccount {};
+    get_account_vip_level => GetAccountVIPLevel {};
+    get_account_of_currency => GetAccountOfCurrency (params);
+    get_internal_transfers => GetInternalTransfers (params);
+    get_internal_transfer_by_uuid => GetInternalTransferByUUID (params);
+    get_rewards => GetRewards (params);
+    get_rewards_of_type => GetRewardsOfType (params);
+    get_saving_interest_history => GetSavingInterestHistory (params);
+    get_max_rewards_yesterday => GetMaxRewardsYesterday {};
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::OrderSide;
+    use crate::util::ratelimit::Quota;
+    use crate::util::test_util::*;
+    use surf_vcr::VcrMode;
+
+    async fn create_client(cassette: &str) -> MaxRestClient {
+        let mut path_builder = test_resource_path();
+        path_builder.extend(cassette.split('/'));
+        let client =
+            create_test_recording_client(VcrMode::Replay, path_builder.as_path().to_str().unwrap())
+                .await;
+        MaxRestClient::new(Credentials::from_env(
+            "MAX_TEST_ACCESS_KEY",
+            "MAX_TEST_SECRET_KEY",
+        ))
+        .with_client(client)
+    }
+
+    #[async_std::test]
+    async fn call_public() {
+        let max = create_client("rest/public/misc/get_currencies.yaml").await;
+        let currencies = max.get_currencies().await.unwrap();
+        assert_eq!(currencies[0].id, "twd");
+
+        let max = create_client("rest/public/misc/get_timestamp.yaml")
+            .await
+            .with_rate_limiter(RateLimiter::new(
+                Quota::new(1, Duration::from_secs(1)),
+                Quota::new(1, Duration::from_secs(1)),
+            ));
+        assert_eq!(max.get_timestamp().await.unwrap().0, 1636258261);
+    }
+
+    #[async_std::test]
+    async fn call_auth() {
+        let max = create_client("rest/private/order/get_single_order.yaml").await;
+        let order = max
+            .get_order(GetOrder {
+                id: Some(1545763894),
+                client_oid: None,
+            })
+            .await
+            .unwrap();
+        assert_eq!(order.id, Some(1545763894));
+        assert_eq!(order.side, OrderSide::Buy);
+
+        let max = create_client("rest/private/misc/get_profile_and_account.yaml").await;
+        // the recorded account holds no balance
+        assert_eq!(max.accounts().await.unwrap(), Vec::new());
+    }
+
+    #[async_std::test]
+    async fn call_with_retry() {
+        // maintenance is retried until the attempts run out
+        let max = create_client("rest/public/misc/get_timestamp_maintenance.yaml")
+            .await
+            .with_retry(RetryPlan::new(2).with_backoff(Duration::ZERO, Duration::ZERO));
+        match max.get_timestamp().await {
+            Err(Error::Maintenance(status)) => assert_eq!(status, 503),
+            other => panic!("expect maintenance error, got {:?}", other),
+        }
+    }
+}
@@ -4,7 +4,24 @@
 //! structures provide the following methods:
 //!
 //! - `to_request(&self)` or `to_request(&self, &crate::Credentials)`: create `http_types::Request` and ready to be sent.
+//! - `to_request_with(&self, &ApiHost, ...)`: same as `to_request`, but targets the given [`ApiHost`] instead of
+//!   [`BASE_URL`].
+//! - `try_to_request(...)` and `try_to_request_with(...)`: fallible variants of the above, which return an error
+//!   instead of panicking when the request can not be built, e.g. a dynamic path segment is `..`.
+//! - `to_request_with_options(&self, &ApiHost, ..., &RequestOptions)`: same as `try_to_request_with`, with the
+//!   `User-Agent` and extra headers given by [`RequestOptions`].
 //! - `read_response(http_types::Response)`: parse the response.
+//! - `read_response_enveloped(http_types::Response)`: parse the response into an [`Envelope`], which also carries the
+//!   status code and a few informative headers.
+//! - `read_response_raw(http_types::Response)`: parse the response, and also return the untouched JSON body, e.g. to
+//!   audit fields not modeled by the response types.
+//!
+//! All the parameter structures also implement [`Endpoint`], which allows writing generic code over endpoints, and can
+//! be sent by any HTTP client implementing [`HttpExecutor`] with [`execute`] or [`execute_auth`]. To parse responses
+//! without mixing up endpoints, [`Endpoint::prepare_typed`] binds the request with a typed [`ResponseReader`]. Request
+//! parameters of paginated endpoints implement [`Paginated`], and [`Paginator`] helps to walk through all the pages.
+//! Larger parameter structures come with builders, e.g. [`GetOrders::for_market`], which take the required fields up
+//! front and leave the others unset.
 //!
 //! ```ignore
 //! let client = surf::Client::new();
@@ -20,11 +37,54 @@
 
 pub(crate) mod internal;
 
+use std::collections::HashMap;
+use std::fmt;
+use std::future::Future;
+use std::marker::PhantomData;
+use std::pin::Pin;
+
+use http_types::headers::{HeaderName, HeaderValue};
+use http_types::{
+    Method as HTTPMethod, Request as HTTPRequest, Response as HTTPResponse, StatusCode,
+    Url as HTTPURL,
+};
+
+use crate::error::*;
+use crate::Credentials;
+
+#[cfg(feature = "blocking")]
+mod client_blocking;
+#[cfg(feature = "reqwest")]
+mod client_reqwest;
+#[cfg(feature = "client-surf")]
+mod client_surf;
+mod executor;
+mod fee;
+#[cfg(feature = "http")]
+mod http_compat;
+#[cfg(feature = "client-surf")]
+mod max_client;
+mod pagination;
 mod private;
 mod public;
+mod retry;
 
+#[cfg(feature = "blocking")]
+pub use client_blocking::*;
+#[cfg(feature = "reqwest")]
+pub use client_reqwest::*;
+#[cfg(feature = "client-surf")]
+pub use client_surf::*;
+pub use executor::*;
+pub use fee::*;
+#[cfg(feature = "http")]
+pub use http_compat::*;
+#[cfg(feature = "client-surf")]
+pub use max_client::*;
+pub use pagination::*;
 pub use private::*;
 pub use public::*;
+pub use retry::*;
 
 // =========
 // Utilities
@@ -35,25 +95,18 @@ pub(crate) mod api_impl {
         () => {
             "https://max-api.maicoin.com"
         };
-        ($endpoint:literal) => {
-            concat!(api_url!(), $endpoint)
-        };
-        (dynamic $fmt:literal, $($args:tt)*) => {
-            format!(concat!(api_url!(), $fmt), $($args)*)
-        };
     }
     pub(crate) use api_url;
 
     macro_rules! endpoint_binding {
         (dynamic $sel:ident $gen_endpoint:block) => {
-            fn get_url(&self) -> http_types::Url {
-                http_types::Url::parse((|$sel: &Self| $gen_endpoint)(self).as_str())
-                    .expect("unexpected invalid API URL")
+            fn get_path(&self) -> String {
+                (|$sel: &Self| $gen_endpoint)(self)
             }
         };
         (fixed $endpoint:literal) => {
-            fn get_url(&self) -> http_types::Url {
-                http_types::Url::parse(api_url!($endpoint)).expect("unexpected invalid API URL")
+            fn get_path(&self) -> String {
+                $endpoint.into()
             }
         };
     }
@@ -62,33 +115,241 @@ pub(crate) mod api_impl {
     macro_rules! convert_to_request {
         (GET) => {
             pub fn to_request(&self) -> http_types::Request {
-                crate::v2::rest::internal::make_unauth_get(self)
+                self.to_request_with(&Default::default())
+            }
+
+            pub fn to_request_with(&self, host: &crate::v2::rest::ApiHost) -> http_types::Request {
+                self.try_to_request_with(host)
+                    .expect("failed to build request")
+            }
+
+            pub fn try_to_request(&self) -> crate::error::Result<http_types::Request> {
+                self.try_to_request_with(&Default::default())
+            }
+
+            pub fn try_to_request_with(
+                &self,
+                host: &crate::v2::rest::ApiHost,
+            ) -> crate::error::Result<http_types::Request> {
+                crate::v2::rest::internal::make_unauth_get(self, host)
+            }
+
+            pub fn to_request_with_options(
+                &self,
+                host: &crate::v2::rest::ApiHost,
+                options: &crate::v2::rest::RequestOptions,
+            ) -> crate::error::Result<http_types::Request> {
+                let mut req = self.try_to_request_with(host)?;
+                options.apply(&mut req)?;
+                Ok(req)
             }
         };
         (auth GET) => {
-            pub fn to_request(&self, credentials: &crate::Credentials) -> http_types::Request {
-                crate::v2::rest::internal::make_auth_get(self, credentials)
-            }
+            convert_to_request!(auth make_auth_get);
         };
         (auth POST) => {
+            convert_to_request!(auth make_auth_post);
+        };
+        (auth DELETE) => {
+            convert_to_request!(auth make_auth_delete);
+        };
+        (auth PUT) => {
+            convert_to_request!(auth make_auth_put);
+        };
+        (auth $make_request:ident) => {
             pub fn to_request(&self, credentials: &crate::Credentials) -> http_types::Request {
-                crate::v2::rest::internal::make_auth_post(self, credentials)
+                self.to_request_with(&Default::default(), credentials)
+            }
+
+            pub fn to_request_with(
+                &self,
+                host: &crate::v2::rest::ApiHost,
+                credentials: &crate::Credentials,
+            ) -> http_types::Request {
+                self.try_to_request_with(host, credentials)
+                    .expect("failed to build request")
+            }
+
+            pub fn try_to_request(
+                &self,
+                credentials: &crate::Credentials,
+            ) -> crate::error::Result<http_types::Request> {
+                self.try_to_request_with(&Default::default(), credentials)
+            }
+
+            pub fn try_to_request_with(
+                &self,
+                host: &crate::v2::rest::ApiHost,
+                credentials: &crate::Credentials,
+            ) -> crate::error::Result<http_types::Request> {
+                self.try_to_request_with_nonce(host, credentials, credentials.nonce())
+            }
+
+            /// Create the request signed with the given nonce, instead of the next one of the credentials. The caller
+            /// is responsible to allocate nonces, which should be increasing and close to the current time.
+            pub fn try_to_request_with_nonce(
+                &self,
+                host: &crate::v2::rest::ApiHost,
+                credentials: &crate::Credentials,
+                nonce: u64,
+            ) -> crate::error::Result<http_types::Request> {
+                crate::v2::rest::internal::$make_request(self, host, credentials, nonce)
+            }
+
+            pub fn to_request_with_options(
+                &self,
+                host: &crate::v2::rest::ApiHost,
+                credentials: &crate::Credentials,
+                options: &crate::v2::rest::RequestOptions,
+            ) -> crate::error::Result<http_types::Request> {
+                let mut req = self.try_to_request_with(host, credentials)?;
+                options.apply(&mut req)?;
+                Ok(req)
             }
         };
     }
     pub(crate) use convert_to_request;
 
+    macro_rules! http_method {
+        (GET) => {
+            http_types::Method::Get
+        };
+        (POST) => {
+            http_types::Method::Post
+        };
+        (DELETE) => {
+            http_types::Method::Delete
+        };
+        (PUT) => {
+            http_types::Method::Put
+        };
+    }
+    pub(crate) use http_method;
+
+    macro_rules! impl_endpoint {
+        ($api:ty => $resp:ty : GET) => {
+            impl crate::v2::rest::Endpoint for $api {
+                const METHOD: http_types::Method = http_method!(GET);
+                const AUTH: bool = false;
+                type Response = $resp;
+
+                fn path(&self) -> String {
+                    <Self as crate::v2::rest::internal::RestApiBase>::get_path(self)
+                }
+
+                fn try_prepare_with(
+                    &self,
+                    host: &crate::v2::rest::ApiHost,
+                    _credentials: Option<&crate::Credentials>,
+                ) -> crate::error::Result<http_types::Request> {
+                    self.try_to_request_with(host)
+                }
+
+                fn read_response(
+                    resp: http_types::Response,
+                ) -> crate::v2::rest::ResponseFuture<Self::Response> {
+                    <Self as crate::v2::rest::internal::RestApiBase>::read_response(resp)
+                }
+            }
+            impl crate::v2::rest::PublicEndpoint for $api {}
+        };
+        ($api:ty => $resp:ty : auth $method:ident $(, idempotent $sel:ident $check:block)?) => {
+            impl crate::v2::rest::Endpoint for $api {
+                const METHOD: http_types::Method = http_method!($method);
+                const AUTH: bool = true;
+                type Response = $resp;
+
+                $(
+                    #[allow(clippy::redundant_closure_call)]
+                    fn is_idempotent(&self) -> bool {
+                        (|$sel: &Self| $check)(self)
+                    }
+                )?
+
+                fn path(&self) -> String {
+                    <Self as crate::v2::rest::internal::RestApiBase>::get_path(self)
+                }
+
+                fn try_prepare_with(
+                    &self,
+                    host: &crate::v2::rest::ApiHost,
+                    credentials: Option<&crate::Credentials>,
+                ) -> crate::error::Result<http_types::Request> {
+                    match credentials {
+                        Some(credentials) => self.try_to_request_with(host, credentials),
+                        None => Err(crate::error::Error::InvalidParams(format!(
+                            "credentials required by {}",
+                            <Self as crate::v2::rest::Endpoint>::path(self)
+                        ))),
+                    }
+                }
+
+                fn read_response(
+                    resp: http_types::Response,
+                ) -> crate::v2::rest::ResponseFuture<Self::Response> {
+                    <Self as crate::v2::rest::internal::RestApiBase>::read_response(resp)
+                }
+            }
+            impl crate::v2::rest::AuthEndpoint for $api {}
+            impl_auth_get_debug!($api : $method);
+        };
+    }
+    pub(crate) use impl_endpoint;
+
+    macro_rules! impl_auth_get_debug {
+        ($api:ty : GET) => {
+            impl crate::v2::rest::AuthGetDebug for $api {
+                fn signed_parts_with(
+                    &self,
+                    host: &crate::v2::rest::ApiHost,
+                    credentials: &crate::Credentials,
+                    nonce: u64,
+                ) -> crate::error::Result<(http_types::Url, String, String)> {
+                    crate::v2::rest::internal::signed_query(self, host, credentials, nonce)
+                }
+            }
+        };
+        ($api:ty : $method:ident) => {};
+    }
+    pub(crate) use impl_auth_get_debug;
+
     macro_rules! convert_from_response {
         ($resp:ty) => {
             pub async fn read_response(resp: http_types::Response) -> crate::error::Result<$resp> {
                 <Self as crate::v2::rest::internal::RestApiBase>::read_response(resp).await
             }
+
+            pub async fn read_response_enveloped(
+                resp: http_types::Response,
+            ) -> crate::error::Result<crate::v2::rest::Envelope<$resp>> {
+                <Self as crate::v2::rest::internal::RestApiBase>::read_response_enveloped(resp)
+                    .await
+            }
+
+            pub async fn read_response_raw(
+                resp: http_types::Response,
+            ) -> crate::error::Result<($resp, serde_json::Value)> {
+                <Self as crate::v2::rest::internal::RestApiBase>::read_response_raw(resp).await
+            }
         };
     }
     pub(crate) use convert_from_response;
 
+    // Type to deserialize the response body into, then converted into the response. Replies of some endpoints vary in
+    // shape, e.g. `Vec<T> as OneOrMany<T>` accepts either an object or an array.
+    macro_rules! response_body {
+        ($resp:ty) => {
+            $resp
+        };
+        ($resp:ty as $body:ty) => {
+            $body
+        };
+    }
+    pub(crate) use response_body;
+
     macro_rules! impl_api {
-        ($api:ty => $resp:ty : GET, $endpoint:literal) => {
+        ($api:ty => $resp:ty $(as $body:ty)? : GET, $endpoint:literal) => {
+            impl_endpoint!($api => $resp : GET);
             impl $api {
                 convert_to_request!(GET);
                 convert_from_response!($resp);
@@ -96,9 +357,11 @@ pub(crate) mod api_impl {
             impl crate::v2::rest::internal::RestApiBase for $api {
                 endpoint_binding!(fixed $endpoint);
                 type Response = $resp;
+                type Body = response_body!($resp $(as $body)?);
             }
         };
-        ($api:ty => $resp:ty : GET, dynamic $sel:ident $gen_endpoint:block) => {
+        ($api:ty => $resp:ty $(as $body:ty)? : GET, dynamic $sel:ident $gen_endpoint:block) => {
+            impl_endpoint!($api => $resp : GET);
             impl $api {
                 convert_to_request!(GET);
                 convert_from_response!($resp);
@@ -107,52 +370,99 @@ pub(crate) mod api_impl {
             impl crate::v2::rest::internal::RestApiBase for $api {
                 endpoint_binding!(dynamic $sel $gen_endpoint);
                 type Response = $resp;
+                type Body = response_body!($resp $(as $body)?);
             }
         };
-        ($api:ty => $resp:ty : auth GET, $endpoint:literal) => {
+        ($api:ty => $resp:ty $(as $body:ty)? : auth $method:ident, $endpoint:literal $(, idempotent $sel:ident $check:block)?) => {
+            impl_endpoint!($api => $resp : auth $method $(, idempotent $sel $check)?);
             impl $api {
-                convert_to_request!(auth GET);
+                convert_to_request!(auth $method);
                 convert_from_response!($resp);
             }
             impl crate::v2::rest::internal::RestApiBase for $api {
                 endpoint_binding!(fixed $endpoint);
                 type Response = $resp;
+                type Body = response_body!($resp $(as $body)?);
             }
         };
-        ($api:ty => $resp:ty : auth GET, dynamic $sel:ident $gen_endpoint:block) => {
+        ($api:ty => $resp:ty $(as $body:ty)? : auth $method:ident, dynamic $sel:ident $gen_endpoint:block $(, idempotent $check_sel:ident $check:block)?) => {
+            impl_endpoint!($api => $resp : auth $method $(, idempotent $check_sel $check)?);
             impl $api {
-                convert_to_request!(auth GET);
+                convert_to_request!(auth $method);
                 convert_from_response!($resp);
             }
             #[allow(clippy::redundant_closure_call)]
             impl crate::v2::rest::internal::RestApiBase for $api {
                 endpoint_binding!(dynamic $sel $gen_endpoint);
                 type Response = $resp;
+                type Body = response_body!($resp $(as $body)?);
             }
         };
-        ($api:ty => $resp:ty : auth POST, $endpoint:literal) => {
-            impl $api {
-                convert_to_request!(auth POST);
-                convert_from_response!($resp);
-            }
-            impl crate::v2::rest::internal::RestApiBase for $api {
-                endpoint_binding!(fixed $endpoint);
-                type Response = $resp;
+    }
+    pub(crate) use impl_api;
+
+    macro_rules! impl_paginated {
+        ($api:ty) => {
+            impl crate::v2::rest::Paginated for $api {
+                fn page_params(&self) -> Option<&crate::common::PageParams> {
+                    self.page_params.as_ref()
+                }
+
+                fn set_page_params(&mut self, page_params: crate::common::PageParams) {
+                    self.pagination = Some(true);
+                    self.page_params = Some(page_params);
+                }
             }
         };
-        ($api:ty => $resp:ty : auth POST, dynamic $sel:ident $gen_endpoint:block) => {
-            impl $api {
-                convert_to_request!(auth POST);
-                convert_from_response!($resp);
+        // paging parameters held by a nested field, e.g. `TimeRangeQuery`
+        ($api:ty, $field:ident) => {
+            impl crate::v2::rest::Paginated for $api {
+                fn page_params(&self) -> Option<&crate::common::PageParams> {
+                    self.$field.page_params.as_ref()
+                }
+
+                fn set_page_params(&mut self, page_params: crate::common::PageParams) {
+                    self.$field.pagination = Some(true);
+                    self.$field.page_params = Some(page_params);
+                }
             }
-            #[allow(clippy::redundant_closure_call)]
-            impl crate::v2::rest::internal::RestApiBase for $api {
-                endpoint_binding!(dynamic $sel $gen_endpoint);
-                type Response = $resp;
+        };
+    }
+    pub(crate) use impl_paginated;
+
+    // Paging setters of a request builder, writing the fields reached by the given path.
+    macro_rules! impl_builder_paging {
+        ($builder:ty => $($field:ident).+) => {
+            impl $builder {
+                /// Query the given page, which turns pagination on.
+                pub fn page(mut self, page: u64) -> Self {
+                    self.$($field).+.pagination = Some(true);
+                    self.$($field).+.page_params.get_or_insert_with(Default::default).page = page;
+                    self
+                }
+
+                /// Records returned per page, which turns pagination on.
+                pub fn limit(mut self, limit: u64) -> Self {
+                    self.$($field).+.pagination = Some(true);
+                    self.$($field).+.page_params.get_or_insert_with(Default::default).limit = limit;
+                    self
+                }
+
+                /// Turn pagination on or off explicitly.
+                pub fn pagination(mut self, pagination: bool) -> Self {
+                    self.$($field).+.pagination = Some(pagination);
+                    self
+                }
+
+                /// Records to skip, not applied for pagination.
+                pub fn offset(mut self, offset: u64) -> Self {
+                    self.$($field).+.offset = Some(offset);
+                    self
+                }
             }
         };
     }
-    pub(crate) use impl_api;
+    pub(crate) use impl_builder_paging;
 }
 
 // ================
@@ -161,3 +471,591 @@ pub(crate) mod api_impl {
 
 /// The RESTful API base URL.
 pub const BASE_URL: &str = api_impl::api_url!();
+
+// ==========
+// Interfaces
+// ==========
+
+/// Future of parsing an API response, see [`Endpoint::read_response`].
+pub type ResponseFuture<T> = Pin<Box<dyn Future<Output = Result<T>> + Send>>;
+
+/// Common interface of API endpoints, implemented by all the request parameter structures in this module.
+///
+/// It allows writing generic code over endpoints, e.g. with [Surf](https://crates.io/crates/surf):
+///
+/// ```ignore
+/// async fn call<E: Endpoint>(
+///     client: &surf::Client,
+///     params: &E,
+///     credentials: Option<&Credentials>,
+/// ) -> Result<E::Response> {
+///     let resp = client.send(params.try_prepare(credentials)?).await?;
+///     E::read_response(resp.into()).await
+/// }
+/// ```
+pub trait Endpoint {
+    /// HTTP method of the endpoint.
+    const METHOD: HTTPMethod;
+    /// `true` if the endpoint requires [`Credentials`] to access.
+    const AUTH: bool;
+    /// Parsed response content.
+    type Response;
+
+    /// API path of the endpoint, e.g. `/api/v2/orders`.
+    fn path(&self) -> String;
+
+    /// Endpoint URL on the given API host. Fails with [`Error::InvalidUrl`] if the dynamic API path is malformed.
+    fn url(&self, host: &ApiHost) -> Result<HTTPURL> {
+        host.try_endpoint_url(&self.path())
+    }
+
+    /// Create the request to [`BASE_URL`], see [`Endpoint::try_prepare_with`].
+    fn try_prepare(&self, credentials: Option<&Credentials>) -> Result<HTTPRequest> {
+        self.try_prepare_with(&ApiHost::default(), credentials)
+    }
+
+    /// Create the request to the given API host. Credentials are ignored by public endpoints, and required by
+    /// authenticated endpoints.
+    ///
+    /// Fails with [`Error::InvalidUrl`] if the dynamic API path is malformed (e.g. a `..` path segment), or
+    /// [`Error::InvalidParams`] if the parameters can not be serialized or no credentials given to an authenticated
+    /// endpoint.
+    fn try_prepare_with(
+        &self,
+        host: &ApiHost,
+        credentials: Option<&Credentials>,
+    ) -> Result<HTTPRequest>;
+
+    /// Create the request to the given API host like [`Endpoint::try_prepare_with`], with the headers of the given
+    /// [`RequestOptions`].
+    fn try_prepare_with_options(
+        &self,
+        host: &ApiHost,
+        credentials: Option<&Credentials>,
+        options: &RequestOptions,
+    ) -> Result<HTTPRequest> {
+        let mut req = self.try_prepare_with(host, credentials)?;
+        options.apply(&mut req)?;
+        Ok(req)
+    }
+
+    /// Parse the response.
+    fn read_response(resp: HTTPResponse) -> ResponseFuture<Self::Response>;
+
+    /// `true` if sending the request more than once has the same effect as sending it once, which means it is safe to
+    /// retry after a transport error. All requests except POST are idempotent by default.
+    fn is_idempotent(&self) -> bool {
+        Self::METHOD != HTTPMethod::Post
+    }
+
+    /// Create the request to [`BASE_URL`] along with its typed response reader, see [`PreparedRequest`].
+    fn prepare_typed(&self, credentials: Option<&Credentials>) -> Result<PreparedRequest<Self>>
+    where
+        Self: Sized,
+    {
+        self.prepare_typed_with(&ApiHost::default(), credentials)
+    }
+
+    /// Create the request to the given API host along with its typed response reader, see [`PreparedRequest`].
+    fn prepare_typed_with(
+        &self,
+        host: &ApiHost,
+        credentials: Option<&Credentials>,
+    ) -> Result<PreparedRequest<Self>>
+    where
+        Self: Sized,
+    {
+        Ok(PreparedRequest {
+            request: self.try_prepare_with(host, credentials)?,
+            reader: ResponseReader(PhantomData),
+        })
+    }
+}
+
+/// Request bound to the endpoint it is built from, so that the response can only be parsed as the response of that
+/// endpoint.
+///
+/// ```ignore
+/// let (req, reader) = GetOrders::for_market("btctwd").build().prepare_typed(Some(&credentials))?.into_parts();
+/// let resp = client.send(req).await?;
+/// let orders: Vec<RespOrder> = reader.read(resp.into()).await?;
+/// ```
+#[derive(Debug)]
+pub struct PreparedRequest<E> {
+    request: HTTPRequest,
+    reader: ResponseReader<E>,
+}
+
+impl<E: Endpoint> PreparedRequest<E> {
+    /// The request to be sent.
+    pub fn request(&self) -> &HTTPRequest {
+        &self.request
+    }
+
+    /// The request to be sent, e.g. to add headers.
+    pub fn request_mut(&mut self) -> &mut HTTPRequest {
+        &mut self.request
+    }
+
+    /// Split into the request to be sent, and the reader to parse its response.
+    pub fn into_parts(self) -> (HTTPRequest, ResponseReader<E>) {
+        (self.request, self.reader)
+    }
+}
+
+/// Parser of the response of endpoint `E`, see [`PreparedRequest`].
+pub struct ResponseReader<E>(PhantomData<fn() -> E>);
+
+impl<E: Endpoint> ResponseReader<E> {
+    /// Parse the response, same as [`Endpoint::read_response`] of `E`.
+    pub fn read(self, resp: HTTPResponse) -> ResponseFuture<E::Response> {
+        E::read_response(resp)
+    }
+}
+
+impl<E> fmt::Debug for ResponseReader<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "ResponseReader<{}>", std::any::type_name::<E>())
+    }
+}
+
+/// Inspect the signed parts of authenticated GET requests without sending them, e.g. for logging or verifying
+/// signatures.
+pub trait AuthGetDebug: AuthEndpoint {
+    /// Returns `(url, payload, signature)` of the request to [`BASE_URL`], signed with the next nonce of credentials.
+    ///
+    /// This consumes a nonce, so the request created afterwards would not be identical.
+    fn signed_parts(&self, credentials: &Credentials) -> Result<(HTTPURL, String, String)> {
+        self.signed_parts_with(&ApiHost::default(), credentials, credentials.nonce())
+    }
+
+    /// Returns `(url, payload, signature)` of the request to the given API host, signed with the given nonce.
+    fn signed_parts_with(
+        &self,
+        host: &ApiHost,
+        credentials: &Credentials,
+        nonce: u64,
+    ) -> Result<(HTTPURL, String, String)>;
+}
+
+/// Marker of public endpoints, which are accessible without [`Credentials`].
+pub trait PublicEndpoint: Endpoint {}
+
+/// Marker of authenticated endpoints, which require [`Credentials`] to access.
+pub trait AuthEndpoint: Endpoint {}
+
+// =========
+// Envelopes
+// =========
+
+// Headers captured by `Envelope`, matched case-insensitively. Headers prefixed with `x-ratelimit-` are also kept.
+const ENVELOPE_HEADERS: &[&str] = &[
+    "date",
+    "x-request-id",
+    "page",
+    "per-page",
+    "total",
+    "total-pages",
+    "retry-after",
+];
+const ENVELOPE_HEADER_PREFIX_RATELIMIT: &str = "x-ratelimit-";
+
+/// Parsed response content along with the status code and informative headers of the response.
+///
+/// Only a few headers are kept: `Date`, `X-Request-Id`, pagination headers (`Page`, `Per-Page`, `Total`,
+/// `Total-Pages`), `Retry-After` and `X-RateLimit-*`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Envelope<T> {
+    /// Parsed response content.
+    pub body: T,
+    /// HTTP status code of the response.
+    pub status: StatusCode,
+    headers: HashMap<String, String>,
+}
+
+impl<T> Envelope<T> {
+    pub(crate) fn capture(resp: &HTTPResponse, body: T) -> Self {
+        let headers = resp
+            .iter()
+            .filter_map(|(name, values)| {
+                let name = name.as_str().to_ascii_lowercase();
+                let interested = ENVELOPE_HEADERS.contains(&name.as_str())
+                    || name.starts_with(ENVELOPE_HEADER_PREFIX_RATELIMIT);
+                interested.then(|| (name, values.last().as_str().to_owned()))
+            })
+            .collect();
+        Self {
+            body,
+            status: resp.status(),
+            headers,
+        }
+    }
+
+    /// Value of the captured header, the name is case-insensitive.
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .get(&name.to_ascii_lowercase())
+            .map(String::as_str)
+    }
+
+    /// Iterate over the captured headers, with lowercase names.
+    pub fn headers(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.headers.iter().map(|(k, v)| (k.as_str(), v.as_str()))
+    }
+
+    /// Discard the headers and take the parsed content.
+    pub fn into_body(self) -> T {
+        self.body
+    }
+}
+
+// ===========
+// API servers
+// ===========
+
+/// Host of the RESTful API server, [`BASE_URL`] by default.
+///
+/// Any path in the host URL is kept as prefix of endpoint paths, which is useful for reverse proxies. The signed path
+/// in authentication payload is always the bare API path (e.g. `/api/v2/orders`).
+///
+/// ```
+/// use maicoin_max::v2::rest::{ApiHost, GetTimestamp};
+///
+/// let host = ApiHost::parse("http://127.0.0.1:8080").unwrap();
+/// let req = GetTimestamp {}.to_request_with(&host);
+/// assert_eq!(req.url().host_str(), Some("127.0.0.1"));
+/// assert_eq!(req.url().port(), Some(8080));
+/// assert_eq!(req.url().path(), "/api/v2/timestamp");
+/// ```
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ApiHost(HTTPURL);
+
+impl ApiHost {
+    /// Create API host from base URL.
+    pub fn new(base_url: HTTPURL) -> Self {
+        Self(base_url)
+    }
+
+    /// Parse API host from base URL string.
+    pub fn parse(base_url: &str) -> Result<Self> {
+        HTTPURL::parse(base_url)
+            .map(Self)
+            .map_err(|err| Error::InvalidUrl(format!("{}: {}", base_url, err)))
+    }
+
+    /// Base URL of the API host.
+    pub fn base_url(&self) -> &HTTPURL {
+        &self.0
+    }
+
+    pub(crate) fn endpoint_url(&self, path: &str) -> HTTPURL {
+        let mut url = self.0.clone();
+        url.set_path(&format!("{}{}", self.0.path().trim_end_matches('/'), path));
+        url
+    }
+
+    // Like `endpoint_url`, but rejects the path which is altered while joining, e.g. dot segments which escape the
+    // endpoint, or characters which are not percent-encoded.
+    pub(crate) fn try_endpoint_url(&self, path: &str) -> Result<HTTPURL> {
+        let url = self.endpoint_url(path);
+        let expected = format!("{}{}", self.0.path().trim_end_matches('/'), path);
+        if path.starts_with('/') && url.path() == expected {
+            Ok(url)
+        } else {
+            Err(Error::InvalidUrl(format!("malformed API path {:?}", path)))
+        }
+    }
+}
+
+impl Default for ApiHost {
+    fn default() -> Self {
+        Self::parse(BASE_URL).expect("unexpected invalid API URL")
+    }
+}
+
+// ===============
+// Request options
+// ===============
+
+/// Default `User-Agent` of the requests built with [`RequestOptions`].
+pub const DEFAULT_USER_AGENT: &str = concat!("maicoin_max-rs/", env!("CARGO_PKG_VERSION"));
+
+/// Headers to be attached to the requests, accepted by `to_request_with_options(...)` and
+/// [`Endpoint::try_prepare_with_options`]. The headers are not signed.
+///
+/// ```
+/// use maicoin_max::v2::rest::{ApiHost, GetTimestamp, RequestOptions};
+///
+/// let options = RequestOptions {
+///     user_agent: Some("my-bot/1.0".into()),
+///     extra_headers: vec![("X-Correlation-Id".into(), "42".parse().unwrap())],
+/// };
+/// let req = GetTimestamp {}
+///     .to_request_with_options(&ApiHost::default(), &options)
+///     .unwrap();
+/// assert_eq!(req["User-Agent"].as_str(), "my-bot/1.0");
+/// assert_eq!(req["X-Correlation-Id"].as_str(), "42");
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct RequestOptions {
+    /// `User-Agent` header, [`DEFAULT_USER_AGENT`] if not set.
+    pub user_agent: Option<String>,
+    /// Additional headers, which replace the existing headers of the same name. Authentication headers are reserved,
+    /// and rejected with [`Error::InvalidParams`].
+    pub extra_headers: Vec<(HeaderName, HeaderValue)>,
+}
+
+impl RequestOptions {
+    /// `User-Agent` header to be attached.
+    pub fn user_agent(&self) -> &str {
+        self.user_agent.as_deref().unwrap_or(DEFAULT_USER_AGENT)
+    }
+
+    pub(crate) fn apply(&self, req: &mut HTTPRequest) -> Result<()> {
+        const RESERVED: &[&str] = &[
+            internal::HEADER_AUTH_ACCESS_KEY,
+            internal::HEADER_AUTH_PAYLOAD,
+            internal::HEADER_AUTH_SIGNATURE,
+        ];
+        if let Some((name, _)) = self.extra_headers.iter().find(|(name, _)| {
+            RESERVED
+                .iter()
+                .any(|reserved| name.as_str().eq_ignore_ascii_case(reserved))
+        }) {
+            return Err(Error::InvalidParams(format!(
+                "header {} is reserved for authentication",
+                name
+            )));
+        }
+
+        req.insert_header("User-Agent", self.user_agent());
+        for (name, value) in self.extra_headers.iter() {
+            req.insert_header(name, value.clone());
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::test_util::*;
+    use surf::Client as HTTPClient;
+    use surf_vcr::VcrMode;
+
+    async fn create_client(cassette: &[&str]) -> HTTPClient {
+        let mut path_builder = test_resource_path();
+        path_builder.push("rest");
+        path_builder.extend(cassette);
+        create_test_recording_client(VcrMode::Replay, path_builder.as_path().to_str().unwrap())
+            .await
+    }
+
+    async fn call<E: Endpoint>(
+        client: &HTTPClient,
+        params: &E,
+        credentials: Option<&Credentials>,
+    ) -> Result<E::Response> {
+        let req = params
+            .try_prepare(credentials)
+            .expect("failed to build request");
+        assert_eq!(req.method(), E::METHOD);
+        assert_eq!(req.url().path(), params.path());
+        assert_eq!(
+            req.header(internal::HEADER_AUTH_SIGNATURE).is_some(),
+            E::AUTH
+        );
+        let resp = client.send(req).await.expect("Error while sending request");
+        E::read_response(resp.into()).await
+    }
+
+    #[test]
+    fn request_options_headers() {
+        let host = ApiHost::default();
+        let options = RequestOptions {
+            user_agent: None,
+            extra_headers: vec![("X-Correlation-Id".into(), "abc-123".parse().unwrap())],
+        };
+
+        let req = GetTimestamp {}
+            .to_request_with_options(&host, &options)
+            .unwrap();
+        assert_eq!(req["User-Agent"].as_str(), DEFAULT_USER_AGENT);
+        assert!(DEFAULT_USER_AGENT.starts_with("maicoin_max-rs/"));
+        assert_eq!(req["X-Correlation-Id"].as_str(), "abc-123");
+
+        let credentials = Credentials::new("api key".into(), "api secret".into());
+        let options = RequestOptions {
+            user_agent: Some("my-bot/1.0".into()),
+            ..options
+        };
+        let req = GetProfile {}
+            .to_request_with_options(&host, &credentials, &options)
+            .unwrap();
+        assert_eq!(req["User-Agent"].as_str(), "my-bot/1.0");
+        assert_eq!(req["X-Correlation-Id"].as_str(), "abc-123");
+        assert_eq!(req[internal::HEADER_AUTH_ACCESS_KEY].as_str(), "api key");
+
+        let req = GetProfile {}
+            .try_prepare_with_options(&host, Some(&credentials), &options)
+            .unwrap();
+        assert_eq!(req["User-Agent"].as_str(), "my-bot/1.0");
+        assert!(req.header(internal::HEADER_AUTH_SIGNATURE).is_some());
+    }
+
+    #[test]
+    fn request_options_reserved_headers() {
+        let credentials = Credentials::new("api key".into(), "api secret".into());
+        for name in ["X-MAX-ACCESSKEY", "x-max-payload", "X-Max-Signature"] {
+            let options = RequestOptions {
+                user_agent: None,
+                extra_headers: vec![(name.into(), "forged".parse().unwrap())],
+            };
+            match (GetProfile {}).to_request_with_options(
+                &ApiHost::default(),
+                &credentials,
+                &options,
+            ) {
+                Err(Error::InvalidParams(_)) => {}
+                other => panic!("expect invalid params error, got {:?}", other),
+            }
+            assert!(GetTimestamp {}
+                .to_request_with_options(&ApiHost::default(), &options)
+                .is_err());
+        }
+    }
+
+    #[test]
+    fn request_with_explicit_nonce() {
+        let credentials = Credentials::new("api key".into(), "api secret".into());
+        let nonce = credentials.nonce();
+        let host = ApiHost::default();
+        let headers = |req: &http_types::Request| {
+            vec![
+                internal::HEADER_AUTH_PAYLOAD,
+                internal::HEADER_AUTH_SIGNATURE,
+            ]
+            .into_iter()
+            .map(|name| req.header(name).unwrap().as_str().to_owned())
+            .collect::<Vec<_>>()
+        };
+
+        // signed identically, without consuming the nonces of credentials
+        let params = GetOrder {
+            id: Some(1545763894),
+            client_oid: None,
+        };
+        let req = params
+            .try_to_request_with_nonce(&host, &credentials, nonce)
+            .unwrap();
+        let again = params
+            .try_to_request_with_nonce(&host, &credentials, nonce)
+            .unwrap();
+        assert_eq!(req.url(), again.url());
+        assert_eq!(headers(&req), headers(&again));
+        assert_eq!(
+            req.url().query(),
+            Some(format!("id=1545763894&nonce={}", nonce).as_str())
+        );
+        assert!(credentials.nonce() > nonce);
+
+        // the payload in body is signed with the same nonce as well
+        let params = DeleteOrder {
+            id: Some(1545763894),
+            client_oid: None,
+        };
+        let mut req = params
+            .try_to_request_with_nonce(&host, &credentials, nonce)
+            .unwrap();
+        let mut again = params
+            .try_to_request_with_nonce(&host, &credentials, nonce)
+            .unwrap();
+        assert_eq!(headers(&req), headers(&again));
+        let body = async_std::task::block_on(req.body_string()).unwrap();
+        assert_eq!(
+            body,
+            async_std::task::block_on(again.body_string()).unwrap()
+        );
+        assert_eq!(
+            serde_json::from_str::<serde_json::Value>(&body).unwrap()["nonce"],
+            nonce
+        );
+    }
+
+    #[async_std::test]
+    async fn prepared_request_reader() {
+        let credentials = Credentials::new("api key".into(), "api secret".into());
+        let params = GetOrder {
+            id: Some(1545763894),
+            client_oid: None,
+        };
+        let prepared = params.prepare_typed(Some(&credentials)).unwrap();
+        assert_eq!(prepared.request().url().path(), params.path());
+        assert!(prepared
+            .request()
+            .header(internal::HEADER_AUTH_SIGNATURE)
+            .is_some());
+
+        let (_req, reader) = prepared.into_parts();
+        let order = reader
+            .read(canned_response(200, fixtures::ORDER))
+            .await
+            .unwrap();
+        assert_eq!(order.id, Some(1545763894));
+    }
+
+    #[async_std::test]
+    async fn read_response_with_raw_json() {
+        let body = fixtures::ORDER.replacen('{', r#"{"unmodeled_field":"kept","#, 1);
+        let (order, raw) = GetOrder::read_response_raw(canned_response(200, &body))
+            .await
+            .unwrap();
+        assert_eq!(order.id, Some(1545763894));
+        assert_eq!(raw["unmodeled_field"], "kept");
+        assert_eq!(raw["id"], 1545763894);
+
+        // errors are reported as by the typed reader
+        let err =
+            GetOrder::read_response_raw(canned_response(404, fixtures::ERROR_ORDER_NOT_FOUND))
+                .await
+                .unwrap_err();
+        assert!(err.is_order_not_found());
+        let err = GetTimestamp::read_response_raw(canned_response(503, "<html></html>"))
+            .await
+            .unwrap_err();
+        assert!(matches!(err, Error::Maintenance(503)));
+    }
+
+    #[async_std::test]
+    async fn generic_endpoint_call() {
+        let client = create_client(&["public", "misc", "get_timestamp.yaml"]).await;
+        let ts = call(&client, &GetTimestamp {}, None)
+            .await
+            .expect("failed to parse result");
+        assert_eq!(ts.0, 1636258261);
+
+        let client = create_client(&["public", "misc", "get_vip_by_level.yaml"]).await;
+        let level = call(
+            &client,
+            &GetVIPByLevel { level: 3 },
+            Some(&TEST_CREDENTIALS),
+        )
+        .await
+        .expect("failed to parse result");
+        assert_eq!(level.level, 3);
+
+        let client = create_client(&["auth", "auth_success.yaml"]).await;
+        let result = call(&client, &GetProfile {}, Some(&TEST_CREDENTIALS)).await;
+        assert!(result.is_ok());
+    }
+
+    #[test]
+    fn auth_endpoint_without_credentials() {
+        assert!(matches!(
+            GetProfile {}.try_prepare(None),
+            Err(Error::InvalidParams(_))
+        ));
+        // credentials are not needed by public endpoints
+        assert!(GetVIPByLevel { level: 3 }.try_prepare(None).is_ok());
+    }
+}
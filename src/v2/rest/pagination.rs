@@ -0,0 +1,216 @@
+use http_types::Request as HTTPRequest;
+
+use crate::common::PageParams;
+use crate::error::Result;
+use crate::v2::rest::{ApiHost, Endpoint};
+use crate::Credentials;
+
+/// Default cap of pages requested by [`Paginator`].
+pub const DEFAULT_MAX_PAGES: u64 = 100;
+
+/// Request parameters of paginated endpoints.
+pub trait Paginated {
+    /// Current pagination parameters, `None` if not specified.
+    fn page_params(&self) -> Option<&PageParams>;
+
+    /// Set pagination parameters, which also turns on pagination of the request.
+    fn set_page_params(&mut self, page_params: PageParams);
+}
+
+/// Runtime-agnostic driver to walk through all the pages of a paginated endpoint.
+///
+/// The paginator only creates requests, the caller is responsible to send them and report the length of each parsed
+/// page via [`Paginator::feed`]. The paginator stops after a short or empty page, or when the max pages reached.
+///
+/// ```ignore
+/// let mut paginator = Paginator::new(GetOrders::for_market("btctwd").build());
+/// while let Some(req) = paginator.next_request(Some(&credentials)) {
+///     let resp = client.send(req?).await?;
+///     let orders = GetOrders::read_response(resp.into()).await?;
+///     paginator.feed(orders.len());
+///     // continue work with orders
+/// }
+/// ```
+#[derive(Debug)]
+pub struct Paginator<P: Paginated + Endpoint> {
+    params: P,
+    page_params: PageParams,
+    pages_fed: u64,
+    max_pages: u64,
+    done: bool,
+}
+
+impl<P: Paginated + Endpoint> Paginator<P> {
+    /// Start from the page given in `params` (the first page by default), with at most [`DEFAULT_MAX_PAGES`] pages.
+    pub fn new(params: P) -> Self {
+        let page_params = params.page_params().cloned().unwrap_or_default();
+        Self {
+            params,
+            page_params,
+            pages_fed: 0,
+            max_pages: DEFAULT_MAX_PAGES,
+            done: false,
+        }
+    }
+
+    /// Set the max number of pages to request.
+    pub fn with_max_pages(mut self, max_pages: u64) -> Self {
+        self.max_pages = max_pages;
+        self.done = self.pages_fed >= max_pages;
+        self
+    }
+
+    /// Create the request of current page to [`crate::v2::rest::BASE_URL`], `None` if no more page to fetch.
+    pub fn next_request(
+        &mut self,
+        credentials: Option<&Credentials>,
+    ) -> Option<Result<HTTPRequest>> {
+        self.next_request_with(&ApiHost::default(), credentials)
+    }
+
+    /// Create the request of current page to the given API host, `None` if no more page to fetch.
+    ///
+    /// The page does not advance until [`Paginator::feed`] is called. The request fails to build as by
+    /// [`Endpoint::try_prepare_with`], e.g. without credentials for an authenticated endpoint.
+    pub fn next_request_with(
+        &mut self,
+        host: &ApiHost,
+        credentials: Option<&Credentials>,
+    ) -> Option<Result<HTTPRequest>> {
+        if self.done {
+            return None;
+        }
+        self.params.set_page_params(self.page_params);
+        Some(self.params.try_prepare_with(host, credentials))
+    }
+
+    /// Report the number of records in the parsed page, and decide whether to continue.
+    ///
+    /// Returns `true` if there are more pages to fetch.
+    pub fn feed(&mut self, page_len: usize) -> bool {
+        if self.done {
+            return false;
+        }
+        self.pages_fed += 1;
+        if (page_len as u64) < self.page_params.limit || self.pages_fed >= self.max_pages {
+            self.done = true;
+        } else {
+            self.page_params = self.page_params.next();
+        }
+        !self.done
+    }
+
+    /// Page number of the next request.
+    pub fn page(&self) -> u64 {
+        self.page_params.page
+    }
+
+    /// `true` if no more page to fetch.
+    pub fn is_done(&self) -> bool {
+        self.done
+    }
+
+    /// Current request parameters.
+    pub fn params(&self) -> &P {
+        &self.params
+    }
+
+    /// Take the request parameters back.
+    pub fn into_params(self) -> P {
+        self.params
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::TimeRangeQuery;
+    use crate::util::test_util::TEST_CREDENTIALS;
+    use crate::v2::rest::{GetDeposits, GetOrders};
+
+    fn query_page(req: &HTTPRequest) -> (u64, u64) {
+        let find = |key: &str| {
+            req.url()
+                .query_pairs()
+                .find(|(k, _)| k == key)
+                .map(|(_, v)| v.parse::<u64>().unwrap())
+                .unwrap()
+        };
+        (find("page"), find("limit"))
+    }
+
+    fn get_orders() -> GetOrders {
+        GetOrders {
+            market: "maxtwd".into(),
+            state: vec![],
+            order_by: None,
+            group_id: None,
+            pagination: None,
+            page_params: None,
+            offset: None,
+        }
+    }
+
+    #[test]
+    fn stop_on_short_page() {
+        let mut paginator = Paginator::new(get_orders());
+        let mut pages = Vec::new();
+        for page_len in [50, 50, 12, 50] {
+            match paginator.next_request(Some(&TEST_CREDENTIALS)) {
+                Some(req) => pages.push(query_page(&req.unwrap())),
+                None => break,
+            }
+            paginator.feed(page_len);
+        }
+        assert_eq!(pages, vec![(1, 50), (2, 50), (3, 50)]);
+        assert!(paginator.is_done());
+        assert!(paginator.next_request(Some(&TEST_CREDENTIALS)).is_none());
+    }
+
+    #[test]
+    fn stop_on_empty_page() {
+        let mut params = GetDeposits {
+            currency: "twd".into(),
+            state: None,
+            range: TimeRangeQuery::default(),
+        };
+        params.set_page_params(PageParams { page: 3, limit: 10 });
+        let mut paginator = Paginator::new(params);
+        let req = paginator
+            .next_request(Some(&TEST_CREDENTIALS))
+            .unwrap()
+            .unwrap();
+        assert_eq!(query_page(&req), (3, 10));
+        assert!(paginator.feed(10));
+        let req = paginator
+            .next_request(Some(&TEST_CREDENTIALS))
+            .unwrap()
+            .unwrap();
+        assert_eq!(query_page(&req), (4, 10));
+        assert!(!paginator.feed(0));
+        assert!(paginator.next_request(Some(&TEST_CREDENTIALS)).is_none());
+        assert_eq!(paginator.params().range.pagination, Some(true));
+    }
+
+    #[test]
+    fn stop_on_max_pages() {
+        let mut paginator = Paginator::new(get_orders()).with_max_pages(2);
+        let mut pages = Vec::new();
+        while let Some(req) = paginator.next_request(Some(&TEST_CREDENTIALS)) {
+            pages.push(query_page(&req.unwrap()).0);
+            paginator.feed(50);
+        }
+        assert_eq!(pages, vec![1, 2]);
+        assert_eq!(paginator.into_params().page_params.unwrap().page, 2);
+    }
+
+    #[test]
+    fn without_credentials() {
+        let mut paginator = Paginator::new(get_orders());
+        assert!(matches!(
+            paginator.next_request(None),
+            Some(Err(crate::error::Error::InvalidParams(_)))
+        ));
+        assert!(!paginator.is_done());
+    }
+}
@@ -3,6 +3,7 @@ use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
 
 use crate::common::*;
+use crate::util::serde::{time, OneOrMany};
 use crate::v2::rest::api_impl::*;
 
 // ========
@@ -12,38 +13,72 @@ use crate::v2::rest::api_impl::*;
 /// GET /api/v2/deposits
 ///
 /// Get your deposits history
-#[derive(Serialize, Debug)]
+///
+/// Build with [`GetDeposits::for_currency`].
+#[derive(Serialize, Clone, Eq, PartialEq, Debug)]
 pub struct GetDeposits {
     /// Unique currency id, check /api/v2/currencies for available currencies
-    pub currency: String,
-    /// Target period start (Epoch time in seconds)
-    #[serde(
-        rename = "from",
-        skip_serializing_if = "Option::is_none",
-        with = "chrono_serde::ts_seconds_option"
-    )]
-    pub from_timestamp: Option<DateTime>,
-    /// Target period end (Epoch time in seconds)
-    #[serde(
-        rename = "to",
-        skip_serializing_if = "Option::is_none",
-        with = "chrono_serde::ts_seconds_option"
-    )]
-    pub to_timestamp: Option<DateTime>,
+    pub currency: Currency,
     /// Filter deposit state
     #[serde(skip_serializing_if = "Option::is_none")]
     pub state: Option<DepositState>,
-    /// Do pagination & return metadata in header (default true)
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub pagination: Option<bool>,
-    /// Paging parameters.
-    #[serde(flatten, skip_serializing_if = "Option::is_none")]
-    pub page_params: Option<PageParams>,
-    /// Records to skip, not applied for pagination (default 0).
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub offset: Option<u64>,
+    /// Time range and paging of the query.
+    #[serde(flatten)]
+    pub range: TimeRangeQuery,
 }
 impl_api!(GetDeposits => Vec<RespDepositRecord> : auth GET, "/api/v2/deposits");
+impl_paginated!(GetDeposits, range);
+
+impl GetDeposits {
+    /// Start building the query of deposits in the currency.
+    pub fn for_currency(currency: impl Into<Currency>) -> GetDepositsBuilder {
+        GetDepositsBuilder {
+            inner: GetDeposits {
+                currency: currency.into(),
+                state: None,
+                range: TimeRangeQuery::default(),
+            },
+        }
+    }
+}
+
+/// Builder of [`GetDeposits`], created by [`GetDeposits::for_currency`].
+#[derive(Debug)]
+pub struct GetDepositsBuilder {
+    inner: GetDeposits,
+}
+
+impl GetDepositsBuilder {
+    /// Filter by deposit state.
+    pub fn state(mut self, state: DepositState) -> Self {
+        self.inner.state = Some(state);
+        self
+    }
+
+    /// Start of the target period.
+    pub fn since(mut self, from: DateTime) -> Self {
+        self.inner.range.time.from = Some(from);
+        self
+    }
+
+    /// End of the target period.
+    pub fn until(mut self, to: DateTime) -> Self {
+        self.inner.range.time.to = Some(to);
+        self
+    }
+
+    /// Replace the time range and paging parameters.
+    pub fn range(mut self, range: TimeRangeQuery) -> Self {
+        self.inner.range = range;
+        self
+    }
+
+    /// Finish the request.
+    pub fn build(self) -> GetDeposits {
+        self.inner
+    }
+}
+impl_builder_paging!(GetDepositsBuilder => inner.range);
 
 /// GET /api/v2/deposit
 ///
@@ -62,9 +97,9 @@ impl_api!(GetDepositDetail => RespDepositRecord : auth GET, "/api/v2/deposit");
 #[derive(Serialize, Debug)]
 pub struct GetDepositAddresses {
     /// Unique currency id, check /api/v2/currencies for available currencies
-    pub currency: String,
+    pub currency: Currency,
     /// Do pagination & return metadata in header (default false)
-    #[serde(flatten, skip_serializing_if = "Option::is_none")]
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub pagination: Option<bool>,
     /// pagination parameters.
     #[serde(flatten, skip_serializing_if = "Option::is_none")]
@@ -82,16 +117,24 @@ impl_api!(GetDepositAddresses => Vec<DepositAddress> : auth GET, "/api/v2/deposi
 #[derive(Serialize, Eq, PartialEq, Debug)]
 pub struct CreateDepositAddress {
     /// Unique currency id, check /api/v2/currencies for available currencies
-    pub currency: String,
+    pub currency: Currency,
+}
+impl_api!(CreateDepositAddress => Vec<DepositAddress> as OneOrMany<DepositAddress> : auth POST, "/api/v2/deposit_addresses");
+
+impl CreateDepositAddress {
+    pub fn new(currency: impl Into<Currency>) -> Self {
+        Self {
+            currency: currency.into(),
+        }
+    }
 }
-impl_api!(CreateDepositAddress => Vec<DepositAddress> : auth POST, "/api/v2/deposit_addresses");
 
 // =========
 // Responses
 // =========
 
 /// Deposit detail
-#[derive(Deserialize, Default, Eq, PartialEq, Debug)]
+#[derive(Serialize, Deserialize, Clone, Default, Eq, PartialEq, Debug)]
 #[serde(default)]
 pub struct RespDepositRecord {
     /// uuid (string, optional): unique deposit id
@@ -102,28 +145,41 @@ pub struct RespDepositRecord {
     pub currency_version: String,
     /// amount (string, optional): deposit amount
     pub amount: Decimal,
-    /// fee (string, optional): deposit fee
-    pub fee: Decimal,
+    /// fee (string, optional): deposit fee, `None` if not given (e.g. `null` for fiat deposits)
+    #[serde(deserialize_with = "crate::util::serde::lenient_decimal_option")]
+    pub fee: Option<Decimal>,
     /// txid (string, optional): unique transaction id
     pub txid: String,
-    /// created_at (integer, optional): received timestamp (second)
-    #[serde(with = "chrono_serde::ts_seconds_option")]
+    /// created_at (integer, optional): received timestamp (second, or millisecond in some replies), `0` if unknown
+    #[serde(with = "time::ts_seconds_or_milliseconds_option")]
     pub created_at: Option<DateTime>,
     /// confirmations (string, optional): confirmations for crypto currency
     pub confirmations: u64,
-    /// updated_at (integer, optional): lastest updated timestamp (second)
-    #[serde(with = "chrono_serde::ts_seconds_option")]
+    /// confirmations_required (integer, optional): confirmations to accept the deposit, 0 if not provided
+    pub confirmations_required: u64,
+    /// updated_at (integer, optional): lastest updated timestamp (second, or millisecond in some replies), `0` if unknown
+    #[serde(with = "time::ts_seconds_or_milliseconds_option")]
     pub updated_at: Option<DateTime>,
     /// state (string, optional): current state
     pub state: DepositState,
 }
 
+impl RespDepositRecord {
+    /// Ratio of received confirmations to the required ones, capped at `1.0`. `None` if the requirement is unknown.
+    pub fn confirmation_progress(&self) -> Option<f64> {
+        if self.confirmations_required == 0 {
+            return None;
+        }
+        Some((self.confirmations as f64 / self.confirmations_required as f64).min(1.0))
+    }
+}
+
 // ============================
 // Inner structures and options
 // ============================
 
 /// Possible deposit state
-#[derive(Serialize, Deserialize, Copy, Clone, Eq, PartialEq, Debug)]
+#[derive(Serialize, Deserialize, Copy, Clone, Eq, PartialEq, Debug, Default)]
 #[serde(rename_all = "snake_case")]
 pub enum DepositState {
     Submitting,
@@ -136,23 +192,32 @@ pub enum DepositState {
     Refunded,
     Suspect,
     RefundCanceled,
+    #[default]
     Unknown,
 }
 
+impl_wire_str!(DepositState {
+    Submitting => "submitting",
+    Cancelled => "cancelled",
+    Submitted => "submitted",
+    Suspended => "suspended",
+    Rejected => "rejected",
+    Accepted => "accepted",
+    Checking => "checking",
+    Refunded => "refunded",
+    Suspect => "suspect",
+    RefundCanceled => "refund_canceled",
+    Unknown => "unknown",
+});
+
 impl DepositState {
     pub fn is_unknown(&self) -> bool {
         self == &Self::Unknown
     }
 }
 
-impl Default for DepositState {
-    fn default() -> Self {
-        Self::Unknown
-    }
-}
-
 /// Deposit address.The addresses could be empty before generated, please call POST /deposit_addresses in that case
-#[derive(Deserialize, Eq, PartialEq, Default, Debug)]
+#[derive(Serialize, Deserialize, Clone, Eq, PartialEq, Default, Debug)]
 #[serde(default)]
 pub struct DepositAddress {
     /// sn (integer, optional): unique address id
@@ -197,13 +262,9 @@ mod tests {
     #[async_std::test]
     async fn get_deposits() {
         let params = GetDeposits {
-            currency: "twd".to_string(),
-            from_timestamp: None,
-            to_timestamp: None,
+            currency: Currency::TWD,
             state: None,
-            pagination: None,
-            page_params: None,
-            offset: None,
+            range: TimeRangeQuery::default(),
         };
         let resp = create_client("get_deposits.yaml")
             .await
@@ -234,11 +295,12 @@ mod tests {
                 currency: "twd".into(),
                 currency_version: "twd".into(),
                 amount: dec!(50000.0),
-                fee: dec!(0),
+                fee: Some(dec!(0)),
                 txid: "(test erased txid)".into(),
-                created_at: Some(Utc.timestamp(1608626791, 0)),
+                created_at: Some(Utc.timestamp_opt(1608626791, 0).unwrap()),
                 confirmations: 0,
-                updated_at: Some(Utc.timestamp(1608626791, 0)),
+                confirmations_required: 0,
+                updated_at: Some(Utc.timestamp_opt(1608626791, 0).unwrap()),
                 state: DepositState::Accepted,
             }
         );
@@ -269,7 +331,7 @@ mod tests {
                 address: "(test erased address)".into(),
                 label: None,
                 wallet_type: "exchange".into(),
-                created_at: Some(Utc.timestamp(1599742451, 0)),
+                created_at: Some(Utc.timestamp_opt(1599742451, 0).unwrap()),
             }]
         );
     }
@@ -297,8 +359,126 @@ mod tests {
                 address: "(test erased address)".into(),
                 label: None,
                 wallet_type: "exchange".into(),
-                created_at: Some(Utc.timestamp(1599742451, 0)),
+                created_at: Some(Utc.timestamp_opt(1599742451, 0).unwrap()),
             }]
         );
     }
+
+    #[async_std::test]
+    async fn create_deposit_address_single_object() {
+        let raw = r#"{"sn":"1","composite_currency":"btc","version":null,"currency":"btc","address":"abc","label":null,"type":"exchange","created_at":1599742451}"#;
+        let addr_list = CreateDepositAddress::read_response(canned_response(201, raw))
+            .await
+            .expect("failed to parse result");
+        assert_eq!(addr_list.len(), 1);
+        assert_eq!(addr_list[0].address, "abc");
+
+        let raw = format!("[{}]", raw);
+        let addr_list = CreateDepositAddress::read_response(canned_response(201, &raw))
+            .await
+            .expect("failed to parse result");
+        assert_eq!(addr_list.len(), 1);
+    }
+
+    #[test]
+    fn get_deposits_time_range_query() {
+        let params = GetDeposits {
+            currency: "twd".into(),
+            state: Some(DepositState::Accepted),
+            range: TimeRangeQuery::default()
+                .since(Utc.timestamp_opt(1637316000, 0).unwrap())
+                .until(Utc.timestamp_opt(1637402400, 0).unwrap())
+                .with_page(PageParams { page: 2, limit: 10 })
+                .with_offset(5),
+        };
+        let req = params.to_request(&TEST_CREDENTIALS);
+        let query: Vec<String> = req
+            .url()
+            .query_pairs()
+            .filter(|(k, _)| k != "nonce")
+            .map(|(k, v)| format!("{}={}", k, v))
+            .collect();
+        assert_eq!(
+            query.join("&"),
+            "currency=twd&state=accepted&from=1637316000&to=1637402400&pagination=true&page=2&limit=10&offset=5"
+        );
+    }
+
+    #[test]
+    fn get_deposits_builder() {
+        let to = Utc.timestamp_opt(1637402400, 0).unwrap();
+        assert_eq!(
+            GetDeposits::for_currency("twd")
+                .state(DepositState::Accepted)
+                .until(to)
+                .limit(10)
+                .offset(5)
+                .build(),
+            GetDeposits {
+                currency: "twd".into(),
+                state: Some(DepositState::Accepted),
+                range: TimeRangeQuery::default()
+                    .until(to)
+                    .with_page(PageParams { page: 1, limit: 10 })
+                    .with_offset(5),
+            }
+        );
+    }
+
+    #[test]
+    fn deposit_confirmation_progress() {
+        let raw =
+            r#"{"currency":"btc","confirmations":3,"confirmations_required":6,"state":"checking"}"#;
+        let record: RespDepositRecord = serde_json::from_str(raw).unwrap();
+        assert_eq!(record.confirmations_required, 6);
+        assert_eq!(record.confirmation_progress(), Some(0.5));
+
+        let record = RespDepositRecord {
+            confirmations: 8,
+            ..record
+        };
+        assert_eq!(record.confirmation_progress(), Some(1.0));
+
+        // fiat deposits come without the requirement
+        let record: RespDepositRecord = serde_json::from_str(r#"{"currency":"twd"}"#).unwrap();
+        assert_eq!(record.confirmation_progress(), None);
+    }
+
+    #[test]
+    fn deposit_record_timestamps() {
+        let raw = r#"{"currency":"btc","created_at":1635853116,"updated_at":0}"#;
+        let record: RespDepositRecord = serde_json::from_str(raw).unwrap();
+        assert_eq!(record.created_at, Utc.timestamp_opt(1635853116, 0).single());
+        assert_eq!(record.updated_at, None);
+
+        let raw = r#"{"currency":"btc","created_at":1635853116000,"updated_at":1635853634047}"#;
+        let record: RespDepositRecord = serde_json::from_str(raw).unwrap();
+        assert_eq!(record.created_at, Utc.timestamp_opt(1635853116, 0).single());
+        assert_eq!(
+            record.updated_at,
+            Utc.timestamp_millis_opt(1635853634047).single()
+        );
+    }
+
+    #[test]
+    fn enum_wire_str() {
+        assert_wire_str(DepositState::VARIANTS);
+    }
+
+    #[test]
+    fn deposit_lenient_fee() {
+        for raw in [
+            r#"{"currency":"twd","amount":"1000","fee":null}"#,
+            r#"{"currency":"twd","amount":"1000","fee":""}"#,
+            r#"{"currency":"twd","amount":"1000"}"#,
+        ] {
+            let record: RespDepositRecord = serde_json::from_str(raw).unwrap();
+            assert_eq!(record.fee, None, "{}", raw);
+            assert_eq!(record.amount, dec!(1000));
+        }
+        let record: RespDepositRecord =
+            serde_json::from_str(r#"{"currency":"btc","fee":0.0005}"#).unwrap();
+        assert_eq!(record.fee, Some(dec!(0.0005)));
+        assert!(serde_json::from_str::<RespDepositRecord>(r#"{"fee":"free"}"#).is_err());
+    }
 }
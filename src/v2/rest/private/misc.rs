@@ -1,11 +1,13 @@
 use std::collections::HashMap;
 
 use chrono::serde as chrono_serde;
+use chrono::{DateTime as ChronoDateTime, TimeZone, Utc};
 use rust_decimal::Decimal;
-use serde::{Deserialize, Serialize};
+use serde::{de, Deserialize, Deserializer, Serialize};
 
 use crate::common::*;
 use crate::v2::rest::api_impl::*;
+use crate::v2::rest::internal::encode_path_segment;
 
 pub use crate::v2::rest::public::RespVIPLevel;
 
@@ -41,12 +43,23 @@ impl_api!(GetAccountVIPLevel => RespAccountVIPInfo : auth GET, "/api/v2/members/
 pub struct GetAccountOfCurrency {
     /// Get personal accounts information of a currency.
     #[serde(skip)]
-    pub path_currency: String,
+    pub path_currency: Currency,
 }
 impl_api!(GetAccountOfCurrency => RespAccountCurrencyInfo : auth GET, dynamic params {
-    api_url!(dynamic "/api/v2/members/accounts/{}", params.path_currency)
+    format!(
+        "/api/v2/members/accounts/{}",
+        encode_path_segment(&params.path_currency)
+    )
 });
 
+impl GetAccountOfCurrency {
+    pub fn new(currency: impl Into<Currency>) -> Self {
+        Self {
+            path_currency: currency.into(),
+        }
+    }
+}
+
 /// GET /api/v2/internal_transfers
 ///
 /// Get internal transfers history.
@@ -54,34 +67,15 @@ impl_api!(GetAccountOfCurrency => RespAccountCurrencyInfo : auth GET, dynamic pa
 pub struct GetInternalTransfers {
     /// Unique currency id.
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub currency: Option<String>,
+    pub currency: Option<Currency>,
     /// Transfer side.
     pub side: InternalTransferSide,
-    /// Target period start (Epoch time in seconds).
-    #[serde(
-        rename = "from",
-        with = "chrono_serde::ts_seconds_option",
-        skip_serializing_if = "Option::is_none"
-    )]
-    pub from_timestamp: Option<DateTime>,
-    /// Target period end (Epoch time in seconds).
-    #[serde(
-        rename = "to",
-        with = "chrono_serde::ts_seconds_option",
-        skip_serializing_if = "Option::is_none"
-    )]
-    pub to_timestamp: Option<DateTime>,
-    /// Do pagination & return metadata in header (default `true`).
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub pagination: Option<bool>,
-    /// Pagination parameters, see [`crate::common::PageParams`].
-    #[serde(flatten, skip_serializing_if = "Option::is_none")]
-    pub page_params: Option<PageParams>,
-    /// Records to skip, not applied for pagination (default `0`).
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub offset: Option<u64>,
+    /// Time range and paging of the query.
+    #[serde(flatten)]
+    pub range: TimeRangeQuery,
 }
 impl_api!(GetInternalTransfers => Vec<RespInternalTransferRecord> : auth GET, "/api/v2/internal_transfers");
+impl_paginated!(GetInternalTransfers, range);
 
 /// GET /api/v2/internal_transfer
 ///
@@ -96,36 +90,17 @@ impl_api!(GetInternalTransferByUUID => RespInternalTransferRecord : auth GET, "/
 /// GET /api/v2/rewards
 ///
 /// Get rewards history.
-#[derive(Serialize, Debug)]
+#[derive(Serialize, Default, Debug)]
 pub struct GetRewards {
     /// Unique currency id.
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub currency: Option<String>,
-    /// Target period start (Epoch time in seconds).
-    #[serde(
-        rename = "from",
-        with = "chrono_serde::ts_seconds_option",
-        skip_serializing_if = "Option::is_none"
-    )]
-    pub from_timestamp: Option<DateTime>,
-    /// Target period end (Epoch time in seconds).
-    #[serde(
-        rename = "to",
-        with = "chrono_serde::ts_seconds_option",
-        skip_serializing_if = "Option::is_none"
-    )]
-    pub to_timestamp: Option<DateTime>,
-    /// Do pagination & return metadata in header (default `true`).
-    #[serde(flatten, skip_serializing_if = "Option::is_none")]
-    pub pagination: Option<bool>,
-    /// Pagination parameters, see [`crate::common::PageParams`].
-    #[serde(flatten, skip_serializing_if = "Option::is_none")]
-    pub page_params: Option<PageParams>,
-    /// Records to skip, not applied for pagination (default `0`).
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub offset: Option<u64>,
+    pub currency: Option<Currency>,
+    /// Time range and paging of the query.
+    #[serde(flatten)]
+    pub range: TimeRangeQuery,
 }
 impl_api!(GetRewards => Vec<RewardRecord> : auth GET, "/api/v2/rewards");
+impl_paginated!(GetRewards, range);
 
 /// GET /api/v2/rewards/{path_reward_type}
 ///
@@ -147,7 +122,7 @@ impl_api!(GetRewardsOfType => Vec<RewardRecord> : auth GET, dynamic params {
         }
         reward_str.push(ch.to_ascii_lowercase());
     }
-    api_url!(dynamic "/api/v2/rewards/{}", reward_str)
+    format!("/api/v2/rewards/{}", reward_str)
 });
 
 /// GET /api/v2/yields
@@ -156,30 +131,10 @@ impl_api!(GetRewardsOfType => Vec<RewardRecord> : auth GET, dynamic params {
 #[derive(Serialize, Debug)]
 pub struct GetSavingInterestHistory {
     /// Unique currency id.
-    pub currency: String,
-    /// Target period start (Epoch time in seconds).
-    #[serde(
-        rename = "from",
-        with = "chrono_serde::ts_seconds_option",
-        skip_serializing_if = "Option::is_none"
-    )]
-    pub from_timestamp: Option<DateTime>,
-    /// Target period end (Epoch time in seconds).
-    #[serde(
-        rename = "to",
-        with = "chrono_serde::ts_seconds_option",
-        skip_serializing_if = "Option::is_none"
-    )]
-    pub to_timestamp: Option<DateTime>,
-    /// Do pagination & return metadata in header (default `true`).
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub pagination: Option<bool>,
-    /// Pagination parameters, see [`crate::common::PageParams`].
-    #[serde(flatten, skip_serializing_if = "Option::is_none")]
-    pub page_params: Option<PageParams>,
-    /// Records to skip, not applied for pagination (default `0`).
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub offset: Option<u64>,
+    pub currency: Currency,
+    /// Time range and paging of the query.
+    #[serde(flatten)]
+    pub range: TimeRangeQuery,
 }
 impl_api!(GetSavingInterestHistory => Vec<RewardRecord> : auth GET, "/api/v2/yields");
 
@@ -197,7 +152,7 @@ impl_api!(GetMaxRewardsYesterday => RespMAXReward : auth GET, "/api/v2/max_rewar
 /// Personal profile information.
 ///
 /// (Represents both `External_V2_Entities_Member` and `External_V2_Entities_MemberAttributes_Profile` in official API document)
-#[derive(Deserialize, Eq, PartialEq, Debug, Default)]
+#[derive(Serialize, Deserialize, Clone, Eq, PartialEq, Debug, Default)]
 #[serde(default)]
 pub struct RespProfile {
     /// sn (string, optional): unique serial number.
@@ -268,7 +223,7 @@ pub struct RespProfile {
     /// current_two_factor_type (string, optional): app/sms/nil.
     pub current_two_factor_type: Option<String>,
     /// locked_status_of_2fa (object, optional): time that 2fa lock ends.
-    pub locked_status_of_2fa: Option<String>,
+    pub locked_status_of_2fa: Option<TwoFactorLock>,
     /// documents (External_V2_Entities_MemberDocs, optional).
     pub documents: Option<HashMap<String, String>>,
     /// supplemental_document_type (string, optional): supplemental document type.
@@ -284,7 +239,7 @@ pub struct RespProfile {
 }
 
 /// VIP level info.
-#[derive(Deserialize, Eq, PartialEq, Default, Debug)]
+#[derive(Serialize, Deserialize, Clone, Eq, PartialEq, Default, Debug)]
 pub struct RespAccountVIPInfo {
     /// current_vip_level (`External_V2_Entities_VipLevel`, optional): current vip level.
     #[serde(rename = "current_vip_level")]
@@ -294,8 +249,20 @@ pub struct RespAccountVIPInfo {
     next: RespVIPLevel,
 }
 
+impl RespAccountVIPInfo {
+    /// Current VIP level.
+    pub fn current(&self) -> &RespVIPLevel {
+        &self.current
+    }
+
+    /// Next VIP level.
+    pub fn next(&self) -> &RespVIPLevel {
+        &self.next
+    }
+}
+
 /// Personal accounts information of a currency.
-#[derive(Deserialize, Eq, PartialEq, Default, Debug)]
+#[derive(Serialize, Deserialize, Clone, Eq, PartialEq, Default, Debug)]
 pub struct RespAccountCurrencyInfo {
     /// currency (string, optional): currency id, e.g. twd, btc, ...
     pub currency: String,
@@ -313,7 +280,7 @@ pub struct RespAccountCurrencyInfo {
 }
 
 /// Internal transfer.
-#[derive(Deserialize, Eq, PartialEq, Default, Debug)]
+#[derive(Serialize, Deserialize, Clone, Eq, PartialEq, Default, Debug)]
 pub struct RespInternalTransferRecord {
     /// uuid (string, optional): unique internal transfer id
     pub uuid: String,
@@ -333,7 +300,7 @@ pub struct RespInternalTransferRecord {
 }
 
 /// Recent MAX reward.
-#[derive(Deserialize, Eq, PartialEq, Default, Debug)]
+#[derive(Serialize, Deserialize, Clone, Eq, PartialEq, Default, Debug)]
 pub struct RespMAXReward {
     /// trading_reward (string, optional): trading reward amount
     pub trading_reward: Decimal,
@@ -346,7 +313,7 @@ pub struct RespMAXReward {
 // ============================
 
 /// Types of reward.
-#[derive(Serialize, Deserialize, Copy, Clone, Eq, PartialEq, Debug)]
+#[derive(Serialize, Deserialize, Copy, Clone, Eq, PartialEq, Debug, Default)]
 #[serde(rename_all = "snake_case")]
 pub enum RewardType {
     MiningReward,
@@ -357,45 +324,54 @@ pub enum RewardType {
     RedemptionReward,
     VipRebate,
     SavingsInterest,
+    #[default]
     Unknown,
 }
 
+impl_wire_str!(RewardType {
+    MiningReward => "mining_reward",
+    HoldingReward => "holding_reward",
+    TradingReward => "trading_reward",
+    Commission => "commission",
+    AirdropReward => "airdrop_reward",
+    RedemptionReward => "redemption_reward",
+    VipRebate => "vip_rebate",
+    SavingsInterest => "savings_interest",
+    Unknown => "unknown",
+});
+
 impl RewardType {
     pub fn is_unknown(&self) -> bool {
         self == &Self::Unknown
     }
 }
 
-impl Default for RewardType {
-    fn default() -> Self {
-        Self::Unknown
-    }
-}
-
 /// Account status.
-#[derive(Deserialize, Eq, PartialEq, Debug)]
+#[derive(Serialize, Deserialize, Clone, Eq, PartialEq, Debug, Default)]
 #[serde(rename_all = "lowercase")]
 pub enum AccountStatus {
     Inactivated,
     Activated,
     Frozen,
+    #[default]
     Unknown,
 }
 
+impl_wire_str!(AccountStatus {
+    Inactivated => "inactivated",
+    Activated => "activated",
+    Frozen => "frozen",
+    Unknown => "unknown",
+});
+
 impl AccountStatus {
     pub fn is_unknown(&self) -> bool {
         self == &Self::Unknown
     }
 }
 
-impl Default for AccountStatus {
-    fn default() -> Self {
-        Self::Unknown
-    }
-}
-
 /// Member type.
-#[derive(Deserialize, Eq, PartialEq, Debug)]
+#[derive(Serialize, Deserialize, Clone, Eq, PartialEq, Debug, Default)]
 pub enum MemberType {
     #[serde(rename = "type_guest")]
     Guest,
@@ -403,25 +379,27 @@ pub enum MemberType {
     Coin,
     #[serde(rename = "type_twd")]
     TWD,
+    #[default]
     Unknown,
 }
 
+impl_wire_str!(MemberType {
+    Guest => "type_guest",
+    Coin => "type_coin",
+    TWD => "type_twd",
+    Unknown => "Unknown",
+});
+
 impl MemberType {
     pub fn is_unknown(&self) -> bool {
         self == &Self::Unknown
     }
 }
 
-impl Default for MemberType {
-    fn default() -> Self {
-        Self::Unknown
-    }
-}
-
 /// Member bank information
 ///
 /// (Represents both `External_V2_Entities_Bank` and `External_V2_Entities_Mcoin_BankAccount` in official API document)
-#[derive(Deserialize, Eq, PartialEq, Default, Debug)]
+#[derive(Serialize, Deserialize, Clone, Eq, PartialEq, Default, Debug)]
 #[serde(default)]
 pub struct BankInfo {
     /// bank_code (string, optional): bank code
@@ -447,7 +425,7 @@ pub struct BankInfo {
 }
 
 /// Member gender.
-#[derive(Deserialize, Eq, PartialEq, Debug)]
+#[derive(Serialize, Deserialize, Clone, Eq, PartialEq, Debug, Default)]
 pub enum Gender {
     #[serde(rename = "M")]
     Male,
@@ -455,21 +433,23 @@ pub enum Gender {
     Female,
     #[serde(rename = "C")]
     Corporation,
+    #[default]
     Unknown,
 }
 
+impl_wire_str!(Gender {
+    Male => "M",
+    Female => "F",
+    Corporation => "C",
+    Unknown => "Unknown",
+});
+
 impl Gender {
     pub fn is_unknown(&self) -> bool {
         self == &Self::Unknown
     }
 }
 
-impl Default for Gender {
-    fn default() -> Self {
-        Self::Unknown
-    }
-}
-
 /// Internal transfer side, in or out.
 #[derive(Serialize, Copy, Clone, Eq, PartialEq, Debug)]
 #[serde(rename_all = "lowercase")]
@@ -478,8 +458,82 @@ pub enum InternalTransferSide {
     Out,
 }
 
+impl_wire_str!(InternalTransferSide {
+    In => "in",
+    Out => "out",
+});
+
+/// Lock status of two-factor authentication.
+///
+/// Server may reply either an object (`{"locked_until": ...}`) or a bare timestamp string, both are accepted. The
+/// timestamp can be seconds since Unix epoch or a RFC 3339 string.
+///
+/// It is serialized as an object with the timestamp in seconds.
+#[derive(Serialize, Clone, Eq, PartialEq, Default, Debug)]
+pub struct TwoFactorLock {
+    /// Time that 2fa lock ends, `None` if not given.
+    #[serde(with = "chrono_serde::ts_seconds_option")]
+    pub locked_until: Option<DateTime>,
+}
+
+impl<'de> Deserialize<'de> for TwoFactorLock {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum LockTime {
+            Seconds(i64),
+            Text(String),
+        }
+
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum RawLock {
+            Object {
+                #[serde(default)]
+                locked_until: Option<LockTime>,
+            },
+            Plain(LockTime),
+        }
+
+        fn to_datetime<E: de::Error>(lock_time: LockTime) -> Result<Option<DateTime>, E> {
+            let ts = match lock_time {
+                LockTime::Seconds(ts) => ts,
+                LockTime::Text(text) if text.trim().is_empty() => return Ok(None),
+                LockTime::Text(text) => match text.trim().parse::<i64>() {
+                    Ok(ts) => ts,
+                    Err(_) => {
+                        return ChronoDateTime::parse_from_rfc3339(text.trim())
+                            .map(|time| Some(time.with_timezone(&Utc)))
+                            .map_err(|_| {
+                                de::Error::invalid_value(
+                                    de::Unexpected::Str(&text),
+                                    &"timestamp in seconds or RFC 3339",
+                                )
+                            })
+                    }
+                },
+            };
+            Utc.timestamp_opt(ts, 0)
+                .single()
+                .map(Some)
+                .ok_or_else(|| de::Error::custom(format!("timestamp out of range: {}", ts)))
+        }
+
+        let locked_until = match RawLock::deserialize(deserializer)? {
+            RawLock::Object { locked_until } => {
+                locked_until.map(to_datetime).transpose()?.flatten()
+            }
+            RawLock::Plain(lock_time) => to_datetime(lock_time)?,
+        };
+        Ok(Self { locked_until })
+    }
+}
+
 /// Reward record
-#[derive(Deserialize, Eq, PartialEq, Default, Debug)]
+#[derive(Serialize, Deserialize, Clone, Eq, PartialEq, Default, Debug)]
 pub struct RewardRecord {
     /// uuid (string, optional): unique reward id
     pub uuid: String,
@@ -503,7 +557,6 @@ pub struct RewardRecord {
 mod tests {
     use super::*;
     use crate::util::test_util::*;
-    use chrono::{TimeZone, Utc};
     use rust_decimal_macros::dec;
     use surf::Client as HTTPClient;
     use surf_vcr::VcrMode;
@@ -693,6 +746,44 @@ mod tests {
         );
     }
 
+    #[test]
+    fn account_of_currency_escaped_path() {
+        let params = GetAccountOfCurrency {
+            path_currency: "usdt erc20/x?y".into(),
+        };
+        let credentials = crate::Credentials::new("api key".into(), "api secret".into());
+        let req = params.to_request(&credentials);
+        assert_eq!(
+            req.url().path(),
+            "/api/v2/members/accounts/usdt%20erc20%2Fx%3Fy"
+        );
+        assert_eq!(req.url().query_pairs().count(), 1); // nonce only
+        assert_eq!(
+            crate::v2::rest::Endpoint::path(&params),
+            "/api/v2/members/accounts/usdt%20erc20%2Fx%3Fy"
+        );
+    }
+
+    #[test]
+    fn account_of_currency_hostile_path() {
+        let credentials = crate::Credentials::new("api key".into(), "api secret".into());
+        for hostile in ["..", "."] {
+            let params = GetAccountOfCurrency {
+                path_currency: hostile.into(),
+            };
+            match params.try_to_request(&credentials) {
+                Err(crate::error::Error::InvalidUrl(_)) => {}
+                other => panic!("expect invalid URL error, got {:?}", other),
+            }
+        }
+
+        let params = GetAccountOfCurrency {
+            path_currency: "../orders".into(),
+        };
+        let req = params.try_to_request(&credentials).unwrap();
+        assert_eq!(req.url().path(), "/api/v2/members/accounts/..%2Forders");
+    }
+
     #[async_std::test]
     async fn get_account_of_currency() {
         let params = GetAccountOfCurrency {
@@ -723,11 +814,7 @@ mod tests {
         let params = GetInternalTransfers {
             currency: Some("max".into()),
             side: InternalTransferSide::In,
-            from_timestamp: None,
-            to_timestamp: None,
-            pagination: None,
-            page_params: None,
-            offset: None,
+            range: TimeRangeQuery::default(),
         };
         let resp = create_client("get_internal_transfers.yaml")
             .await
@@ -743,7 +830,7 @@ mod tests {
                 uuid: "(test erased uuid)".into(),
                 currency: "max".into(),
                 amount: dec!(1.0),
-                created_at: Some(Utc.timestamp(1605265665, 0)),
+                created_at: Some(Utc.timestamp_opt(1605265665, 0).unwrap()),
                 state: "done".into(),
                 from_member: "(test erased from_member)".into(),
                 to_member: "(test erased to_member)".into()
@@ -769,7 +856,7 @@ mod tests {
                 uuid: "(test erased uuid)".into(),
                 currency: "max".into(),
                 amount: dec!(1.0),
-                created_at: Some(Utc.timestamp(1605265665, 0)),
+                created_at: Some(Utc.timestamp_opt(1605265665, 0).unwrap()),
                 state: "done".into(),
                 from_member: "(test erased from_member)".into(),
                 to_member: "(test erased to_member)".into()
@@ -781,11 +868,7 @@ mod tests {
     async fn get_rewards() {
         let params = GetRewards {
             currency: Some("max".into()),
-            from_timestamp: Some(Utc.timestamp(1637316000, 0)),
-            to_timestamp: None,
-            pagination: None,
-            page_params: None,
-            offset: None,
+            range: TimeRangeQuery::default().since(Utc.timestamp_opt(1637316000, 0).unwrap()),
         };
         let resp = create_client("get_rewards.yaml")
             .await
@@ -801,7 +884,7 @@ mod tests {
                 reward_type: RewardType::HoldingReward,
                 currency: "max".into(),
                 amount: dec!(6.21724144),
-                created_at: Some(Utc.timestamp(1637346829, 0)),
+                created_at: Some(Utc.timestamp_opt(1637346829, 0).unwrap()),
                 state: "done".into(),
                 note: "(test erased note)".into()
             }]
@@ -814,11 +897,7 @@ mod tests {
             reward_type: RewardType::HoldingReward,
             detail: GetRewards {
                 currency: Some("max".into()),
-                from_timestamp: Some(Utc.timestamp(1637316000, 0)),
-                to_timestamp: None,
-                pagination: None,
-                page_params: None,
-                offset: None,
+                range: TimeRangeQuery::default().since(Utc.timestamp_opt(1637316000, 0).unwrap()),
             },
         };
         let resp = create_client("get_rewards_of_type.yaml")
@@ -835,7 +914,7 @@ mod tests {
                 reward_type: RewardType::HoldingReward,
                 currency: "max".into(),
                 amount: dec!(6.21724144),
-                created_at: Some(Utc.timestamp(1637346829, 0)),
+                created_at: Some(Utc.timestamp_opt(1637346829, 0).unwrap()),
                 state: "done".into(),
                 note: "(test erased note)".into()
             }]
@@ -845,12 +924,8 @@ mod tests {
     #[async_std::test]
     async fn get_saving_interest_history() {
         let params = GetSavingInterestHistory {
-            currency: "usdt".to_string(),
-            from_timestamp: Some(Utc.timestamp(1634724000, 0)),
-            to_timestamp: None,
-            pagination: None,
-            page_params: None,
-            offset: None,
+            currency: Currency::USDT,
+            range: TimeRangeQuery::default().since(Utc.timestamp_opt(1634724000, 0).unwrap()),
         };
         let resp = create_client("get_saving_interest_history.yaml")
             .await
@@ -867,7 +942,7 @@ mod tests {
                     reward_type: RewardType::SavingsInterest,
                     currency: "usdt".to_string(),
                     amount: dec!(0.00005154),
-                    created_at: Some(Utc.timestamp(1635711201, 0)),
+                    created_at: Some(Utc.timestamp_opt(1635711201, 0).unwrap()),
                     state: "done".to_string(),
                     note: "(test erased note)".to_string()
                 },
@@ -876,7 +951,7 @@ mod tests {
                     reward_type: RewardType::SavingsInterest,
                     currency: "usdt".to_string(),
                     amount: dec!(0.03194253),
-                    created_at: Some(Utc.timestamp(1634760738, 0)),
+                    created_at: Some(Utc.timestamp_opt(1634760738, 0).unwrap()),
                     state: "done".to_string(),
                     note: "(test erased note)".to_string()
                 }
@@ -902,4 +977,87 @@ mod tests {
             }
         );
     }
+
+    #[test]
+    fn two_factor_lock_object_form() {
+        let lock: TwoFactorLock = serde_json::from_str(r#"{"locked_until":1637671372}"#).unwrap();
+        assert_eq!(
+            lock.locked_until,
+            Some(Utc.timestamp_opt(1637671372, 0).unwrap())
+        );
+        let lock: TwoFactorLock =
+            serde_json::from_str(r#"{"locked_until":"2021-11-23T12:42:52Z"}"#).unwrap();
+        assert_eq!(
+            lock.locked_until,
+            Some(Utc.timestamp_opt(1637671372, 0).unwrap())
+        );
+        let lock: TwoFactorLock = serde_json::from_str(r#"{"locked_until":null}"#).unwrap();
+        assert_eq!(lock.locked_until, None);
+
+        let profile: RespProfile =
+            serde_json::from_str(r#"{"locked_status_of_2fa":{"locked_until":1637671372}}"#)
+                .unwrap();
+        assert_eq!(
+            profile.locked_status_of_2fa,
+            Some(TwoFactorLock {
+                locked_until: Some(Utc.timestamp_opt(1637671372, 0).unwrap())
+            })
+        );
+    }
+
+    #[test]
+    fn two_factor_lock_string_form() {
+        let lock: TwoFactorLock = serde_json::from_str(r#""2021-11-23T20:42:52+08:00""#).unwrap();
+        assert_eq!(
+            lock.locked_until,
+            Some(Utc.timestamp_opt(1637671372, 0).unwrap())
+        );
+        let lock: TwoFactorLock = serde_json::from_str(r#""1637671372""#).unwrap();
+        assert_eq!(
+            lock.locked_until,
+            Some(Utc.timestamp_opt(1637671372, 0).unwrap())
+        );
+        let lock: TwoFactorLock = serde_json::from_str(r#""""#).unwrap();
+        assert_eq!(lock.locked_until, None);
+        assert!(serde_json::from_str::<TwoFactorLock>(r#""tomorrow""#).is_err());
+
+        let profile: RespProfile =
+            serde_json::from_str(r#"{"locked_status_of_2fa":"2021-11-23T12:42:52Z"}"#).unwrap();
+        assert_eq!(
+            profile.locked_status_of_2fa.unwrap().locked_until,
+            Some(Utc.timestamp_opt(1637671372, 0).unwrap())
+        );
+        let profile: RespProfile =
+            serde_json::from_str(r#"{"locked_status_of_2fa":null}"#).unwrap();
+        assert_eq!(profile.locked_status_of_2fa, None);
+    }
+
+    #[test]
+    fn two_factor_lock_json_round_trip() {
+        let lock = TwoFactorLock {
+            locked_until: Some(Utc.timestamp_opt(1637671372, 0).unwrap()),
+        };
+        let value = serde_json::to_value(&lock).unwrap();
+        assert_eq!(value, serde_json::json!({"locked_until": 1637671372}));
+        assert_eq!(
+            serde_json::from_value::<TwoFactorLock>(value).unwrap(),
+            lock
+        );
+
+        let value = serde_json::to_value(TwoFactorLock::default()).unwrap();
+        assert_eq!(value, serde_json::json!({ "locked_until": null }));
+        assert_eq!(
+            serde_json::from_value::<TwoFactorLock>(value).unwrap(),
+            TwoFactorLock::default()
+        );
+    }
+
+    #[test]
+    fn enum_wire_str() {
+        assert_wire_str(RewardType::VARIANTS);
+        assert_wire_str(AccountStatus::VARIANTS);
+        assert_wire_str(MemberType::VARIANTS);
+        assert_wire_str(Gender::VARIANTS);
+        assert_wire_str(InternalTransferSide::VARIANTS);
+    }
 }
@@ -13,9 +13,13 @@ pub use withdrawal::*;
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::common::{GroupId, OrderBy, PageParams, TimeRange, TimeRangeQuery};
     use crate::error::Error;
     use crate::util::test_util::*;
+    use crate::v2::rest::{ApiHost, AuthGetDebug};
     use crate::Credentials;
+    use chrono::{TimeZone, Utc};
+    use serde_json::{json, Value as JsonValue};
     use surf::Client as HTTPClient;
     use surf_vcr::VcrMode;
 
@@ -56,4 +60,221 @@ mod tests {
             panic!("Authentication must fail with empty credentials.");
         }
     }
+
+    // Sign with a fixed nonce, check that the query string and the signed payload carry identical parameters, and
+    // return the query string.
+    fn signed_query(params: &impl AuthGetDebug) -> String {
+        let credentials = Credentials::new("api key".into(), "api secret".into());
+        let (url, payload, _) = params
+            .signed_parts_with(&ApiHost::default(), &credentials, 1637671372000)
+            .expect("failed to sign request");
+
+        // parameters of signed payload, with all scalars in string form
+        let mut payload: JsonValue =
+            serde_json::from_slice(&base64::decode(payload).expect("payload is not base64"))
+                .expect("payload is not JSON");
+        let payload = payload.as_object_mut().expect("payload is not an object");
+        assert_eq!(payload.remove("path"), Some(json!(params.path())));
+        let stringify = |val: &JsonValue| match val {
+            JsonValue::String(val) => json!(val),
+            val => json!(val.to_string()),
+        };
+        let payload_params: serde_json::Map<_, _> = payload
+            .iter()
+            .map(|(key, val)| match val {
+                JsonValue::Array(items) => (key.clone(), items.iter().map(stringify).collect()),
+                val => (key.clone(), stringify(val)),
+            })
+            .collect();
+
+        // parameters of query string, "key[]" pairs are collected into arrays
+        let mut query_params = serde_json::Map::new();
+        for (key, val) in url.query_pairs() {
+            match key.strip_suffix("[]") {
+                Some(key) => query_params
+                    .entry(key)
+                    .or_insert_with(|| json!([]))
+                    .as_array_mut()
+                    .unwrap()
+                    .push(json!(val)),
+                None => assert!(
+                    query_params.insert(key.into_owned(), json!(val)).is_none(),
+                    "duplicated query key"
+                ),
+            }
+        }
+
+        assert_eq!(query_params, payload_params);
+        url.query().unwrap_or_default().to_owned()
+    }
+
+    fn page_params() -> Option<PageParams> {
+        Some(PageParams { page: 2, limit: 10 })
+    }
+
+    #[test]
+    fn signed_query_consistency() {
+        let from = Some(Utc.timestamp_opt(1637316000, 0).unwrap());
+        let to = Some(Utc.timestamp_opt(1637402400, 0).unwrap());
+        let range = |pagination| TimeRangeQuery {
+            time: TimeRange { from, to },
+            pagination: Some(pagination),
+            page_params: page_params(),
+            offset: Some(5),
+        };
+        let nonce = "nonce=1637671372000";
+
+        assert_eq!(signed_query(&GetProfile {}), nonce);
+        assert_eq!(signed_query(&GetProfileAndAccount {}), nonce);
+        assert_eq!(signed_query(&GetAccountVIPLevel {}), nonce);
+        assert_eq!(signed_query(&GetMaxRewardsYesterday {}), nonce);
+        assert_eq!(
+            signed_query(&GetAccountOfCurrency {
+                path_currency: "usdt".into(),
+            }),
+            nonce
+        );
+
+        assert_eq!(
+            signed_query(&GetOrder {
+                id: Some(1545763894),
+                client_oid: Some("my order".into()),
+            }),
+            "id=1545763894&client_oid=my+order&nonce=1637671372000"
+        );
+        assert_eq!(
+            signed_query(&GetOrders {
+                market: "dotusdt".into(),
+                state: vec![OrderState::Wait, OrderState::Done],
+                order_by: Some(OrderBy::Desc),
+                group_id: Some(GroupId(12)),
+                pagination: Some(true),
+                page_params: page_params(),
+                offset: Some(5),
+            }),
+            "market=dotusdt&state%5B%5D=wait&state%5B%5D=done&order_by=desc&group_id=12&pagination=true&page=2&limit=10&offset=5&nonce=1637671372000"
+        );
+
+        let orders = |state| GetOrders {
+            market: "dotusdt".into(),
+            state,
+            order_by: None,
+            group_id: None,
+            pagination: None,
+            page_params: None,
+            offset: None,
+        };
+        assert_eq!(
+            signed_query(&orders(vec![])),
+            "market=dotusdt&nonce=1637671372000"
+        );
+        assert_eq!(
+            signed_query(&orders(vec![OrderState::Convert])),
+            "market=dotusdt&state%5B%5D=convert&nonce=1637671372000"
+        );
+
+        assert_eq!(
+            signed_query(&GetMyTradesOfOrder {
+                id: None,
+                client_oid: Some("oid".into()),
+            }),
+            "client_oid=oid&nonce=1637671372000"
+        );
+        assert_eq!(
+            signed_query(&GetMyTrades {
+                market: "dotusdt".into(),
+                timestamp_before: to,
+                after_order_id: Some(29009000),
+                before_order_id: Some(29009100),
+                order_by: Some(OrderBy::Asc),
+                pagination: Some(true),
+                page_params: page_params(),
+                offset: Some(5),
+            }),
+            "market=dotusdt&timestamp=1637402400&from=29009000&to=29009100&order_by=asc&pagination=true&page=2&limit=10&offset=5&nonce=1637671372000"
+        );
+
+        assert_eq!(
+            signed_query(&GetDeposits {
+                currency: "twd".into(),
+                state: Some(DepositState::Accepted),
+                range: range(true),
+            }),
+            "currency=twd&state=accepted&from=1637316000&to=1637402400&pagination=true&page=2&limit=10&offset=5&nonce=1637671372000"
+        );
+        assert_eq!(
+            signed_query(&GetDepositDetail {
+                txid: "tx-1".into(),
+            }),
+            "txid=tx-1&nonce=1637671372000"
+        );
+        assert_eq!(
+            signed_query(&GetDepositAddresses {
+                currency: "btc".into(),
+                pagination: Some(true),
+                page_params: page_params(),
+                offset: Some(5),
+            }),
+            "currency=btc&pagination=true&page=2&limit=10&offset=5&nonce=1637671372000"
+        );
+
+        assert_eq!(
+            signed_query(&GetInternalTransfers {
+                currency: Some("max".into()),
+                side: InternalTransferSide::In,
+                range: range(false),
+            }),
+            "currency=max&side=in&from=1637316000&to=1637402400&pagination=false&page=2&limit=10&offset=5&nonce=1637671372000"
+        );
+        assert_eq!(
+            signed_query(&GetInternalTransferByUUID {
+                uuid: "2011131107100357467635".into(),
+            }),
+            "uuid=2011131107100357467635&nonce=1637671372000"
+        );
+        let rewards = || GetRewards {
+            currency: Some("max".into()),
+            range: range(true),
+        };
+        let rewards_query = "currency=max&from=1637316000&to=1637402400&pagination=true&page=2&limit=10&offset=5&nonce=1637671372000";
+        assert_eq!(signed_query(&rewards()), rewards_query);
+        assert_eq!(
+            signed_query(&GetRewardsOfType {
+                reward_type: RewardType::HoldingReward,
+                detail: rewards(),
+            }),
+            rewards_query
+        );
+        assert_eq!(
+            signed_query(&GetSavingInterestHistory {
+                currency: "usdt".into(),
+                range: range(true),
+            }),
+            "currency=usdt&from=1637316000&to=1637402400&pagination=true&page=2&limit=10&offset=5&nonce=1637671372000"
+        );
+
+        assert_eq!(
+            signed_query(&GetWithdrawal {
+                uuid: "211120074215374658171".into(),
+            }),
+            "uuid=211120074215374658171&nonce=1637671372000"
+        );
+        assert_eq!(
+            signed_query(&GetWithdrawals {
+                currency: Some("sol".into()),
+                state: Some(WithdrawalState::Accepted),
+                range: range(true),
+            }),
+            "currency=sol&state=accepted&from=1637316000&to=1637402400&pagination=true&page=2&limit=10&offset=5&nonce=1637671372000"
+        );
+        assert_eq!(
+            signed_query(&GetWithdrawAddresses {
+                currency: "sol".into(),
+                pagination: None,
+                page_params: None,
+                offset: None,
+            }),
+            "currency=sol&nonce=1637671372000"
+        );
+    }
 }
@@ -1,11 +1,11 @@
 use chrono::serde as chrono_serde;
-use http_types::Request as HTTPRequest;
 use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
 
+use crate::common::precision::{self, PrecisionError};
 use crate::common::*;
 use crate::v2::rest::api_impl::*;
-use crate::v2::rest::internal;
+use crate::v2::rest::MarketInfo;
 
 // ========
 // Requests
@@ -28,7 +28,10 @@ impl_api!(GetOrder => RespOrder : auth GET, "/api/v2/order");
 /// GET /api/v2/orders
 ///
 /// Get your orders, results is paginated.
-#[derive(Serialize, Debug)]
+///
+/// Build with [`GetOrders::for_market`], e.g.
+/// `GetOrders::for_market("btctwd").states([OrderState::Wait]).page(2).build()`.
+#[derive(Serialize, Clone, Eq, PartialEq, Debug)]
 pub struct GetOrders {
     /// Unique market id, check /api/v2/markets for available markets.
     pub market: Symbol,
@@ -40,7 +43,7 @@ pub struct GetOrders {
     pub order_by: Option<OrderBy>,
     /// Group order id.
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub group_id: Option<u64>,
+    pub group_id: Option<GroupId>,
     /// Do pagination & return metadata in header (default `true`).
     #[serde(skip_serializing_if = "Option::is_none")]
     pub pagination: Option<bool>,
@@ -51,64 +54,78 @@ pub struct GetOrders {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub offset: Option<u64>,
 }
+impl_api!(GetOrders => Vec<RespOrder> : auth GET, "/api/v2/orders");
+impl_paginated!(GetOrders);
+
+impl GetOrders {
+    /// Orders still open in the market, i.e. in `wait` or `convert` state.
+    pub fn open(market: impl Into<Symbol>) -> Self {
+        Self::for_market(market)
+            .states([OrderState::Wait, OrderState::Convert])
+            .build()
+    }
 
-impl internal::RestApiBase for GetOrders {
-    endpoint_binding!(fixed "/api/v2/orders");
-    type Response = Vec<RespOrder>;
+    /// Orders in any state in the market.
+    pub fn all(market: impl Into<Symbol>) -> Self {
+        Self::for_market(market)
+            .states([
+                OrderState::Wait,
+                OrderState::Done,
+                OrderState::Cancel,
+                OrderState::Convert,
+                OrderState::Finalizing,
+                OrderState::Failed,
+            ])
+            .build()
+    }
+
+    /// Start building the query of orders in the market.
+    pub fn for_market(market: impl Into<Symbol>) -> GetOrdersBuilder {
+        GetOrdersBuilder {
+            inner: GetOrders {
+                market: market.into(),
+                state: Vec::new(),
+                order_by: None,
+                group_id: None,
+                pagination: None,
+                page_params: None,
+                offset: None,
+            },
+        }
+    }
 }
 
-impl GetOrders {
-    convert_from_response!(Vec<RespOrder>);
-
-    pub fn to_request(&self, credentials: &crate::Credentials) -> HTTPRequest {
-        let (url, header_payload, header_signature) = {
-            use internal::RestApiBase;
-
-            let mut url = self.get_url();
-            let path = url.path().to_string();
-            let params = internal::AuthParamsOuterWrapper {
-                path: &path,
-                inner: internal::AuthParamsInnerWrapper {
-                    params: self,
-                    nonce: credentials.nonce(),
-                },
-            };
-            {
-                // workaround for "state[]=..."
-                let mut qs_builder = url.query_pairs_mut();
-                qs_builder.append_pair("market", &self.market);
-                self.state.iter().for_each(|item| {
-                    qs_builder.append_pair("state[]", item.as_srt());
-                });
-                if let Some(ref order_by) = self.order_by {
-                    qs_builder.append_pair(
-                        "order_by",
-                        format!("{:?}", order_by).to_lowercase().as_str(),
-                    );
-                }
-                if let Some(ref pagination) = self.pagination {
-                    qs_builder.append_pair("pagination", &pagination.to_string());
-                }
-                if let Some(ref page_params) = self.page_params {
-                    qs_builder.append_pair("page", &page_params.page.to_string());
-                    qs_builder.append_pair("limit", &page_params.limit.to_string());
-                }
-                if let Some(ref offset) = self.offset {
-                    qs_builder.append_pair("offset", &offset.to_string());
-                }
-                qs_builder.append_pair("nonce", &params.inner.nonce.to_string());
-            }
-            let (payload, signature) = params.signed_payload(credentials);
-            (url, payload, signature)
-        };
+/// Builder of [`GetOrders`], created by [`GetOrders::for_market`].
+#[derive(Debug)]
+pub struct GetOrdersBuilder {
+    inner: GetOrders,
+}
 
-        let mut req = HTTPRequest::get(url);
-        req.insert_header(internal::HEADER_AUTH_ACCESS_KEY, &credentials.access_key);
-        req.insert_header(internal::HEADER_AUTH_PAYLOAD, header_payload);
-        req.insert_header(internal::HEADER_AUTH_SIGNATURE, header_signature);
-        req
+impl GetOrdersBuilder {
+    /// Filter by states.
+    pub fn states(mut self, states: impl IntoIterator<Item = OrderState>) -> Self {
+        self.inner.state = states.into_iter().collect();
+        self
+    }
+
+    /// Order in created time.
+    pub fn order_by(mut self, order_by: OrderBy) -> Self {
+        self.inner.order_by = Some(order_by);
+        self
+    }
+
+    /// Filter by group order id.
+    pub fn group_id(mut self, group_id: impl Into<GroupId>) -> Self {
+        self.inner.group_id = Some(group_id.into());
+        self
+    }
+
+    /// Finish the request.
+    pub fn build(self) -> GetOrders {
+        self.inner
     }
 }
+impl_builder_paging!(GetOrdersBuilder => inner);
 
 /// POST /api/v2/orders
 ///
@@ -134,9 +151,27 @@ pub struct CreateOrder {
     pub ord_type: OrderType,
     /// Group order id.
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub group_id: Option<u64>,
+    pub group_id: Option<GroupId>,
+}
+// Re-creating an order is safe only if the server can dedupe it by client_oid.
+impl_api!(CreateOrder => RespOrder : auth POST, "/api/v2/orders", idempotent params {
+    params.client_oid.is_some()
+});
+
+impl CreateOrder {
+    /// Check that the volume fits the base unit precision of the market, and the prices fit the quote unit
+    /// precision. Round them beforehand with [`round_price`] and [`round_volume`].
+    ///
+    /// [`round_price`]: crate::common::precision::round_price
+    /// [`round_volume`]: crate::common::precision::round_volume
+    pub fn validate(&self, market: &MarketInfo) -> Result<(), PrecisionError> {
+        precision::check(self.volume, market.base_unit_precision)?;
+        for price in self.price.iter().chain(self.stop_price.iter()) {
+            precision::check(*price, market.quote_unit_precision)?;
+        }
+        Ok(())
+    }
 }
-impl_api!(CreateOrder => RespOrder : auth POST, "/api/v2/orders");
 
 // TODO: implement batch order creation
 // impl_api!(CreateOneByOneOrder => POST "/api/v2/orders/multi/onebyone")
@@ -144,7 +179,7 @@ impl_api!(CreateOrder => RespOrder : auth POST, "/api/v2/orders");
 /// POST /api/v2/order/delete
 ///
 /// Cancel an order.
-#[derive(Serialize, Debug)]
+#[derive(Serialize, Default, Debug)]
 pub struct DeleteOrder {
     /// Unique order id.
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -153,7 +188,25 @@ pub struct DeleteOrder {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub client_oid: Option<String>,
 }
-impl_api!(DeleteOrder => RespOrder : auth POST, "/api/v2/order/delete");
+impl_api!(DeleteOrder => RespOrder : auth POST, "/api/v2/order/delete", idempotent _params { true });
+
+impl DeleteOrder {
+    /// Cancel the order of the unique order id.
+    pub fn by_id(id: u64) -> Self {
+        Self {
+            id: Some(id),
+            client_oid: None,
+        }
+    }
+
+    /// Cancel the order of the user specific order id.
+    pub fn by_client_oid(client_oid: impl Into<String>) -> Self {
+        Self {
+            id: None,
+            client_oid: Some(client_oid.into()),
+        }
+    }
+}
 
 /// POST /api/v2/orders/clear
 ///
@@ -166,16 +219,16 @@ pub struct ClearOrders {
     pub side: OrderSide,
     /// Group order id.
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub group_id: Option<u64>,
+    pub group_id: Option<GroupId>,
 }
-impl_api!(ClearOrders => Vec<RespOrder> : auth POST, "/api/v2/orders/clear");
+impl_api!(ClearOrders => Vec<RespOrder> : auth POST, "/api/v2/orders/clear", idempotent _params { true });
 
 // =========
 // Responses
 // =========
 
 /// Submitted order detail.
-#[derive(Deserialize, Eq, PartialEq, Default, Debug)]
+#[derive(Serialize, Deserialize, Clone, Eq, PartialEq, Default, Debug)]
 #[serde(default)]
 pub struct RespOrder {
     /// id (integer, optional): unique order id.
@@ -187,10 +240,13 @@ pub struct RespOrder {
     /// ord_type (string, optional): `'limit'`, `'market'`, `'stop_limit'`, `'stop_market'`, `'post_only'` or `'ioc_limit'`
     pub ord_type: OrderType,
     /// price (string, optional): price of a unit.
+    #[serde(deserialize_with = "crate::util::serde::lenient_decimal_option")]
     pub price: Option<Decimal>,
     /// stop_price (string, optional): price to trigger a stop order.
+    #[serde(deserialize_with = "crate::util::serde::lenient_decimal_option")]
     pub stop_price: Option<Decimal>,
     /// avg_price (string, optional): average execution price.
+    #[serde(deserialize_with = "crate::util::serde::lenient_decimal_option")]
     pub avg_price: Option<Decimal>,
     /// state (string, optional): `'wait'`, `'done'`, `'cancel'`, or `'convert'`; `'wait'` means waiting for fulfillment; `'done'` means fullfilled; `'cancel'` means cancelled; `'convert'` means the stop order is triggered.
     pub state: OrderState,
@@ -209,15 +265,18 @@ pub struct RespOrder {
     #[serde(with = "chrono_serde::ts_milliseconds_option")]
     pub updated_at_in_ms: Option<DateTime>,
     /// volume (string, optional): total amount to sell/buy, an order could be partially executed.
+    #[serde(deserialize_with = "crate::util::serde::lenient_decimal_option")]
     pub volume: Option<Decimal>,
     /// remaining_volume (string, optional): remaining volume.
+    #[serde(deserialize_with = "crate::util::serde::lenient_decimal_option")]
     pub remaining_volume: Option<Decimal>,
     /// executed_volume (string, optional): executed volume.
+    #[serde(deserialize_with = "crate::util::serde::lenient_decimal_option")]
     pub executed_volume: Option<Decimal>,
     /// trades_count (integer, optional): trade count.
     pub trades_count: Option<u64>,
     /// group_id (integer, optional): group order id.
-    pub group_id: Option<u64>,
+    pub group_id: Option<GroupId>,
 }
 
 // ============================
@@ -225,7 +284,7 @@ pub struct RespOrder {
 // ============================
 
 /// Order types.
-#[derive(Serialize, Deserialize, Copy, Clone, Eq, PartialEq, Debug)]
+#[derive(Serialize, Deserialize, Copy, Clone, Eq, PartialEq, Debug, Default)]
 #[serde(rename_all = "snake_case")]
 pub enum OrderType {
     Limit,
@@ -234,23 +293,28 @@ pub enum OrderType {
     StopMarket,
     PostOnly,
     IocLimit,
+    #[default]
     Unknown,
 }
 
+impl_wire_str!(OrderType {
+    Limit => "limit",
+    Market => "market",
+    StopLimit => "stop_limit",
+    StopMarket => "stop_market",
+    PostOnly => "post_only",
+    IocLimit => "ioc_limit",
+    Unknown => "unknown",
+});
+
 impl OrderType {
     pub fn is_unknown(&self) -> bool {
         self == &Self::Unknown
     }
 }
 
-impl Default for OrderType {
-    fn default() -> Self {
-        Self::Unknown
-    }
-}
-
 /// Order state.
-#[derive(Serialize, Deserialize, Copy, Clone, Eq, PartialEq, Debug)]
+#[derive(Serialize, Deserialize, Copy, Clone, Eq, PartialEq, Debug, Default)]
 #[serde(rename_all = "lowercase")]
 pub enum OrderState {
     Wait,
@@ -259,9 +323,20 @@ pub enum OrderState {
     Convert,
     Finalizing,
     Failed,
+    #[default]
     Unknown,
 }
 
+impl_wire_str!(OrderState {
+    Wait => "wait",
+    Done => "done",
+    Cancel => "cancel",
+    Convert => "convert",
+    Finalizing => "finalizing",
+    Failed => "failed",
+    Unknown => "unknown",
+});
+
 impl OrderState {
     pub fn is_wait(&self) -> bool {
         self == &Self::Wait
@@ -279,22 +354,37 @@ impl OrderState {
         self == &Self::Unknown
     }
 
-    pub fn as_srt(&self) -> &'static str {
-        match *self {
-            Self::Wait => "wait",
-            Self::Done => "done",
-            Self::Cancel => "cancel",
-            Self::Convert => "convert",
-            Self::Finalizing => "finalizing",
-            Self::Failed => "failed",
-            Self::Unknown => "unknown",
+    /// `true` if the order is closed and will not change any more, i.e. done, cancelled or failed.
+    pub fn is_terminal(&self) -> bool {
+        matches!(self, Self::Done | Self::Cancel | Self::Failed)
+    }
+
+    /// How far along the lifecycle the order is: wait < convert < finalizing < done, cancel and failed. The terminal
+    /// states share the same rank, so this is not a total order of the states and `Ord` is not implemented.
+    ///
+    /// `None` for unknown states, which can not be ranked.
+    pub fn rank(&self) -> Option<u8> {
+        match self {
+            Self::Wait => Some(0),
+            Self::Convert => Some(1),
+            Self::Finalizing => Some(2),
+            Self::Done | Self::Cancel | Self::Failed => Some(3),
+            Self::Unknown => None,
         }
     }
-}
 
-impl Default for OrderState {
-    fn default() -> Self {
-        Self::Unknown
+    /// `true` if this state is ranked before the previous one, e.g. a later push of the order showing an earlier
+    /// state. Unknown states are never taken as regressions.
+    pub fn regresses_from(&self, previous: &Self) -> bool {
+        match (self.rank(), previous.rank()) {
+            (Some(rank), Some(previous)) => rank < previous,
+            _ => false,
+        }
+    }
+
+    #[deprecated(note = "use `as_str` instead")]
+    pub fn as_srt(&self) -> &'static str {
+        self.as_str()
     }
 }
 
@@ -341,10 +431,10 @@ mod tests {
                 avg_price: Some(dec!(52.0)),
                 state: OrderState::Done,
                 market: "dotusdt".into(),
-                created_at: Some(Utc.timestamp(1635853116, 0)),
-                created_at_in_ms: Some(Utc.timestamp(1635853116, 171000000)),
-                updated_at: Some(Utc.timestamp(1635853634, 0)),
-                updated_at_in_ms: Some(Utc.timestamp(1635853634, 47000000)),
+                created_at: Some(Utc.timestamp_opt(1635853116, 0).unwrap()),
+                created_at_in_ms: Some(Utc.timestamp_opt(1635853116, 171000000).unwrap()),
+                updated_at: Some(Utc.timestamp_opt(1635853634, 0).unwrap()),
+                updated_at_in_ms: Some(Utc.timestamp_opt(1635853634, 47000000).unwrap()),
                 volume: Some(dec!(3.14)),
                 remaining_volume: Some(dec!(0.0)),
                 executed_volume: Some(dec!(3.14)),
@@ -354,8 +444,8 @@ mod tests {
         );
     }
 
-    #[async_std::test]
-    async fn get_all_orders() {
+    // The cassette was recorded before `GetOrders` was signed by the shared path, which also sends `Content-Type`.
+    fn get_all_orders_request() -> http_types::Request {
         let params = GetOrders {
             market: "dotusdt".into(),
             state: vec![
@@ -372,9 +462,16 @@ mod tests {
             page_params: None,
             offset: None,
         };
+        let mut req = params.to_request(&TEST_CREDENTIALS);
+        req.remove_header("Content-Type");
+        req
+    }
+
+    #[async_std::test]
+    async fn get_all_orders() {
         let resp = create_client("get_all_orders.yaml")
             .await
-            .send(params.to_request(&TEST_CREDENTIALS))
+            .send(get_all_orders_request())
             .await
             .expect("Error while sending request");
         let result: Vec<RespOrder> = GetOrders::read_response(resp.into()).await.unwrap();
@@ -391,10 +488,10 @@ mod tests {
                 avg_price: Some(dec!(52.0)),
                 state: OrderState::Done,
                 market: "dotusdt".into(),
-                created_at: Some(Utc.timestamp(1635853116, 0)),
-                created_at_in_ms: Some(Utc.timestamp(1635853116, 171000000)),
-                updated_at: Some(Utc.timestamp(1635853634, 0)),
-                updated_at_in_ms: Some(Utc.timestamp(1635853634, 47000000)),
+                created_at: Some(Utc.timestamp_opt(1635853116, 0).unwrap()),
+                created_at_in_ms: Some(Utc.timestamp_opt(1635853116, 171000000).unwrap()),
+                updated_at: Some(Utc.timestamp_opt(1635853634, 0).unwrap()),
+                updated_at_in_ms: Some(Utc.timestamp_opt(1635853634, 47000000).unwrap()),
                 volume: Some(dec!(3.14)),
                 remaining_volume: Some(dec!(0.0)),
                 executed_volume: Some(dec!(3.14)),
@@ -404,6 +501,22 @@ mod tests {
         );
     }
 
+    #[async_std::test]
+    async fn get_all_orders_enveloped() {
+        let resp = create_client("get_all_orders.yaml")
+            .await
+            .send(get_all_orders_request())
+            .await
+            .expect("Error while sending request");
+        let envelope = GetOrders::read_response_enveloped(resp.into())
+            .await
+            .unwrap();
+        assert_eq!(envelope.status, 200);
+        assert_eq!(envelope.header("Page"), Some("1"));
+        assert_eq!(envelope.header("Per-Page"), Some("100"));
+        assert_eq!(envelope.body.len(), 2);
+    }
+
     #[async_std::test]
     async fn create_order() {
         let params = CreateOrder {
@@ -434,10 +547,10 @@ mod tests {
                 avg_price: Some(dec!(0.0)),
                 state: OrderState::Wait,
                 market: "maxusdt".into(),
-                created_at: Some(Utc.timestamp(1636876252, 0)),
-                created_at_in_ms: Some(Utc.timestamp(1636876252, 685000000)),
-                updated_at: Some(Utc.timestamp(1636876252, 0)),
-                updated_at_in_ms: Some(Utc.timestamp(1636876252, 685000000)),
+                created_at: Some(Utc.timestamp_opt(1636876252, 0).unwrap()),
+                created_at_in_ms: Some(Utc.timestamp_opt(1636876252, 685000000).unwrap()),
+                updated_at: Some(Utc.timestamp_opt(1636876252, 0).unwrap()),
+                updated_at_in_ms: Some(Utc.timestamp_opt(1636876252, 685000000).unwrap()),
                 volume: Some(dec!(23.4)),
                 remaining_volume: Some(dec!(23.4)),
                 executed_volume: Some(dec!(0.0)),
@@ -471,10 +584,10 @@ mod tests {
                 avg_price: Some(dec!(52.0)),
                 state: OrderState::Done,
                 market: "dotusdt".into(),
-                created_at: Some(Utc.timestamp(1635853116, 0)),
-                created_at_in_ms: Some(Utc.timestamp(1635853116, 171000000)),
-                updated_at: Some(Utc.timestamp(1635853634, 0)),
-                updated_at_in_ms: Some(Utc.timestamp(1635853634, 47000000)),
+                created_at: Some(Utc.timestamp_opt(1635853116, 0).unwrap()),
+                created_at_in_ms: Some(Utc.timestamp_opt(1635853116, 171000000).unwrap()),
+                updated_at: Some(Utc.timestamp_opt(1635853634, 0).unwrap()),
+                updated_at_in_ms: Some(Utc.timestamp_opt(1635853634, 47000000).unwrap()),
                 volume: Some(dec!(3.14)),
                 remaining_volume: Some(dec!(0.0)),
                 executed_volume: Some(dec!(3.14)),
@@ -484,6 +597,22 @@ mod tests {
         );
     }
 
+    #[async_std::test]
+    async fn delete_order_not_found() {
+        let params = DeleteOrder {
+            id: Some(1),
+            client_oid: None,
+        };
+        let resp = create_client("delete_order_not_found.yaml")
+            .await
+            .send(params.to_request(&TEST_CREDENTIALS))
+            .await
+            .expect("Error while sending request");
+        let err = DeleteOrder::read_response(resp.into()).await.unwrap_err();
+        assert!(err.is_order_not_found(), "{:?}", err);
+        assert!(!err.is_nonce_error());
+    }
+
     #[async_std::test]
     async fn clear_order() {
         let params = ClearOrders {
@@ -509,10 +638,10 @@ mod tests {
                 avg_price: Some(dec!(0.0)),
                 state: OrderState::Wait,
                 market: "maxusdt".into(),
-                created_at: Some(Utc.timestamp(1636875985, 0)),
-                created_at_in_ms: Some(Utc.timestamp(1636875985, 861000000)),
-                updated_at: Some(Utc.timestamp(1636875985, 0)),
-                updated_at_in_ms: Some(Utc.timestamp(1636875985, 861000000)),
+                created_at: Some(Utc.timestamp_opt(1636875985, 0).unwrap()),
+                created_at_in_ms: Some(Utc.timestamp_opt(1636875985, 861000000).unwrap()),
+                updated_at: Some(Utc.timestamp_opt(1636875985, 0).unwrap()),
+                updated_at_in_ms: Some(Utc.timestamp_opt(1636875985, 861000000).unwrap()),
                 volume: Some(dec!(23.4)),
                 remaining_volume: Some(dec!(23.4)),
                 executed_volume: Some(dec!(0.0)),
@@ -521,4 +650,249 @@ mod tests {
             }]
         );
     }
+
+    #[test]
+    fn order_json_round_trip() {
+        let order: RespOrder = serde_json::from_str(fixtures::ORDER).expect("invalid test case");
+        let value = serde_json::to_value(&order).unwrap();
+        // timestamps are serialized back to numbers, in the same unit
+        let raw: serde_json::Value = serde_json::from_str(fixtures::ORDER).unwrap();
+        assert_eq!(value, raw);
+        assert_eq!(serde_json::from_value::<RespOrder>(value).unwrap(), order);
+
+        let order = RespOrder::default();
+        let value = serde_json::to_value(&order).unwrap();
+        assert!(value["created_at"].is_null());
+        assert_eq!(serde_json::from_value::<RespOrder>(value).unwrap(), order);
+    }
+
+    #[test]
+    fn order_empty_decimals() {
+        let order: RespOrder = serde_json::from_str(
+            r#"{"id":1,"ord_type":"market","price":"","stop_price":null,"avg_price":" ","volume":"0.01","executed_volume":0.005}"#,
+        )
+        .unwrap();
+        assert_eq!(order.price, None);
+        assert_eq!(order.stop_price, None);
+        assert_eq!(order.avg_price, None);
+        assert_eq!(order.remaining_volume, None);
+        assert_eq!(order.volume, Some(dec!(0.01)));
+        assert_eq!(order.executed_volume, Some(dec!(0.005)));
+
+        assert!(serde_json::from_str::<RespOrder>(r#"{"price":"n/a"}"#).is_err());
+    }
+
+    #[test]
+    fn get_orders_builder() {
+        assert_eq!(
+            GetOrders::for_market("btctwd").build(),
+            GetOrders {
+                market: "btctwd".into(),
+                state: vec![],
+                order_by: None,
+                group_id: None,
+                pagination: None,
+                page_params: None,
+                offset: None,
+            }
+        );
+        assert_eq!(
+            GetOrders::for_market("btctwd")
+                .states([OrderState::Wait, OrderState::Convert])
+                .order_by(OrderBy::Desc)
+                .group_id(12)
+                .page(2)
+                .limit(10)
+                .offset(5)
+                .build(),
+            GetOrders {
+                market: "btctwd".into(),
+                state: vec![OrderState::Wait, OrderState::Convert],
+                order_by: Some(OrderBy::Desc),
+                group_id: Some(GroupId(12)),
+                pagination: Some(true),
+                page_params: Some(PageParams { page: 2, limit: 10 }),
+                offset: Some(5),
+            }
+        );
+        // paging keeps the default limit, and pagination can be turned off afterwards
+        assert_eq!(
+            GetOrders::for_market("btctwd")
+                .page(3)
+                .pagination(false)
+                .build(),
+            GetOrders {
+                market: "btctwd".into(),
+                state: vec![],
+                order_by: None,
+                group_id: None,
+                pagination: Some(false),
+                page_params: Some(PageParams { page: 3, limit: 50 }),
+                offset: None,
+            }
+        );
+    }
+
+    #[test]
+    fn get_orders_order_by_query() {
+        let order_by = |params: GetOrders| -> Vec<String> {
+            params
+                .to_request(&TEST_CREDENTIALS)
+                .url()
+                .query_pairs()
+                .filter(|(k, _)| k == "order_by")
+                .map(|(_, v)| v.into_owned())
+                .collect()
+        };
+        let params = GetOrders::for_market("btctwd").order_by(OrderBy::Asc.reverse());
+        assert_eq!(order_by(params.build()), ["desc"]);
+        let params = GetOrders::for_market("btctwd").order_by("ASC".parse().unwrap());
+        assert_eq!(order_by(params.build()), ["asc"]);
+        assert!(order_by(GetOrders::for_market("btctwd").build()).is_empty());
+    }
+
+    #[test]
+    fn get_orders_state_presets() {
+        let states = |params: GetOrders| -> Vec<String> {
+            params
+                .to_request(&TEST_CREDENTIALS)
+                .url()
+                .query_pairs()
+                .filter(|(k, _)| k == "state[]")
+                .map(|(_, v)| v.into_owned())
+                .collect()
+        };
+        assert_eq!(states(GetOrders::open("btctwd")), ["wait", "convert"]);
+        assert_eq!(
+            states(GetOrders::all("btctwd")),
+            ["wait", "done", "cancel", "convert", "finalizing", "failed"]
+        );
+        assert_eq!(GetOrders::open("btctwd").market, "btctwd");
+    }
+
+    #[test]
+    fn delete_order_constructors() {
+        let body = |params: DeleteOrder| serde_json::to_value(params).unwrap();
+        assert_eq!(
+            body(DeleteOrder::by_id(1545763894)),
+            serde_json::json!({"id": 1545763894})
+        );
+        assert_eq!(
+            body(DeleteOrder::by_client_oid("my-order")),
+            serde_json::json!({"client_oid": "my-order"})
+        );
+    }
+
+    #[test]
+    fn enum_wire_str() {
+        assert_wire_str(OrderType::VARIANTS);
+        assert_wire_str(OrderState::VARIANTS);
+    }
+
+    #[test]
+    fn create_order_validate() {
+        let market = MarketInfo {
+            id: "maxusdt".into(),
+            base_unit_precision: 2,
+            quote_unit_precision: 4,
+            ..Default::default()
+        };
+        let order = CreateOrder {
+            market: "maxusdt".into(),
+            side: OrderSide::Sell,
+            volume: dec!(23.4),
+            price: Some(dec!(0.3512)),
+            client_oid: None,
+            stop_price: None,
+            ord_type: OrderType::Limit,
+            group_id: None,
+        };
+        assert_eq!(order.validate(&market), Ok(()));
+
+        let order = CreateOrder {
+            volume: dec!(23.456),
+            ..order
+        };
+        assert_eq!(
+            order.validate(&market),
+            Err(PrecisionError::TooPrecise {
+                value: dec!(23.456),
+                places: 2
+            })
+        );
+        let order = CreateOrder {
+            volume: precision::round_volume(
+                order.volume,
+                &market,
+                order.side,
+                precision::RoundingMode::Passive,
+            )
+            .unwrap(),
+            stop_price: Some(dec!(0.35125)),
+            ..order
+        };
+        assert_eq!(order.volume, dec!(23.45));
+        assert_eq!(
+            order.validate(&market),
+            Err(PrecisionError::TooPrecise {
+                value: dec!(0.35125),
+                places: 4
+            })
+        );
+
+        let order = CreateOrder {
+            price: Some(dec!(-1)),
+            stop_price: None,
+            ..order
+        };
+        assert_eq!(
+            order.validate(&market),
+            Err(PrecisionError::NotPositive(dec!(-1)))
+        );
+    }
+
+    #[test]
+    fn order_state_rank() {
+        use OrderState::*;
+
+        assert!(Wait.rank() < Convert.rank());
+        assert!(Convert.rank() < Finalizing.rank());
+        assert!(Finalizing.rank() < Done.rank());
+        assert_eq!(Done.rank(), Cancel.rank());
+        assert_eq!(Cancel.rank(), Failed.rank());
+        assert_eq!(Unknown.rank(), None);
+        // every terminal state is ranked last
+        for state in OrderState::VARIANTS {
+            assert_eq!(state.is_terminal(), state.rank() == Some(3), "{}", state);
+        }
+
+        assert!(Wait.regresses_from(&Done));
+        assert!(Convert.regresses_from(&Finalizing));
+        assert!(!Done.regresses_from(&Wait));
+        assert!(!Cancel.regresses_from(&Done));
+        assert!(!Wait.regresses_from(&Wait));
+        assert!(!Unknown.regresses_from(&Done));
+        assert!(!Wait.regresses_from(&Unknown));
+    }
+
+    #[test]
+    fn order_state_terminal() {
+        let terminal: Vec<_> = OrderState::VARIANTS
+            .iter()
+            .filter(|state| state.is_terminal())
+            .collect();
+        assert_eq!(
+            terminal,
+            [&OrderState::Done, &OrderState::Cancel, &OrderState::Failed]
+        );
+    }
+
+    #[test]
+    #[allow(deprecated)]
+    fn order_state_as_srt() {
+        for state in OrderState::VARIANTS {
+            assert_eq!(state.as_srt(), state.as_str());
+        }
+        assert_eq!(OrderState::Convert.as_srt(), "convert");
+    }
 }
@@ -11,7 +11,7 @@ use crate::v2::rest::api_impl::*;
 /// GET /api/v2/trades/my/of_order
 ///
 /// Get your executed trades related to a order.
-#[derive(Serialize, Debug)]
+#[derive(Serialize, Default, Debug)]
 pub struct GetMyTradesOfOrder {
     /// Unique order id.
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -25,7 +25,9 @@ impl_api!(GetMyTradesOfOrder => Vec<TradeRecord> : auth GET, "/api/v2/trades/my/
 /// GET /api/v2/trades/my
 ///
 /// Get your executed trades, sorted in reverse creation order.
-#[derive(Serialize, Debug)]
+///
+/// Build with [`GetMyTrades::for_market`].
+#[derive(Serialize, Clone, Eq, PartialEq, Debug)]
 pub struct GetMyTrades {
     /// Unique market id, check /api/v2/markets for available markets.
     pub market: Symbol,
@@ -56,6 +58,63 @@ pub struct GetMyTrades {
     pub offset: Option<u64>,
 }
 impl_api!(GetMyTrades => Vec<TradeRecord> : auth GET, "/api/v2/trades/my");
+impl_paginated!(GetMyTrades);
+
+impl GetMyTrades {
+    /// Start building the query of your trades in the market.
+    pub fn for_market(market: impl Into<Symbol>) -> GetMyTradesBuilder {
+        GetMyTradesBuilder {
+            inner: GetMyTrades {
+                market: market.into(),
+                timestamp_before: None,
+                after_order_id: None,
+                before_order_id: None,
+                order_by: None,
+                pagination: None,
+                page_params: None,
+                offset: None,
+            },
+        }
+    }
+}
+
+/// Builder of [`GetMyTrades`], created by [`GetMyTrades::for_market`].
+#[derive(Debug)]
+pub struct GetMyTradesBuilder {
+    inner: GetMyTrades,
+}
+
+impl GetMyTradesBuilder {
+    /// Return trades executed before the time only.
+    pub fn timestamp_before(mut self, timestamp: DateTime) -> Self {
+        self.inner.timestamp_before = Some(timestamp);
+        self
+    }
+
+    /// Return trades created after the trade id.
+    pub fn after_order_id(mut self, id: u64) -> Self {
+        self.inner.after_order_id = Some(id);
+        self
+    }
+
+    /// Return trades created before the trade id.
+    pub fn before_order_id(mut self, id: u64) -> Self {
+        self.inner.before_order_id = Some(id);
+        self
+    }
+
+    /// Order the trades by created time.
+    pub fn order_by(mut self, order_by: OrderBy) -> Self {
+        self.inner.order_by = Some(order_by);
+        self
+    }
+
+    /// Finish the request.
+    pub fn build(self) -> GetMyTrades {
+        self.inner
+    }
+}
+impl_builder_paging!(GetMyTradesBuilder => inner);
 
 // =========
 // Responses
@@ -67,12 +126,13 @@ impl_api!(GetMyTrades => Vec<TradeRecord> : auth GET, "/api/v2/trades/my");
 // Inner structures and options
 // ============================
 
-pub use crate::v2::rest::public::{TradeMakerInfo, TradeMakerType, TradeRecord};
+pub use crate::v2::rest::public::TradeRecord;
 
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::util::test_util::*;
+    use crate::v2::rest::public::{TradeMakerInfo, TradeMakerType};
     use chrono::{TimeZone, Utc};
     use rust_decimal_macros::dec;
     use surf::Client as HTTPClient;
@@ -111,8 +171,8 @@ mod tests {
                 funds: Some(dec!(163.28)),
                 market: "dotusdt".into(),
                 market_name: "DOT/USDT".into(),
-                created_at: Utc.timestamp(1635853634, 0),
-                created_at_in_ms: Utc.timestamp(1635853634, 52000000),
+                created_at: Utc.timestamp_opt(1635853634, 0).unwrap(),
+                created_at_in_ms: Utc.timestamp_opt(1635853634, 52000000).unwrap(),
                 side: TradeSide::Bid,
                 fee: Some(dec!(0.08908907)),
                 fee_currency: Some("max".into()),
@@ -126,7 +186,7 @@ mod tests {
     async fn get_all_trades() {
         let params = GetMyTrades {
             market: "dotusdt".into(),
-            timestamp_before: Some(Utc.timestamp(1635854000, 0)),
+            timestamp_before: Some(Utc.timestamp_opt(1635854000, 0).unwrap()),
             after_order_id: Some(29009000),
             before_order_id: None,
             order_by: None,
@@ -149,8 +209,8 @@ mod tests {
                 funds: Some(dec!(163.28)),
                 market: "dotusdt".into(),
                 market_name: "DOT/USDT".into(),
-                created_at: Utc.timestamp(1635853634, 0),
-                created_at_in_ms: Utc.timestamp(1635853634, 52000000),
+                created_at: Utc.timestamp_opt(1635853634, 0).unwrap(),
+                created_at_in_ms: Utc.timestamp_opt(1635853634, 52000000).unwrap(),
                 side: TradeSide::Bid,
                 fee: Some(dec!(0.08908907)),
                 fee_currency: Some("max".into()),
@@ -165,4 +225,53 @@ mod tests {
             }]
         );
     }
+
+    #[test]
+    fn trade_maker_info() {
+        // info of the get_all_trades fixture, and the same maker on the ask side
+        let bid: TradeMakerType = serde_json::from_str(
+            r#"{"ask":null,"bid":{"fee":"0.08908907","fee_currency":"max","order_id":1545763894},"maker":"bid"}"#,
+        )
+        .unwrap();
+        let ask: TradeMakerType = serde_json::from_str(
+            r#"{"ask":{"fee":"0.08908907","fee_currency":"max","order_id":1545763894},"bid":null,"maker":"ask"}"#,
+        )
+        .unwrap();
+        let expected = TradeMakerInfo {
+            fee: dec!(0.08908907),
+            fee_currency: "max".into(),
+            order_id: 1545763894,
+        };
+
+        assert_eq!(bid.info(), Some(&expected));
+        assert_eq!(bid.side(), Some(TradeSide::Bid));
+        assert_eq!(ask.info(), Some(&expected));
+        assert_eq!(ask.side(), Some(TradeSide::Ask));
+        assert_eq!(TradeMakerType::Unknown.info(), None);
+        assert_eq!(TradeMakerType::Unknown.side(), None);
+    }
+
+    #[test]
+    fn get_my_trades_builder() {
+        let before = Utc.timestamp_opt(1637402400, 0).unwrap();
+        assert_eq!(
+            GetMyTrades::for_market("dotusdt")
+                .timestamp_before(before)
+                .after_order_id(29009000)
+                .before_order_id(29009100)
+                .order_by(OrderBy::Asc)
+                .limit(10)
+                .build(),
+            GetMyTrades {
+                market: "dotusdt".into(),
+                timestamp_before: Some(before),
+                after_order_id: Some(29009000),
+                before_order_id: Some(29009100),
+                order_by: Some(OrderBy::Asc),
+                pagination: Some(true),
+                page_params: Some(PageParams { page: 1, limit: 10 }),
+                offset: None,
+            }
+        );
+    }
 }
@@ -22,31 +22,75 @@ impl_api!(GetWithdrawal => RespWithdrawalDetail : auth GET, "/api/v2/withdrawal"
 /// GET /api/v2/withdrawals
 ///
 /// Get your external withdrawals history.
-#[derive(Serialize, Debug)]
+///
+/// Build with [`GetWithdrawals::builder`].
+#[derive(Serialize, Clone, Eq, PartialEq, Default, Debug)]
 pub struct GetWithdrawals {
     /// Unique currency id, check /api/v2/currencies for available currencies.
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub currency: Option<String>,
-    /// Target period start (Epoch time in seconds).
-    #[serde(rename = "from", skip_serializing_if = "Option::is_none")]
-    pub from_timestamp: Option<DateTime>,
-    /// Target period end (Epoch time in seconds).
-    #[serde(rename = "to", skip_serializing_if = "Option::is_none")]
-    pub to_timestamp: Option<DateTime>,
+    pub currency: Option<Currency>,
     /// Withdrawal state.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub state: Option<WithdrawalState>,
-    /// Do pagination & return metadata in header (default `false`).
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub pagination: Option<bool>,
-    /// Pagination parameters, see [`crate::common::PageParams`].
-    #[serde(flatten, skip_serializing_if = "Option::is_none")]
-    pub page_params: Option<PageParams>,
-    /// Records to skip, not applied for pagination (default `0`).
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub offset: Option<u64>,
+    /// Time range and paging of the query.
+    #[serde(flatten)]
+    pub range: TimeRangeQuery,
 }
 impl_api!(GetWithdrawals => Vec<RespWithdrawalDetail> : auth GET, "/api/v2/withdrawals");
+impl_paginated!(GetWithdrawals, range);
+
+impl GetWithdrawals {
+    /// Start building the query of withdrawals in all currencies.
+    pub fn builder() -> GetWithdrawalsBuilder {
+        GetWithdrawalsBuilder {
+            inner: GetWithdrawals::default(),
+        }
+    }
+}
+
+/// Builder of [`GetWithdrawals`], created by [`GetWithdrawals::builder`].
+#[derive(Debug)]
+pub struct GetWithdrawalsBuilder {
+    inner: GetWithdrawals,
+}
+
+impl GetWithdrawalsBuilder {
+    /// Filter by currency.
+    pub fn currency(mut self, currency: impl Into<Currency>) -> Self {
+        self.inner.currency = Some(currency.into());
+        self
+    }
+
+    /// Filter by withdrawal state.
+    pub fn state(mut self, state: WithdrawalState) -> Self {
+        self.inner.state = Some(state);
+        self
+    }
+
+    /// Start of the target period.
+    pub fn since(mut self, from: DateTime) -> Self {
+        self.inner.range.time.from = Some(from);
+        self
+    }
+
+    /// End of the target period.
+    pub fn until(mut self, to: DateTime) -> Self {
+        self.inner.range.time.to = Some(to);
+        self
+    }
+
+    /// Replace the time range and paging parameters.
+    pub fn range(mut self, range: TimeRangeQuery) -> Self {
+        self.inner.range = range;
+        self
+    }
+
+    /// Finish the request.
+    pub fn build(self) -> GetWithdrawals {
+        self.inner
+    }
+}
+impl_builder_paging!(GetWithdrawalsBuilder => inner.range);
 
 /// POST /api/v2/withdrawal
 ///
@@ -54,7 +98,7 @@ impl_api!(GetWithdrawals => Vec<RespWithdrawalDetail> : auth GET, "/api/v2/withd
 #[derive(Serialize, Debug)]
 pub struct CreateWithdrawal {
     /// Unique currency id, check /api/v2/currencies for available currencies.
-    pub currency: String,
+    pub currency: Currency,
     /// Unique withdraw address id, check GET /api/v2/withdraw_addresses for available withdraw addresses.
     pub withdraw_address_uuid: String,
     /// Withdraw amount.
@@ -62,13 +106,27 @@ pub struct CreateWithdrawal {
 }
 impl_api!(CreateWithdrawal => RespCreatedWithdraw : auth POST, "/api/v2/withdrawal");
 
+impl CreateWithdrawal {
+    pub fn new(
+        currency: impl Into<Currency>,
+        withdraw_address_uuid: impl Into<String>,
+        amount: Decimal,
+    ) -> Self {
+        Self {
+            currency: currency.into(),
+            withdraw_address_uuid: withdraw_address_uuid.into(),
+            amount,
+        }
+    }
+}
+
 /// GET /api/v2/withdraw_addresses
 ///
 /// Get withdraw addresses by currency.
 #[derive(Serialize, Debug)]
 pub struct GetWithdrawAddresses {
     /// Unique currency id, check /api/v2/currencies for available currencies.
-    pub currency: String,
+    pub currency: Currency,
     /// Do pagination & return metadata in header (default `false`).
     #[serde(skip_serializing_if = "Option::is_none")]
     pub pagination: Option<bool>,
@@ -86,7 +144,7 @@ impl_api!(GetWithdrawAddresses => Vec<WithdrawAddress> : auth GET, "/api/v2/with
 // =========
 
 /// Withdrawal detail
-#[derive(Deserialize, Eq, PartialEq, Default, Debug)]
+#[derive(Serialize, Deserialize, Clone, Eq, PartialEq, Default, Debug)]
 #[serde(default)]
 pub struct RespWithdrawalDetail {
     /// uuid (string, optional): unique withdraw id.
@@ -97,8 +155,9 @@ pub struct RespWithdrawalDetail {
     pub currency_version: String,
     /// amount (string, optional): withdraw amount.
     pub amount: Decimal,
-    /// fee (string, optional): withdraw fee.
-    pub fee: Decimal,
+    /// fee (string, optional): withdraw fee, `None` if not given.
+    #[serde(deserialize_with = "crate::util::serde::lenient_decimal_option")]
+    pub fee: Option<Decimal>,
     /// fee_currency (string, optional): withdraw fee currency.
     pub fee_currency: String,
     /// txid (string, optional): transaction id.
@@ -114,7 +173,7 @@ pub struct RespWithdrawalDetail {
 }
 
 /// Response of a withdrawal submission
-#[derive(Deserialize, Eq, PartialEq, Debug)]
+#[derive(Serialize, Deserialize, Clone, Eq, PartialEq, Debug)]
 pub struct RespCreatedWithdraw {
     /// Withdrawal detail.
     #[serde(flatten)]
@@ -122,8 +181,8 @@ pub struct RespCreatedWithdraw {
     /// type (string, optional): internal/external transfer.
     #[serde(default, rename = "type")]
     pub transaction_direction: TransactionDirection,
-    /// transaction_type (string, optional): transaction type.
-    pub transaction_type: String,
+    /// transaction_type (string, optional): transaction type, see [`WithdrawalTransactionType`].
+    pub transaction_type: WithdrawalTransactionType,
     /// notes (string, optional): withdraw note.
     pub notes: Option<String>,
     /// sender (object, optional): sender mask email.
@@ -136,12 +195,35 @@ pub struct RespCreatedWithdraw {
     pub recipient: Option<String>,
 }
 
+impl RespCreatedWithdraw {
+    /// Amount actually sent out, in the withdrawn currency.
+    ///
+    /// The fee is deducted only if it is charged in the withdrawn currency, e.g. fees paid in MAX token leave the
+    /// amount as is.
+    pub fn net_amount(&self) -> Decimal {
+        if self
+            .detail
+            .fee_currency
+            .eq_ignore_ascii_case(&self.detail.currency)
+        {
+            self.detail.amount - self.detail.fee.unwrap_or_default()
+        } else {
+            self.detail.amount
+        }
+    }
+
+    /// `true` if this is an internal transfer between MAX members.
+    pub fn is_internal(&self) -> bool {
+        self.transaction_direction == TransactionDirection::Internal
+    }
+}
+
 // ============================
 // Inner structures and options
 // ============================
 
 /// Possible withdraw states.
-#[derive(Serialize, Deserialize, Copy, Clone, Eq, PartialEq, Debug)]
+#[derive(Serialize, Deserialize, Copy, Clone, Eq, PartialEq, Debug, Default)]
 #[serde(rename_all = "snake_case")]
 pub enum WithdrawalState {
     Submitting,
@@ -164,66 +246,162 @@ pub enum WithdrawalState {
     KgiManuallyConfirmed,
     KgiPossibleFailed,
     SygnaVerifying,
+    #[default]
     Unknown,
 }
 
+impl_wire_str!(WithdrawalState {
+    Submitting => "submitting",
+    Submitted => "submitted",
+    Rejected => "rejected",
+    Accepted => "accepted",
+    Suspect => "suspect",
+    Approved => "approved",
+    DelistedProcessing => "delisted_processing",
+    Reviewing => "reviewing",
+    Processing => "processing",
+    Retryable => "retryable",
+    Sent => "sent",
+    Canceled => "canceled",
+    Failed => "failed",
+    Pending => "pending",
+    Confirmed => "confirmed",
+    Overdue => "overdue",
+    KgiManuallyProcessing => "kgi_manually_processing",
+    KgiManuallyConfirmed => "kgi_manually_confirmed",
+    KgiPossibleFailed => "kgi_possible_failed",
+    SygnaVerifying => "sygna_verifying",
+    Unknown => "unknown",
+});
+
 impl WithdrawalState {
     pub fn is_unknown(&self) -> bool {
         self == &Self::Unknown
     }
 }
 
-impl Default for WithdrawalState {
-    fn default() -> Self {
-        Self::Unknown
-    }
-}
-
 /// Response of a withdrawal submission.
-#[derive(Deserialize, Eq, PartialEq, Debug)]
+#[derive(Serialize, Deserialize, Clone, Eq, PartialEq, Debug, Default)]
 #[serde(rename_all = "lowercase")]
 pub enum TransactionDirection {
     Internal,
     External,
+    #[default]
     Unknown,
 }
 
+impl_wire_str!(TransactionDirection {
+    Internal => "internal",
+    External => "external",
+    Unknown => "unknown",
+});
+
 impl TransactionDirection {
     pub fn is_unknown(&self) -> bool {
         self == &Self::Unknown
     }
 }
 
-impl Default for TransactionDirection {
-    fn default() -> Self {
-        Self::Unknown
+/// Transaction type of a withdrawal.
+///
+/// Types not known by this crate are kept as is in [`WithdrawalTransactionType::Other`].
+#[derive(Serialize, Deserialize, Clone, Eq, PartialEq, Hash, Debug)]
+#[serde(from = "String", into = "String")]
+pub enum WithdrawalTransactionType {
+    /// Sent to an external address.
+    ExternalSend,
+    /// Sent to another MAX member.
+    InternalSend,
+    /// Types not known by this crate.
+    Other(String),
+}
+
+impl WithdrawalTransactionType {
+    /// The string used by API server.
+    pub fn as_str(&self) -> &str {
+        match self {
+            Self::ExternalSend => "external_send",
+            Self::InternalSend => "internal_send",
+            Self::Other(s) => s,
+        }
+    }
+
+    /// `true` if the type is not known by this crate, kept as the string replied by the server.
+    pub fn is_other(&self) -> bool {
+        matches!(self, Self::Other(_))
+    }
+}
+
+impl std::fmt::Display for WithdrawalTransactionType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl From<&str> for WithdrawalTransactionType {
+    fn from(s: &str) -> Self {
+        if s.eq_ignore_ascii_case("external_send") {
+            Self::ExternalSend
+        } else if s.eq_ignore_ascii_case("internal_send") {
+            Self::InternalSend
+        } else {
+            Self::Other(s.to_owned())
+        }
+    }
+}
+
+impl From<String> for WithdrawalTransactionType {
+    fn from(s: String) -> Self {
+        match Self::from(s.as_str()) {
+            Self::Other(_) => Self::Other(s),
+            known => known,
+        }
+    }
+}
+
+impl From<WithdrawalTransactionType> for String {
+    fn from(t: WithdrawalTransactionType) -> Self {
+        match t {
+            WithdrawalTransactionType::Other(s) => s,
+            known => known.as_str().to_owned(),
+        }
+    }
+}
+
+impl std::str::FromStr for WithdrawalTransactionType {
+    type Err = std::convert::Infallible;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(s.into())
     }
 }
 
 /// Withdraw address state: unverified/verified/disabled.
-#[derive(Deserialize, Eq, PartialEq, Debug)]
+#[derive(Serialize, Deserialize, Clone, Eq, PartialEq, Debug, Default)]
 #[serde(rename_all = "lowercase")]
 pub enum WithdrawAddressState {
     Unverified,
     Verified,
     Disabled,
+    #[default]
     Unknown,
 }
 
+impl_wire_str!(WithdrawAddressState {
+    Unverified => "unverified",
+    Verified => "verified",
+    Disabled => "disabled",
+    Unknown => "unknown",
+});
+
 impl WithdrawAddressState {
     pub fn is_unknown(&self) -> bool {
         self == &Self::Unknown
     }
 }
 
-impl Default for WithdrawAddressState {
-    fn default() -> Self {
-        Self::Unknown
-    }
-}
-
 /// Withdraw address.
-#[derive(Deserialize, Eq, PartialEq, Default, Debug)]
+#[derive(Serialize, Deserialize, Clone, Eq, PartialEq, Default, Debug)]
 pub struct WithdrawAddress {
     /// uuid (string, optional): unique withdraw address id.
     pub uuid: String,
@@ -292,11 +470,11 @@ mod tests {
                 currency: "sol".into(),
                 currency_version: "sol".into(),
                 amount: dec!(1.0),
-                fee: dec!(4.21265078),
+                fee: Some(dec!(4.21265078)),
                 fee_currency: "max".into(),
                 txid: Some("(test erased txid)".into()),
-                created_at: Some(Utc.timestamp(1637394145, 0)),
-                updated_at: Some(Utc.timestamp(1637394215, 0)),
+                created_at: Some(Utc.timestamp_opt(1637394145, 0).unwrap()),
+                updated_at: Some(Utc.timestamp_opt(1637394215, 0).unwrap()),
                 state: WithdrawalState::Confirmed,
             }
         );
@@ -306,12 +484,8 @@ mod tests {
     async fn get_all_withdrawal() {
         let params = GetWithdrawals {
             currency: Some("sol".into()),
-            from_timestamp: None,
-            to_timestamp: None,
             state: None,
-            pagination: None,
-            page_params: None,
-            offset: None,
+            range: TimeRangeQuery::default(),
         };
         let resp = create_client("get_all_withdrawal.yaml")
             .await
@@ -328,11 +502,11 @@ mod tests {
                     currency: "sol".into(),
                     currency_version: "sol".into(),
                     amount: dec!(1.0),
-                    fee: dec!(4.21265078),
+                    fee: Some(dec!(4.21265078)),
                     fee_currency: "max".into(),
                     txid: Some("(test erased txid)".into()),
-                    created_at: Some(Utc.timestamp(1637394145, 0)),
-                    updated_at: Some(Utc.timestamp(1637394215, 0)),
+                    created_at: Some(Utc.timestamp_opt(1637394145, 0).unwrap()),
+                    updated_at: Some(Utc.timestamp_opt(1637394215, 0).unwrap()),
                     state: WithdrawalState::Confirmed,
                 },
                 RespWithdrawalDetail {
@@ -340,11 +514,11 @@ mod tests {
                     currency: "sol".into(),
                     currency_version: "sol".into(),
                     amount: dec!(4.32),
-                    fee: dec!(4.60232158),
+                    fee: Some(dec!(4.60232158)),
                     fee_currency: "max".into(),
                     txid: Some("(test erased txid)".into()),
-                    created_at: Some(Utc.timestamp(1635983513, 0)),
-                    updated_at: Some(Utc.timestamp(1635983641, 0)),
+                    created_at: Some(Utc.timestamp_opt(1635983513, 0).unwrap()),
+                    updated_at: Some(Utc.timestamp_opt(1635983641, 0).unwrap()),
                     state: WithdrawalState::Confirmed,
                 }
             ]
@@ -373,20 +547,34 @@ mod tests {
                     currency: "sol".into(),
                     currency_version: "sol".into(),
                     amount: dec!(1.0),
-                    fee: dec!(4.21265078),
+                    fee: Some(dec!(4.21265078)),
                     fee_currency: "max".into(),
                     txid: None,
-                    created_at: Some(Utc.timestamp(1637394145, 0)),
-                    updated_at: Some(Utc.timestamp(1637394145, 0)),
+                    created_at: Some(Utc.timestamp_opt(1637394145, 0).unwrap()),
+                    updated_at: Some(Utc.timestamp_opt(1637394145, 0).unwrap()),
                     state: WithdrawalState::Submitted,
                 },
                 transaction_direction: TransactionDirection::External,
-                transaction_type: "external_send".into(),
+                transaction_type: WithdrawalTransactionType::ExternalSend,
                 notes: None,
                 sender: Some("(test erased sender)".into()),
                 recipient: Some("(test erased recipient)".into()),
             }
         );
+
+        assert_eq!(result.transaction_type.as_str(), "external_send");
+        assert!(!result.transaction_type.is_other());
+
+        // fee is paid in MAX token
+        assert_eq!(result.net_amount(), dec!(1.0));
+        assert!(!result.is_internal());
+
+        let mut result = result;
+        result.detail.fee = Some(dec!(0.01));
+        result.detail.fee_currency = "sol".into();
+        result.transaction_direction = TransactionDirection::Internal;
+        assert_eq!(result.net_amount(), dec!(0.99));
+        assert!(result.is_internal());
     }
 
     #[async_std::test]
@@ -414,7 +602,7 @@ mod tests {
                 currency_protocol_name: None,
                 address: "(test erased address)".to_string(),
                 extra_label: "(test erased extra_label)".to_string(),
-                created_at: Some(Utc.timestamp(1635983472, 0)),
+                created_at: Some(Utc.timestamp_opt(1635983472, 0).unwrap()),
                 deleted_at: None,
                 state: None,
                 sygna_vasp_code: None,
@@ -424,4 +612,86 @@ mod tests {
             }]
         );
     }
+
+    #[test]
+    fn get_withdrawals_builder() {
+        assert_eq!(GetWithdrawals::builder().build(), GetWithdrawals::default());
+        let from = Utc.timestamp_opt(1637316000, 0).unwrap();
+        assert_eq!(
+            GetWithdrawals::builder()
+                .currency("sol")
+                .state(WithdrawalState::Accepted)
+                .since(from)
+                .page(2)
+                .build(),
+            GetWithdrawals {
+                currency: Some("sol".into()),
+                state: Some(WithdrawalState::Accepted),
+                range: TimeRangeQuery {
+                    time: TimeRange::since(from),
+                    pagination: Some(true),
+                    page_params: Some(PageParams { page: 2, limit: 50 }),
+                    offset: None,
+                },
+            }
+        );
+    }
+
+    #[test]
+    fn create_withdrawal_currency_normalized() {
+        let params = CreateWithdrawal::new("USDT", "addr-uuid", dec!(10.5));
+        assert_eq!(params.currency, Currency::USDT);
+        assert_eq!(params.currency, "usdt");
+        assert_eq!(
+            serde_json::to_value(&params).unwrap(),
+            serde_json::json!({
+                "currency": "usdt",
+                "withdraw_address_uuid": "addr-uuid",
+                "amount": "10.5",
+            })
+        );
+    }
+
+    #[test]
+    fn enum_wire_str() {
+        assert_wire_str(WithdrawalState::VARIANTS);
+        assert_wire_str(TransactionDirection::VARIANTS);
+        assert_wire_str(WithdrawAddressState::VARIANTS);
+    }
+
+    #[test]
+    fn withdrawal_transaction_type() {
+        for t in [
+            WithdrawalTransactionType::ExternalSend,
+            WithdrawalTransactionType::InternalSend,
+            WithdrawalTransactionType::Other("airdrop".into()),
+        ] {
+            let json = serde_json::to_string(&t).unwrap();
+            assert_eq!(json, format!("\"{}\"", t));
+            assert_eq!(
+                serde_json::from_str::<WithdrawalTransactionType>(&json).unwrap(),
+                t
+            );
+        }
+        assert_eq!(
+            "INTERNAL_SEND"
+                .parse::<WithdrawalTransactionType>()
+                .unwrap(),
+            WithdrawalTransactionType::InternalSend
+        );
+        assert!(WithdrawalTransactionType::from("airdrop").is_other());
+    }
+
+    #[test]
+    fn withdrawal_lenient_fee() {
+        let detail: RespWithdrawalDetail = serde_json::from_str(
+            r#"{"currency":"twd","amount":"500","fee":"","fee_currency":"twd"}"#,
+        )
+        .unwrap();
+        assert_eq!(detail.fee, None);
+        let detail: RespWithdrawalDetail =
+            serde_json::from_str(r#"{"currency":"btc","fee":"0.0005"}"#).unwrap();
+        assert_eq!(detail.fee, Some(dec!(0.0005)));
+        assert!(serde_json::from_str::<RespWithdrawalDetail>(r#"{"fee":"1,5"}"#).is_err());
+    }
 }
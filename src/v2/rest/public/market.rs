@@ -1,11 +1,32 @@
 use std::collections::HashMap;
+use std::convert::TryFrom;
+use std::fmt;
+use std::str::FromStr;
 
-use chrono::serde as chrono_serde;
+use chrono::{serde as chrono_serde, Duration};
+use rust_decimal::prelude::ToPrimitive;
 use rust_decimal::Decimal;
-use serde::{Deserialize, Serialize};
+use serde::{ser, ser::SerializeTuple, Deserialize, Serialize};
 
 use crate::common::*;
+use crate::error::ParseEnumError;
 use crate::v2::rest::api_impl::*;
+use crate::v2::rest::internal::encode_path_segment;
+use crate::v2::ws::feed::{PubOrderBookFeed, PubOrderBookRec};
+
+// `f64` getters of decimal fields, for the libraries working with floating numbers, e.g. charting.
+macro_rules! impl_f64_getters {
+    ($ty:ty { $($getter:ident => $field:ident),+ $(,)? }) => {
+        impl $ty {
+            $(
+                #[doc = concat!("`", stringify!($field), "` as `f64`, which may lose precision.")]
+                pub fn $getter(&self) -> f64 {
+                    self.$field.to_f64().unwrap_or(f64::NAN)
+                }
+            )+
+        }
+    };
+}
 
 // ========
 // Requests
@@ -21,7 +42,7 @@ pub struct GetOHLC {
     /// Returned data points limit, default to 30
     #[serde(skip_serializing_if = "Option::is_none")]
     pub limit: Option<u64>,
-    /// Time period of K line in minute, default to 1
+    /// Time period of K line in minute, default to 1. See [`KlinePeriod`] for supported values.
     #[serde(rename = "period")]
     pub period_minutes: u16,
     /// The seconds elapsed since Unix epoch, set to return data after the timestamp only
@@ -34,6 +55,13 @@ pub struct GetOHLC {
 }
 impl_api!(GetOHLC => Vec<OHLC> : GET, "/api/v2/k");
 
+impl GetOHLC {
+    /// Typed K line period, `None` if `period_minutes` is not supported by server.
+    pub fn period(&self) -> Option<KlinePeriod> {
+        KlinePeriod::try_from(self.period_minutes).ok()
+    }
+}
+
 /// GET /api/v2/depth
 ///
 /// Get depth of a specified market
@@ -111,7 +139,10 @@ pub struct GetTickersOfMarket {
     pub market: Symbol,
 }
 impl_api!(GetTickersOfMarket => RespTickerInfo : GET, dynamic params {
-    api_url!(dynamic "/api/v2/tickers/{}", params.market)
+    format!(
+        "/api/v2/tickers/{}",
+        encode_path_segment(&params.market)
+    )
 });
 
 // =========
@@ -119,7 +150,7 @@ impl_api!(GetTickersOfMarket => RespTickerInfo : GET, dynamic params {
 // =========
 
 /// All Depth of a specified market
-#[derive(Deserialize, Eq, PartialEq, Debug)]
+#[derive(Serialize, Deserialize, Clone, Eq, PartialEq, Debug)]
 pub struct RespDepth {
     /// timestamp: timestamp
     #[serde(rename = "timestamp", with = "chrono_serde::ts_seconds")]
@@ -134,8 +165,103 @@ pub struct RespDepth {
     pub bids: Vec<DepthEntry>,
 }
 
+impl RespDepth {
+    /// Convert into an orderbook snapshot feed of the given market, as if it were pushed by the websocket server.
+    ///
+    /// This allows the local orderbook to be seeded from REST snapshot by the same code handling websocket feeds,
+    /// before applying the websocket updates.
+    pub fn into_snapshot_feed(self, market: Symbol) -> PubOrderBookFeed {
+        PubOrderBookFeed {
+            is_snapshot: true,
+            market,
+            ask: self.asks.into_iter().map(Into::into).collect(),
+            bid: self.bids.into_iter().map(Into::into).collect(),
+            time: self.time,
+        }
+    }
+
+    /// Lowest asked price, `None` if there is no ask.
+    pub fn best_ask(&self) -> Option<Decimal> {
+        self.asks.iter().map(|entry| entry.price).min()
+    }
+
+    /// Highest bid price, `None` if there is no bid.
+    pub fn best_bid(&self) -> Option<Decimal> {
+        self.bids.iter().map(|entry| entry.price).max()
+    }
+
+    /// Middle of the best ask and bid prices, `None` if either side is empty.
+    pub fn mid_price(&self) -> Option<Decimal> {
+        Some((self.best_ask()? + self.best_bid()?) / Decimal::TWO)
+    }
+
+    // Price levels taken by a market order of the side, from the best one, regardless of the sorting of the response.
+    fn levels_for(&self, side: OrderSide) -> Vec<&DepthEntry> {
+        let mut levels: Vec<_> = match side {
+            OrderSide::Buy => self.asks.iter().collect(),
+            OrderSide::Sell => self.bids.iter().collect(),
+            OrderSide::Unknown => Vec::new(),
+        };
+        match side {
+            OrderSide::Buy => levels.sort_by_key(|entry| entry.price),
+            _ => levels.sort_by_key(|entry| std::cmp::Reverse(entry.price)),
+        }
+        levels
+    }
+
+    /// Expected fill of a market order of the volume, walking the levels from the best price, as `(average price,
+    /// worst price)`. `None` if the volume is not positive or the book is too thin to fill it.
+    pub fn vwap_for_volume(&self, side: OrderSide, volume: Decimal) -> Option<(Decimal, Decimal)> {
+        if volume <= Decimal::ZERO {
+            return None;
+        }
+        let mut remaining = volume;
+        let mut cost = Decimal::ZERO;
+        for level in self.levels_for(side) {
+            let taken = remaining.min(level.volume);
+            cost += taken * level.price;
+            remaining -= taken;
+            if remaining.is_zero() {
+                return Some((cost / volume, level.price));
+            }
+        }
+        None
+    }
+
+    /// Imbalance of the volume of the best `levels` on each side, `(bid - ask) / (bid + ask)` from -1 (asks only) to 1
+    /// (bids only). Zero if both sides are empty.
+    pub fn imbalance(&self, levels: usize) -> Decimal {
+        let volume = |side| -> Decimal {
+            self.levels_for(side)
+                .into_iter()
+                .take(levels)
+                .map(|entry| entry.volume)
+                .sum()
+        };
+        let (bid, ask) = (volume(OrderSide::Sell), volume(OrderSide::Buy));
+        let total = bid + ask;
+        if total.is_zero() {
+            Decimal::ZERO
+        } else {
+            (bid - ask) / total
+        }
+    }
+
+    /// Relative cost of filling a market order of the volume against the mid price, positive when filled worse than
+    /// the mid, e.g. `0.001` for 0.1%. `None` if the book is too thin, or either side is empty.
+    pub fn slippage_vs_mid(&self, side: OrderSide, volume: Decimal) -> Option<Decimal> {
+        let mid = self.mid_price().filter(|mid| !mid.is_zero())?;
+        let (average, _) = self.vwap_for_volume(side, volume)?;
+        match side {
+            OrderSide::Buy => Some((average - mid) / mid),
+            OrderSide::Sell => Some((mid - average) / mid),
+            OrderSide::Unknown => None,
+        }
+    }
+}
+
 /// Overview of market data for all tickers
-#[derive(Deserialize, Eq, PartialEq, Default, Debug)]
+#[derive(Serialize, Deserialize, Clone, Eq, PartialEq, Default, Debug)]
 pub struct RespSummary {
     /// tickers: tickers of all markets.
     pub tickers: HashMap<Symbol, RespTickerInfo>,
@@ -144,7 +270,7 @@ pub struct RespSummary {
 }
 
 /// Ticker information
-#[derive(Deserialize, Eq, PartialEq, Debug)]
+#[derive(Serialize, Deserialize, Clone, Eq, PartialEq, Debug)]
 pub struct RespTickerInfo {
     /// at: timestamp in seconds since Unix epoch ,
     #[serde(with = "chrono_serde::ts_seconds")]
@@ -170,12 +296,22 @@ pub struct RespTickerInfo {
     pub volume_in_btc: Decimal,
 }
 
+impl_f64_getters!(RespTickerInfo {
+    buy_f64 => buy,
+    sell_f64 => sell,
+    open_f64 => open,
+    low_f64 => low,
+    high_f64 => high,
+    last_price_f64 => last_price,
+    volume_f64 => volume,
+});
+
 // ============================
 // Inner structures and options
 // ============================
 
 /// OHLC in K line
-#[derive(Deserialize, Eq, PartialEq, Debug)]
+#[derive(Deserialize, Clone, Eq, PartialEq, Debug)]
 pub struct OHLC {
     // note: field order matters
     /// timestamp: timestamp
@@ -195,8 +331,131 @@ pub struct OHLC {
     pub volume: Decimal,
 }
 
+impl_f64_getters!(OHLC {
+    open_f64 => open,
+    high_f64 => high,
+    low_f64 => low,
+    close_f64 => close,
+    volume_f64 => volume,
+});
+
+// K lines are sent as `[time, open, high, low, close, volume]` arrays.
+impl Serialize for OHLC {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: ser::Serializer,
+    {
+        let mut tuple = serializer.serialize_tuple(6)?;
+        tuple.serialize_element(&self.time.timestamp())?;
+        tuple.serialize_element(&self.open)?;
+        tuple.serialize_element(&self.high)?;
+        tuple.serialize_element(&self.low)?;
+        tuple.serialize_element(&self.close)?;
+        tuple.serialize_element(&self.volume)?;
+        tuple.end()
+    }
+}
+
+/// Supported time periods of K line.
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub enum KlinePeriod {
+    OneMinute,
+    FiveMinutes,
+    FifteenMinutes,
+    ThirtyMinutes,
+    OneHour,
+    TwoHours,
+    FourHours,
+    SixHours,
+    TwelveHours,
+    OneDay,
+    ThreeDays,
+    OneWeek,
+}
+
+impl KlinePeriod {
+    /// Length of the period in minutes.
+    pub fn minutes(&self) -> u16 {
+        match *self {
+            Self::OneMinute => 1,
+            Self::FiveMinutes => 5,
+            Self::FifteenMinutes => 15,
+            Self::ThirtyMinutes => 30,
+            Self::OneHour => 60,
+            Self::TwoHours => 120,
+            Self::FourHours => 240,
+            Self::SixHours => 360,
+            Self::TwelveHours => 720,
+            Self::OneDay => 1440,
+            Self::ThreeDays => 4320,
+            Self::OneWeek => 10080,
+        }
+    }
+
+    /// Length of the period.
+    pub fn duration(&self) -> Duration {
+        Duration::minutes(self.minutes().into())
+    }
+}
+
+impl From<KlinePeriod> for u16 {
+    fn from(period: KlinePeriod) -> Self {
+        period.minutes()
+    }
+}
+
+impl From<KlinePeriod> for Duration {
+    fn from(period: KlinePeriod) -> Self {
+        period.duration()
+    }
+}
+
+// K line periods are sent as the number of minutes.
+impl fmt::Display for KlinePeriod {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.minutes())
+    }
+}
+
+impl FromStr for KlinePeriod {
+    type Err = ParseEnumError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        s.trim()
+            .parse::<u16>()
+            .ok()
+            .and_then(|minutes| Self::try_from(minutes).ok())
+            .ok_or_else(|| ParseEnumError {
+                type_name: "KlinePeriod",
+                value: s.to_owned(),
+            })
+    }
+}
+
+impl TryFrom<u16> for KlinePeriod {
+    type Error = u16;
+
+    fn try_from(minutes: u16) -> Result<Self, u16> {
+        match minutes {
+            1 => Ok(Self::OneMinute),
+            5 => Ok(Self::FiveMinutes),
+            15 => Ok(Self::FifteenMinutes),
+            30 => Ok(Self::ThirtyMinutes),
+            60 => Ok(Self::OneHour),
+            120 => Ok(Self::TwoHours),
+            240 => Ok(Self::FourHours),
+            360 => Ok(Self::SixHours),
+            720 => Ok(Self::TwelveHours),
+            1440 => Ok(Self::OneDay),
+            4320 => Ok(Self::ThreeDays),
+            10080 => Ok(Self::OneWeek),
+            other => Err(other),
+        }
+    }
+}
+
 /// Depth entry of a specified market.
-#[derive(Deserialize, Eq, PartialEq, Debug)]
+#[derive(Deserialize, Clone, Eq, PartialEq, Debug)]
 pub struct DepthEntry {
     /// price: price of given level
     pub price: Decimal,
@@ -204,16 +463,55 @@ pub struct DepthEntry {
     pub volume: Decimal,
 }
 
+impl_f64_getters!(DepthEntry {
+    price_f64 => price,
+    volume_f64 => volume,
+});
+
+// Depth entries are sent as `[price, volume]` pairs.
+impl Serialize for DepthEntry {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: ser::Serializer,
+    {
+        let mut tuple = serializer.serialize_tuple(2)?;
+        tuple.serialize_element(&self.price)?;
+        tuple.serialize_element(&self.volume)?;
+        tuple.end()
+    }
+}
+
+impl From<DepthEntry> for PubOrderBookRec {
+    fn from(entry: DepthEntry) -> Self {
+        Self {
+            price: entry.price,
+            volume: entry.volume,
+        }
+    }
+}
+
 /// Trade record
-#[derive(Deserialize, Eq, PartialEq, Debug)]
+#[derive(Serialize, Deserialize, Clone, Eq, PartialEq, Debug)]
 pub struct TradeRecord {
     /// id: trade id
     pub id: u64,
     /// price: strike price
+    #[serde(
+        default,
+        deserialize_with = "crate::util::serde::lenient_decimal_option"
+    )]
     pub price: Option<Decimal>,
     /// volume: traded volume
+    #[serde(
+        default,
+        deserialize_with = "crate::util::serde::lenient_decimal_option"
+    )]
     pub volume: Option<Decimal>,
     /// funds: total traded amount
+    #[serde(
+        default,
+        deserialize_with = "crate::util::serde::lenient_decimal_option"
+    )]
     pub funds: Option<Decimal>,
     /// Unique market id, check /api/v2/markets for available markets.
     pub market: Symbol,
@@ -225,8 +523,15 @@ pub struct TradeRecord {
     #[serde(with = "chrono_serde::ts_milliseconds")]
     pub created_at_in_ms: DateTime,
     /// side: 'bid' or 'ask'; side of maker for public trades; side of your order when querying your own trades (can be 'self-trade')
+    ///
+    /// Sides not known by this crate, including self-trades, are [`TradeSide::Unknown`].
+    #[serde(deserialize_with = "crate::util::serde::from_str_or_default")]
     pub side: TradeSide,
     /// fee: your related fee (show ask side if self-trade)
+    #[serde(
+        default,
+        deserialize_with = "crate::util::serde::lenient_decimal_option"
+    )]
     pub fee: Option<Decimal>,
     /// fee_currency: fee currency (show ask side if self-trade)
     pub fee_currency: Option<String>,
@@ -238,11 +543,16 @@ pub struct TradeRecord {
 }
 
 /// Trade info inside trade record
-#[derive(Deserialize, Eq, PartialEq, Debug)]
+#[derive(Serialize, Deserialize, Clone, Eq, PartialEq, Debug, Default)]
 #[serde(tag = "maker", rename_all = "lowercase")]
 pub enum TradeMakerType {
-    Ask { ask: TradeMakerInfo },
-    Bid { bid: TradeMakerInfo },
+    Ask {
+        ask: TradeMakerInfo,
+    },
+    Bid {
+        bid: TradeMakerInfo,
+    },
+    #[default]
     Unknown,
 }
 
@@ -250,16 +560,28 @@ impl TradeMakerType {
     pub fn is_unknown(&self) -> bool {
         self == &Self::Unknown
     }
-}
 
-impl Default for TradeMakerType {
-    fn default() -> Self {
-        Self::Unknown
+    /// Trade info of the maker, regardless of its side. `None` if the maker is unknown.
+    pub fn info(&self) -> Option<&TradeMakerInfo> {
+        match self {
+            Self::Ask { ask } => Some(ask),
+            Self::Bid { bid } => Some(bid),
+            Self::Unknown => None,
+        }
+    }
+
+    /// Side of the maker. `None` if the maker is unknown.
+    pub fn side(&self) -> Option<TradeSide> {
+        match self {
+            Self::Ask { .. } => Some(TradeSide::Ask),
+            Self::Bid { .. } => Some(TradeSide::Bid),
+            Self::Unknown => None,
+        }
     }
 }
 
 /// Trade info inside trade record
-#[derive(Deserialize, Default, Eq, PartialEq, Debug)]
+#[derive(Serialize, Deserialize, Clone, Default, Eq, PartialEq, Debug)]
 pub struct TradeMakerInfo {
     /// fee: trade fee
     pub fee: Decimal,
@@ -270,7 +592,7 @@ pub struct TradeMakerInfo {
 }
 
 /// Market information
-#[derive(Deserialize, Eq, PartialEq, Default, Debug)]
+#[derive(Serialize, Deserialize, Clone, Eq, PartialEq, Default, Debug)]
 #[serde(default)]
 pub struct MarketInfo {
     /// id: unique market id, check /api/v2/markets for available markets.
@@ -295,25 +617,66 @@ pub struct MarketInfo {
     pub m_wallet_supported: bool,
 }
 
+impl MarketInfo {
+    /// Units of the market, as `(base_unit, quote_unit)`.
+    pub fn pair(&self) -> (&str, &str) {
+        (&self.base_unit, &self.quote_unit)
+    }
+}
+
+/// Markets indexed by symbol, built from the result of [`GetMarkets`].
+#[derive(Clone, Eq, PartialEq, Default, Debug)]
+pub struct MarketTable(HashMap<Symbol, MarketInfo>);
+
+impl MarketTable {
+    /// Get the market of the symbol.
+    pub fn get(&self, symbol: &str) -> Option<&MarketInfo> {
+        self.0.get(symbol)
+    }
+
+    /// Split the symbol into `(base_unit, quote_unit)`, or `None` for unknown markets.
+    ///
+    /// Lengths of units vary (e.g. `maxtwd` and `usdttwd`), so the symbol string itself cannot be split reliably.
+    pub fn split_symbol(&self, symbol: &str) -> Option<(&str, &str)> {
+        self.get(symbol).map(MarketInfo::pair)
+    }
+}
+
+impl From<Vec<MarketInfo>> for MarketTable {
+    fn from(markets: Vec<MarketInfo>) -> Self {
+        Self(markets.into_iter().map(|m| (m.id.clone(), m)).collect())
+    }
+}
+
 /// Coin information
-#[derive(Deserialize, Eq, PartialEq, Debug)]
+#[derive(Serialize, Deserialize, Clone, Eq, PartialEq, Debug)]
 pub struct CoinInfo {
     /// name: coin name.
     pub name: String,
     /// withdraw: able to withdraw.
-    #[serde(deserialize_with = "crate::util::serde::bool_from_onoff")]
+    #[serde(
+        deserialize_with = "crate::util::serde::bool_from_onoff",
+        serialize_with = "crate::util::serde::bool_to_onoff"
+    )]
     pub withdraw: bool,
     /// deposit: able to deposit.
-    #[serde(deserialize_with = "crate::util::serde::bool_from_onoff")]
+    #[serde(
+        deserialize_with = "crate::util::serde::bool_from_onoff",
+        serialize_with = "crate::util::serde::bool_to_onoff"
+    )]
     pub deposit: bool,
     /// trade: able to trade.
-    #[serde(deserialize_with = "crate::util::serde::bool_from_onoff")]
+    #[serde(
+        deserialize_with = "crate::util::serde::bool_from_onoff",
+        serialize_with = "crate::util::serde::bool_to_onoff"
+    )]
     pub trade: bool,
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::error::Error;
     use crate::util::test_util::*;
     use chrono::{TimeZone, Utc};
     use rust_decimal_macros::dec;
@@ -349,7 +712,7 @@ mod tests {
         assert_eq!(
             ohlcs[1],
             OHLC {
-                time: Utc.timestamp(1636257660, 0),
+                time: Utc.timestamp_opt(1636257660, 0).unwrap(),
                 open: dec!(1735077.9),
                 high: dec!(1735077.9),
                 low: dec!(1735077.9),
@@ -360,7 +723,7 @@ mod tests {
         assert_eq!(
             ohlcs[3],
             OHLC {
-                time: Utc.timestamp(1636257780, 0),
+                time: Utc.timestamp_opt(1636257780, 0).unwrap(),
                 open: dec!(1738000),
                 high: dec!(1738000),
                 low: dec!(1738000),
@@ -370,6 +733,32 @@ mod tests {
         );
     }
 
+    #[test]
+    fn ohlc_period() {
+        let params = GetOHLC {
+            market: "btctwd".into(),
+            limit: None,
+            period_minutes: KlinePeriod::FourHours.into(),
+            after_timestamp: None,
+        };
+        assert_eq!(params.period_minutes, 240);
+        assert_eq!(params.period(), Some(KlinePeriod::FourHours));
+        assert_eq!(KlinePeriod::FourHours.to_string(), "240");
+        assert_eq!("240".parse::<KlinePeriod>(), Ok(KlinePeriod::FourHours));
+        assert!("7".parse::<KlinePeriod>().is_err());
+        assert!("4h".parse::<KlinePeriod>().is_err());
+        assert_eq!(
+            params.period().map(Duration::from),
+            Some(Duration::minutes(240))
+        );
+
+        let params = GetOHLC {
+            period_minutes: 7,
+            ..params
+        };
+        assert_eq!(params.period(), None);
+    }
+
     #[async_std::test]
     async fn get_depth() {
         let params = GetDepth {
@@ -404,11 +793,126 @@ mod tests {
         );
     }
 
+    #[test]
+    fn depth_analytics() {
+        // top levels of get_depth.yaml, asks are sorted from the highest
+        let depth: RespDepth = serde_json::from_str(
+            r#"{"timestamp":1636258205,"last_update_version":1635855699255,"last_update_id":4870357,
+            "asks":[["1738690.3","0.00122943"],["1738604.8","0.0199928"],["1738001.0","0.01522115"],["1738000.0","0.1159757"]],
+            "bids":[["1737000.0","0.2567111"],["1734381.3","0.001639"],["1734207.8","0.003217"],["1734034.3","0.004825"]]}"#,
+        )
+        .unwrap();
+        assert_eq!(depth.best_ask(), Some(dec!(1738000.0)));
+        assert_eq!(depth.best_bid(), Some(dec!(1737000.0)));
+        assert_eq!(depth.mid_price(), Some(dec!(1737500)));
+
+        // within the best level
+        assert_eq!(
+            depth.vwap_for_volume(OrderSide::Buy, dec!(0.1)),
+            Some((dec!(1738000), dec!(1738000.0)))
+        );
+        let (average, worst) = depth.vwap_for_volume(OrderSide::Buy, dec!(0.12)).unwrap();
+        assert_eq!(average.round_dp(6), dec!(1738000.033536));
+        assert_eq!(worst, dec!(1738001.0));
+        let (average, worst) = depth.vwap_for_volume(OrderSide::Sell, dec!(0.26)).unwrap();
+        assert_eq!(average.round_dp(6), dec!(1736965.773461));
+        assert_eq!(worst, dec!(1734207.8));
+        // all the asks are 0.15241908
+        assert!(depth
+            .vwap_for_volume(OrderSide::Buy, dec!(0.15241908))
+            .is_some());
+        assert_eq!(
+            depth.vwap_for_volume(OrderSide::Buy, dec!(0.15241909)),
+            None
+        );
+        assert_eq!(depth.vwap_for_volume(OrderSide::Buy, dec!(0)), None);
+
+        assert_eq!(
+            depth
+                .slippage_vs_mid(OrderSide::Buy, dec!(0.12))
+                .unwrap()
+                .round_dp(10),
+            dec!(0.0002877891)
+        );
+        assert_eq!(
+            depth
+                .slippage_vs_mid(OrderSide::Sell, dec!(0.26))
+                .unwrap()
+                .round_dp(10),
+            dec!(0.0003074685)
+        );
+
+        assert_eq!(depth.imbalance(1).round_dp(10), dec!(0.3776237849));
+        assert_eq!(depth.imbalance(2).round_dp(10), dec!(0.3264131576));
+
+        let empty = RespDepth {
+            asks: Vec::new(),
+            bids: Vec::new(),
+            ..depth
+        };
+        assert_eq!(empty.imbalance(5), dec!(0));
+        assert_eq!(empty.mid_price(), None);
+        assert_eq!(empty.vwap_for_volume(OrderSide::Sell, dec!(1)), None);
+        assert_eq!(empty.slippage_vs_mid(OrderSide::Buy, dec!(1)), None);
+    }
+
+    #[test]
+    fn depth_into_snapshot_feed() {
+        use crate::v2::ws::feed::Feed;
+        use std::collections::BTreeMap;
+
+        let depth: RespDepth = serde_json::from_str(fixtures::DEPTH).expect("invalid test case");
+        let snapshot = depth.into_snapshot_feed("btctwd".into());
+        assert!(snapshot.is_snapshot());
+        assert_eq!(snapshot.market, "btctwd");
+        assert_eq!(snapshot.time, Utc.timestamp_opt(1636258205, 0).unwrap());
+
+        // a local orderbook applying snapshot and updates in the same way, zero volume removes the price level
+        let mut asks = BTreeMap::new();
+        let mut bids = BTreeMap::new();
+        let mut apply = |feed: PubOrderBookFeed| {
+            if feed.is_snapshot() {
+                asks.clear();
+                bids.clear();
+            }
+            let (ask, bid) = feed.into_record();
+            for (book, records) in [(&mut asks, ask), (&mut bids, bid)] {
+                for rec in records {
+                    if rec.volume.is_zero() {
+                        book.remove(&rec.price);
+                    } else {
+                        book.insert(rec.price, rec.volume);
+                    }
+                }
+            }
+        };
+
+        apply(snapshot);
+        apply(
+            serde_json::from_str(
+                r#"{"e":"update","M":"btctwd","a":[["1739716.7","0"]],"b":[["1737500.0","0.5"]],"T":1636258206000}"#,
+            )
+            .expect("invalid test case"),
+        );
+        assert_eq!(
+            asks.into_iter().collect::<Vec<_>>(),
+            vec![(dec!(1739999.0), dec!(0.00278039))]
+        );
+        assert_eq!(
+            bids.into_iter().rev().collect::<Vec<_>>(),
+            vec![
+                (dec!(1737500.0), dec!(0.5)),
+                (dec!(1737000.0), dec!(0.2567111)),
+                (dec!(1734381.3), dec!(0.001639)),
+            ]
+        );
+    }
+
     #[async_std::test]
     async fn get_public_trades() {
         let params = GetPublicTrades {
             market: "btctwd".into(),
-            timestamp_before: Utc.timestamp(1636212254, 0),
+            timestamp_before: Utc.timestamp_opt(1636212254, 0).unwrap(),
             after_order_id: None,
             before_order_id: None,
             order_by: None,
@@ -431,10 +935,10 @@ mod tests {
                 price: Some(dec!(1699352.1)),
                 volume: Some(dec!(0.001092)),
                 funds: Some(dec!(1855.7)),
-                market: "btctwd".to_string(),
+                market: "btctwd".into(),
                 market_name: "BTC/TWD".to_string(),
-                created_at: Utc.timestamp(1636212047, 0),
-                created_at_in_ms: Utc.timestamp(1636212047, 217000000),
+                created_at: Utc.timestamp_opt(1636212047, 0).unwrap(),
+                created_at_in_ms: Utc.timestamp_opt(1636212047, 217000000).unwrap(),
                 side: TradeSide::Ask,
                 fee: None,
                 fee_currency: None,
@@ -469,7 +973,13 @@ mod tests {
                 min_quote_amount: dec!(250),
                 m_wallet_supported: false,
             }
-        )
+        );
+        assert_eq!(market_list[0].pair(), ("max", "twd"));
+
+        let table = MarketTable::from(market_list);
+        assert_eq!(table.split_symbol("maxtwd"), Some(("max", "twd")));
+        assert_eq!(table.split_symbol("usdttwd"), Some(("usdt", "twd")));
+        assert_eq!(table.split_symbol("nosuchmarket"), None);
     }
 
     #[async_std::test]
@@ -498,7 +1008,7 @@ mod tests {
         assert_eq!(
             summary.tickers.get("btctwd"),
             Some(&RespTickerInfo {
-                at: Utc.timestamp(1636258205, 0),
+                at: Utc.timestamp_opt(1636258205, 0).unwrap(),
                 buy: dec!(1737000.0),
                 sell: dec!(1738000.0),
                 open: dec!(1708337.2),
@@ -525,7 +1035,7 @@ mod tests {
         assert_eq!(
             tickers.get("maxtwd"),
             Some(&RespTickerInfo {
-                at: Utc.timestamp(1636258205, 0),
+                at: Utc.timestamp_opt(1636258205, 0).unwrap(),
                 buy: dec!(11.4951),
                 sell: dec!(11.5376),
                 open: dec!(11.5499),
@@ -535,7 +1045,48 @@ mod tests {
                 volume: dec!(78450.18),
                 volume_in_btc: dec!(0.51921291849962826),
             })
-        )
+        );
+        // symbols are looked up by `&str`, or by a symbol normalized from user input
+        let market: Symbol = "MAXTWD".parse().unwrap();
+        assert_eq!(tickers.get(&market), tickers.get("maxtwd"));
+        assert!(tickers
+            .keys()
+            .all(|market| market.to_lowercase() == market.as_str()));
+    }
+
+    #[test]
+    fn ticker_of_market_escaped_path() {
+        let req = GetTickersOfMarket {
+            market: "btc twd/../markets".into(),
+        }
+        .to_request();
+        assert_eq!(req.url().path(), "/api/v2/tickers/btc%20twd%2F..%2Fmarkets");
+        assert_eq!(
+            req.url().as_str(),
+            "https://max-api.maicoin.com/api/v2/tickers/btc%20twd%2F..%2Fmarkets?"
+        );
+    }
+
+    #[test]
+    fn ticker_of_market_hostile_path() {
+        let params = GetTickersOfMarket {
+            market: "..".into(),
+        };
+        assert!(matches!(params.try_to_request(), Err(Error::InvalidUrl(_))));
+        assert!(matches!(
+            crate::v2::rest::Endpoint::try_prepare(&params, None),
+            Err(Error::InvalidUrl(_))
+        ));
+        assert!(matches!(
+            crate::v2::rest::Endpoint::url(&params, &Default::default()),
+            Err(Error::InvalidUrl(_))
+        ));
+
+        let params = GetTickersOfMarket {
+            market: "btc twd?x=1#y".into(),
+        };
+        let req = params.try_to_request().unwrap();
+        assert_eq!(req.url().path(), "/api/v2/tickers/btc%20twd%3Fx%3D1%23y");
     }
 
     #[async_std::test]
@@ -553,7 +1104,7 @@ mod tests {
         assert_eq!(
             ticker,
             RespTickerInfo {
-                at: Utc.timestamp(1636258205, 0),
+                at: Utc.timestamp_opt(1636258205, 0).unwrap(),
                 buy: dec!(1737000.0),
                 sell: dec!(1738000.0),
                 open: dec!(1708337.2),
@@ -565,4 +1116,90 @@ mod tests {
             }
         );
     }
+
+    #[test]
+    fn money_fields_as_f64() {
+        let ohlc: OHLC =
+            serde_json::from_str(r#"[1636258200,"1.5","2.5","1.0","2.0","12.34"]"#).unwrap();
+        assert_eq!(
+            [
+                ohlc.open_f64(),
+                ohlc.high_f64(),
+                ohlc.low_f64(),
+                ohlc.close_f64(),
+                ohlc.volume_f64()
+            ],
+            [1.5, 2.5, 1.0, 2.0, 12.34]
+        );
+
+        let entry = DepthEntry {
+            price: dec!(1738000.0),
+            volume: dec!(0.00012345),
+        };
+        assert_eq!(entry.price_f64(), 1738000.0);
+        assert_eq!(entry.volume_f64(), 0.00012345);
+
+        let ticker = RespTickerInfo {
+            at: Utc.timestamp_opt(1636258205, 0).unwrap(),
+            buy: dec!(1737000.0),
+            sell: dec!(1738000.0),
+            open: dec!(1708337.2),
+            low: dec!(1682500.0),
+            high: dec!(1739517.2),
+            last_price: dec!(1738000.0),
+            volume: dec!(23.70350862),
+            volume_in_btc: dec!(23.70350862),
+        };
+        assert_eq!(ticker.buy_f64(), 1737000.0);
+        assert_eq!(ticker.sell_f64(), 1738000.0);
+        assert_eq!(ticker.open_f64(), 1708337.2);
+        assert_eq!(ticker.low_f64(), 1682500.0);
+        assert_eq!(ticker.high_f64(), 1739517.2);
+        assert_eq!(ticker.last_price_f64(), 1738000.0);
+        assert_eq!(ticker.volume_f64(), 23.70350862);
+        // string form keeps the exact decimal
+        assert_eq!(ticker.volume.to_string(), "23.70350862");
+    }
+
+    #[test]
+    fn depth_json_round_trip() {
+        let depth: RespDepth = serde_json::from_str(fixtures::DEPTH).expect("invalid test case");
+        let value = serde_json::to_value(&depth).unwrap();
+        let raw: serde_json::Value = serde_json::from_str(fixtures::DEPTH).unwrap();
+        assert_eq!(value, raw);
+        assert_eq!(serde_json::from_value::<RespDepth>(value).unwrap(), depth);
+    }
+
+    #[test]
+    fn ohlc_and_coin_json_round_trip() {
+        let ohlc: Vec<OHLC> =
+            serde_json::from_str(r#"[[1636258200,"1.5","2.5","1.0","2.0","12.34"]]"#).unwrap();
+        let value = serde_json::to_value(&ohlc).unwrap();
+        assert_eq!(
+            value,
+            serde_json::json!([[1636258200, "1.5", "2.5", "1.0", "2.0", "12.34"]])
+        );
+        assert_eq!(serde_json::from_value::<Vec<OHLC>>(value).unwrap(), ohlc);
+
+        let raw = r#"{"name":"TWD","withdraw":"ON","deposit":"OFF","trade":"ON"}"#;
+        let coin: CoinInfo = serde_json::from_str(raw).unwrap();
+        assert_eq!(
+            serde_json::to_value(&coin).unwrap(),
+            serde_json::from_str::<serde_json::Value>(raw).unwrap()
+        );
+    }
+
+    #[test]
+    fn trade_record_lenient_decimals() {
+        let raw = r#"{"id":1,"price":"","volume":"0.5","market":"btctwd","market_name":"BTC/TWD",
+            "created_at":1636258205,"created_at_in_ms":1636258205123,"side":"bid","fee":null}"#;
+        let trade: TradeRecord = serde_json::from_str(raw).unwrap();
+        assert_eq!(trade.price, None);
+        assert_eq!(trade.volume, Some(dec!(0.5)));
+        assert_eq!(trade.funds, None);
+        assert_eq!(trade.fee, None);
+
+        let malformed = raw.replace(r#""volume":"0.5""#, r#""volume":"half""#);
+        assert!(serde_json::from_str::<TradeRecord>(&malformed).is_err());
+    }
 }
@@ -1,7 +1,7 @@
 use std::convert::From;
 
-use chrono::{NaiveDateTime, Utc};
-use rust_decimal::Decimal;
+use chrono::{TimeZone, Utc};
+use rust_decimal::{Decimal, RoundingStrategy};
 use serde::{Deserialize, Serialize};
 
 use crate::common::*;
@@ -28,7 +28,7 @@ pub struct GetVIPByLevel {
     pub level: u8,
 }
 impl_api!(GetVIPByLevel => RespVIPLevel : GET, dynamic params {
-    api_url!(dynamic "/api/v2/vip_levels/{}", params.level)
+    format!("/api/v2/vip_levels/{}", params.level)
 });
 
 /// GET /api/v2/currencies
@@ -52,16 +52,25 @@ impl_api!(GetTimestamp => RespTimestamp : GET, "/api/v2/timestamp");
 pub struct GetWithdrawalConstraints {
     /// Unique currency id, check /api/v2/currencies for available currencies.
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub currency: Option<String>,
+    pub currency: Option<Currency>,
 }
 impl_api!(GetWithdrawalConstraints => Vec<WithdrawalConstraints> : GET, "/api/v2/withdrawal/constraint");
 
+impl GetWithdrawalConstraints {
+    /// Constraints of the currency only.
+    pub fn of(currency: impl Into<Currency>) -> Self {
+        Self {
+            currency: Some(currency.into()),
+        }
+    }
+}
+
 // =========
 // Responses
 // =========
 
 /// Response of GET /api/v2/vip_levels*
-#[derive(Deserialize, Eq, PartialEq, Default, Debug)]
+#[derive(Serialize, Deserialize, Clone, Eq, PartialEq, Default, Debug)]
 #[serde(default)]
 pub struct RespVIPLevel {
     /// level: VIP level
@@ -76,13 +85,25 @@ pub struct RespVIPLevel {
     pub taker_fee: Decimal,
 }
 
+impl RespVIPLevel {
+    /// Expected fee of trading the given notional amount in this level. Negative fee means rebate.
+    pub fn expected_fee(&self, notional: Decimal, is_maker: bool) -> Decimal {
+        let fee_rate = if is_maker {
+            self.maker_fee
+        } else {
+            self.taker_fee
+        };
+        notional * fee_rate
+    }
+}
+
 /// Server current time, in seconds since Unix epoch.
-#[derive(Deserialize, Eq, PartialEq, Debug)]
+#[derive(Serialize, Deserialize, Clone, Eq, PartialEq, Debug)]
 pub struct RespTimestamp(pub i64);
 
 impl From<RespTimestamp> for DateTime {
     fn from(resp: RespTimestamp) -> Self {
-        DateTime::from_utc(NaiveDateTime::from_timestamp(resp.0, 0), Utc)
+        Utc.timestamp_opt(resp.0, 0).unwrap()
     }
 }
 
@@ -91,7 +112,7 @@ impl From<RespTimestamp> for DateTime {
 // ============================
 
 /// Response of GET /api/v2/currencies
-#[derive(Deserialize, Eq, PartialEq, Default, Debug)]
+#[derive(Serialize, Deserialize, Clone, Eq, PartialEq, Default, Debug)]
 #[serde(default)]
 pub struct CurrencyInfo {
     /// id: unique currency id
@@ -100,10 +121,41 @@ pub struct CurrencyInfo {
     pub precision: u8,
     /// sygna_supported: if support sygna travel rule
     pub sygna_supported: bool,
+    /// networks: supported deposit/withdrawal networks, e.g. ERC20 and TRC20 for USDT
+    pub networks: Vec<CurrencyNetwork>,
+}
+
+impl CurrencyInfo {
+    /// Find the network by protocol name, case-insensitive.
+    pub fn network(&self, protocol: &str) -> Option<&CurrencyNetwork> {
+        self.networks
+            .iter()
+            .find(|network| network.protocol.eq_ignore_ascii_case(protocol))
+    }
+
+    /// Render the amount at the precision of the currency, rounding half away from zero and padding with zeros.
+    pub fn format(&self, amount: Decimal) -> String {
+        let precision = self.precision as u32;
+        let rounded =
+            amount.round_dp_with_strategy(precision, RoundingStrategy::MidpointAwayFromZero);
+        format!("{:.*}", precision as usize, rounded)
+    }
+}
+
+/// Deposit/withdrawal network of a currency.
+#[derive(Serialize, Deserialize, Clone, Eq, PartialEq, Default, Debug)]
+#[serde(default)]
+pub struct CurrencyNetwork {
+    /// protocol: network protocol, e.g. erc20, trc20
+    pub protocol: String,
+    /// min_confirmations: required block confirmations of deposit
+    pub min_confirmations: u32,
+    /// withdrawal_fee: fee of withdrawal via this network
+    pub withdrawal_fee: Decimal,
 }
 
 /// Response of GET /api/v2/withdrawal/constraint
-#[derive(Deserialize, Eq, PartialEq, Default, Debug)]
+#[derive(Serialize, Deserialize, Clone, Eq, PartialEq, Default, Debug)]
 #[serde(default)]
 pub struct WithdrawalConstraints {
     /// currency: currency id.
@@ -119,8 +171,8 @@ pub struct WithdrawalConstraints {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::error::Error;
     use crate::util::test_util::*;
-    use chrono::TimeZone;
     use rust_decimal_macros::dec;
     use surf::Client as HTTPClient;
     use surf_vcr::VcrMode;
@@ -145,8 +197,8 @@ mod tests {
             .expect("Error while sending request");
         let result = GetVIPLevels::read_response(resp.into()).await;
         let levels: Vec<RespVIPLevel> = result.expect("failed to parse result");
-        for lv in 0..10 {
-            assert_eq!(levels[lv].level, lv as u8);
+        for (lv, level) in levels.iter().enumerate().take(10) {
+            assert_eq!(level.level, lv as u8);
         }
         assert_eq!(
             levels[4],
@@ -160,6 +212,26 @@ mod tests {
         )
     }
 
+    #[async_std::test]
+    async fn vip_level_expected_fee() {
+        let params = GetVIPLevels {};
+        let resp = create_client("get_vip_level_list.yaml")
+            .await
+            .send(params.to_request())
+            .await
+            .expect("Error while sending request");
+        let levels = GetVIPLevels::read_response(resp.into())
+            .await
+            .expect("failed to parse result");
+        let level_0 = &levels[0];
+        assert_eq!(level_0.maker_fee, dec!(0.00045));
+        assert_eq!(level_0.taker_fee, dec!(0.0015));
+        assert_eq!(level_0.expected_fee(dec!(10000), true), dec!(4.5));
+        assert_eq!(level_0.expected_fee(dec!(10000), false), dec!(15));
+        assert_eq!(level_0.expected_fee(dec!(0), false), dec!(0));
+        assert!(levels[5].expected_fee(dec!(10000), true).is_sign_negative());
+    }
+
     #[async_std::test]
     async fn get_vip_by_level() {
         let params = GetVIPByLevel { level: 3 };
@@ -197,11 +269,40 @@ mod tests {
             CurrencyInfo {
                 id: "twd".into(),
                 precision: 0,
-                sygna_supported: false
+                sygna_supported: false,
+                networks: vec![],
             }
         );
     }
 
+    #[async_std::test]
+    async fn get_currencies_networks() {
+        let params = GetCurrencies {};
+        let resp = create_client("get_currencies_networks.yaml")
+            .await
+            .send(params.to_request())
+            .await
+            .expect("Error while sending request");
+        let result = GetCurrencies::read_response(resp.into()).await;
+        let currencies: Vec<CurrencyInfo> = result.expect("failed to parse result");
+        assert!(currencies[0].networks.is_empty());
+        assert_eq!(currencies[1].networks.len(), 1);
+
+        let usdt = &currencies[2];
+        assert_eq!(usdt.id, "usdt");
+        assert_eq!(usdt.networks.len(), 3);
+        assert_eq!(
+            usdt.network("TRC20"),
+            Some(&CurrencyNetwork {
+                protocol: "trc20".into(),
+                min_confirmations: 20,
+                withdrawal_fee: dec!(1.0),
+            })
+        );
+        assert_eq!(usdt.network("erc20").unwrap().withdrawal_fee, dec!(15));
+        assert_eq!(usdt.network("bep20"), None);
+    }
+
     #[async_std::test]
     async fn get_timestamp() {
         let params = GetTimestamp {};
@@ -213,7 +314,48 @@ mod tests {
         let result = GetTimestamp::read_response(resp.into()).await;
         let ts: RespTimestamp = result.expect("failed to parse result");
         assert_eq!(ts.0, 1636258261);
-        assert_eq!(Into::<DateTime>::into(ts), Utc.timestamp(1636258261, 0))
+        assert_eq!(
+            Into::<DateTime>::into(ts),
+            Utc.timestamp_opt(1636258261, 0).unwrap()
+        )
+    }
+
+    #[async_std::test]
+    async fn get_timestamp_maintenance() {
+        let params = GetTimestamp {};
+        let resp = create_client("get_timestamp_maintenance.yaml")
+            .await
+            .send(params.to_request())
+            .await
+            .expect("Error while sending request");
+        match GetTimestamp::read_response(resp.into()).await {
+            Err(Error::Maintenance(status)) => assert_eq!(status, 503),
+            other => panic!("expect maintenance error, got {:?}", other),
+        }
+    }
+
+    #[async_std::test]
+    async fn get_timestamp_enveloped() {
+        let params = GetTimestamp {};
+        let resp = create_client("get_timestamp.yaml")
+            .await
+            .send(params.to_request())
+            .await
+            .expect("Error while sending request");
+        let envelope = GetTimestamp::read_response_enveloped(resp.into())
+            .await
+            .expect("failed to parse result");
+        assert_eq!(envelope.status, 200);
+        assert_eq!(
+            envelope.header("X-Request-Id"),
+            Some("1-618751d5-082d0fed2b7e78345969f0f9")
+        );
+        assert_eq!(
+            envelope.header("date"),
+            Some("Sun, 07 Nov 2021 04:11:01 GMT")
+        );
+        assert_eq!(envelope.header("content-type"), None);
+        assert_eq!(envelope.into_body().0, 1636258261);
     }
 
     #[async_std::test]
@@ -251,4 +393,23 @@ mod tests {
             }
         )
     }
+
+    #[test]
+    fn currency_format_precision() {
+        let twd = CurrencyInfo {
+            id: "twd".into(),
+            precision: 0,
+            ..Default::default()
+        };
+        let btc = CurrencyInfo {
+            id: "btc".into(),
+            precision: 8,
+            ..Default::default()
+        };
+        assert_eq!(twd.format(dec!(1234.5)), "1235");
+        assert_eq!(btc.format(dec!(1234.5)), "1234.50000000");
+        assert_eq!(twd.format(dec!(-0.5)), "-1");
+        assert_eq!(btc.format(dec!(0.123456785)), "0.12345679");
+        assert_eq!(btc.format(dec!(0)), "0.00000000");
+    }
 }
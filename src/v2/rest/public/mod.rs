@@ -1,5 +1,6 @@
 mod market;
 mod misc;
+pub mod ohlc;
 
 pub use market::*;
 pub use misc::*;
@@ -0,0 +1,218 @@
+//! Helpers for K lines from [`GetOHLC`](super::GetOHLC), e.g. resampling 1 minute candles into 7 minutes ones.
+//!
+//! Resampled candles are aligned to the Unix epoch, i.e. each starts at a multiple of the target period.
+//!
+//! ```
+//! use chrono::Duration;
+//! use maicoin_max::v2::rest::{ohlc, OHLC};
+//!
+//! # let candles: Vec<OHLC> = serde_json::from_str("[[1700000040,1,2,1,2,1],[1700000100,2,3,2,3,1]]").unwrap();
+//! ohlc::validate(&candles)?;
+//! let candles = ohlc::resample(&candles, Duration::minutes(2))?;
+//! assert_eq!(candles.len(), 1);
+//! # Ok::<(), maicoin_max::error::ResampleError>(())
+//! ```
+
+use chrono::Duration;
+use rust_decimal::Decimal;
+
+use super::OHLC;
+pub use crate::error::ResampleError;
+
+/// Check the candles are in time order without duplicates, and each has the high no lower than the low.
+pub fn validate(candles: &[OHLC]) -> Result<(), ResampleError> {
+    for candle in candles.iter() {
+        if candle.high < candle.low {
+            return Err(ResampleError::HighBelowLow(candle.time));
+        }
+    }
+    match candles.windows(2).find(|pair| pair[1].time <= pair[0].time) {
+        Some(pair) => Err(ResampleError::NotMonotonic(pair[1].time)),
+        None => Ok(()),
+    }
+}
+
+/// Aggregate the candles into candles of the target period: open of the first, close of the last, the highest high,
+/// the lowest low and the total volume. Periods without any candle are skipped, see [`resample_filling_gaps`] to keep
+/// them.
+///
+/// The candles are validated by [`validate`], and the target must be a multiple of the spacing of the candles, taken
+/// as the shortest interval between them.
+pub fn resample(candles: &[OHLC], target: Duration) -> Result<Vec<OHLC>, ResampleError> {
+    validate(candles)?;
+    let period = target.num_seconds();
+    if period <= 0 || Duration::seconds(period) != target {
+        return Err(ResampleError::InvalidPeriod(period));
+    }
+    let spacing = candles
+        .windows(2)
+        .map(|pair| (pair[1].time - pair[0].time).num_seconds())
+        .min();
+    if let Some(spacing) = spacing.filter(|spacing| period % spacing != 0) {
+        return Err(ResampleError::NotMultiple {
+            target: period,
+            spacing,
+        });
+    }
+
+    let mut resampled: Vec<OHLC> = Vec::new();
+    for candle in candles.iter() {
+        let timestamp = candle.time.timestamp();
+        let start = candle.time - Duration::seconds(timestamp.rem_euclid(period));
+        match resampled.last_mut() {
+            Some(last) if last.time == start => {
+                last.high = last.high.max(candle.high);
+                last.low = last.low.min(candle.low);
+                last.close = candle.close;
+                last.volume += candle.volume;
+            }
+            _ => resampled.push(OHLC {
+                time: start,
+                ..candle.clone()
+            }),
+        }
+    }
+    Ok(resampled)
+}
+
+/// Same as [`resample`], but the periods without any candle are filled with zero volume candles, which open, close,
+/// and stay at the close of the previous one.
+pub fn resample_filling_gaps(
+    candles: &[OHLC],
+    target: Duration,
+) -> Result<Vec<OHLC>, ResampleError> {
+    let resampled = resample(candles, target)?;
+    let mut filled: Vec<OHLC> = Vec::with_capacity(resampled.len());
+    for candle in resampled.into_iter() {
+        if let Some(last) = filled.last() {
+            let close = last.close;
+            let mut time = last.time + target;
+            while time < candle.time {
+                filled.push(OHLC {
+                    time,
+                    open: close,
+                    high: close,
+                    low: close,
+                    close,
+                    volume: Decimal::ZERO,
+                });
+                time += target;
+            }
+        }
+        filled.push(candle);
+    }
+    Ok(filled)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::DateTime;
+    use chrono::{TimeZone, Utc};
+    use rust_decimal_macros::dec;
+
+    fn at(minute: i64) -> DateTime {
+        Utc.timestamp_opt(1700000040 + minute * 60, 0).unwrap()
+    }
+
+    // 1 minute candles of (minute, open, high, low, close, volume)
+    fn candles(raw: &[(i64, Decimal, Decimal, Decimal, Decimal, Decimal)]) -> Vec<OHLC> {
+        raw.iter()
+            .map(|&(minute, open, high, low, close, volume)| OHLC {
+                time: at(minute),
+                open,
+                high,
+                low,
+                close,
+                volume,
+            })
+            .collect()
+    }
+
+    fn with_gap() -> Vec<OHLC> {
+        // 1700000040 is a multiple of 120 seconds, minutes 4 and 5 are missing
+        candles(&[
+            (0, dec!(10), dec!(12), dec!(9), dec!(11), dec!(1)),
+            (1, dec!(11), dec!(15), dec!(10), dec!(14), dec!(2)),
+            (2, dec!(14), dec!(14), dec!(13), dec!(13), dec!(0.5)),
+            (3, dec!(13), dec!(16), dec!(12), dec!(16), dec!(1.5)),
+            (6, dec!(17), dec!(18), dec!(8), dec!(9), dec!(3)),
+        ])
+    }
+
+    #[test]
+    fn resample_candles() {
+        let resampled = resample(&with_gap(), Duration::minutes(2)).unwrap();
+        assert_eq!(
+            resampled,
+            candles(&[
+                (0, dec!(10), dec!(15), dec!(9), dec!(14), dec!(3)),
+                (2, dec!(14), dec!(16), dec!(12), dec!(16), dec!(2)),
+                (6, dec!(17), dec!(18), dec!(8), dec!(9), dec!(3)),
+            ])
+        );
+
+        let filled = resample_filling_gaps(&with_gap(), Duration::minutes(2)).unwrap();
+        assert_eq!(filled.len(), 4);
+        assert_eq!(
+            filled[2],
+            candles(&[(4, dec!(16), dec!(16), dec!(16), dec!(16), dec!(0))])[0]
+        );
+        assert_eq!(filled[3], resampled[2]);
+
+        // aligned to the epoch: minute 0 starts in the middle of a 7 minutes period
+        let resampled = resample(&with_gap(), Duration::minutes(7)).unwrap();
+        let starts: Vec<_> = resampled
+            .iter()
+            .map(|candle| candle.time.timestamp() % 420)
+            .collect();
+        assert_eq!(starts, [0, 0]);
+        assert_eq!(resampled[0].volume + resampled[1].volume, dec!(8));
+
+        assert_eq!(resample(&[], Duration::hours(4)).unwrap(), []);
+    }
+
+    #[test]
+    fn resample_errors() {
+        assert_eq!(
+            resample(&with_gap(), Duration::seconds(90)),
+            Err(ResampleError::NotMultiple {
+                target: 90,
+                spacing: 60
+            })
+        );
+        assert_eq!(
+            resample(&with_gap(), Duration::zero()),
+            Err(ResampleError::InvalidPeriod(0))
+        );
+        assert!(matches!(
+            resample(&with_gap(), Duration::milliseconds(60500)),
+            Err(ResampleError::InvalidPeriod(_))
+        ));
+    }
+
+    #[test]
+    fn validate_candles() {
+        assert_eq!(validate(&with_gap()), Ok(()));
+
+        let mut unordered = with_gap();
+        unordered.swap(1, 2);
+        assert_eq!(
+            validate(&unordered),
+            Err(ResampleError::NotMonotonic(at(1)))
+        );
+        let mut duplicated = with_gap();
+        duplicated[1].time = at(0);
+        assert_eq!(
+            validate(&duplicated),
+            Err(ResampleError::NotMonotonic(at(0)))
+        );
+
+        let mut inverted = with_gap();
+        inverted[3].low = dec!(17);
+        assert_eq!(
+            resample(&inverted, Duration::minutes(2)),
+            Err(ResampleError::HighBelowLow(at(3)))
+        );
+    }
+}
@@ -0,0 +1,347 @@
+use std::time::Duration;
+
+use crate::error::Error;
+use crate::v2::rest::Endpoint;
+
+/// Default max attempts of [`RetryPlan`], including the first one.
+pub const DEFAULT_MAX_ATTEMPTS: u32 = 3;
+/// Default backoff delay of the first retry.
+pub const DEFAULT_BASE_DELAY: Duration = Duration::from_millis(500);
+/// Default upper bound of backoff delay.
+pub const DEFAULT_MAX_DELAY: Duration = Duration::from_secs(30);
+
+/// Decision made by [`RetryPlan`] after a failed request.
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub enum Retry {
+    /// Give up and report the error.
+    No,
+    /// Send the same request again after the delay.
+    After(Duration),
+    /// Create the request again (e.g. by `to_request`) and send it after the delay. Authenticated requests are signed
+    /// with nonce, which can not be reused.
+    AfterRebuild(Duration),
+}
+
+/// Retry policy of API calls, with jittered exponential backoff.
+///
+/// It is a pure state machine: the caller reports each failure and gets back a [`Retry`] decision, then sleeps and
+/// sends requests by themselves. Requests are retried only if it is safe:
+///
+/// - Maintenance: the request did not reach the API server, so it is always retried.
+/// - Transport errors: the server may or may not have processed the request, so only idempotent requests (see
+///   [`Endpoint::is_idempotent`]) are retried. For example, [`crate::v2::rest::CreateOrder`] without `client_oid` is
+///   never retried.
+/// - Rejected nonce: the request was not processed, and is retried immediately with a new nonce.
+/// - Other errors, e.g. errors replied by server or invalid response body: never retried.
+///
+/// ```ignore
+/// let mut plan = RetryPlan::new(5);
+/// let result = loop {
+///     let resp = client.send(params.to_request(&credentials)).await;
+///     let err = match ... {
+///         Ok(result) => break Ok(result),
+///         Err(err) => err,
+///     };
+///     match plan.decide(&params, &err) {
+///         Retry::No => break Err(err),
+///         Retry::After(delay) | Retry::AfterRebuild(delay) => task::sleep(delay).await,
+///     }
+/// };
+/// ```
+#[derive(Clone, Debug)]
+pub struct RetryPlan {
+    max_attempts: u32,
+    base_delay: Duration,
+    max_delay: Duration,
+    attempts: u32,
+    rng_state: u64,
+}
+
+impl Default for RetryPlan {
+    fn default() -> Self {
+        Self::new(DEFAULT_MAX_ATTEMPTS)
+    }
+}
+
+impl RetryPlan {
+    /// Create a plan with at most `max_attempts` attempts, including the first one.
+    pub fn new(max_attempts: u32) -> Self {
+        Self {
+            max_attempts,
+            base_delay: DEFAULT_BASE_DELAY,
+            max_delay: DEFAULT_MAX_DELAY,
+            attempts: 1,
+            rng_state: 0,
+        }
+        .with_seed(random_seed())
+    }
+
+    /// Set the backoff delay of the first retry and the upper bound of delays.
+    pub fn with_backoff(mut self, base_delay: Duration, max_delay: Duration) -> Self {
+        self.base_delay = base_delay;
+        self.max_delay = max_delay;
+        self
+    }
+
+    /// Set the seed of jitter, which makes the delays reproducible.
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        // xorshift must not start from zero
+        self.rng_state = seed | 1;
+        self
+    }
+
+    /// Number of attempts made so far, including the first one.
+    pub fn attempts(&self) -> u32 {
+        self.attempts
+    }
+
+    /// Start over for a new API call.
+    pub fn reset(&mut self) {
+        self.attempts = 1;
+    }
+
+    /// Decide whether to retry the failed request of the endpoint.
+    pub fn decide<E: Endpoint>(&mut self, params: &E, err: &Error) -> Retry {
+        self.decide_raw(E::AUTH, params.is_idempotent(), err)
+    }
+
+    /// Decide whether to retry a failed request, given if it is authenticated and idempotent.
+    pub fn decide_raw(&mut self, auth: bool, idempotent: bool, err: &Error) -> Retry {
+        if self.attempts >= self.max_attempts {
+            return Retry::No;
+        }
+        let delay = match err {
+            Error::Maintenance(_) => self.backoff(),
+            Error::Transport(_) if idempotent => self.backoff(),
+            err if auth && err.is_nonce_error() => Duration::ZERO,
+            _ => return Retry::No,
+        };
+        self.attempts += 1;
+        if auth {
+            Retry::AfterRebuild(delay)
+        } else {
+            Retry::After(delay)
+        }
+    }
+
+    // Exponential backoff of the current attempt, jittered to [50%, 100%].
+    fn backoff(&mut self) -> Duration {
+        let exp = self.attempts.saturating_sub(1).min(31);
+        let delay = self
+            .base_delay
+            .checked_mul(1 << exp)
+            .unwrap_or(self.max_delay)
+            .min(self.max_delay);
+        let jitter = (self.next_random() % 1000) as u32;
+        delay / 2 + delay / 2 * jitter / 999
+    }
+
+    fn next_random(&mut self) -> u64 {
+        let mut x = self.rng_state;
+        x ^= x << 13;
+        x ^= x >> 7;
+        x ^= x << 17;
+        self.rng_state = x;
+        x
+    }
+}
+
+#[cfg(not(all(target_arch = "wasm32", target_os = "unknown")))]
+fn random_seed() -> u64 {
+    use std::time::{SystemTime, UNIX_EPOCH};
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_nanos() as u64)
+        .unwrap_or_default()
+}
+
+// `SystemTime::now()` panics on wasm32-unknown-unknown, use the random source of JS runtime instead
+#[cfg(all(target_arch = "wasm32", target_os = "unknown"))]
+fn random_seed() -> u64 {
+    (js_sys::Math::random() * u64::MAX as f64) as u64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::OrderSide;
+    use crate::v2::rest::{CreateOrder, DeleteOrder, GetOrder, GetTimestamp, OrderType};
+    use rust_decimal_macros::dec;
+
+    fn transport_error() -> Error {
+        Error::Transport(Box::new(anyhow::anyhow!("connection reset")))
+    }
+
+    fn create_order(client_oid: Option<String>) -> CreateOrder {
+        CreateOrder {
+            market: "maxtwd".into(),
+            side: OrderSide::Buy,
+            volume: dec!(100),
+            price: Some(dec!(9.5)),
+            client_oid,
+            stop_price: None,
+            ord_type: OrderType::Limit,
+            group_id: None,
+        }
+    }
+
+    fn plan() -> RetryPlan {
+        RetryPlan::new(4)
+            .with_backoff(Duration::from_millis(100), Duration::from_millis(250))
+            .with_seed(42)
+    }
+
+    fn delay_of(retry: Retry) -> Duration {
+        match retry {
+            Retry::After(delay) | Retry::AfterRebuild(delay) => delay,
+            Retry::No => panic!("expect retry"),
+        }
+    }
+
+    #[test]
+    fn idempotency_of_endpoints() {
+        assert!(GetTimestamp {}.is_idempotent());
+        assert!(GetOrder {
+            id: Some(1),
+            client_oid: None
+        }
+        .is_idempotent());
+        assert!(DeleteOrder {
+            id: Some(1),
+            client_oid: None
+        }
+        .is_idempotent());
+        assert!(create_order(Some("my-order".into())).is_idempotent());
+        assert!(!create_order(None).is_idempotent());
+    }
+
+    #[test]
+    fn public_transport_error() {
+        let mut plan = plan();
+        let err = transport_error();
+        let delays: Vec<Duration> = (0..3)
+            .map(|_| match plan.decide(&GetTimestamp {}, &err) {
+                Retry::After(delay) => delay,
+                other => panic!("unexpected decision {:?}", other),
+            })
+            .collect();
+        assert_eq!(plan.decide(&GetTimestamp {}, &err), Retry::No);
+        assert_eq!(plan.attempts(), 4);
+
+        // exponential backoff bounded by max delay, jittered to [50%, 100%]
+        let bounds = [(50, 100), (100, 200), (125, 250)];
+        for (delay, (min, max)) in delays.iter().zip(bounds.iter()) {
+            assert!(*delay >= Duration::from_millis(*min), "{:?}", delay);
+            assert!(*delay <= Duration::from_millis(*max), "{:?}", delay);
+        }
+
+        // reproducible with the same seed
+        let mut replay = self::plan();
+        let replayed: Vec<Duration> = (0..3)
+            .map(|_| delay_of(replay.decide(&GetTimestamp {}, &err)))
+            .collect();
+        assert_eq!(replayed, delays);
+
+        plan.reset();
+        assert_eq!(plan.attempts(), 1);
+        assert_ne!(plan.decide(&GetTimestamp {}, &err), Retry::No);
+    }
+
+    #[test]
+    fn auth_requests_rebuild() {
+        let mut plan = plan();
+        let params = GetOrder {
+            id: Some(1),
+            client_oid: None,
+        };
+        assert!(matches!(
+            plan.decide(&params, &transport_error()),
+            Retry::AfterRebuild(_)
+        ));
+        assert!(matches!(
+            plan.decide(&params, &Error::Maintenance(503)),
+            Retry::AfterRebuild(_)
+        ));
+    }
+
+    #[test]
+    fn create_order_without_client_oid() {
+        let mut plan = plan();
+        assert_eq!(
+            plan.decide(&create_order(None), &transport_error()),
+            Retry::No
+        );
+        assert_eq!(plan.attempts(), 1);
+
+        // maintenance means the order never reached the server
+        assert!(matches!(
+            plan.decide(&create_order(None), &Error::Maintenance(503)),
+            Retry::AfterRebuild(_)
+        ));
+        assert!(matches!(
+            plan.decide(&create_order(Some("my-order".into())), &transport_error()),
+            Retry::AfterRebuild(_)
+        ));
+    }
+
+    #[test]
+    fn nonce_errors() {
+        let mut plan = plan();
+        let used = Error::RestApi(
+            2006,
+            "The nonce has already been used by access key.".into(),
+        );
+        let invalid = Error::RestApi(2007, "The nonce is invalid.".into());
+        assert!(used.is_nonce_error());
+        assert!(invalid.is_nonce_error());
+        assert_eq!(
+            plan.decide(&create_order(None), &used),
+            Retry::AfterRebuild(Duration::ZERO)
+        );
+        assert_eq!(
+            plan.decide_raw(true, false, &invalid),
+            Retry::AfterRebuild(Duration::ZERO)
+        );
+        // public endpoints are not signed
+        assert_eq!(plan.decide_raw(false, true, &invalid), Retry::No);
+    }
+
+    #[test]
+    fn non_retryable_errors() {
+        let errors = [
+            Error::RestApi(2005, "Signature is incorrect.".into()),
+            Error::ReadResponse(Box::new(anyhow::anyhow!("invalid JSON"))),
+            Error::InvalidUrl("http://".into()),
+            Error::InvalidHttpMessage("invalid header".into()),
+            Error::WsInvalidValue("invalid".into()),
+        ];
+        for err in errors.iter() {
+            let mut plan = plan();
+            assert_eq!(plan.decide_raw(false, true, err), Retry::No, "{:?}", err);
+            assert_eq!(plan.decide_raw(true, true, err), Retry::No, "{:?}", err);
+            assert_eq!(plan.attempts(), 1);
+        }
+    }
+
+    #[test]
+    fn max_attempts() {
+        let mut plan = RetryPlan::new(1);
+        assert_eq!(
+            plan.decide(&GetTimestamp {}, &Error::Maintenance(503)),
+            Retry::No
+        );
+        let mut plan = RetryPlan::default();
+        assert_ne!(
+            plan.decide(&GetTimestamp {}, &Error::Maintenance(503)),
+            Retry::No
+        );
+        assert_ne!(
+            plan.decide(&GetTimestamp {}, &Error::Maintenance(503)),
+            Retry::No
+        );
+        assert_eq!(
+            plan.decide(&GetTimestamp {}, &Error::Maintenance(503)),
+            Retry::No
+        );
+    }
+}
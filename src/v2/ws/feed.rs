@@ -10,16 +10,21 @@
 //! - Private balance changes ([`PrivBalanceFeed`])
 //!
 //! Each feeds implement [`Feed`] trait, which makes it easy to be dispatched by [`crate::v2::ws::ServerPushEvent`].
+//! [`Feed::is_private`] tells apart the feeds of the authenticated user from the public market data.
+//! [`FeedSequencer`] flags the updates received before any snapshot, which usually means a missed message.
 
+use std::collections::HashSet;
+use std::marker::PhantomData;
 use std::result::Result as StdResult;
 
 use chrono::serde as chrono_serde;
 use rust_decimal::Decimal;
-use serde::{de, de::DeserializeOwned, Deserialize};
+use serde::{de, de::DeserializeOwned, ser, ser::SerializeTuple, Deserialize, Serialize};
 use serde_json::Value as JsonValue;
 
 use crate::common::*;
 use crate::error::*;
+use crate::v2::rest::{OrderState, RespTickerInfo};
 
 // ========================
 // Interfaces and Utilities
@@ -33,12 +38,30 @@ where
     /// Feed content data.
     type Records;
 
+    /// Whether the feed is pushed to authenticated connections only.
+    const PRIVATE: bool;
+
+    /// Returns whether the feed carries private data of the authenticated user, or public market data.
+    fn is_private() -> bool {
+        Self::PRIVATE
+    }
+
     /// Returns whether current feed event is a snapshot, or an update.
     fn is_snapshot(&self) -> bool;
 
     /// Transform the feed into the records it contains.
     fn into_record(self) -> Self::Records;
 
+    /// Market of the feed, `None` if the feed is not bound to a single market, e.g. private feeds.
+    fn market(&self) -> Option<&Symbol> {
+        None
+    }
+
+    /// Timestamp of the feed, `None` if not given by server.
+    fn time(&self) -> Option<DateTime> {
+        None
+    }
+
     /// Deserialize a serde_json::Value into a feed event. You are unlikely to need to work with this directly except via
     /// [`crate::v2::ws::ServerPushEvent`].
     fn from_json_value(value: JsonValue) -> Result<Self> {
@@ -46,6 +69,93 @@ where
     }
 }
 
+/// Order of a feed relative to the snapshots seen by [`FeedSequencer`].
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub enum FeedSequence {
+    /// A snapshot, after which updates are expected.
+    Snapshot,
+    /// An update following a snapshot of the same market.
+    Update,
+    /// An update before any snapshot of the same market, which usually means a missed message.
+    UpdateBeforeSnapshot,
+}
+
+impl FeedSequence {
+    /// `true` if the feed arrived out of order.
+    pub fn is_out_of_order(&self) -> bool {
+        self == &Self::UpdateBeforeSnapshot
+    }
+}
+
+/// Tracker of the snapshots received per market, which flags the updates arriving before any snapshot.
+///
+/// Feeds not bound to a single market, i.e. private feeds, are tracked as a whole. Use a sequencer for each feed type,
+/// and [`FeedSequencer::reset`] it after reconnecting.
+///
+/// ```
+/// use maicoin_max::v2::ws::feed::{FeedSequence, FeedSequencer, PubTradeFeed};
+///
+/// let mut sequencer = FeedSequencer::<PubTradeFeed>::new();
+/// let update: PubTradeFeed = serde_json::from_str(
+///     r#"{"e": "update", "M": "btctwd", "t": [], "T": 1637998469525}"#,
+/// ).unwrap();
+/// assert_eq!(sequencer.observe(&update), FeedSequence::UpdateBeforeSnapshot);
+/// ```
+#[derive(Debug)]
+pub struct FeedSequencer<F> {
+    snapshots: HashSet<Option<Symbol>>,
+    _feed: PhantomData<fn(&F)>,
+}
+
+impl<F> Default for FeedSequencer<F> {
+    fn default() -> Self {
+        Self {
+            snapshots: HashSet::new(),
+            _feed: PhantomData,
+        }
+    }
+}
+
+impl<F: Feed> FeedSequencer<F> {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Record the feed, and tell whether it arrives in order.
+    pub fn observe(&mut self, feed: &F) -> FeedSequence {
+        let market = feed.market().cloned();
+        if feed.is_snapshot() {
+            self.snapshots.insert(market);
+            FeedSequence::Snapshot
+        } else if self.snapshots.contains(&market) {
+            FeedSequence::Update
+        } else {
+            #[cfg(feature = "tracing")]
+            tracing::warn!(
+                feed = std::any::type_name::<F>(),
+                market = market.as_deref().unwrap_or("N/A"),
+                "feed update before snapshot"
+            );
+            FeedSequence::UpdateBeforeSnapshot
+        }
+    }
+
+    /// `true` if a snapshot of the market was seen. Pass `None` for the feeds not bound to a single market.
+    pub fn has_snapshot(&self, market: Option<&Symbol>) -> bool {
+        self.snapshots.contains(&market.cloned())
+    }
+
+    /// Forget the snapshot of the market, e.g. after unsubscribing it.
+    pub fn forget(&mut self, market: Option<&Symbol>) {
+        self.snapshots.remove(&market.cloned());
+    }
+
+    /// Forget all the snapshots, e.g. after reconnecting.
+    pub fn reset(&mut self) {
+        self.snapshots.clear();
+    }
+}
+
 fn parse_pub_feed_type<'de, D>(deserializer: D) -> StdResult<bool, D::Error>
 where
     D: de::Deserializer<'de>,
@@ -61,6 +171,13 @@ where
     }
 }
 
+fn serialize_pub_feed_type<S>(is_snapshot: &bool, serializer: S) -> StdResult<S::Ok, S::Error>
+where
+    S: ser::Serializer,
+{
+    serializer.serialize_str(if *is_snapshot { "snapshot" } else { "update" })
+}
+
 fn parse_priv_feed_type<'de, D>(deserializer: D) -> StdResult<bool, D::Error>
 where
     D: de::Deserializer<'de>,
@@ -76,6 +193,39 @@ where
     }
 }
 
+fn serialize_priv_feed_type<S>(
+    prefix: &str,
+    is_snapshot: bool,
+    serializer: S,
+) -> StdResult<S::Ok, S::Error>
+where
+    S: ser::Serializer,
+{
+    let suffix = if is_snapshot { "snapshot" } else { "update" };
+    serializer.serialize_str(&format!("{}_{}", prefix, suffix))
+}
+
+fn serialize_order_feed_type<S>(is_snapshot: &bool, serializer: S) -> StdResult<S::Ok, S::Error>
+where
+    S: ser::Serializer,
+{
+    serialize_priv_feed_type("order", *is_snapshot, serializer)
+}
+
+fn serialize_trade_feed_type<S>(is_snapshot: &bool, serializer: S) -> StdResult<S::Ok, S::Error>
+where
+    S: ser::Serializer,
+{
+    serialize_priv_feed_type("trade", *is_snapshot, serializer)
+}
+
+fn serialize_account_feed_type<S>(is_snapshot: &bool, serializer: S) -> StdResult<S::Ok, S::Error>
+where
+    S: ser::Serializer,
+{
+    serialize_priv_feed_type("account", *is_snapshot, serializer)
+}
+
 // ==================================
 // Orderbook feed from public channel
 // ==================================
@@ -83,10 +233,14 @@ where
 /// Orderbook feed from public channel.
 ///
 /// [Official document](https://maicoin.github.io/max-websocket-docs/#/public_orderbook?id=orderbook-subscription)
-#[derive(Deserialize, Debug, Eq, PartialEq)]
+#[derive(Serialize, Deserialize, Clone, Debug, Eq, PartialEq)]
 pub struct PubOrderBookFeed {
     /// `true` if this feed is a snapshot.
-    #[serde(rename = "e", deserialize_with = "parse_pub_feed_type")]
+    #[serde(
+        rename = "e",
+        deserialize_with = "parse_pub_feed_type",
+        serialize_with = "serialize_pub_feed_type"
+    )]
     pub is_snapshot: bool,
     /// Market name.
     #[serde(rename = "M")]
@@ -104,6 +258,7 @@ pub struct PubOrderBookFeed {
 
 impl Feed for PubOrderBookFeed {
     type Records = (Vec<PubOrderBookRec>, Vec<PubOrderBookRec>);
+    const PRIVATE: bool = false;
 
     fn is_snapshot(&self) -> bool {
         self.is_snapshot
@@ -112,14 +267,35 @@ impl Feed for PubOrderBookFeed {
     fn into_record(self) -> Self::Records {
         (self.ask, self.bid)
     }
+
+    fn market(&self) -> Option<&Symbol> {
+        Some(&self.market)
+    }
+
+    fn time(&self) -> Option<DateTime> {
+        Some(self.time)
+    }
 }
 
-#[derive(Deserialize, Debug, Eq, PartialEq)]
+#[derive(Deserialize, Clone, Debug, Eq, PartialEq)]
 pub struct PubOrderBookRec {
     pub price: Decimal,
     pub volume: Decimal,
 }
 
+// Orderbook entries are sent as `[price, volume]` pairs.
+impl Serialize for PubOrderBookRec {
+    fn serialize<S>(&self, serializer: S) -> StdResult<S::Ok, S::Error>
+    where
+        S: ser::Serializer,
+    {
+        let mut tuple = serializer.serialize_tuple(2)?;
+        tuple.serialize_element(&self.price)?;
+        tuple.serialize_element(&self.volume)?;
+        tuple.end()
+    }
+}
+
 // ==============================
 // Trade feed from public channel
 // ==============================
@@ -127,10 +303,14 @@ pub struct PubOrderBookRec {
 /// Trade feed from public channel.
 ///
 /// [Official document](https://maicoin.github.io/max-websocket-docs/#/public_trade?id=trade-subscription)
-#[derive(Deserialize, Debug, Eq, PartialEq)]
+#[derive(Serialize, Deserialize, Clone, Debug, Eq, PartialEq)]
 pub struct PubTradeFeed {
     /// `true` if this feed is a snapshot.
-    #[serde(rename = "e", deserialize_with = "parse_pub_feed_type")]
+    #[serde(
+        rename = "e",
+        deserialize_with = "parse_pub_feed_type",
+        serialize_with = "serialize_pub_feed_type"
+    )]
     pub is_snapshot: bool,
     /// Market name.
     #[serde(rename = "M")]
@@ -145,6 +325,7 @@ pub struct PubTradeFeed {
 
 impl Feed for PubTradeFeed {
     type Records = Vec<PubTradeRec>;
+    const PRIVATE: bool = false;
 
     fn is_snapshot(&self) -> bool {
         self.is_snapshot
@@ -153,9 +334,17 @@ impl Feed for PubTradeFeed {
     fn into_record(self) -> Self::Records {
         self.trades
     }
+
+    fn market(&self) -> Option<&Symbol> {
+        Some(&self.market)
+    }
+
+    fn time(&self) -> Option<DateTime> {
+        Some(self.time)
+    }
 }
 
-#[derive(Deserialize, Debug, Eq, PartialEq)]
+#[derive(Serialize, Deserialize, Clone, Debug, Eq, PartialEq)]
 pub struct PubTradeRec {
     #[serde(rename = "p")]
     pub price: Decimal,
@@ -174,10 +363,14 @@ pub struct PubTradeRec {
 /// Ticker feed from public channel.
 ///
 /// [Official document](https://maicoin.github.io/max-websocket-docs/#/public_ticker?id=ticker-subscription)
-#[derive(Deserialize, Debug, Eq, PartialEq)]
+#[derive(Serialize, Deserialize, Clone, Debug, Eq, PartialEq)]
 pub struct PubTickerFeed {
     /// `true` if this feed is a snapshot.
-    #[serde(rename = "e", deserialize_with = "parse_pub_feed_type")]
+    #[serde(
+        rename = "e",
+        deserialize_with = "parse_pub_feed_type",
+        serialize_with = "serialize_pub_feed_type"
+    )]
     pub is_snapshot: bool,
     /// Market name.
     #[serde(rename = "M")]
@@ -192,6 +385,7 @@ pub struct PubTickerFeed {
 
 impl Feed for PubTickerFeed {
     type Records = TickerRec;
+    const PRIVATE: bool = false;
 
     fn is_snapshot(&self) -> bool {
         self.is_snapshot
@@ -200,18 +394,44 @@ impl Feed for PubTickerFeed {
     fn into_record(self) -> Self::Records {
         self.tick
     }
+
+    fn market(&self) -> Option<&Symbol> {
+        Some(&self.market)
+    }
+
+    fn time(&self) -> Option<DateTime> {
+        Some(self.time)
+    }
+}
+
+impl PubTickerFeed {
+    /// Convert into the ticker of REST API, for the code built on [`RespTickerInfo`]. The feed carries no order book
+    /// nor the volume in BTC, so `buy` and `sell` are both the close price, and `volume_in_btc` is zero.
+    pub fn to_ticker_info(&self) -> RespTickerInfo {
+        RespTickerInfo {
+            at: self.time,
+            buy: self.tick.close,
+            sell: self.tick.close,
+            open: self.tick.open,
+            low: self.tick.low,
+            high: self.tick.high,
+            last_price: self.tick.close,
+            volume: self.tick.volume,
+            volume_in_btc: Decimal::ZERO,
+        }
+    }
 }
 
-#[derive(Deserialize, Debug, Eq, PartialEq)]
+#[derive(Serialize, Deserialize, Clone, Debug, Eq, PartialEq)]
 pub struct TickerRec {
     #[serde(rename = "O")]
     pub open: Decimal,
     #[serde(rename = "H")]
-    pub close: Decimal,
-    #[serde(rename = "L")]
     pub high: Decimal,
-    #[serde(rename = "C")]
+    #[serde(rename = "L")]
     pub low: Decimal,
+    #[serde(rename = "C")]
+    pub close: Decimal,
     #[serde(rename = "v")]
     pub volume: Decimal,
 }
@@ -223,12 +443,16 @@ pub struct TickerRec {
 /// Market status feed from public channel.
 ///
 /// [Official document](https://maicoin.github.io/max-websocket-docs/#/public_market_status)
-#[derive(Deserialize, Debug, Eq, PartialEq)]
+#[derive(Serialize, Deserialize, Clone, Debug, Eq, PartialEq)]
 pub struct PubMarketStatueFeed {
     /// `true` if this feed is a snapshot.
     #[serde(rename = "c")]
     pub channel: String,
-    #[serde(rename = "e", deserialize_with = "parse_pub_feed_type")]
+    #[serde(
+        rename = "e",
+        deserialize_with = "parse_pub_feed_type",
+        serialize_with = "serialize_pub_feed_type"
+    )]
     pub is_snapshot: bool,
     /// Market name.
     #[serde(rename = "ms")]
@@ -237,6 +461,7 @@ pub struct PubMarketStatueFeed {
 
 impl Feed for PubMarketStatueFeed {
     type Records = Vec<MarketStatusInfo>;
+    const PRIVATE: bool = false;
 
     fn is_snapshot(&self) -> bool {
         self.is_snapshot
@@ -247,7 +472,7 @@ impl Feed for PubMarketStatueFeed {
     }
 }
 
-#[derive(Deserialize, Debug, Eq, PartialEq)]
+#[derive(Serialize, Deserialize, Clone, Debug, Eq, PartialEq)]
 pub struct MarketStatusInfo {
     #[serde(rename = "M")]
     pub market: String,
@@ -269,6 +494,50 @@ pub struct MarketStatusInfo {
     pub m_wallet_supported: bool,
 }
 
+impl MarketStatusInfo {
+    /// Parsed [`MarketStatusInfo::status`], [`MarketStatus::Unknown`] for unrecognized values.
+    pub fn status_enum(&self) -> MarketStatus {
+        self.status.as_str().into()
+    }
+}
+
+/// Trading status of a market.
+#[derive(Serialize, Deserialize, Copy, Clone, Eq, PartialEq, Debug, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum MarketStatus {
+    /// Orders are accepted and matched.
+    Active,
+    /// Trading is suspended.
+    Suspended,
+    /// Only order cancellations are accepted, market makers should stop quoting.
+    CancelOnly,
+    #[default]
+    #[serde(other)]
+    Unknown,
+}
+
+impl MarketStatus {
+    pub fn is_unknown(&self) -> bool {
+        self == &Self::Unknown
+    }
+
+    /// `true` if new orders are accepted by the market.
+    pub fn accepts_new_orders(&self) -> bool {
+        self == &Self::Active
+    }
+}
+
+impl From<&str> for MarketStatus {
+    fn from(status: &str) -> Self {
+        match status {
+            "active" => Self::Active,
+            "suspended" => Self::Suspended,
+            "cancel_only" => Self::CancelOnly,
+            _ => Self::Unknown,
+        }
+    }
+}
+
 // ===================================================
 // Orderbook feed from private (authenticated) channel
 // ===================================================
@@ -276,10 +545,14 @@ pub struct MarketStatusInfo {
 /// Orderbook feed from private (authenticated) channel.
 ///
 /// [Official document](https://maicoin.github.io/max-websocket-docs/#/private_channels?id=order-response)
-#[derive(Deserialize, Debug, Eq, PartialEq)]
+#[derive(Serialize, Deserialize, Clone, Debug, Eq, PartialEq)]
 pub struct PrivOrderBookFeed {
     /// `true` if this feed is a snapshot.
-    #[serde(rename = "e", deserialize_with = "parse_priv_feed_type")]
+    #[serde(
+        rename = "e",
+        deserialize_with = "parse_priv_feed_type",
+        serialize_with = "serialize_order_feed_type"
+    )]
     pub is_snapshot: bool,
     /// List of submitted orders.
     #[serde(rename = "o")]
@@ -291,6 +564,7 @@ pub struct PrivOrderBookFeed {
 
 impl Feed for PrivOrderBookFeed {
     type Records = Vec<PrivOrderBookRec>;
+    const PRIVATE: bool = true;
 
     fn is_snapshot(&self) -> bool {
         self.is_snapshot
@@ -299,9 +573,13 @@ impl Feed for PrivOrderBookFeed {
     fn into_record(self) -> Self::Records {
         self.orders
     }
+
+    fn time(&self) -> Option<DateTime> {
+        Some(self.time)
+    }
 }
 
-#[derive(Deserialize, Debug, Eq, PartialEq)]
+#[derive(Serialize, Deserialize, Clone, Debug, Eq, PartialEq)]
 pub struct PrivOrderBookRec {
     /// Order ID.
     #[serde(rename = "i")]
@@ -321,9 +599,12 @@ pub struct PrivOrderBookRec {
     /// Average price.
     #[serde(rename = "ap")]
     pub avg_price: Option<Decimal>,
-    /// Order state.
-    #[serde(rename = "S")]
-    pub state: String,
+    /// Order state, [`OrderState::Unknown`] for states not known by this crate.
+    #[serde(
+        rename = "S",
+        deserialize_with = "crate::util::serde::from_str_or_default"
+    )]
+    pub state: OrderState,
     /// Market name.
     #[serde(rename = "M")]
     pub market: Symbol,
@@ -347,7 +628,46 @@ pub struct PrivOrderBookRec {
     pub client_oid: Option<String>,
     /// Group ID.
     #[serde(rename = "gi")]
-    pub group_id: Option<u64>,
+    pub group_id: Option<GroupId>,
+}
+
+impl PrivOrderBookRec {
+    /// Lifecycle of the order at this push, by the state, then by the executed and remaining volume for the orders
+    /// which are neither done nor cancelled.
+    pub fn lifecycle(&self) -> OrderLifecycle {
+        match self.state {
+            OrderState::Cancel => return OrderLifecycle::Cancelled,
+            OrderState::Done => return OrderLifecycle::Filled,
+            _ => {}
+        }
+        let executed = self
+            .executed_volume
+            .or_else(|| {
+                self.remaining_volume
+                    .map(|remaining| self.volume - remaining)
+            })
+            .unwrap_or_default();
+        if executed <= Decimal::ZERO {
+            OrderLifecycle::New
+        } else if self.remaining_volume == Some(Decimal::ZERO) {
+            OrderLifecycle::Filled
+        } else {
+            OrderLifecycle::PartiallyFilled
+        }
+    }
+}
+
+/// Lifecycle of an order pushed by [`PrivOrderBookFeed`], see [`PrivOrderBookRec::lifecycle`].
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub enum OrderLifecycle {
+    /// Placed without any execution.
+    New,
+    /// Partially executed and still open.
+    PartiallyFilled,
+    /// Fully executed.
+    Filled,
+    /// Cancelled, possibly after partial executions.
+    Cancelled,
 }
 
 // ===============================================
@@ -357,10 +677,14 @@ pub struct PrivOrderBookRec {
 /// Trade feed from private (authenticated) channel.
 ///
 /// [Official document](https://maicoin.github.io/max-websocket-docs/#/private_channels?id=trade-response)
-#[derive(Deserialize, Debug, Eq, PartialEq)]
+#[derive(Serialize, Deserialize, Clone, Debug, Eq, PartialEq)]
 pub struct PrivTradeFeed {
     /// `true` if this feed is a snapshot.
-    #[serde(rename = "e", deserialize_with = "parse_priv_feed_type")]
+    #[serde(
+        rename = "e",
+        deserialize_with = "parse_priv_feed_type",
+        serialize_with = "serialize_trade_feed_type"
+    )]
     pub is_snapshot: bool,
     /// List of filled trades.
     #[serde(rename = "t")]
@@ -372,6 +696,7 @@ pub struct PrivTradeFeed {
 
 impl Feed for PrivTradeFeed {
     type Records = Vec<PrivTradeRec>;
+    const PRIVATE: bool = true;
 
     fn is_snapshot(&self) -> bool {
         self.is_snapshot
@@ -380,9 +705,13 @@ impl Feed for PrivTradeFeed {
     fn into_record(self) -> Self::Records {
         self.trades
     }
+
+    fn time(&self) -> Option<DateTime> {
+        Some(self.time)
+    }
 }
 
-#[derive(Deserialize, Debug, Eq, PartialEq)]
+#[derive(Serialize, Deserialize, Clone, Debug, Eq, PartialEq)]
 pub struct PrivTradeRec {
     /// Trade ID.
     #[serde(rename = "i")]
@@ -411,6 +740,12 @@ pub struct PrivTradeRec {
     /// Is trade maker or not.
     #[serde(rename = "m")]
     pub is_maker: bool,
+    /// Order ID of the trade, absent in the pushes of older servers.
+    #[serde(rename = "oi", default, skip_serializing_if = "Option::is_none")]
+    pub oid: Option<u64>,
+    /// Client order ID of the trade.
+    #[serde(rename = "ci", default, skip_serializing_if = "Option::is_none")]
+    pub client_oid: Option<String>,
 }
 
 // =============================================================
@@ -420,10 +755,14 @@ pub struct PrivTradeRec {
 /// Balance information feed from private (authenticated) channel.
 ///
 /// [Official document](https://maicoin.github.io/max-websocket-docs/#/private_channels?id=account-response)
-#[derive(Deserialize, Debug, Eq, PartialEq)]
+#[derive(Serialize, Deserialize, Clone, Debug, Eq, PartialEq)]
 pub struct PrivBalanceFeed {
     /// `true` if this feed is a snapshot.
-    #[serde(rename = "e", deserialize_with = "parse_priv_feed_type")]
+    #[serde(
+        rename = "e",
+        deserialize_with = "parse_priv_feed_type",
+        serialize_with = "serialize_account_feed_type"
+    )]
     pub is_snapshot: bool,
     /// Balance for each wallets.
     #[serde(rename = "B")]
@@ -435,6 +774,7 @@ pub struct PrivBalanceFeed {
 
 impl Feed for PrivBalanceFeed {
     type Records = Vec<PrivBalanceItem>;
+    const PRIVATE: bool = true;
 
     fn is_snapshot(&self) -> bool {
         self.is_snapshot
@@ -443,9 +783,13 @@ impl Feed for PrivBalanceFeed {
     fn into_record(self) -> Self::Records {
         self.balance
     }
+
+    fn time(&self) -> Option<DateTime> {
+        Some(self.time)
+    }
 }
 
-#[derive(Deserialize, Debug, Eq, PartialEq)]
+#[derive(Serialize, Deserialize, Clone, Debug, Eq, PartialEq)]
 pub struct PrivBalanceItem {
     /// Currency name.
     #[serde(rename = "cu")]
@@ -461,6 +805,8 @@ pub struct PrivBalanceItem {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use rust_decimal_macros::dec;
+    use serde_json::json;
 
     #[test]
     fn test_pub_feed_type_parse() {
@@ -506,4 +852,353 @@ mod tests {
         parse(r#""""#).expect_err(ERROR_MSG);
         parse(r#""updatesnapshot""#).expect_err(ERROR_MSG);
     }
+
+    #[test]
+    fn test_ticker_to_ticker_info() {
+        let ticker: PubTickerFeed = serde_json::from_value(json!({
+            "c": "ticker",
+            "e": "snapshot",
+            "M": "maxtwd",
+            "tk": {"O": "11.4", "H": "11.9", "L": "11.2", "C": "11.6", "v": "1234.5"},
+            "T": 1637998469526u64
+        }))
+        .expect("invalid test case");
+        assert_eq!(ticker.tick.high, dec!(11.9));
+        assert_eq!(ticker.tick.low, dec!(11.2));
+        assert_eq!(ticker.tick.close, dec!(11.6));
+        let info = ticker.to_ticker_info();
+        assert_eq!(
+            info,
+            RespTickerInfo {
+                at: ticker.time,
+                buy: dec!(11.6),
+                sell: dec!(11.6),
+                open: dec!(11.4),
+                low: dec!(11.2),
+                high: dec!(11.9),
+                last_price: dec!(11.6),
+                volume: dec!(1234.5),
+                volume_in_btc: dec!(0),
+            }
+        );
+        assert_eq!(info.at.timestamp_millis(), 1637998469526);
+    }
+
+    #[test]
+    fn test_feed_market_and_time() {
+        let ticker: PubTickerFeed = serde_json::from_value(json!({
+            "c": "ticker",
+            "e": "snapshot",
+            "M": "maxtwd",
+            "tk": {"O": "11.4", "H": "11.9", "L": "11.2", "C": "11.6", "v": "1234.5"},
+            "T": 1637998469526u64
+        }))
+        .expect("invalid test case");
+        assert_eq!(ticker.market(), Some(&Symbol::from("maxtwd")));
+        assert_eq!(
+            ticker.time().map(|t| t.timestamp_millis()),
+            Some(1637998469526)
+        );
+
+        let balance: PrivBalanceFeed = serde_json::from_value(json!({
+            "c": "user",
+            "e": "account_update",
+            "B": [{"cu": "btc", "av": "123.4", "l": "0.5"}],
+            "T": 1521726960357u64
+        }))
+        .expect("invalid test case");
+        assert_eq!(balance.market(), None);
+        assert_eq!(
+            balance.time().map(|t| t.timestamp_millis()),
+            Some(1521726960357)
+        );
+    }
+
+    #[test]
+    fn test_feed_visibility() {
+        assert!(PrivTradeFeed::is_private());
+        assert!(PrivOrderBookFeed::is_private());
+        assert!(PrivBalanceFeed::is_private());
+        assert!(!PubTradeFeed::is_private());
+        assert!(!PubOrderBookFeed::is_private());
+        assert!(!PubTickerFeed::is_private());
+        assert!(!PubMarketStatueFeed::is_private());
+    }
+
+    #[test]
+    fn test_pub_trade_feed_json_round_trip() {
+        let orig = json!({
+          "e": "update",
+          "M": "btctwd",
+          "t":[{
+            "p": "5337.3",
+            "v": "0.1",
+            "T": 123456789,
+            "tr": "up"
+          }],
+          "T": 123456789
+        });
+        let feed: PubTradeFeed = serde_json::from_value(orig.clone()).expect("invalid test case");
+        let serialized = serde_json::to_value(&feed).expect("failed to serialize");
+        assert_eq!(orig, serialized);
+        let replayed: PubTradeFeed =
+            serde_json::from_value(serialized).expect("failed to deserialize");
+        assert_eq!(feed, replayed);
+    }
+
+    #[test]
+    fn test_market_status_feed_json_deserialize() {
+        let feed: PubMarketStatueFeed = serde_json::from_value(json!({
+          "c": "market_status",
+          "e": "update",
+          "ms": [{
+              "M": "btctwd",
+              "st": "active",
+              "bu": "btc",
+              "bup": 8,
+              "mba": 0.0004,
+              "qu": "twd",
+              "qup": 1,
+              "mqa": 250,
+              "mws": true
+            }, {
+              "M": "ethtwd",
+              "st": "cancel_only",
+              "bu": "eth",
+              "bup": 6,
+              "mba": 0.0043,
+              "qu": "twd",
+              "qup": 1,
+              "mqa": 250,
+              "mws": false
+            }],
+          "T": 1659428472313u64
+        }))
+        .expect("invalid test case");
+        let statuses: Vec<_> = feed.markets.iter().map(|m| m.status_enum()).collect();
+        assert_eq!(
+            statuses,
+            vec![MarketStatus::Active, MarketStatus::CancelOnly]
+        );
+        assert!(statuses[0].accepts_new_orders());
+        assert!(!statuses[1].accepts_new_orders());
+
+        assert_eq!(MarketStatus::from("suspended"), MarketStatus::Suspended);
+        assert!(MarketStatus::from("halted").is_unknown());
+        let parsed: MarketStatus = serde_json::from_value(json!("halted")).unwrap();
+        assert!(parsed.is_unknown());
+        assert_eq!(
+            serde_json::to_value(MarketStatus::CancelOnly).unwrap(),
+            json!("cancel_only")
+        );
+    }
+
+    #[test]
+    fn test_feed_type_json_serialize() {
+        let orderbook: PubOrderBookFeed = serde_json::from_value(json!({
+            "e": "snapshot",
+            "M": "btcusdt",
+            "a": [["5337.3", "0.1"]],
+            "b": [["5333.3", "0.5"]],
+            "T": 1637998469525u64
+        }))
+        .expect("invalid test case");
+        let serialized = serde_json::to_value(&orderbook).expect("failed to serialize");
+        assert_eq!(serialized["e"], "snapshot");
+        assert_eq!(serialized["a"], json!([["5337.3", "0.1"]]));
+
+        let balance: PrivBalanceFeed = serde_json::from_value(json!({
+            "e": "account_update",
+            "B": [{"cu": "btc", "av": "123.4", "l": "0.5"}],
+            "T": 123456789
+        }))
+        .expect("invalid test case");
+        let serialized = serde_json::to_value(&balance).expect("failed to serialize");
+        assert_eq!(serialized["e"], "account_update");
+    }
+
+    #[test]
+    fn test_priv_order_state() {
+        fn state_of(state: &str) -> OrderState {
+            let rec: PrivOrderBookRec = serde_json::from_value(json!({
+                "i": 87,
+                "sd": "bid",
+                "ot": "limit",
+                "p": "21499.0",
+                "sp": null,
+                "ap": "21499.0",
+                "S": state,
+                "M": "ethtwd",
+                "T": 1521726960357u64,
+                "v": "0.2658",
+                "rv": "0.2658",
+                "ev": "0.0",
+                "tc": 0,
+                "ci": null,
+                "gi": null
+            }))
+            .expect("invalid test case");
+            rec.state
+        }
+
+        assert_eq!(state_of("finalizing"), OrderState::Finalizing);
+        assert_eq!(state_of("failed"), OrderState::Failed);
+        assert_eq!(state_of("wait"), OrderState::Wait);
+        assert_eq!(state_of("Done"), OrderState::Done);
+        assert_eq!(state_of("no_such_state"), OrderState::Unknown);
+    }
+
+    #[test]
+    fn test_priv_order_lifecycle() {
+        fn order(state: &str, remaining: Option<&str>, executed: Option<&str>) -> PrivOrderBookRec {
+            serde_json::from_value(json!({
+                "i": 87,
+                "sd": "bid",
+                "ot": "limit",
+                "p": "21499.0",
+                "sp": null,
+                "ap": "21499.0",
+                "S": state,
+                "M": "ethtwd",
+                "T": 1521726960357u64,
+                "v": "0.2658",
+                "rv": remaining,
+                "ev": executed,
+                "tc": 1,
+                "ci": null,
+                "gi": null
+            }))
+            .expect("invalid test case")
+        }
+
+        let cases = vec![
+            ("wait", Some("0.2658"), Some("0.0"), OrderLifecycle::New),
+            ("wait", None, None, OrderLifecycle::New),
+            (
+                "wait",
+                Some("0.1"),
+                Some("0.1658"),
+                OrderLifecycle::PartiallyFilled,
+            ),
+            ("wait", Some("0.1"), None, OrderLifecycle::PartiallyFilled),
+            ("wait", Some("0.0"), Some("0.2658"), OrderLifecycle::Filled),
+            ("done", Some("0.0"), Some("0.2658"), OrderLifecycle::Filled),
+            ("DONE", None, None, OrderLifecycle::Filled),
+            (
+                "cancel",
+                Some("0.2658"),
+                Some("0.0"),
+                OrderLifecycle::Cancelled,
+            ),
+            (
+                "cancel",
+                Some("0.1"),
+                Some("0.1658"),
+                OrderLifecycle::Cancelled,
+            ),
+        ];
+        for (state, remaining, executed, expected) in cases {
+            assert_eq!(
+                order(state, remaining, executed).lifecycle(),
+                expected,
+                "state {} remaining {:?} executed {:?}",
+                state,
+                remaining,
+                executed
+            );
+        }
+    }
+
+    fn trade_feed(is_snapshot: bool, market: &str) -> PubTradeFeed {
+        serde_json::from_value(json!({
+            "e": if is_snapshot { "snapshot" } else { "update" },
+            "M": market,
+            "t": [],
+            "T": 1637998469525u64
+        }))
+        .expect("invalid test case")
+    }
+
+    #[test]
+    fn test_feed_sequencer_public() {
+        let mut sequencer = FeedSequencer::<PubTradeFeed>::new();
+        let sequence: Vec<_> = [
+            trade_feed(false, "btctwd"),
+            trade_feed(true, "btctwd"),
+            trade_feed(false, "btctwd"),
+            trade_feed(false, "ethtwd"),
+            trade_feed(true, "ethtwd"),
+            trade_feed(false, "ethtwd"),
+        ]
+        .iter()
+        .map(|feed| sequencer.observe(feed))
+        .collect();
+        assert_eq!(
+            sequence,
+            vec![
+                FeedSequence::UpdateBeforeSnapshot,
+                FeedSequence::Snapshot,
+                FeedSequence::Update,
+                FeedSequence::UpdateBeforeSnapshot,
+                FeedSequence::Snapshot,
+                FeedSequence::Update,
+            ]
+        );
+        assert!(sequence[0].is_out_of_order());
+        assert!(!sequence[2].is_out_of_order());
+
+        let btctwd = Symbol::from("btctwd");
+        assert!(sequencer.has_snapshot(Some(&btctwd)));
+        sequencer.forget(Some(&btctwd));
+        assert!(!sequencer.has_snapshot(Some(&btctwd)));
+        assert!(sequencer
+            .observe(&trade_feed(false, "btctwd"))
+            .is_out_of_order());
+        assert!(!sequencer
+            .observe(&trade_feed(false, "ethtwd"))
+            .is_out_of_order());
+
+        sequencer.reset();
+        assert!(sequencer
+            .observe(&trade_feed(false, "ethtwd"))
+            .is_out_of_order());
+    }
+
+    #[test]
+    fn test_feed_sequencer_private() {
+        let feed = |event: &str| -> PrivTradeFeed {
+            serde_json::from_value(json!({"e": event, "t": [], "T": 1521726960357u64}))
+                .expect("invalid test case")
+        };
+        let mut sequencer = FeedSequencer::<PrivTradeFeed>::new();
+        assert_eq!(
+            sequencer.observe(&feed("trade_update")),
+            FeedSequence::UpdateBeforeSnapshot
+        );
+        assert_eq!(
+            sequencer.observe(&feed("trade_snapshot")),
+            FeedSequence::Snapshot
+        );
+        assert_eq!(
+            sequencer.observe(&feed("trade_update")),
+            FeedSequence::Update
+        );
+        assert!(sequencer.has_snapshot(None));
+    }
+
+    #[cfg(feature = "tracing")]
+    #[test]
+    fn test_feed_sequencer_tracing() {
+        use crate::util::test_util::capture_tracing_events;
+
+        let (_, events) = capture_tracing_events(|| {
+            let mut sequencer = FeedSequencer::<PubTradeFeed>::new();
+            sequencer.observe(&trade_feed(false, "btctwd"));
+            sequencer.observe(&trade_feed(true, "btctwd"));
+            sequencer.observe(&trade_feed(false, "btctwd"));
+        });
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0]["market"], "btctwd");
+        assert_eq!(events[0]["message"], "feed update before snapshot");
+    }
 }
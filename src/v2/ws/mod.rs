@@ -11,15 +11,17 @@
 //! # use async_tungstenite::async_std::connect_async;
 //! # use async_tungstenite::tungstenite::Message;
 //! # use futures::{sink::SinkExt, stream::StreamExt};
-//! use maicoin_max::v2::ws::{ServerPushEvent, SubRequest, BASE_URL};
+//! use maicoin_max::v2::ws::{ServerPushEvent, SubRequest, WsHost};
 //!
 //! # fn main() -> Result<()> {
 //! #     task::block_on(async {
-//! let mut stream = connect_async(BASE_URL).await?.0.fuse();
+//! // connect to `MAX_WS_URL` if set, otherwise the official server
+//! let host = WsHost::from_env("MAX_WS_URL")?;
+//! let mut stream = connect_async(host.as_str()).await?.0.fuse();
 //!
 //! // send subscription request
 //! let mut sub = SubRequest::new_sub(String::new());
-//! sub.subset().insert_ticker("usdttwd".into());
+//! sub.subset().insert_ticker("usdttwd");
 //! let req = serde_json::to_string(&sub)?;
 //! stream.send(Message::text(req)).await?;
 //!
@@ -50,11 +52,16 @@
 pub mod feed;
 
 use std::collections::HashMap;
+use std::env::var as env_var;
+use std::ffi::OsStr;
 use std::fmt;
+use std::iter::FromIterator;
 use std::result::Result as StdResult;
+use std::sync::atomic::{AtomicU64, Ordering};
 
 use chrono::serde as chrono_serde;
 use hmac::{Hmac, Mac, NewMac};
+use http_types::Url as WsURL;
 use serde::{
     de,
     de::{SeqAccess, Visitor},
@@ -67,6 +74,7 @@ use sha2::Sha256;
 
 use crate::common::*;
 use crate::error::*;
+use crate::v2::rest::MarketInfo;
 use crate::Credentials;
 
 // ================
@@ -76,11 +84,86 @@ use crate::Credentials;
 /// The websocket API base URL.
 pub const BASE_URL: &str = "wss://max-stream.maicoin.com/ws";
 
+// ===========
+// API servers
+// ===========
+
+/// URL of the websocket API server, [`BASE_URL`] by default.
+///
+/// Use it to point the websocket client at another server, e.g. a mock server on `ws://localhost:...`.
+///
+/// ```
+/// use maicoin_max::v2::ws::{WsHost, BASE_URL};
+///
+/// let host = WsHost::parse("ws://127.0.0.1:8080/ws").unwrap();
+/// assert_eq!(host.as_str(), "ws://127.0.0.1:8080/ws");
+/// assert_eq!(WsHost::default().as_str(), BASE_URL);
+/// assert!(WsHost::parse("https://127.0.0.1:8080").is_err());
+/// ```
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct WsHost(WsURL);
+
+impl WsHost {
+    /// Parse server URL, which should be of `ws` or `wss` scheme.
+    pub fn parse(url: &str) -> Result<Self> {
+        let url =
+            WsURL::parse(url).map_err(|err| Error::InvalidUrl(format!("{}: {}", url, err)))?;
+        match url.scheme() {
+            "ws" | "wss" => Ok(Self(url)),
+            scheme => Err(Error::InvalidUrl(format!(
+                "{}: unsupported websocket scheme {}",
+                url, scheme
+            ))),
+        }
+    }
+
+    /// Read server URL from environment variable, fallback to [`BASE_URL`] if the variable is not set.
+    pub fn from_env(url_var: impl AsRef<OsStr>) -> Result<Self> {
+        match env_var(url_var) {
+            Ok(url) => Self::parse(&url),
+            Err(_) => Ok(Self::default()),
+        }
+    }
+
+    /// Server URL to connect with.
+    pub fn url(&self) -> &WsURL {
+        &self.0
+    }
+
+    /// Server URL to connect with, as string slice.
+    pub fn as_str(&self) -> &str {
+        self.0.as_str()
+    }
+}
+
+impl Default for WsHost {
+    fn default() -> Self {
+        Self::parse(BASE_URL).expect("unexpected invalid websocket URL")
+    }
+}
+
+impl fmt::Display for WsHost {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
 // ====================
 // Client side requests
 // ====================
 
 /// Channel subscription/unsubscription requests
+///
+/// The `id` is echoed by server in [`SubResponse::id`] or [`ServerPushError::id`], which is the key to correlate the
+/// responses with requests.
+///
+/// ```
+/// use maicoin_max::v2::ws::SubRequest;
+///
+/// let (mut sub, id) = SubRequest::new_sub(String::new()).with_generated_id();
+/// sub.subset().insert_ticker("btctwd");
+/// assert_eq!(sub.id(), id);
+/// ```
 #[derive(Serialize, Deserialize, Debug, Eq, PartialEq)]
 #[serde(tag = "action")]
 pub enum SubRequest {
@@ -112,6 +195,62 @@ impl SubRequest {
         }
     }
 
+    /// Replace the client ID with a generated one, which is unique within the process. Returns the request with the
+    /// generated ID, so that the caller could keep it to correlate the response.
+    pub fn with_generated_id(mut self) -> (Self, String) {
+        let id = generate_request_id();
+        match self {
+            Self::Subscribe {
+                id: ref mut req_id, ..
+            } => *req_id = id.clone(),
+            Self::Unsubscribe {
+                id: ref mut req_id, ..
+            } => *req_id = id.clone(),
+        }
+        (self, id)
+    }
+
+    /// Split into requests of at most `max_channels` channels each, in case the server refuses a large request. A
+    /// request within the limit is returned as is, otherwise the chunks are identified by the original ID suffixed
+    /// with `-{index}`.
+    pub fn split(self, max_channels: usize) -> Vec<Self> {
+        let max_channels = max_channels.max(1);
+        let (subscribe, subscriptions, id) = match self {
+            Self::Subscribe { subscriptions, id } => (true, subscriptions, id),
+            Self::Unsubscribe { subscriptions, id } => (false, subscriptions, id),
+        };
+        if subscriptions.len() <= max_channels {
+            let request = if subscribe {
+                Self::Subscribe { subscriptions, id }
+            } else {
+                Self::Unsubscribe { subscriptions, id }
+            };
+            return vec![request];
+        }
+        let mut entries: Vec<_> = subscriptions.0.into_iter().collect();
+        let mut chunks = Vec::new();
+        while !entries.is_empty() {
+            let rest = entries.split_off(entries.len().min(max_channels));
+            let subscriptions = SubscribeChannelSet(entries.into_iter().collect());
+            let id = format!("{}-{}", id, chunks.len());
+            chunks.push(if subscribe {
+                Self::Subscribe { subscriptions, id }
+            } else {
+                Self::Unsubscribe { subscriptions, id }
+            });
+            entries = rest;
+        }
+        chunks
+    }
+
+    /// Client ID of the request.
+    pub fn id(&self) -> &str {
+        match self {
+            Self::Subscribe { id, .. } => id,
+            Self::Unsubscribe { id, .. } => id,
+        }
+    }
+
     pub fn subset(&mut self) -> &mut SubscribeChannelSet {
         match self {
             Self::Subscribe {
@@ -126,29 +265,35 @@ impl SubRequest {
     }
 }
 
+// Generate request ID from the current time and a process-wide sequence number.
+fn generate_request_id() -> String {
+    static SEQ: AtomicU64 = AtomicU64::new(0);
+    format!("{}-{}", crate::clock(), SEQ.fetch_add(1, Ordering::Relaxed))
+}
+
 /// Set of channels to subscribe/unsubscribe.
 #[derive(Debug, Default, Eq, PartialEq)]
-pub struct SubscribeChannelSet(HashMap<(PubChannelType, String), PubChannelDetails>);
+pub struct SubscribeChannelSet(HashMap<ChannelKey, PubChannelDetails>);
 
 /// Subscription types of public channels.
-#[derive(Eq, PartialEq, Hash, Debug)]
-enum PubChannelType {
+#[derive(Clone, Copy, Eq, PartialEq, Hash, Debug)]
+pub enum PublicChannel {
     Orderbook, // "orderbook"
     Trade,     // "trade"
     Ticker,    // "ticker"
 }
 
-impl ToString for PubChannelType {
-    fn to_string(&self) -> String {
-        match self {
-            Self::Orderbook => "book".into(),
-            Self::Trade => "trade".into(),
-            Self::Ticker => "ticker".into(),
-        }
+impl fmt::Display for PublicChannel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Orderbook => "book",
+            Self::Trade => "trade",
+            Self::Ticker => "ticker",
+        })
     }
 }
 
-impl std::str::FromStr for PubChannelType {
+impl std::str::FromStr for PublicChannel {
     type Err = Error;
 
     fn from_str(s: &str) -> Result<Self> {
@@ -162,8 +307,24 @@ impl std::str::FromStr for PubChannelType {
     }
 }
 
+/// Identity of a public channel subscription, regardless of orderbook depth.
+#[derive(Clone, Eq, PartialEq, Hash, Debug)]
+pub struct ChannelKey {
+    pub kind: PublicChannel,
+    pub market: Symbol,
+}
+
+impl ChannelKey {
+    pub fn new(kind: PublicChannel, market: impl Into<Symbol>) -> Self {
+        Self {
+            kind,
+            market: market.into(),
+        }
+    }
+}
+
 /// Channel subscription details.
-#[derive(Serialize, Deserialize, Default, Debug, Eq, PartialEq)]
+#[derive(Serialize, Deserialize, Clone, Default, Debug, Eq, PartialEq)]
 pub struct PubChannelDetails {
     pub channel: String,
     pub market: Symbol,
@@ -172,18 +333,29 @@ pub struct PubChannelDetails {
     pub depth: Option<u32>,
 }
 
+impl PubChannelDetails {
+    /// Identity of the subscribed channel, e.g. to deduplicate subscriptions. Fails if the channel name is unknown.
+    pub fn key(&self) -> Result<ChannelKey> {
+        Ok(ChannelKey::new(
+            self.channel.to_lowercase().parse()?,
+            self.market.clone(),
+        ))
+    }
+}
+
 impl SubscribeChannelSet {
     pub fn new() -> Self {
         Default::default()
     }
 
     /// Insert an orderbook subscription.
-    pub fn insert_orderbook(&mut self, market: Symbol, depth: Option<u32>) -> bool {
+    pub fn insert_orderbook(&mut self, market: impl Into<Symbol>, depth: Option<u32>) -> bool {
+        let market = market.into();
         self.0
             .insert(
-                (PubChannelType::Orderbook, market.clone()),
+                ChannelKey::new(PublicChannel::Orderbook, market.clone()),
                 PubChannelDetails {
-                    channel: PubChannelType::Orderbook.to_string(),
+                    channel: PublicChannel::Orderbook.to_string(),
                     market,
                     depth,
                 },
@@ -192,12 +364,13 @@ impl SubscribeChannelSet {
     }
 
     /// Insert a trade subscription.
-    pub fn insert_trade(&mut self, market: Symbol) -> bool {
+    pub fn insert_trade(&mut self, market: impl Into<Symbol>) -> bool {
+        let market = market.into();
         self.0
             .insert(
-                (PubChannelType::Trade, market.clone()),
+                ChannelKey::new(PublicChannel::Trade, market.clone()),
                 PubChannelDetails {
-                    channel: PubChannelType::Trade.to_string(),
+                    channel: PublicChannel::Trade.to_string(),
                     market,
                     ..Default::default()
                 },
@@ -206,12 +379,13 @@ impl SubscribeChannelSet {
     }
 
     /// Insert a ticker subscription.
-    pub fn insert_ticker(&mut self, market: Symbol) -> bool {
+    pub fn insert_ticker(&mut self, market: impl Into<Symbol>) -> bool {
+        let market = market.into();
         self.0
             .insert(
-                (PubChannelType::Ticker, market.clone()),
+                ChannelKey::new(PublicChannel::Ticker, market.clone()),
                 PubChannelDetails {
-                    channel: PubChannelType::Ticker.to_string(),
+                    channel: PublicChannel::Ticker.to_string(),
                     market,
                     ..Default::default()
                 },
@@ -219,31 +393,59 @@ impl SubscribeChannelSet {
             .is_none()
     }
 
+    /// Insert a ticker subscription for each of the markets, e.g. all markets from
+    /// [`GetMarkets`](crate::v2::rest::GetMarkets). Returns the number of newly inserted channels.
+    ///
+    /// The server may refuse a request of too many channels, chunk it by [`SubRequest::split`] before sending.
+    ///
+    /// ```
+    /// use maicoin_max::v2::rest::MarketInfo;
+    /// use maicoin_max::v2::ws::SubRequest;
+    ///
+    /// let markets: Vec<MarketInfo> = ["btctwd", "ethtwd", "usdttwd"]
+    ///     .iter()
+    ///     .map(|id| MarketInfo { id: (*id).into(), ..Default::default() })
+    ///     .collect();
+    /// let mut sub = SubRequest::new_sub("tickers".into());
+    /// assert_eq!(sub.subset().insert_all_tickers(&markets), 3);
+    /// assert_eq!(sub.split(2).len(), 2);
+    /// ```
+    pub fn insert_all_tickers(&mut self, markets: &[MarketInfo]) -> usize {
+        markets
+            .iter()
+            .filter(|market| self.insert_ticker(market.id.clone()))
+            .count()
+    }
+
     fn insert_entry(&mut self, entry: PubChannelDetails) -> Result<bool> {
         let mut entry = entry;
         entry.channel = entry.channel.to_lowercase();
-        let book_type: PubChannelType = entry.channel.parse()?;
-        Ok(self
-            .0
-            .insert((book_type, entry.market.clone()), entry)
-            .is_none())
+        let key = entry.key()?;
+        Ok(self.0.insert(key, entry).is_none())
     }
 
     /// Insert an orderbook subscription.
-    pub fn remove_orderbook(&mut self, market: Symbol) -> bool {
+    pub fn remove_orderbook(&mut self, market: impl Into<Symbol>) -> bool {
+        let market = market.into();
         self.0
-            .remove(&(PubChannelType::Orderbook, market))
+            .remove(&ChannelKey::new(PublicChannel::Orderbook, market))
             .is_some()
     }
 
     /// Insert a trade subscription.
-    pub fn remove_trade(&mut self, market: Symbol) -> bool {
-        self.0.remove(&(PubChannelType::Trade, market)).is_some()
+    pub fn remove_trade(&mut self, market: impl Into<Symbol>) -> bool {
+        let market = market.into();
+        self.0
+            .remove(&ChannelKey::new(PublicChannel::Trade, market))
+            .is_some()
     }
 
     /// Insert a ticker subscription.
-    pub fn remove_ticker(&mut self, market: Symbol) -> bool {
-        self.0.remove(&(PubChannelType::Ticker, market)).is_some()
+    pub fn remove_ticker(&mut self, market: impl Into<Symbol>) -> bool {
+        let market = market.into();
+        self.0
+            .remove(&ChannelKey::new(PublicChannel::Ticker, market))
+            .is_some()
     }
 
     pub fn len(&self) -> usize {
@@ -258,8 +460,27 @@ impl SubscribeChannelSet {
         self.0.clear();
     }
 
+    /// Move all the channels of `other` into this set. Orderbook channels in both sets take the depth of `other`.
+    pub fn merge(&mut self, other: SubscribeChannelSet) {
+        self.0.extend(other.0);
+    }
+
+    /// Channels in this set but not in `other`, regardless of orderbook depth.
+    ///
+    /// Given the current and the desired subscriptions, `desired.difference(&current)` are the channels to subscribe,
+    /// and `current.difference(&desired)` are the channels to unsubscribe.
+    pub fn difference(&self, other: &SubscribeChannelSet) -> SubscribeChannelSet {
+        SubscribeChannelSet(
+            self.0
+                .iter()
+                .filter(|(key, _)| !other.0.contains_key(key))
+                .map(|(key, entry)| (key.clone(), entry.clone()))
+                .collect(),
+        )
+    }
+
     pub fn iter(&self) -> Box<dyn Iterator<Item = &'_ PubChannelDetails> + '_> {
-        Box::new(self.0.iter().map(|(_k, v)| v))
+        Box::new(self.0.values())
     }
 }
 
@@ -338,14 +559,77 @@ pub enum PrivFeedType {
     TradeUpdate,
 }
 
+/// Filter of private feeds to subscribe, see [`AuthRequest::with_filter`].
+///
+/// The server treats an authentication request without filters as subscribing to all private feeds, while an empty
+/// filter list subscribes to none of them and the connection stays silent. An empty `PrivFeedFilter` thus means all
+/// feeds, so that a filter built from an empty list does not end up with no feed at all.
+#[derive(Clone, Default, Eq, PartialEq, Debug)]
+pub struct PrivFeedFilter(Vec<PrivFeedType>);
+
+impl PrivFeedFilter {
+    /// Filter of all private feeds.
+    pub fn all() -> Self {
+        Default::default()
+    }
+
+    /// Add a feed to subscribe. Duplicated feeds are ignored.
+    pub fn with(mut self, feed: PrivFeedType) -> Self {
+        if !self.0.contains(&feed) {
+            self.0.push(feed);
+        }
+        self
+    }
+
+    /// `true` if all the private feeds are subscribed.
+    pub fn is_all(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Feeds in the filter, empty for all feeds.
+    pub fn feeds(&self) -> &[PrivFeedType] {
+        &self.0
+    }
+
+    // Filters of the authentication request, which is omitted to subscribe to all feeds.
+    fn into_filters(self) -> Option<Vec<PrivFeedType>> {
+        if self.0.is_empty() {
+            None
+        } else {
+            Some(self.0)
+        }
+    }
+}
+
+impl FromIterator<PrivFeedType> for PrivFeedFilter {
+    fn from_iter<I: IntoIterator<Item = PrivFeedType>>(iter: I) -> Self {
+        iter.into_iter().fold(Self::all(), Self::with)
+    }
+}
+
 impl AuthRequest {
     /// Create authentication request from credentials. Note that the authentication request contains time-based nonce
     /// information. Caller is responsible to send the request out as soon as possible.
+    ///
+    /// Filters of `None` subscribe to all private feeds, while `Some(vec![])` subscribes to none. Prefer
+    /// [`AuthRequest::with_filter`], which can not end up with an empty filter list.
+    ///
+    /// Panics if the request can not be signed, see [`AuthRequest::try_new`].
     pub fn new(
         credential: &Credentials,
         id: Option<String>,
         filters: Option<Vec<PrivFeedType>>,
     ) -> Self {
+        Self::try_new(credential, id, filters).expect("failed to sign authentication request")
+    }
+
+    /// Fallible variant of [`AuthRequest::new`], which returns [`Error::InvalidParams`] if the secret key can not be
+    /// used to sign the request.
+    pub fn try_new(
+        credential: &Credentials,
+        id: Option<String>,
+        filters: Option<Vec<PrivFeedType>>,
+    ) -> Result<Self> {
         Self::new_with_nonce(
             credential.access_key.as_str(),
             credential.secret_key.as_str(),
@@ -355,6 +639,18 @@ impl AuthRequest {
         )
     }
 
+    /// Create authentication request subscribing to the private feeds of the filter, or all of them if the filter is
+    /// empty. See [`AuthRequest::new`] for the nonce.
+    ///
+    /// Panics if the request can not be signed, see [`AuthRequest::try_new`].
+    pub fn with_filter(
+        credential: &Credentials,
+        id: Option<String>,
+        filter: PrivFeedFilter,
+    ) -> Self {
+        Self::new(credential, id, filter.into_filters())
+    }
+
     // Helper constructor for testing.
     fn new_with_nonce(
         key: &str,
@@ -362,19 +658,19 @@ impl AuthRequest {
         nonce: u64,
         id: Option<String>,
         filters: Option<Vec<PrivFeedType>>,
-    ) -> Self {
-        let mut mac =
-            Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("Hmac::new(api_sec)");
+    ) -> Result<Self> {
+        let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes())
+            .map_err(|err| Error::InvalidParams(format!("unusable secret key: {}", err)))?;
         mac.update(nonce.to_string().as_bytes());
         let signature = hex::encode(mac.finalize().into_bytes());
-        Self {
+        Ok(Self {
             action: "auth",
             api_key: key.to_owned(),
             nonce,
             signature,
             id,
             filters,
-        }
+        })
     }
 }
 
@@ -429,6 +725,29 @@ impl<'de> Deserialize<'de> for ServerPushEvent {
         D: de::Deserializer<'de>,
     {
         let root: JsonValue = Deserialize::deserialize(deserializer)?;
+
+        // only the event type and channel are recorded, feeds may contain private information
+        #[cfg(feature = "tracing")]
+        let traced = (
+            root["e"].as_str().unwrap_or("N/A").to_owned(),
+            root["c"].as_str().unwrap_or("N/A").to_owned(),
+            root["E"].is_array(),
+        );
+        let result = Self::from_json(root);
+        #[cfg(feature = "tracing")]
+        tracing::debug!(
+            event = %traced.0,
+            channel = %traced.1,
+            is_error = traced.2,
+            parsed = result.is_ok(),
+            "server push event"
+        );
+        result.map_err(de::Error::custom)
+    }
+}
+
+impl ServerPushEvent {
+    fn from_json(root: JsonValue) -> serde_json::Result<Self> {
         if root["E"].is_array() {
             serde_json::from_value(root).map(Self::Error)
         } else {
@@ -457,23 +776,20 @@ impl<'de> Deserialize<'de> for ServerPushEvent {
                     serde_json::from_value(root).map(Self::PrivBalanceFeed)
                 }
 
-                _ => {
-                    return Err(de::Error::unknown_variant(
-                        &format!("{{e: {}, c: {}}}", event_type, channel),
-                        &[
-                            "(subscribed, N/A)",
-                            "(unsubscribed, N/A)",
-                            "(authenticated, N/A)",
-                            "(snapshot/uppdate, book/trade/ticker)",
-                            "(order_*, user)",
-                            "(trade_*, user)",
-                            "(account_*, user)",
-                        ],
-                    ))
-                }
+                _ => Err(de::Error::unknown_variant(
+                    &format!("{{e: {}, c: {}}}", event_type, channel),
+                    &[
+                        "(subscribed, N/A)",
+                        "(unsubscribed, N/A)",
+                        "(authenticated, N/A)",
+                        "(snapshot/uppdate, book/trade/ticker)",
+                        "(order_*, user)",
+                        "(trade_*, user)",
+                        "(account_*, user)",
+                    ],
+                )),
             }
         }
-        .map_err(de::Error::custom)
     }
 }
 
@@ -484,6 +800,7 @@ impl<'de> Deserialize<'de> for ServerPushEvent {
 pub struct ServerPushError {
     #[serde(rename = "E")]
     pub msg: Vec<String>,
+    /// Client ID of the failed request, to correlate with the request.
     #[serde(rename = "i")]
     pub id: String,
     #[serde(rename = "T", with = "chrono_serde::ts_milliseconds")]
@@ -501,7 +818,7 @@ pub struct SubResponse {
     /// Channels that subscribed/unsubscribed.
     #[serde(rename = "s")]
     pub subscriptions: SubscribeChannelSet,
-    /// Client ID.
+    /// Client ID echoed from [`SubRequest`], to correlate with the request.
     #[serde(rename = "i")]
     pub id: String,
     /// Timestamp.
@@ -529,7 +846,7 @@ impl SubResponse {
 /// Authenication result.
 #[derive(Deserialize, Debug, Eq, PartialEq)]
 pub struct AuthResult {
-    /// Client ID.
+    /// Client ID echoed from [`AuthRequest`], to correlate with the request.
     #[serde(rename = "i")]
     pub id: String,
     /// Timestamp.
@@ -542,14 +859,15 @@ mod tests {
     use super::*;
     use chrono::{SubsecRound, Utc};
     use serde_json::json;
+    use std::collections::HashSet;
 
     #[test]
     fn test_reqsub_subscribe_json_serialize_deserialize() {
         let mut orig = SubRequest::new_sub(String::new());
-        orig.subset().insert_orderbook("market_A".into(), Some(1));
-        orig.subset().insert_orderbook("market_B".into(), None);
-        orig.subset().insert_trade("market_C".into());
-        orig.subset().insert_ticker("market_D".into());
+        orig.subset().insert_orderbook("market_A", Some(1));
+        orig.subset().insert_orderbook("market_B", None);
+        orig.subset().insert_trade("market_C");
+        orig.subset().insert_ticker("market_D");
         let mut result = serde_json::to_value(orig).expect("failed to serialize");
         let result_subset: SubscribeChannelSet =
             serde_json::from_value(result["subscriptions"].take()).expect("failed to deserialize");
@@ -570,9 +888,9 @@ mod tests {
     #[test]
     fn test_reqsub_unsubscribe_json_serialize_deserialize() {
         let mut orig = SubRequest::new_unsub(String::new());
-        orig.subset().insert_orderbook("market_A".into(), None);
-        orig.subset().insert_orderbook("market_B".into(), Some(100));
-        orig.subset().insert_trade("market_C".into());
+        orig.subset().insert_orderbook("market_A", None);
+        orig.subset().insert_orderbook("market_B", Some(100));
+        orig.subset().insert_trade("market_C");
         orig.subset().insert_ticker(String::new());
         let mut result = serde_json::to_value(orig).expect("failed to serialize");
         let result_subset: SubscribeChannelSet =
@@ -591,15 +909,71 @@ mod tests {
         assert_eq!(result_subset, expect_subset);
     }
 
+    #[test]
+    fn test_reqsub_generated_id() {
+        let (mut sub, sub_id) = SubRequest::new_sub(String::new()).with_generated_id();
+        sub.subset().insert_ticker("market_A");
+        let (unsub, unsub_id) = SubRequest::new_unsub("orig".into()).with_generated_id();
+        assert!(!sub_id.is_empty());
+        assert_ne!(sub_id, unsub_id);
+        assert_eq!(unsub.id(), unsub_id);
+
+        let result = serde_json::to_value(&sub).expect("failed to serialize");
+        assert_eq!(result["id"], json!(sub_id));
+        let result = serde_json::to_value(&unsub).expect("failed to serialize");
+        assert_eq!(result["id"], json!(unsub_id));
+    }
+
+    #[test]
+    fn test_subchanset_merge() {
+        let mut set = SubscribeChannelSet::new();
+        set.insert_orderbook("market_A", Some(1));
+        set.insert_trade("market_A");
+        let mut other = SubscribeChannelSet::new();
+        other.insert_orderbook("market_A", Some(5));
+        other.insert_ticker("market_B");
+
+        set.merge(other);
+        let mut expected = SubscribeChannelSet::new();
+        expected.insert_orderbook("market_A", Some(5));
+        expected.insert_trade("market_A");
+        expected.insert_ticker("market_B");
+        assert_eq!(set, expected);
+    }
+
+    #[test]
+    fn test_subchanset_difference() {
+        let mut current = SubscribeChannelSet::new();
+        current.insert_orderbook("market_A", Some(1));
+        current.insert_trade("market_A");
+        current.insert_ticker("market_B");
+        let mut desired = SubscribeChannelSet::new();
+        desired.insert_orderbook("market_A", Some(5));
+        desired.insert_ticker("market_B");
+        desired.insert_ticker("market_C");
+
+        let mut to_sub = SubscribeChannelSet::new();
+        to_sub.insert_ticker("market_C");
+        assert_eq!(desired.difference(&current), to_sub);
+
+        let mut to_unsub = SubscribeChannelSet::new();
+        to_unsub.insert_trade("market_A");
+        assert_eq!(current.difference(&desired), to_unsub);
+
+        assert!(current.difference(&current).is_empty());
+        assert_eq!(current.difference(&SubscribeChannelSet::new()), current);
+    }
+
     #[test]
     fn test_subchanset_orderbook_add() {
         let mut set = SubscribeChannelSet::new();
-        set.insert_orderbook("market_A".into(), Some(3));
-        set.insert_orderbook("market_B".into(), Some(0));
-        set.insert_orderbook("market_A".into(), None);
+        set.insert_orderbook("market_A", Some(3));
+        set.insert_orderbook("market_B", Some(0));
+        set.insert_orderbook("market_A", None);
         assert_eq!(set.0.len(), 2);
         assert_eq!(
-            set.0.get(&(PubChannelType::Orderbook, "market_A".into())),
+            set.0
+                .get(&ChannelKey::new(PublicChannel::Orderbook, "market_A")),
             Some(&PubChannelDetails {
                 channel: "book".into(),
                 market: "market_A".into(),
@@ -607,7 +981,8 @@ mod tests {
             })
         );
         assert_eq!(
-            set.0.get(&(PubChannelType::Orderbook, "market_B".into())),
+            set.0
+                .get(&ChannelKey::new(PublicChannel::Orderbook, "market_B")),
             Some(&PubChannelDetails {
                 channel: "book".into(),
                 market: "market_B".into(),
@@ -619,12 +994,13 @@ mod tests {
     #[test]
     fn test_subchanset_trade_add() {
         let mut set = SubscribeChannelSet::new();
-        set.insert_trade("market_A".into());
-        set.insert_trade("market_B".into());
-        set.insert_trade("market_A".into());
+        set.insert_trade("market_A");
+        set.insert_trade("market_B");
+        set.insert_trade("market_A");
         assert_eq!(set.0.len(), 2);
         assert_eq!(
-            set.0.get(&(PubChannelType::Trade, "market_A".into())),
+            set.0
+                .get(&ChannelKey::new(PublicChannel::Trade, "market_A")),
             Some(&PubChannelDetails {
                 channel: "trade".into(),
                 market: "market_A".into(),
@@ -632,7 +1008,8 @@ mod tests {
             })
         );
         assert_eq!(
-            set.0.get(&(PubChannelType::Trade, "market_B".into())),
+            set.0
+                .get(&ChannelKey::new(PublicChannel::Trade, "market_B")),
             Some(&PubChannelDetails {
                 channel: "trade".into(),
                 market: "market_B".into(),
@@ -644,12 +1021,13 @@ mod tests {
     #[test]
     fn test_subchanset_ticker_add() {
         let mut set = SubscribeChannelSet::new();
-        set.insert_ticker("market_A".into());
-        set.insert_ticker("market_B".into());
-        set.insert_ticker("market_A".into());
+        set.insert_ticker("market_A");
+        set.insert_ticker("market_B");
+        set.insert_ticker("market_A");
         assert_eq!(set.0.len(), 2);
         assert_eq!(
-            set.0.get(&(PubChannelType::Ticker, "market_A".into())),
+            set.0
+                .get(&ChannelKey::new(PublicChannel::Ticker, "market_A")),
             Some(&PubChannelDetails {
                 channel: "ticker".into(),
                 market: "market_A".into(),
@@ -657,7 +1035,8 @@ mod tests {
             })
         );
         assert_eq!(
-            set.0.get(&(PubChannelType::Ticker, "market_B".into())),
+            set.0
+                .get(&ChannelKey::new(PublicChannel::Ticker, "market_B")),
             Some(&PubChannelDetails {
                 channel: "ticker".into(),
                 market: "market_B".into(),
@@ -666,18 +1045,55 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_subchanset_all_tickers() {
+        let markets: Vec<MarketInfo> = ["market_A", "market_B", "market_C"]
+            .iter()
+            .map(|id| MarketInfo {
+                id: (*id).into(),
+                ..Default::default()
+            })
+            .collect();
+        let mut set = SubscribeChannelSet::new();
+        set.insert_ticker("market_A");
+        assert_eq!(set.insert_all_tickers(&markets), 2);
+        assert_eq!(set.0.len(), 3);
+        for market in markets.iter() {
+            assert!(set
+                .0
+                .contains_key(&ChannelKey::new(PublicChannel::Ticker, market.id.clone())));
+        }
+
+        let mut sub = SubRequest::new_sub("all".into());
+        sub.subset().merge(set);
+        let chunks = sub.split(2);
+        let ids: Vec<_> = chunks.iter().map(|chunk| chunk.id().to_owned()).collect();
+        assert_eq!(ids, ["all-0", "all-1"]);
+        let mut merged = SubscribeChannelSet::new();
+        for mut chunk in chunks {
+            assert!(matches!(chunk, SubRequest::Subscribe { .. }));
+            assert!(chunk.subset().len() <= 2);
+            merged.merge(std::mem::take(chunk.subset()));
+        }
+        assert_eq!(merged.len(), 3);
+
+        let unsub = SubRequest::new_unsub("small".into()).split(2);
+        assert!(matches!(&unsub[..], [SubRequest::Unsubscribe { id, .. }] if id == "small"));
+    }
+
     #[test]
     fn test_subchanset_channel_remove() {
         let mut set = SubscribeChannelSet::new();
-        set.insert_orderbook("market_A".into(), Some(3));
-        set.insert_orderbook("market_B".into(), Some(5));
-        set.insert_trade("market_B".into());
-        set.insert_ticker("market_A".into());
-        set.remove_orderbook("market_A".into());
-        set.remove_ticker("market_C".into());
+        set.insert_orderbook("market_A", Some(3));
+        set.insert_orderbook("market_B", Some(5));
+        set.insert_trade("market_B");
+        set.insert_ticker("market_A");
+        set.remove_orderbook("market_A");
+        set.remove_ticker("market_C");
         assert_eq!(set.len(), 3);
         assert_eq!(
-            set.0.get(&(PubChannelType::Orderbook, "market_B".into())),
+            set.0
+                .get(&ChannelKey::new(PublicChannel::Orderbook, "market_B")),
             Some(&PubChannelDetails {
                 channel: "book".into(),
                 market: "market_B".into(),
@@ -685,7 +1101,8 @@ mod tests {
             })
         );
         assert_eq!(
-            set.0.get(&(PubChannelType::Trade, "market_B".into())),
+            set.0
+                .get(&ChannelKey::new(PublicChannel::Trade, "market_B")),
             Some(&PubChannelDetails {
                 channel: "trade".into(),
                 market: "market_B".into(),
@@ -693,7 +1110,8 @@ mod tests {
             })
         );
         assert_eq!(
-            set.0.get(&(PubChannelType::Ticker, "market_A".into())),
+            set.0
+                .get(&ChannelKey::new(PublicChannel::Ticker, "market_A")),
             Some(&PubChannelDetails {
                 channel: "ticker".into(),
                 market: "market_A".into(),
@@ -705,10 +1123,10 @@ mod tests {
     #[test]
     fn test_subchanset_json_serialize_deserialize() {
         let mut orig = SubscribeChannelSet::new();
-        orig.insert_orderbook("market_A".into(), Some(3));
-        orig.insert_orderbook("market_B".into(), Some(5));
-        orig.insert_trade("market_B".into());
-        orig.insert_ticker("market_A".into());
+        orig.insert_orderbook("market_A", Some(3));
+        orig.insert_orderbook("market_B", Some(5));
+        orig.insert_trade("market_B");
+        orig.insert_ticker("market_A");
         let json_str = serde_json::to_string(&orig).expect("failed to serialize");
         assert!(!json_str.is_empty());
         let result: SubscribeChannelSet =
@@ -716,11 +1134,39 @@ mod tests {
         assert_eq!(orig, result);
     }
 
+    #[test]
+    fn test_channel_key_dedup() {
+        let mut set = SubscribeChannelSet::new();
+        set.insert_orderbook("market_A", Some(3));
+        set.insert_trade("market_A");
+
+        let mut keys: HashSet<ChannelKey> = set.iter().map(|e| e.key().unwrap()).collect();
+        assert_eq!(keys.len(), 2);
+        assert!(!keys.insert(ChannelKey::new(PublicChannel::Orderbook, "MARKET_A")));
+        assert!(!keys.insert(ChannelKey::new(PublicChannel::Trade, "market_a")));
+        assert!(keys.insert(ChannelKey::new(PublicChannel::Ticker, "market_A")));
+        assert!(keys.insert(ChannelKey::new(PublicChannel::Trade, "market_B")));
+        assert_eq!(keys.len(), 4);
+
+        // orderbook depth is not part of the identity
+        let details = PubChannelDetails {
+            channel: "BOOK".into(),
+            market: "market_A".into(),
+            depth: Some(1),
+        };
+        assert!(keys.contains(&details.key().unwrap()));
+        let unknown = PubChannelDetails {
+            channel: "unknown".into(),
+            ..details
+        };
+        assert!(unknown.key().is_err());
+    }
+
     #[test]
     fn test_auth_request_json_serialize() {
         let orig = AuthRequest::new_with_nonce(
-            &"api key",
-            &"api secret",
+            "api key",
+            "api secret",
             12345,
             Some("client_id".into()),
             Some(vec![
@@ -729,7 +1175,8 @@ mod tests {
                 PrivFeedType::Order,
                 PrivFeedType::TradeUpdate,
             ]),
-        );
+        )
+        .unwrap();
         let expect = json!({
             "action": "auth",
             "apiKey": "api key",
@@ -745,6 +1192,44 @@ mod tests {
         assert_eq!(expect, result);
     }
 
+    #[test]
+    fn test_priv_feed_filter() {
+        let credentials = Credentials::new("api key".into(), "api secret".into());
+
+        // an empty filter subscribes to all feeds, instead of none of them
+        let empty: PrivFeedFilter = Vec::new().into_iter().collect();
+        assert!(empty.is_all());
+        assert_eq!(empty, PrivFeedFilter::all());
+        let req = AuthRequest::with_filter(&credentials, None, empty);
+        assert_eq!(req.filters, None);
+        let json = serde_json::to_value(&req).unwrap();
+        assert!(json.get("filters").is_none());
+
+        let filter = PrivFeedFilter::all()
+            .with(PrivFeedType::Order)
+            .with(PrivFeedType::Trade)
+            .with(PrivFeedType::Order);
+        assert!(!filter.is_all());
+        assert_eq!(filter.feeds(), [PrivFeedType::Order, PrivFeedType::Trade]);
+        let req = AuthRequest::with_filter(&credentials, None, filter);
+        assert_eq!(
+            serde_json::to_value(&req).unwrap()["filters"],
+            json!(["order", "trade"])
+        );
+
+        // the raw constructor still passes an explicitly empty list through
+        let req = AuthRequest::new(&credentials, None, Some(vec![]));
+        assert_eq!(serde_json::to_value(&req).unwrap()["filters"], json!([]));
+    }
+
+    #[test]
+    fn test_auth_request_empty_secret() {
+        // HMAC accepts keys of any length, the server rejects the signature instead
+        let credentials = Credentials::new("api key".into(), String::new());
+        let req = AuthRequest::try_new(&credentials, None, None).unwrap();
+        assert_eq!(req.signature.len(), 64);
+    }
+
     #[test]
     fn test_error_resp_json_deserialize() {
         let test_time = Utc::now().trunc_subsecs(0);
@@ -1015,4 +1500,79 @@ mod tests {
         }
         assert_eq!(0, checked);
     }
+
+    #[cfg(feature = "tracing")]
+    #[test]
+    fn test_server_push_event_tracing() {
+        use crate::util::test_util::capture_tracing_events;
+
+        let (results, events) = capture_tracing_events(|| {
+            vec![
+                serde_json::from_value::<ServerPushEvent>(json!({
+                  "c": "user",
+                  "e": "account_update",
+                  "B": [{"cu": "btc", "av": "123.4", "l": "0.5"}],
+                  "T": 123456789
+                }))
+                .is_ok(),
+                serde_json::from_value::<ServerPushEvent>(json!({
+                  "e": "error",
+                  "E": ["invalid request"],
+                  "i": "client1",
+                  "T": 123456789
+                }))
+                .is_ok(),
+                serde_json::from_value::<ServerPushEvent>(json!({"c": "unknown", "e": "update"}))
+                    .is_ok(),
+            ]
+        });
+        assert_eq!(results, vec![true, true, false]);
+        assert_eq!(events.len(), 3);
+
+        assert_eq!(events[0]["event"], "account_update");
+        assert_eq!(events[0]["channel"], "user");
+        assert_eq!(events[0]["is_error"], "false");
+        assert_eq!(events[0]["parsed"], "true");
+        assert_eq!(events[1]["event"], "error");
+        assert_eq!(events[1]["is_error"], "true");
+        assert_eq!(events[2]["channel"], "unknown");
+        assert_eq!(events[2]["parsed"], "false");
+
+        for event in events.iter() {
+            assert!(event
+                .values()
+                .all(|value| !value.contains("123.4") && !value.contains("invalid request")));
+        }
+    }
+
+    #[async_std::test]
+    async fn test_ws_host_connect_mock_server() {
+        use async_std::net::TcpListener;
+        use async_tungstenite::tungstenite::Message;
+        use futures::{SinkExt, StreamExt};
+
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let server = async_std::task::spawn(async move {
+            let (tcp, _) = listener.accept().await.unwrap();
+            let mut ws = async_tungstenite::accept_async(tcp).await.unwrap();
+            match ws.next().await {
+                Some(Ok(Message::Text(raw))) => serde_json::from_str::<SubRequest>(&raw).unwrap(),
+                other => panic!("unexpected message: {:?}", other),
+            }
+        });
+
+        let host = WsHost::parse(&format!("ws://{}/ws", addr)).unwrap();
+        let mut stream = async_tungstenite::async_std::connect_async(host.as_str())
+            .await
+            .expect("failed to connect mock server")
+            .0;
+        let mut sub = SubRequest::new_sub("mock".into());
+        sub.subset().insert_ticker("maxtwd");
+        stream
+            .send(Message::text(serde_json::to_string(&sub).unwrap()))
+            .await
+            .unwrap();
+        assert_eq!(server.await, sub);
+    }
 }
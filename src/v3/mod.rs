@@ -0,0 +1,5 @@
+//! API v3 implementation.
+//!
+//! - [v3 REST API Documentation](https://max.maicoin.com/api-docs)
+
+pub mod rest;
@@ -0,0 +1,287 @@
+use chrono::serde as chrono_serde;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+
+use crate::common::*;
+use crate::v2::rest::api_impl::*;
+
+// ========
+// Requests
+// ========
+
+/// GET /api/v3/wallet/m/liquidations
+///
+/// Get the liquidation history of M-wallet.
+#[derive(Serialize, Clone, Eq, PartialEq, Default, Debug)]
+pub struct GetLiquidations {
+    /// Target period of the liquidations, see [`TimeRange`].
+    #[serde(flatten)]
+    pub time: TimeRange,
+    /// Returned limit (1~1000, default 50).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub limit: Option<u64>,
+}
+impl_api!(GetLiquidations => Vec<RespLiquidation> : auth GET, "/api/v3/wallet/m/liquidations");
+
+/// GET /api/v3/wallet/m/liquidation
+///
+/// Get a liquidation of M-wallet along with its trades.
+#[derive(Serialize, Clone, Eq, PartialEq, Debug)]
+pub struct GetLiquidation {
+    /// Unique liquidation serial number.
+    pub sn: String,
+}
+impl_api!(GetLiquidation => RespLiquidation : auth GET, "/api/v3/wallet/m/liquidation");
+
+/// GET /api/v3/wallet/m/ad_ratio
+///
+/// Get the current AD ratio (asset to debt ratio) of M-wallet, which is liquidated if the ratio gets too low.
+#[derive(Serialize, Clone, Eq, PartialEq, Default, Debug)]
+pub struct GetMarginRiskRatio {}
+impl_api!(GetMarginRiskRatio => RespAdRatio : auth GET, "/api/v3/wallet/m/ad_ratio");
+
+// =========
+// Responses
+// =========
+
+/// Liquidation of M-wallet. Fields not modeled here are ignored.
+#[derive(Serialize, Deserialize, Clone, Eq, PartialEq, Default, Debug)]
+#[serde(default)]
+pub struct RespLiquidation {
+    /// sn (string): unique liquidation serial number.
+    pub sn: String,
+    /// state (string): `'processing'`, `'completed'` or `'failed'`.
+    #[serde(deserialize_with = "crate::util::serde::from_str_or_default")]
+    pub state: LiquidationState,
+    /// ad_ratio (string): AD ratio triggering the liquidation.
+    pub ad_ratio: Option<Decimal>,
+    /// expected_ad_ratio (string): AD ratio expected after the liquidation.
+    pub expected_ad_ratio: Option<Decimal>,
+    /// debt_in_usdt (string): debt left after the liquidation, in USDT.
+    pub debt_in_usdt: Option<Decimal>,
+    /// created_at (integer): created timestamp (millisecond).
+    #[serde(with = "chrono_serde::ts_milliseconds_option")]
+    pub created_at: Option<DateTime>,
+    /// liquidation_details (array): trades to liquidate the wallet, empty if not given.
+    pub liquidation_details: Vec<RespLiquidationTrade>,
+}
+
+/// Trade executed by a liquidation. Fields not modeled here are ignored.
+#[derive(Serialize, Deserialize, Clone, Eq, PartialEq, Default, Debug)]
+#[serde(default)]
+pub struct RespLiquidationTrade {
+    /// market (string): market id.
+    pub market: Symbol,
+    /// side (string): `'bid'` or `'ask'`.
+    pub side: TradeSide,
+    /// price (string): strike price.
+    pub price: Option<Decimal>,
+    /// volume (string): traded volume.
+    pub volume: Option<Decimal>,
+    /// fee (string): trade fee.
+    pub fee: Option<Decimal>,
+    /// fee_currency (string): currency of the trade fee.
+    pub fee_currency: Option<Currency>,
+    /// created_at (integer): created timestamp (millisecond).
+    #[serde(with = "chrono_serde::ts_milliseconds_option")]
+    pub created_at: Option<DateTime>,
+}
+
+/// AD ratio of M-wallet. Fields not modeled here are ignored.
+#[derive(Serialize, Deserialize, Clone, Eq, PartialEq, Default, Debug)]
+#[serde(default)]
+pub struct RespAdRatio {
+    /// ad_ratio (string): asset to debt ratio, `None` if there is no debt.
+    pub ad_ratio: Option<Decimal>,
+    /// asset_in_usdt (string): total asset value, in USDT.
+    pub asset_in_usdt: Decimal,
+    /// debt_in_usdt (string): total debt value, in USDT.
+    pub debt_in_usdt: Decimal,
+}
+
+impl RespAdRatio {
+    /// `true` if the AD ratio is below the level, e.g. to alert before the wallet is liquidated. Never `true` without
+    /// debt.
+    pub fn is_below(&self, level: Decimal) -> bool {
+        self.ad_ratio.is_some_and(|ratio| ratio < level)
+    }
+}
+
+// ============================
+// Inner structures and options
+// ============================
+
+/// State of liquidations.
+#[derive(Serialize, Copy, Clone, Eq, PartialEq, Hash, Debug, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum LiquidationState {
+    /// Liquidating the wallet.
+    Processing,
+    /// Done.
+    Completed,
+    /// Stopped without liquidating the wallet.
+    Failed,
+    /// States not known by this crate.
+    #[default]
+    Unknown,
+}
+
+impl_wire_str!(LiquidationState {
+    Processing => "processing",
+    Completed => "completed",
+    Failed => "failed",
+    Unknown => "unknown",
+});
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::test_util::*;
+    use chrono::{TimeZone, Utc};
+    use rust_decimal_macros::dec;
+    use surf::Client as HTTPClient;
+    use surf_vcr::VcrMode;
+
+    async fn create_client(cassette: &'static str) -> HTTPClient {
+        let mut path_builder = test_resource_path();
+        path_builder.push("v3");
+        path_builder.push("rest");
+        path_builder.push("liquidation");
+        path_builder.push(cassette);
+        create_test_recording_client(VcrMode::Replay, path_builder.as_path().to_str().unwrap())
+            .await
+    }
+
+    #[test]
+    fn liquidation_state() {
+        assert_wire_str(LiquidationState::VARIANTS);
+        let liquidation: RespLiquidation =
+            serde_json::from_str(r#"{"sn":"1","state":"Reverted"}"#).unwrap();
+        assert_eq!(liquidation.state, LiquidationState::Unknown);
+        assert!(liquidation.liquidation_details.is_empty());
+    }
+
+    #[test]
+    fn ad_ratio_level() {
+        let ratio = RespAdRatio {
+            ad_ratio: Some(dec!(1.3)),
+            asset_in_usdt: dec!(1300),
+            debt_in_usdt: dec!(1000),
+        };
+        assert!(ratio.is_below(dec!(1.5)));
+        assert!(!ratio.is_below(dec!(1.3)));
+        let ratio: RespAdRatio =
+            serde_json::from_str(r#"{"ad_ratio":null,"asset_in_usdt":"100","debt_in_usdt":"0"}"#)
+                .unwrap();
+        assert!(!ratio.is_below(dec!(1.5)));
+    }
+
+    #[async_std::test]
+    async fn get_liquidations_empty() {
+        let params = GetLiquidations::default();
+        let resp = create_client("get_liquidations_empty.yaml")
+            .await
+            .send(params.to_request(&TEST_CREDENTIALS))
+            .await
+            .expect("Error while sending request");
+        let liquidations = GetLiquidations::read_response(resp.into())
+            .await
+            .expect("failed to parse result");
+        assert!(liquidations.is_empty());
+    }
+
+    #[async_std::test]
+    async fn get_liquidations() {
+        let params = GetLiquidations {
+            time: TimeRange::since(Utc.timestamp_opt(1676300000, 0).unwrap()),
+            limit: Some(10),
+        };
+        let resp = create_client("get_liquidations.yaml")
+            .await
+            .send(params.to_request(&TEST_CREDENTIALS))
+            .await
+            .expect("Error while sending request");
+        let liquidations = GetLiquidations::read_response(resp.into())
+            .await
+            .expect("failed to parse result");
+        assert_eq!(
+            liquidations,
+            vec![
+                RespLiquidation {
+                    sn: "2302150003".into(),
+                    state: LiquidationState::Processing,
+                    ad_ratio: Some(dec!(1.04)),
+                    expected_ad_ratio: Some(dec!(1.5)),
+                    debt_in_usdt: None,
+                    created_at: Some(Utc.timestamp_millis_opt(1676430000000).unwrap()),
+                    liquidation_details: vec![],
+                },
+                RespLiquidation {
+                    sn: "2302150001".into(),
+                    state: LiquidationState::Completed,
+                    ad_ratio: Some(dec!(1.05)),
+                    expected_ad_ratio: Some(dec!(1.5)),
+                    debt_in_usdt: Some(dec!(812.34)),
+                    created_at: Some(Utc.timestamp_millis_opt(1676400000123).unwrap()),
+                    liquidation_details: vec![RespLiquidationTrade {
+                        market: "btcusdt".into(),
+                        side: TradeSide::Ask,
+                        price: Some(dec!(21500.0)),
+                        volume: Some(dec!(0.01)),
+                        fee: Some(dec!(0.3225)),
+                        fee_currency: Some(Currency::USDT),
+                        created_at: Some(Utc.timestamp_millis_opt(1676400001456).unwrap()),
+                    }],
+                },
+            ]
+        );
+    }
+
+    #[async_std::test]
+    async fn get_liquidation() {
+        let params = GetLiquidation {
+            sn: "2302150001".into(),
+        };
+        let resp = create_client("get_liquidation.yaml")
+            .await
+            .send(params.to_request(&TEST_CREDENTIALS))
+            .await
+            .expect("Error while sending request");
+        let liquidation = GetLiquidation::read_response(resp.into())
+            .await
+            .expect("failed to parse result");
+        assert_eq!(liquidation.state, LiquidationState::Completed);
+        let sides: Vec<_> = liquidation
+            .liquidation_details
+            .iter()
+            .map(|trade| (trade.market.as_str(), trade.side))
+            .collect();
+        assert_eq!(
+            sides,
+            [("btcusdt", TradeSide::Ask), ("ethusdt", TradeSide::Ask)]
+        );
+        // fields missing in the reply are left empty
+        assert_eq!(liquidation.liquidation_details[1].fee_currency, None);
+    }
+
+    #[async_std::test]
+    async fn get_margin_risk_ratio() {
+        let resp = create_client("get_margin_risk_ratio.yaml")
+            .await
+            .send(GetMarginRiskRatio {}.to_request(&TEST_CREDENTIALS))
+            .await
+            .expect("Error while sending request");
+        let ratio = GetMarginRiskRatio::read_response(resp.into())
+            .await
+            .expect("failed to parse result");
+        assert_eq!(
+            ratio,
+            RespAdRatio {
+                ad_ratio: Some(dec!(1.42)),
+                asset_in_usdt: dec!(1420.5),
+                debt_in_usdt: dec!(1000.35),
+            }
+        );
+        assert!(ratio.is_below(dec!(1.5)));
+    }
+}
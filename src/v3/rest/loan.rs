@@ -0,0 +1,301 @@
+use chrono::serde as chrono_serde;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+
+use crate::common::*;
+use crate::error::{Error, Result};
+use crate::v2::rest::api_impl::*;
+use crate::v2::rest::CurrencyInfo;
+
+// ========
+// Requests
+// ========
+
+/// POST /api/v3/wallet/m/loan
+///
+/// Borrow the currency into M-wallet.
+///
+/// Constructing by the public fields skips validation, prefer [`CreateLoan::new`].
+#[derive(Serialize, Clone, Eq, PartialEq, Debug)]
+pub struct CreateLoan {
+    /// Unique currency id.
+    pub currency: Currency,
+    /// Amount to borrow.
+    pub amount: Decimal,
+}
+impl_api!(CreateLoan => RespLoan : auth POST, "/api/v3/wallet/m/loan");
+
+impl CreateLoan {
+    /// Borrow the amount of the currency, or [`Error::InvalidParams`] if the amount is not positive or finer than the
+    /// precision of the currency.
+    pub fn new(currency: &CurrencyInfo, amount: Decimal) -> Result<Self> {
+        Ok(Self {
+            currency: currency.id.as_str().into(),
+            amount: check_amount(currency, amount)?,
+        })
+    }
+}
+
+/// POST /api/v3/wallet/m/repayment
+///
+/// Repay the loan of the currency in M-wallet.
+///
+/// Constructing by the public fields skips validation, prefer [`CreateRepayment::new`].
+#[derive(Serialize, Clone, Eq, PartialEq, Debug)]
+pub struct CreateRepayment {
+    /// Unique currency id.
+    pub currency: Currency,
+    /// Amount to repay.
+    pub amount: Decimal,
+}
+impl_api!(CreateRepayment => RespRepayment : auth POST, "/api/v3/wallet/m/repayment");
+
+impl CreateRepayment {
+    /// Repay the amount of the currency, or [`Error::InvalidParams`] if the amount is not positive or finer than the
+    /// precision of the currency.
+    pub fn new(currency: &CurrencyInfo, amount: Decimal) -> Result<Self> {
+        Ok(Self {
+            currency: currency.id.as_str().into(),
+            amount: check_amount(currency, amount)?,
+        })
+    }
+}
+
+fn check_amount(currency: &CurrencyInfo, amount: Decimal) -> Result<Decimal> {
+    if amount <= Decimal::ZERO {
+        return Err(Error::InvalidParams(format!(
+            "amount must be positive: {}",
+            amount
+        )));
+    }
+    if amount.normalize().scale() > currency.precision as u32 {
+        return Err(Error::InvalidParams(format!(
+            "amount {} exceeds the precision of {} ({} decimal places)",
+            amount, currency.id, currency.precision
+        )));
+    }
+    Ok(amount)
+}
+
+/// GET /api/v3/wallet/m/loans
+///
+/// Get the loans of the currency in M-wallet.
+#[derive(Serialize, Clone, Eq, PartialEq, Debug)]
+pub struct GetLoans {
+    /// Unique currency id.
+    pub currency: Currency,
+    /// Set to return the loans of the state only.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub state: Option<LoanState>,
+    /// Target period of the loans, see [`TimeRange`].
+    #[serde(flatten)]
+    pub time: TimeRange,
+    /// Returned limit (1~1000, default 50).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub limit: Option<u64>,
+}
+impl_api!(GetLoans => Vec<RespLoan> : auth GET, "/api/v3/wallet/m/loans");
+
+impl GetLoans {
+    /// All the loans of the currency.
+    pub fn of(currency: impl Into<Currency>) -> Self {
+        Self {
+            currency: currency.into(),
+            state: None,
+            time: Default::default(),
+            limit: None,
+        }
+    }
+}
+
+// =========
+// Responses
+// =========
+
+/// Loan record of M-wallet. Fields not modeled here are ignored.
+#[derive(Serialize, Deserialize, Clone, Eq, PartialEq, Default, Debug)]
+#[serde(default)]
+pub struct RespLoan {
+    /// id (string): unique loan id.
+    pub id: String,
+    /// currency (string): currency id.
+    pub currency: Currency,
+    /// amount (string): borrowed amount.
+    pub amount: Decimal,
+    /// interest_rate (string): interest rate of the loan.
+    pub interest_rate: Option<Decimal>,
+    /// state (string): `'pending'`, `'confirmed'` or `'failed'`.
+    #[serde(deserialize_with = "crate::util::serde::from_str_or_default")]
+    pub state: LoanState,
+    /// created_at (integer): created timestamp (millisecond).
+    #[serde(with = "chrono_serde::ts_milliseconds_option")]
+    pub created_at: Option<DateTime>,
+}
+
+/// Repayment record of M-wallet. Fields not modeled here are ignored.
+#[derive(Serialize, Deserialize, Clone, Eq, PartialEq, Default, Debug)]
+#[serde(default)]
+pub struct RespRepayment {
+    /// id (string): unique repayment id.
+    pub id: String,
+    /// currency (string): currency id.
+    pub currency: Currency,
+    /// principal (string): repaid principal.
+    pub principal: Decimal,
+    /// interest (string): repaid interest.
+    pub interest: Decimal,
+    /// state (string): `'pending'`, `'confirmed'` or `'failed'`.
+    #[serde(deserialize_with = "crate::util::serde::from_str_or_default")]
+    pub state: LoanState,
+    /// created_at (integer): created timestamp (millisecond).
+    #[serde(with = "chrono_serde::ts_milliseconds_option")]
+    pub created_at: Option<DateTime>,
+}
+
+/// State of loans and repayments.
+#[derive(Serialize, Copy, Clone, Eq, PartialEq, Hash, Debug, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum LoanState {
+    /// Waiting to be processed.
+    Pending,
+    /// Done.
+    Confirmed,
+    /// Rejected by the server.
+    Failed,
+    /// States not known by this crate.
+    #[default]
+    Unknown,
+}
+
+impl_wire_str!(LoanState {
+    Pending => "pending",
+    Confirmed => "confirmed",
+    Failed => "failed",
+    Unknown => "unknown",
+});
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::test_util::*;
+    use chrono::{TimeZone, Utc};
+    use rust_decimal_macros::dec;
+    use surf::Client as HTTPClient;
+    use surf_vcr::VcrMode;
+
+    async fn create_client(cassette: &'static str) -> HTTPClient {
+        let mut path_builder = test_resource_path();
+        path_builder.push("v3");
+        path_builder.push("rest");
+        path_builder.push("loan");
+        path_builder.push(cassette);
+        create_test_recording_client(VcrMode::Replay, path_builder.as_path().to_str().unwrap())
+            .await
+    }
+
+    fn usdt() -> CurrencyInfo {
+        CurrencyInfo {
+            id: "usdt".into(),
+            precision: 2,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn loan_amount_validation() {
+        let loan = CreateLoan::new(&usdt(), dec!(100.50)).unwrap();
+        assert_eq!(loan.currency, Currency::USDT);
+        assert_eq!(loan.amount, dec!(100.50));
+        // trailing zeros are not counted as precision
+        assert!(CreateRepayment::new(&usdt(), dec!(1.2000)).is_ok());
+
+        for amount in [dec!(0), dec!(-1), dec!(0.001)] {
+            assert!(matches!(
+                CreateLoan::new(&usdt(), amount),
+                Err(Error::InvalidParams(_))
+            ));
+            assert!(matches!(
+                CreateRepayment::new(&usdt(), amount),
+                Err(Error::InvalidParams(_))
+            ));
+        }
+    }
+
+    #[test]
+    fn loan_state() {
+        assert_wire_str(LoanState::VARIANTS);
+        let loan: RespLoan = serde_json::from_str(r#"{"id":"1","state":"Refunded"}"#).unwrap();
+        assert_eq!(loan.state, LoanState::Unknown);
+    }
+
+    #[async_std::test]
+    async fn create_loan() {
+        let params = CreateLoan::new(&usdt(), dec!(100.5)).unwrap();
+        let resp = create_client("create_loan.yaml")
+            .await
+            .send(params.to_request(&TEST_CREDENTIALS))
+            .await
+            .expect("Error while sending request");
+        let loan = CreateLoan::read_response(resp.into())
+            .await
+            .expect("failed to parse result");
+        assert_eq!(
+            loan,
+            RespLoan {
+                id: "2302142001".into(),
+                currency: Currency::USDT,
+                amount: dec!(100.5),
+                interest_rate: Some(dec!(0.0001)),
+                state: LoanState::Pending,
+                created_at: Some(Utc.timestamp_millis_opt(1676365200123).unwrap()),
+            }
+        );
+    }
+
+    #[async_std::test]
+    async fn create_repayment() {
+        let params = CreateRepayment::new(&usdt(), dec!(50)).unwrap();
+        let resp = create_client("create_repayment.yaml")
+            .await
+            .send(params.to_request(&TEST_CREDENTIALS))
+            .await
+            .expect("Error while sending request");
+        let repayment = CreateRepayment::read_response(resp.into())
+            .await
+            .expect("failed to parse result");
+        assert_eq!(
+            repayment,
+            RespRepayment {
+                id: "2302142101".into(),
+                currency: Currency::USDT,
+                principal: dec!(49.99),
+                interest: dec!(0.01),
+                state: LoanState::Pending,
+                created_at: Some(Utc.timestamp_millis_opt(1676368800456).unwrap()),
+            }
+        );
+    }
+
+    #[async_std::test]
+    async fn get_loans() {
+        let params = GetLoans {
+            state: Some(LoanState::Confirmed),
+            time: TimeRange::since(Utc.timestamp_opt(1676300000, 0).unwrap()),
+            ..GetLoans::of(Currency::USDT)
+        };
+        let resp = create_client("get_loans.yaml")
+            .await
+            .send(params.to_request(&TEST_CREDENTIALS))
+            .await
+            .expect("Error while sending request");
+        let loans = GetLoans::read_response(resp.into())
+            .await
+            .expect("failed to parse result");
+        let ids: Vec<_> = loans.iter().map(|loan| loan.id.as_str()).collect();
+        assert_eq!(ids, ["2302142001", "2302131501"]);
+        assert!(loans
+            .iter()
+            .all(|loan| loan.state == LoanState::Confirmed && loan.currency == Currency::USDT));
+        assert_eq!(loans[1].amount, dec!(300.0));
+    }
+}
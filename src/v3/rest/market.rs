@@ -0,0 +1,54 @@
+use std::collections::HashMap;
+
+use rust_decimal::Decimal;
+use serde::Serialize;
+
+use crate::common::*;
+use crate::v2::rest::api_impl::*;
+
+// ========
+// Requests
+// ========
+
+/// GET /api/v3/wallet/m/index_prices
+///
+/// Get the index prices of the markets, which are the reference prices to value the assets and debts of M-wallet.
+/// They differ from the last traded prices in [`crate::v2::rest::RespTickerInfo`].
+#[derive(Serialize, Clone, Eq, PartialEq, Default, Debug)]
+pub struct GetIndexPrices {}
+impl_api!(GetIndexPrices => HashMap<Symbol, Decimal> : GET, "/api/v3/wallet/m/index_prices");
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::test_util::*;
+    use rust_decimal_macros::dec;
+    use surf::Client as HTTPClient;
+    use surf_vcr::VcrMode;
+
+    async fn create_client(cassette: &'static str) -> HTTPClient {
+        let mut path_builder = test_resource_path();
+        path_builder.push("v3");
+        path_builder.push("rest");
+        path_builder.push("market");
+        path_builder.push(cassette);
+        create_test_recording_client(VcrMode::Replay, path_builder.as_path().to_str().unwrap())
+            .await
+    }
+
+    #[async_std::test]
+    async fn get_index_prices() {
+        let resp = create_client("get_index_prices.yaml")
+            .await
+            .send(GetIndexPrices {}.to_request())
+            .await
+            .expect("Error while sending request");
+        let prices = GetIndexPrices::read_response(resp.into())
+            .await
+            .expect("failed to parse result");
+        assert_eq!(prices.len(), 4);
+        assert_eq!(prices.get("btcusdt"), Some(&dec!(21734.52)));
+        assert_eq!(prices.get("usdttwd"), Some(&dec!(30.32)));
+        assert_eq!(prices.get("dogeusdt"), None);
+    }
+}
@@ -0,0 +1,60 @@
+use chrono::{TimeZone, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::common::*;
+use crate::v2::rest::api_impl::*;
+
+// ========
+// Requests
+// ========
+
+/// GET /api/v3/timestamp
+///
+/// Get server current time, in seconds since Unix epoch.
+#[derive(Serialize, Clone, Eq, PartialEq, Default, Debug)]
+pub struct GetTimestamp {}
+impl_api!(GetTimestamp => RespTimestamp : GET, "/api/v3/timestamp");
+
+// =========
+// Responses
+// =========
+
+/// Server current time. Unlike v2, it is wrapped in an object.
+#[derive(Serialize, Deserialize, Clone, Eq, PartialEq, Debug)]
+pub struct RespTimestamp {
+    /// timestamp (integer): seconds since Unix epoch
+    pub timestamp: i64,
+}
+
+impl From<RespTimestamp> for DateTime {
+    fn from(resp: RespTimestamp) -> Self {
+        Utc.timestamp_opt(resp.timestamp, 0).unwrap()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::v2::rest::Endpoint;
+
+    #[test]
+    fn timestamp_request() {
+        let params = GetTimestamp {};
+        assert_eq!(params.path(), "/api/v3/timestamp");
+        let req = params.to_request();
+        assert_eq!(req.url().host_str(), Some("max-api.maicoin.com"));
+        assert_eq!(req.url().path(), "/api/v3/timestamp");
+    }
+
+    #[async_std::test]
+    async fn timestamp_response() {
+        let mut resp = http_types::Response::new(200);
+        resp.set_body(r#"{"timestamp":1637671372}"#);
+        let ts = GetTimestamp::read_response(resp).await.unwrap();
+        assert_eq!(ts.timestamp, 1637671372);
+        assert_eq!(
+            DateTime::from(ts),
+            Utc.timestamp_opt(1637671372, 0).unwrap()
+        );
+    }
+}
@@ -0,0 +1,64 @@
+//! Components to interact with the v3 RESTful API: <https://max.maicoin.com/api-docs>
+//!
+//! The v3 API shares the server, the authentication scheme and the error replies with v2, so the request structures
+//! here are built on the same machinery: they provide the same `to_request`/`read_response` methods described in
+//! [`crate::v2::rest`], implement [`Endpoint`], and can be sent with the v2 clients and executors.
+//!
+//! Endpoints of wallets are namespaced by [`WalletType`], i.e. `/api/v3/wallet/{spot|m}/...`.
+//!
+//! ```
+//! use maicoin_max::v2::rest::Endpoint;
+//! use maicoin_max::v3::rest::{GetWalletAccounts, WalletType};
+//!
+//! let params = GetWalletAccounts::of(WalletType::M);
+//! assert_eq!(params.path(), "/api/v3/wallet/m/accounts");
+//! ```
+//!
+//! [`Endpoint`]: crate::v2::rest::Endpoint
+
+use serde::{Deserialize, Serialize};
+
+mod liquidation;
+mod loan;
+mod market;
+mod misc;
+mod order;
+mod trade;
+mod user;
+mod wallet;
+
+pub use liquidation::*;
+pub use loan::*;
+pub use market::*;
+pub use misc::*;
+pub use order::*;
+pub use trade::*;
+pub use user::*;
+pub use wallet::*;
+
+/// Wallet of v3 endpoints.
+#[derive(Serialize, Deserialize, Copy, Clone, Eq, PartialEq, Hash, Debug, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum WalletType {
+    /// Spot wallet.
+    #[default]
+    Spot,
+    /// Margin (M-wallet).
+    M,
+}
+
+impl_wire_str!(WalletType {
+    Spot => "spot",
+    M => "m",
+});
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::test_util::assert_wire_str;
+
+    #[test]
+    fn enum_wire_str() {
+        assert_wire_str(WalletType::VARIANTS);
+    }
+}
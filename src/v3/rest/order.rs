@@ -0,0 +1,985 @@
+use chrono::serde as chrono_serde;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+
+use crate::common::*;
+use crate::error::{Error, Result};
+use crate::v2::rest::api_impl::*;
+use crate::v2::rest::{OrderState, OrderType};
+use crate::v3::rest::WalletType;
+
+// ========
+// Requests
+// ========
+
+/// POST /api/v3/wallet/{wallet}/order
+///
+/// Create a sell/buy order in the wallet, same as [`crate::v2::rest::CreateOrder`] except the wallet.
+#[derive(Serialize, Debug)]
+pub struct CreateWalletOrder {
+    /// Wallet to place the order, in the path.
+    #[serde(skip)]
+    pub wallet: WalletType,
+    /// Unique market id, check /api/v2/markets for available markets.
+    pub market: Symbol,
+    /// `'sell'` or `'buy'`.
+    pub side: OrderSide,
+    /// Total amount to sell/buy, an order could be partially executed.
+    pub volume: Decimal,
+    /// Price of a unit.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub price: Option<Decimal>,
+    /// User specific order id. maximum length of client_oid must less or equal to 36. persistence, server will validate uniqueness within 24 hours only.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub client_oid: Option<String>,
+    /// Price to trigger a stop order.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stop_price: Option<Decimal>,
+    /// `'limit'`, `'market'`, `'stop_limit'`, `'stop_market'`, `'post_only'` or `'ioc_limit'`.
+    pub ord_type: OrderType,
+    /// Group order id.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub group_id: Option<GroupId>,
+}
+// Re-creating an order is safe only if the server can dedupe it by client_oid.
+impl_api!(CreateWalletOrder => RespWalletOrder : auth POST, dynamic params {
+    format!("/api/v3/wallet/{}/order", params.wallet)
+}, idempotent params {
+    params.client_oid.is_some()
+});
+
+/// Maximum number of orders in a single [`CreateWalletOrders`] request, checked by the client before sending. It is a
+/// limit of this crate rather than a documented one of the server.
+pub const MAX_BATCH_ORDERS: usize = 100;
+
+/// POST /api/v3/wallet/{wallet}/orders/batch
+///
+/// Create multiple sell/buy orders of a market in one request. Orders are processed one by one, and the result of each
+/// order is given in the same order of the request, see [`RespBatchOrderItem`]. The path and the per-order error shape
+/// are only covered by hand-written fixtures so far, not by recorded sessions.
+#[derive(Serialize, Debug)]
+pub struct CreateWalletOrders {
+    /// Wallet to place the orders, in the path.
+    #[serde(skip)]
+    pub wallet: WalletType,
+    /// Unique market id, check /api/v2/markets for available markets.
+    pub market: Symbol,
+    /// Orders to create, at most [`MAX_BATCH_ORDERS`] of them.
+    #[serde(serialize_with = "serialize_batch_orders")]
+    pub orders: Vec<OrderItem>,
+}
+impl_api!(CreateWalletOrders => Vec<RespBatchOrderItem> : auth POST, dynamic params {
+    format!("/api/v3/wallet/{}/orders/batch", params.wallet)
+});
+
+impl CreateWalletOrders {
+    /// Create the request, or [`Error::InvalidParams`] if there is no order or more than [`MAX_BATCH_ORDERS`] orders.
+    pub fn new(
+        wallet: WalletType,
+        market: impl Into<Symbol>,
+        orders: Vec<OrderItem>,
+    ) -> Result<Self> {
+        check_batch_size(orders.len()).map_err(Error::InvalidParams)?;
+        Ok(Self {
+            wallet,
+            market: market.into(),
+            orders,
+        })
+    }
+}
+
+fn check_batch_size(len: usize) -> std::result::Result<(), String> {
+    match len {
+        0 => Err("no order in the batch".into()),
+        n if n > MAX_BATCH_ORDERS => Err(format!(
+            "{} orders in the batch, at most {} are allowed",
+            n, MAX_BATCH_ORDERS
+        )),
+        _ => Ok(()),
+    }
+}
+
+// The fields are public, so the limit is checked again while building the request body.
+fn serialize_batch_orders<S>(
+    orders: &[OrderItem],
+    serializer: S,
+) -> std::result::Result<S::Ok, S::Error>
+where
+    S: serde::Serializer,
+{
+    check_batch_size(orders.len()).map_err(serde::ser::Error::custom)?;
+    orders.serialize(serializer)
+}
+
+/// Single order of [`CreateWalletOrders`], in which the market is shared by the batch.
+#[derive(Serialize, Clone, Debug)]
+pub struct OrderItem {
+    /// `'sell'` or `'buy'`.
+    pub side: OrderSide,
+    /// Total amount to sell/buy, an order could be partially executed.
+    pub volume: Decimal,
+    /// Price of a unit.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub price: Option<Decimal>,
+    /// User specific order id. maximum length of client_oid must less or equal to 36.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub client_oid: Option<String>,
+    /// Price to trigger a stop order.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stop_price: Option<Decimal>,
+    /// `'limit'`, `'market'`, `'stop_limit'`, `'stop_market'`, `'post_only'` or `'ioc_limit'`.
+    pub ord_type: OrderType,
+    /// Group order id.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub group_id: Option<GroupId>,
+}
+
+/// DELETE /api/v3/order
+///
+/// Cancel an order by either the unique order id or the user specific order id. Only an acknowledgment is returned,
+/// query the order for its final state. Failures caused by the state of the order can be told by [`CancelFailure`].
+#[derive(Serialize, Clone, Eq, PartialEq, Debug)]
+pub struct CancelOrder {
+    /// Unique order id.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<u64>,
+    /// User specific order id. maximum length of client_oid must less or equal to 36.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub client_oid: Option<String>,
+}
+impl_api!(CancelOrder => RespCancelAck : auth DELETE, "/api/v3/order");
+
+impl CancelOrder {
+    /// Cancel the order of the unique order id.
+    pub fn by_id(id: u64) -> Self {
+        Self {
+            id: Some(id),
+            client_oid: None,
+        }
+    }
+
+    /// Cancel the order of the user specific order id.
+    pub fn by_client_oid(client_oid: impl Into<String>) -> Self {
+        Self {
+            id: None,
+            client_oid: Some(client_oid.into()),
+        }
+    }
+}
+
+/// DELETE /api/v3/wallet/{wallet}/orders
+///
+/// Cancel the orders of the wallet matching the filters. Without any filter, all the orders in the wallet are
+/// cancelled, which has to be requested explicitly by [`CancelWalletOrders::confirm_all`].
+#[derive(Serialize, Clone, Eq, PartialEq, Debug)]
+#[non_exhaustive]
+pub struct CancelWalletOrders {
+    /// Wallet of the orders, in the path.
+    #[serde(skip)]
+    pub wallet: WalletType,
+    /// Set to cancel only the orders of the market.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub market: Option<Symbol>,
+    /// Set to cancel only sell (asks) or buy (bids) orders.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub side: Option<OrderSide>,
+    /// Set to cancel only the orders of the group.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub group_id: Option<GroupId>,
+}
+impl_api!(CancelWalletOrders => Vec<RespCancelledOrder> : auth DELETE, dynamic params {
+    format!("/api/v3/wallet/{}/orders", params.wallet)
+});
+
+impl CancelWalletOrders {
+    /// Cancel the orders matching the filters, or [`Error::InvalidParams`] if no filter is given. Use
+    /// [`CancelWalletOrders::confirm_all`] to cancel all the orders in the wallet.
+    pub fn new(
+        wallet: WalletType,
+        market: Option<Symbol>,
+        side: Option<OrderSide>,
+        group_id: Option<GroupId>,
+    ) -> Result<Self> {
+        if market.is_none() && side.is_none() && group_id.is_none() {
+            return Err(Error::InvalidParams(
+                "no filter to cancel orders, use CancelWalletOrders::confirm_all to cancel all orders".into(),
+            ));
+        }
+        Ok(Self {
+            wallet,
+            market,
+            side,
+            group_id,
+        })
+    }
+
+    /// Cancel all the orders in the wallet, of all markets.
+    pub fn confirm_all(wallet: WalletType) -> Self {
+        Self {
+            wallet,
+            market: None,
+            side: None,
+            group_id: None,
+        }
+    }
+
+    /// `true` if all the orders in the wallet are to be cancelled.
+    pub fn is_all(&self) -> bool {
+        self.market.is_none() && self.side.is_none() && self.group_id.is_none()
+    }
+}
+
+/// GET /api/v3/wallet/{wallet}/orders/{kind}
+///
+/// Get the open or closed orders of a market in the wallet. Orders are listed by cursor instead of page number, see
+/// [`GetWalletOrders::next_cursor`].
+#[derive(Serialize, Clone, Eq, PartialEq, Debug)]
+pub struct GetWalletOrders {
+    /// Wallet of the orders, in the path.
+    #[serde(skip)]
+    pub wallet: WalletType,
+    /// Open or closed orders, in the path.
+    #[serde(skip)]
+    pub kind: OrderListKind,
+    /// Unique market id, check /api/v2/markets for available markets.
+    pub market: Symbol,
+    /// Target period of the order creation, see [`TimeRange`].
+    #[serde(flatten)]
+    pub time: TimeRange,
+    /// List orders from the order id on, in ascending order.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub from_id: Option<u64>,
+    /// List orders created before the timestamp (millisecond), in descending order.
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        with = "chrono_serde::ts_milliseconds_option"
+    )]
+    pub timestamp: Option<DateTime>,
+    /// Order of the listed orders, `'desc'` by default.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub order_by: Option<OrderBy>,
+    /// Returned limit (1~1000, default 50).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub limit: Option<u64>,
+}
+impl_api!(GetWalletOrders => Vec<RespWalletOrder> : auth GET, dynamic params {
+    format!("/api/v3/wallet/{}/orders/{}", params.wallet, params.kind)
+});
+
+impl GetWalletOrders {
+    /// Orders returned when the limit is not given.
+    pub const DEFAULT_LIMIT: u64 = 50;
+
+    /// Open orders of the market in the wallet.
+    pub fn open(wallet: WalletType, market: impl Into<Symbol>) -> Self {
+        Self::of_kind(wallet, OrderListKind::Open, market)
+    }
+
+    /// Closed orders of the market in the wallet.
+    pub fn closed(wallet: WalletType, market: impl Into<Symbol>) -> Self {
+        Self::of_kind(wallet, OrderListKind::Closed, market)
+    }
+
+    fn of_kind(wallet: WalletType, kind: OrderListKind, market: impl Into<Symbol>) -> Self {
+        Self {
+            wallet,
+            kind,
+            market: market.into(),
+            time: Default::default(),
+            from_id: None,
+            timestamp: None,
+            order_by: None,
+            limit: None,
+        }
+    }
+
+    /// Replace the target period.
+    pub fn with_time(mut self, time: TimeRange) -> Self {
+        self.time = time;
+        self
+    }
+
+    /// Set the returned limit.
+    pub fn with_limit(mut self, limit: u64) -> Self {
+        self.limit = Some(limit);
+        self
+    }
+
+    /// Set the order of the listed orders.
+    pub fn with_order_by(mut self, order_by: OrderBy) -> Self {
+        self.order_by = Some(order_by);
+        self
+    }
+
+    /// Continue listing from the cursor, replacing the previous one.
+    pub fn with_cursor(mut self, cursor: OrderCursor) -> Self {
+        match cursor {
+            OrderCursor::FromId(id) => {
+                self.from_id = Some(id);
+                self.timestamp = None;
+            }
+            OrderCursor::Before(timestamp) => {
+                self.from_id = None;
+                self.timestamp = Some(timestamp);
+            }
+        }
+        self
+    }
+
+    /// Cursor of the page following the given one, or `None` if it is the last page, i.e. shorter than the limit.
+    ///
+    /// Ascending listings continue after the last order id. Descending listings continue before the creation time of
+    /// the last order, so orders created in the same millisecond as the last one may be listed again.
+    pub fn next_cursor(&self, page: &[RespWalletOrder]) -> Option<OrderCursor> {
+        if (page.len() as u64) < self.limit.unwrap_or(Self::DEFAULT_LIMIT) {
+            return None;
+        }
+        let last = page.last()?;
+        match self.order_by {
+            Some(OrderBy::Asc) => last.id.map(|id| OrderCursor::FromId(id + 1)),
+            _ => last.created_at.map(OrderCursor::Before),
+        }
+    }
+}
+
+/// Orders listed by [`GetWalletOrders`].
+#[derive(Serialize, Deserialize, Copy, Clone, Eq, PartialEq, Hash, Debug, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum OrderListKind {
+    /// Orders waiting to be executed.
+    #[default]
+    Open,
+    /// Orders done or cancelled.
+    Closed,
+}
+
+impl_wire_str!(OrderListKind {
+    Open => "open",
+    Closed => "closed",
+});
+
+/// Position to continue listing orders, see [`GetWalletOrders::next_cursor`].
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub enum OrderCursor {
+    /// From the order id on, for ascending listings.
+    FromId(u64),
+    /// Created before the time, for descending listings.
+    Before(DateTime),
+}
+
+// =========
+// Responses
+// =========
+
+/// Submitted order detail of v3 API.
+///
+/// The shape follows [`crate::v2::rest::RespOrder`], except that the timestamps are in milliseconds without the
+/// `*_in_ms` duplicates, and the wallet of the order is given.
+#[derive(Serialize, Deserialize, Clone, Eq, PartialEq, Default, Debug)]
+#[serde(default)]
+pub struct RespWalletOrder {
+    /// id (integer): unique order id.
+    pub id: Option<u64>,
+    /// client_oid (string, optional): user specific order id.
+    pub client_oid: Option<String>,
+    /// side (string): `'sell'` or `'buy'`
+    pub side: OrderSide,
+    /// ord_type (string): `'limit'`, `'market'`, `'stop_limit'`, `'stop_market'`, `'post_only'` or `'ioc_limit'`.
+    pub ord_type: OrderType,
+    /// price (string, optional): price of a unit.
+    pub price: Option<Decimal>,
+    /// stop_price (string, optional): price to trigger a stop order.
+    pub stop_price: Option<Decimal>,
+    /// avg_price (string): average execution price.
+    pub avg_price: Option<Decimal>,
+    /// state (string): `'wait'`, `'done'`, `'cancel'`, or `'convert'`.
+    pub state: OrderState,
+    /// market (string): market id.
+    pub market: Symbol,
+    /// created_at (integer): created timestamp (millisecond).
+    #[serde(with = "chrono_serde::ts_milliseconds_option")]
+    pub created_at: Option<DateTime>,
+    /// updated_at (integer): updated timestamp (millisecond).
+    #[serde(with = "chrono_serde::ts_milliseconds_option")]
+    pub updated_at: Option<DateTime>,
+    /// volume (string): total amount to sell/buy, an order could be partially executed.
+    pub volume: Option<Decimal>,
+    /// remaining_volume (string): remaining volume.
+    pub remaining_volume: Option<Decimal>,
+    /// executed_volume (string): executed volume.
+    pub executed_volume: Option<Decimal>,
+    /// trades_count (integer): trade count.
+    pub trades_count: Option<u64>,
+    /// group_id (integer, optional): group order id.
+    pub group_id: Option<GroupId>,
+    /// wallet_type (string): wallet of the order.
+    pub wallet_type: WalletType,
+}
+
+/// Result of a single order in [`CreateWalletOrders`]. The batch succeeds as a whole even if some orders are rejected.
+#[derive(Serialize, Deserialize, Clone, Eq, PartialEq, Debug)]
+#[serde(untagged)]
+pub enum RespBatchOrderItem {
+    /// The order is rejected.
+    Failed {
+        /// error (object): reason of the rejection.
+        error: RespBatchOrderError,
+    },
+    /// The order is created.
+    Created(Box<RespWalletOrder>),
+}
+
+impl RespBatchOrderItem {
+    /// The created order, or the rejection as [`Error::RestApi`].
+    pub fn into_result(self) -> Result<RespWalletOrder> {
+        match self {
+            RespBatchOrderItem::Failed { error } => Err(Error::RestApi(error.code, error.message)),
+            RespBatchOrderItem::Created(order) => Ok(*order),
+        }
+    }
+}
+
+/// Reason of a rejected order in [`RespBatchOrderItem`] and [`RespCancelledOrder`].
+#[derive(Serialize, Deserialize, Clone, Eq, PartialEq, Debug)]
+pub struct RespBatchOrderError {
+    /// code (integer): error code, see "Error Codes" in official document.
+    pub code: u64,
+    /// message (string): error message.
+    pub message: String,
+}
+
+/// Acknowledgment of [`CancelOrder`].
+#[derive(Serialize, Deserialize, Clone, Copy, Eq, PartialEq, Default, Debug)]
+pub struct RespCancelAck {
+    /// success (boolean): `true` if the order is going to be cancelled.
+    pub success: bool,
+}
+
+/// Reason why an order can not be cancelled, told from the error of [`CancelOrder`].
+#[derive(Clone, Copy, Eq, PartialEq, Hash, Debug)]
+#[non_exhaustive]
+pub enum CancelFailure {
+    /// No such order, see [`Error::is_order_not_found`].
+    NotFound,
+    /// The server refused to cancel the order, e.g. it is already done or cancelled. MAX replies the generic "failed to
+    /// cancel order" error for it, which does not tell the reason.
+    Rejected,
+}
+
+impl CancelFailure {
+    /// The failure of the error, or `None` for errors not classified, e.g. transport or signature errors.
+    pub fn of(err: &Error) -> Option<Self> {
+        match err {
+            _ if err.is_order_not_found() => Some(CancelFailure::NotFound),
+            Error::RestApi(crate::error::API_ERROR_CANCEL_FAILED, _) => {
+                Some(CancelFailure::Rejected)
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Result of an order cancelled by [`CancelWalletOrders`].
+#[derive(Serialize, Deserialize, Clone, Eq, PartialEq, Default, Debug)]
+#[serde(default)]
+pub struct RespCancelledOrder {
+    /// order (object): the order to cancel.
+    pub order: RespWalletOrder,
+    /// error (object, optional): reason if the order failed to be cancelled.
+    pub error: Option<RespBatchOrderError>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::test_util::*;
+    use crate::v2::rest::internal::{
+        HEADER_AUTH_ACCESS_KEY, HEADER_AUTH_PAYLOAD, HEADER_AUTH_SIGNATURE,
+    };
+    use crate::v2::rest::{ApiHost, Endpoint};
+    use crate::Credentials;
+    use chrono::{TimeZone, Utc};
+    use hmac::{Hmac, Mac, NewMac};
+    use rust_decimal_macros::dec;
+    use serde_json::{json, Value as JsonValue};
+    use sha2::Sha256;
+    use surf::Client as HTTPClient;
+    use surf_vcr::VcrMode;
+
+    async fn create_client(cassette: &'static str) -> HTTPClient {
+        let mut path_builder = test_resource_path();
+        path_builder.push("v3");
+        path_builder.push("rest");
+        path_builder.push("order");
+        path_builder.push(cassette);
+        create_test_recording_client(VcrMode::Replay, path_builder.as_path().to_str().unwrap())
+            .await
+    }
+
+    fn order(wallet: WalletType) -> CreateWalletOrder {
+        CreateWalletOrder {
+            wallet,
+            market: "btcusdt".into(),
+            side: OrderSide::Buy,
+            volume: dec!(0.001),
+            price: Some(dec!(20000.0)),
+            client_oid: Some("max_rs_v3_order".into()),
+            stop_price: None,
+            ord_type: OrderType::Limit,
+            group_id: None,
+        }
+    }
+
+    #[test]
+    fn create_wallet_order_signature() {
+        let credentials = Credentials::new("api key".into(), "api secret".into());
+        for wallet in WalletType::VARIANTS {
+            let params = order(*wallet);
+            let path = format!("/api/v3/wallet/{}/order", wallet);
+            assert_eq!(params.path(), path);
+            assert!(params.is_idempotent());
+
+            let mut req = params.to_request(&credentials);
+            assert_eq!(req.url().path(), path);
+            assert_eq!(req.header(HEADER_AUTH_ACCESS_KEY).unwrap(), "api key");
+
+            // the payload signs the body along with the path of the wallet
+            let payload = req.header(HEADER_AUTH_PAYLOAD).unwrap().as_str().to_owned();
+            let mut signed: JsonValue =
+                serde_json::from_slice(&base64::decode(&payload).unwrap()).unwrap();
+            assert_eq!(signed["path"], path);
+            let body: JsonValue =
+                serde_json::from_str(&async_std::task::block_on(req.body_string()).unwrap())
+                    .unwrap();
+            signed.as_object_mut().unwrap().remove("path");
+            assert_eq!(signed, body);
+
+            // decimals are sent as strings
+            assert_eq!(body["volume"], "0.001");
+            assert_eq!(body["price"], "20000.0");
+            assert_eq!(body["side"], "buy");
+            assert!(body.get("stop_price").is_none());
+
+            let mut hmac = Hmac::<Sha256>::new_from_slice(b"api secret").unwrap();
+            hmac.update(payload.as_bytes());
+            assert_eq!(
+                req.header(HEADER_AUTH_SIGNATURE).unwrap(),
+                format!("{:x}", hmac.finalize().into_bytes()).as_str()
+            );
+        }
+    }
+
+    #[async_std::test]
+    async fn create_wallet_order() {
+        let params = order(WalletType::M);
+        let resp = create_client("create_wallet_order_m.yaml")
+            .await
+            .send(params.to_request(&TEST_CREDENTIALS))
+            .await
+            .expect("Error while sending request");
+        let result = CreateWalletOrder::read_response(resp.into())
+            .await
+            .expect("failed to parse result");
+        assert_eq!(
+            result,
+            RespWalletOrder {
+                id: Some(2931746312),
+                client_oid: Some("max_rs_v3_order".into()),
+                side: OrderSide::Buy,
+                ord_type: OrderType::Limit,
+                price: Some(dec!(20000.0)),
+                stop_price: None,
+                avg_price: Some(dec!(0.0)),
+                state: OrderState::Wait,
+                market: "btcusdt".into(),
+                created_at: Some(Utc.timestamp_opt(1676362350, 123000000).unwrap()),
+                updated_at: Some(Utc.timestamp_opt(1676362350, 123000000).unwrap()),
+                volume: Some(dec!(0.001)),
+                remaining_volume: Some(dec!(0.001)),
+                executed_volume: Some(dec!(0.0)),
+                trades_count: Some(0),
+                group_id: None,
+                wallet_type: WalletType::M,
+            }
+        );
+        assert_eq!(
+            serde_json::to_value(&result).unwrap()["created_at"],
+            json!(1676362350123u64)
+        );
+    }
+
+    fn batch_item(side: OrderSide, price: Decimal, client_oid: &str) -> OrderItem {
+        OrderItem {
+            side,
+            volume: dec!(0.001),
+            price: Some(price),
+            client_oid: Some(client_oid.into()),
+            stop_price: None,
+            ord_type: OrderType::Limit,
+            group_id: None,
+        }
+    }
+
+    #[test]
+    fn batch_order_limit() {
+        let credentials = Credentials::new("api key".into(), "api secret".into());
+        let item = batch_item(OrderSide::Sell, dec!(30000.0), "max_rs_batch");
+
+        assert!(matches!(
+            CreateWalletOrders::new(WalletType::Spot, "btcusdt", vec![]),
+            Err(Error::InvalidParams(_))
+        ));
+        assert!(matches!(
+            CreateWalletOrders::new(
+                WalletType::Spot,
+                "btcusdt",
+                vec![item.clone(); MAX_BATCH_ORDERS + 1]
+            ),
+            Err(Error::InvalidParams(_))
+        ));
+
+        let params = CreateWalletOrders::new(
+            WalletType::Spot,
+            "btcusdt",
+            vec![item.clone(); MAX_BATCH_ORDERS],
+        )
+        .unwrap();
+        assert_eq!(params.path(), "/api/v3/wallet/spot/orders/batch");
+        let mut req = params.to_request(&credentials);
+        let body: JsonValue =
+            serde_json::from_str(&async_std::task::block_on(req.body_string()).unwrap()).unwrap();
+        assert_eq!(body["market"], "btcusdt");
+        assert_eq!(body["orders"].as_array().unwrap().len(), MAX_BATCH_ORDERS);
+        assert_eq!(
+            body["orders"][0],
+            json!({"side": "sell", "volume": "0.001", "price": "30000.0", "client_oid": "max_rs_batch", "ord_type": "limit"})
+        );
+
+        // oversized batch built by the public fields is rejected on signing
+        let params = CreateWalletOrders {
+            wallet: WalletType::Spot,
+            market: "btcusdt".into(),
+            orders: vec![item; MAX_BATCH_ORDERS + 1],
+        };
+        assert!(matches!(
+            params.try_to_request(&credentials),
+            Err(Error::InvalidParams(_))
+        ));
+    }
+
+    #[async_std::test]
+    async fn create_wallet_orders() {
+        let params = CreateWalletOrders::new(
+            WalletType::Spot,
+            "btcusdt",
+            vec![
+                batch_item(OrderSide::Buy, dec!(20000.0), "max_rs_batch_1"),
+                batch_item(OrderSide::Sell, dec!(30000.0), "max_rs_batch_2"),
+            ],
+        )
+        .unwrap();
+        let resp = create_client("create_wallet_orders_spot.yaml")
+            .await
+            .send(params.to_request(&TEST_CREDENTIALS))
+            .await
+            .expect("Error while sending request");
+        let result = CreateWalletOrders::read_response(resp.into())
+            .await
+            .expect("failed to parse result");
+        assert_eq!(result.len(), 2);
+        let orders: Vec<_> = result
+            .into_iter()
+            .map(|item| item.into_result().expect("order rejected"))
+            .collect();
+        assert_eq!(orders[0].id, Some(2931750001));
+        assert_eq!(orders[0].client_oid.as_deref(), Some("max_rs_batch_1"));
+        assert_eq!(orders[0].side, OrderSide::Buy);
+        assert_eq!(orders[1].id, Some(2931750002));
+        assert_eq!(orders[1].price, Some(dec!(30000.0)));
+        assert_eq!(orders[1].wallet_type, WalletType::Spot);
+    }
+
+    #[async_std::test]
+    async fn create_wallet_orders_partially_rejected() {
+        let params = CreateWalletOrders::new(
+            WalletType::M,
+            "btcusdt",
+            vec![
+                batch_item(OrderSide::Buy, dec!(20000.0), "max_rs_batch_1"),
+                batch_item(OrderSide::Buy, dec!(0.0), "max_rs_batch_2"),
+            ],
+        )
+        .unwrap();
+        let resp = create_client("create_wallet_orders_m_mixed.yaml")
+            .await
+            .send(params.to_request(&TEST_CREDENTIALS))
+            .await
+            .expect("Error while sending request");
+        let result = CreateWalletOrders::read_response(resp.into())
+            .await
+            .expect("failed to parse result");
+        assert_eq!(result.len(), 2);
+        assert!(
+            matches!(&result[0], RespBatchOrderItem::Created(order) if order.id == Some(2931750003))
+        );
+        assert_eq!(
+            result[1],
+            RespBatchOrderItem::Failed {
+                error: RespBatchOrderError {
+                    code: 2002,
+                    message: "Failed to create order.".into(),
+                },
+            }
+        );
+        match result[1].clone().into_result() {
+            Err(Error::RestApi(code, _)) => assert_eq!(code, 2002),
+            other => panic!("unexpected result: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn cancel_order_request() {
+        let credentials = Credentials::new("api key".into(), "api secret".into());
+        let params = CancelOrder::by_client_oid("max_rs_v3_order");
+        assert_eq!(params.path(), "/api/v3/order");
+        assert!(params.is_idempotent());
+
+        let req = params
+            .try_to_request_with_nonce(&ApiHost::default(), &credentials, 1676362350123)
+            .unwrap();
+        assert_eq!(req.method(), http_types::Method::Delete);
+        assert_eq!(
+            req.url().as_str(),
+            "https://max-api.maicoin.com/api/v3/order?client_oid=max_rs_v3_order&nonce=1676362350123"
+        );
+        let payload = req.header(HEADER_AUTH_PAYLOAD).unwrap().as_str();
+        let payload: JsonValue = serde_json::from_slice(&base64::decode(payload).unwrap()).unwrap();
+        assert_eq!(
+            payload,
+            json!({"path": "/api/v3/order", "client_oid": "max_rs_v3_order", "nonce": 1676362350123u64})
+        );
+
+        let req = CancelOrder::by_id(2931746312).to_request(&credentials);
+        assert_eq!(
+            req.url().query_pairs().next().unwrap(),
+            ("id".into(), "2931746312".into())
+        );
+    }
+
+    #[async_std::test]
+    async fn cancel_order() {
+        let resp = create_client("cancel_order.yaml")
+            .await
+            .send(CancelOrder::by_id(2931746312).to_request(&TEST_CREDENTIALS))
+            .await
+            .expect("Error while sending request");
+        let result = CancelOrder::read_response(resp.into())
+            .await
+            .expect("failed to parse result");
+        assert_eq!(result, RespCancelAck { success: true });
+    }
+
+    #[async_std::test]
+    async fn cancel_order_already_cancelled() {
+        let resp = create_client("cancel_order_already_cancelled.yaml")
+            .await
+            .send(CancelOrder::by_id(2931746312).to_request(&TEST_CREDENTIALS))
+            .await
+            .expect("Error while sending request");
+        let err = CancelOrder::read_response(resp.into())
+            .await
+            .expect_err("order cancelled twice");
+        match &err {
+            Error::RestApi(code, _) => assert_eq!(*code, 2003),
+            other => panic!("unexpected error: {:?}", other),
+        }
+        assert_eq!(CancelFailure::of(&err), Some(CancelFailure::Rejected));
+
+        let not_found = Error::RestApi(2004, "Order#2931746312 not found.".into());
+        assert_eq!(CancelFailure::of(&not_found), Some(CancelFailure::NotFound));
+        // signing failures must not pass for the order state
+        assert_eq!(
+            CancelFailure::of(&Error::RestApi(2005, "Signature is incorrect.".into())),
+            None
+        );
+        assert_eq!(
+            CancelFailure::of(&Error::RestApi(2006, "nonce used".into())),
+            None
+        );
+    }
+
+    #[test]
+    fn cancel_wallet_orders_filters() {
+        assert!(matches!(
+            CancelWalletOrders::new(WalletType::Spot, None, None, None),
+            Err(Error::InvalidParams(_))
+        ));
+        let all = CancelWalletOrders::confirm_all(WalletType::M);
+        assert!(all.is_all());
+        assert_eq!(all.path(), "/api/v3/wallet/m/orders");
+        assert!(all.is_idempotent());
+
+        let filtered =
+            CancelWalletOrders::new(WalletType::Spot, None, Some(OrderSide::Sell), None).unwrap();
+        assert!(!filtered.is_all());
+        let req = filtered.to_request(&Credentials::new("api key".into(), "api secret".into()));
+        assert_eq!(req.method(), http_types::Method::Delete);
+        assert_eq!(req.url().path(), "/api/v3/wallet/spot/orders");
+        assert_eq!(
+            req.url().query_pairs().next().unwrap(),
+            ("side".into(), "sell".into())
+        );
+    }
+
+    #[async_std::test]
+    async fn cancel_wallet_orders() {
+        let params = CancelWalletOrders::new(
+            WalletType::Spot,
+            Some("btcusdt".into()),
+            Some(OrderSide::Buy),
+            None,
+        )
+        .unwrap();
+        let resp = create_client("cancel_wallet_orders_spot_filtered.yaml")
+            .await
+            .send(params.to_request(&TEST_CREDENTIALS))
+            .await
+            .expect("Error while sending request");
+        let result = CancelWalletOrders::read_response(resp.into())
+            .await
+            .expect("failed to parse result");
+        assert_eq!(result.len(), 1);
+        assert_eq!(result[0].order.id, Some(2931750001));
+        assert_eq!(result[0].order.state, OrderState::Cancel);
+        assert_eq!(result[0].error, None);
+    }
+
+    #[async_std::test]
+    async fn cancel_all_wallet_orders() {
+        let params = CancelWalletOrders::confirm_all(WalletType::M);
+        let resp = create_client("cancel_wallet_orders_m_all.yaml")
+            .await
+            .send(params.to_request(&TEST_CREDENTIALS))
+            .await
+            .expect("Error while sending request");
+        let result = CancelWalletOrders::read_response(resp.into())
+            .await
+            .expect("failed to parse result");
+        let ids: Vec<_> = result.iter().map(|cancelled| cancelled.order.id).collect();
+        assert_eq!(ids, vec![Some(2931750003), Some(2931746312)]);
+        assert_eq!(result[0].order.market, "btcusdt");
+        assert_eq!(result[1].order.market, "ethusdt");
+        assert!(result.iter().all(|cancelled| cancelled.error.is_none()));
+    }
+
+    fn listed_order(id: u64, created_at: i64) -> RespWalletOrder {
+        RespWalletOrder {
+            id: Some(id),
+            created_at: Some(Utc.timestamp_millis_opt(created_at).unwrap()),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn wallet_orders_cursor() {
+        let credentials = Credentials::new("api key".into(), "api secret".into());
+        let params = GetWalletOrders::closed(WalletType::M, "btcusdt")
+            .with_time(TimeRange::since(Utc.timestamp_opt(1676300000, 0).unwrap()))
+            .with_limit(2);
+        assert_eq!(params.path(), "/api/v3/wallet/m/orders/closed");
+        let req = params.to_request(&credentials);
+        assert!(req
+            .url()
+            .query()
+            .unwrap()
+            .starts_with("market=btcusdt&from=1676300000&limit=2&nonce="));
+
+        // descending by default, continued by the creation time of the last order
+        let page = [
+            listed_order(12, 1676362811456),
+            listed_order(11, 1676362800000),
+        ];
+        let cursor = params.next_cursor(&page);
+        assert_eq!(
+            cursor,
+            Some(OrderCursor::Before(
+                Utc.timestamp_millis_opt(1676362800000).unwrap()
+            ))
+        );
+        let next = params.clone().with_cursor(cursor.unwrap());
+        assert_eq!(
+            next.timestamp,
+            Some(Utc.timestamp_millis_opt(1676362800000).unwrap())
+        );
+        assert_eq!(next.from_id, None);
+        assert_eq!(params.next_cursor(&page[..1]), None);
+
+        // ascending listings are continued by the order id
+        let asc = GetWalletOrders::open(WalletType::Spot, "btcusdt")
+            .with_order_by(OrderBy::Asc)
+            .with_limit(2);
+        assert_eq!(asc.path(), "/api/v3/wallet/spot/orders/open");
+        let cursor = asc.next_cursor(&[listed_order(11, 0), listed_order(12, 0)]);
+        assert_eq!(cursor, Some(OrderCursor::FromId(13)));
+        let next = asc.clone().with_cursor(cursor.unwrap());
+        assert_eq!((next.from_id, next.timestamp), (Some(13), None));
+        assert_eq!(asc.next_cursor(&[]), None);
+    }
+
+    #[test]
+    fn order_list_kind_wire_str() {
+        assert_wire_str(OrderListKind::VARIANTS);
+    }
+
+    #[async_std::test]
+    async fn get_open_wallet_orders() {
+        let params = GetWalletOrders::open(WalletType::Spot, "btcusdt");
+        let resp = create_client("get_wallet_orders_open.yaml")
+            .await
+            .send(params.to_request(&TEST_CREDENTIALS))
+            .await
+            .expect("Error while sending request");
+        let orders = GetWalletOrders::read_response(resp.into())
+            .await
+            .expect("failed to parse result");
+        assert_eq!(orders.len(), 1);
+        assert_eq!(orders[0].id, Some(2931750001));
+        assert_eq!(orders[0].state, OrderState::Wait);
+        assert_eq!(params.next_cursor(&orders), None);
+    }
+
+    #[async_std::test]
+    async fn get_closed_wallet_orders() {
+        let params = GetWalletOrders::closed(WalletType::M, "btcusdt").with_limit(2);
+        let resp = create_client("get_wallet_orders_closed.yaml")
+            .await
+            .send(params.to_request(&TEST_CREDENTIALS))
+            .await
+            .expect("Error while sending request");
+        let orders = GetWalletOrders::read_response(resp.into())
+            .await
+            .expect("failed to parse result");
+        let ids: Vec<_> = orders.iter().map(|order| order.id).collect();
+        assert_eq!(ids, vec![Some(2931750003), Some(2931746312)]);
+
+        // continue from the last order of the first page
+        let cursor = params.next_cursor(&orders).expect("no next page");
+        assert_eq!(
+            cursor,
+            OrderCursor::Before(Utc.timestamp_millis_opt(1676362350123).unwrap())
+        );
+        let params = params.with_cursor(cursor);
+        let resp = create_client("get_wallet_orders_closed_next.yaml")
+            .await
+            .send(params.to_request(&TEST_CREDENTIALS))
+            .await
+            .expect("Error while sending request");
+        let orders = GetWalletOrders::read_response(resp.into())
+            .await
+            .expect("failed to parse result");
+        assert_eq!(orders.len(), 1);
+        assert_eq!(orders[0].id, Some(2931740000));
+        assert_eq!(orders[0].state, OrderState::Done);
+        assert_eq!(params.next_cursor(&orders), None);
+    }
+}
@@ -0,0 +1,222 @@
+use chrono::serde as chrono_serde;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+
+use crate::common::*;
+use crate::v2::rest::api_impl::*;
+use crate::v2::rest::{TradeMakerInfo, TradeMakerType, TradeRecord};
+use crate::v3::rest::WalletType;
+
+// ========
+// Requests
+// ========
+
+/// GET /api/v3/wallet/{wallet}/trades
+///
+/// Get your executed trades in the wallet, which is the only way to get the trades of M-wallet.
+#[derive(Serialize, Clone, Eq, PartialEq, Debug)]
+pub struct GetWalletTrades {
+    /// Wallet of the trades, in the path.
+    #[serde(skip)]
+    pub wallet: WalletType,
+    /// Unique market id, set to return the trades of the market only.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub market: Option<Symbol>,
+    /// Target period of the trades, see [`TimeRange`].
+    #[serde(flatten)]
+    pub time: TimeRange,
+    /// Trade id, set to return trades from the trade on.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub from_id: Option<u64>,
+    /// Returned limit (1~1000, default 50).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub limit: Option<u64>,
+}
+impl_api!(GetWalletTrades => Vec<RespWalletTrade> : auth GET, dynamic params {
+    format!("/api/v3/wallet/{}/trades", params.wallet)
+});
+
+impl GetWalletTrades {
+    /// Trades of all markets in the wallet.
+    pub fn of(wallet: WalletType) -> Self {
+        Self {
+            wallet,
+            market: None,
+            time: Default::default(),
+            from_id: None,
+            limit: None,
+        }
+    }
+
+    /// Trades of the market in the wallet.
+    pub fn of_market(wallet: WalletType, market: impl Into<Symbol>) -> Self {
+        Self {
+            market: Some(market.into()),
+            ..Self::of(wallet)
+        }
+    }
+}
+
+// =========
+// Responses
+// =========
+
+/// Trade record of v3 API. Fields not modeled here are ignored.
+///
+/// Converts into [`TradeRecord`] of v2 for the code built on it, see the [`From`] implementation.
+#[derive(Serialize, Deserialize, Clone, Eq, PartialEq, Default, Debug)]
+#[serde(default)]
+pub struct RespWalletTrade {
+    /// id (integer): trade id.
+    pub id: u64,
+    /// order_id (integer): your order of the trade.
+    pub order_id: Option<u64>,
+    /// wallet_type (string): wallet of the trade.
+    pub wallet_type: WalletType,
+    /// price (string): strike price.
+    pub price: Option<Decimal>,
+    /// volume (string): traded volume.
+    pub volume: Option<Decimal>,
+    /// funds (string): total traded amount.
+    pub funds: Option<Decimal>,
+    /// market (string): market id.
+    pub market: Symbol,
+    /// market_name (string): market name.
+    pub market_name: String,
+    /// side (string): `'bid'` or `'ask'`, side of your order.
+    pub side: TradeSide,
+    /// fee (string): trade fee.
+    pub fee: Option<Decimal>,
+    /// fee_currency (string): currency of the trade fee.
+    pub fee_currency: Option<Currency>,
+    /// maker (boolean): `true` if your order is the maker.
+    pub maker: bool,
+    /// created_at (integer): created timestamp (millisecond).
+    #[serde(with = "chrono_serde::ts_milliseconds_option")]
+    pub created_at: Option<DateTime>,
+}
+
+impl From<RespWalletTrade> for TradeRecord {
+    /// The wallet is dropped. The maker info is given only if your order is the maker, as the order and the fee of the
+    /// counterparty are unknown.
+    fn from(trade: RespWalletTrade) -> Self {
+        let created_at = trade.created_at.unwrap_or_default();
+        let fee_currency = trade.fee_currency.map(Currency::into_string);
+        let info = match (trade.maker, trade.order_id) {
+            (true, Some(order_id)) => {
+                let info = TradeMakerInfo {
+                    fee: trade.fee.unwrap_or_default(),
+                    fee_currency: fee_currency.clone().unwrap_or_default(),
+                    order_id,
+                };
+                match trade.side {
+                    TradeSide::Ask => Some(TradeMakerType::Ask { ask: info }),
+                    TradeSide::Bid => Some(TradeMakerType::Bid { bid: info }),
+                    TradeSide::Unknown => None,
+                }
+            }
+            _ => None,
+        };
+        Self {
+            id: trade.id,
+            price: trade.price,
+            volume: trade.volume,
+            funds: trade.funds,
+            market: trade.market,
+            market_name: trade.market_name,
+            created_at,
+            created_at_in_ms: created_at,
+            side: trade.side,
+            fee: trade.fee,
+            fee_currency,
+            order_id: trade.order_id,
+            info,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::test_util::*;
+    use crate::v2::rest::Endpoint;
+    use chrono::{TimeZone, Utc};
+    use rust_decimal_macros::dec;
+    use surf::Client as HTTPClient;
+    use surf_vcr::VcrMode;
+
+    async fn create_client(cassette: &'static str) -> HTTPClient {
+        let mut path_builder = test_resource_path();
+        path_builder.push("v3");
+        path_builder.push("rest");
+        path_builder.push("trade");
+        path_builder.push(cassette);
+        create_test_recording_client(VcrMode::Replay, path_builder.as_path().to_str().unwrap())
+            .await
+    }
+
+    #[async_std::test]
+    async fn get_wallet_trades() {
+        let params = GetWalletTrades {
+            time: TimeRange::since(Utc.timestamp_opt(1676300000, 0).unwrap()),
+            ..GetWalletTrades::of_market(WalletType::M, "btcusdt")
+        };
+        assert_eq!(params.path(), "/api/v3/wallet/m/trades");
+        let resp = create_client("get_wallet_trades_m.yaml")
+            .await
+            .send(params.to_request(&TEST_CREDENTIALS))
+            .await
+            .expect("Error while sending request");
+        let trades = GetWalletTrades::read_response(resp.into())
+            .await
+            .expect("failed to parse result");
+        assert_eq!(
+            trades,
+            vec![
+                RespWalletTrade {
+                    id: 68270001,
+                    order_id: Some(2931740000),
+                    wallet_type: WalletType::M,
+                    price: Some(dec!(20000.0)),
+                    volume: Some(dec!(0.001)),
+                    funds: Some(dec!(20.0)),
+                    market: "btcusdt".into(),
+                    market_name: "BTC/USDT".into(),
+                    side: TradeSide::Bid,
+                    fee: Some(dec!(0.0000015)),
+                    fee_currency: Some(Currency::BTC),
+                    maker: true,
+                    created_at: Some(Utc.timestamp_millis_opt(1676300000456).unwrap()),
+                },
+                RespWalletTrade {
+                    id: 68270002,
+                    order_id: Some(2931740001),
+                    wallet_type: WalletType::M,
+                    price: Some(dec!(20010.0)),
+                    volume: Some(dec!(0.002)),
+                    funds: Some(dec!(40.02)),
+                    market: "btcusdt".into(),
+                    market_name: "BTC/USDT".into(),
+                    side: TradeSide::Ask,
+                    fee: Some(dec!(0.06003)),
+                    fee_currency: Some(Currency::USDT),
+                    maker: false,
+                    created_at: Some(Utc.timestamp_millis_opt(1676300100789).unwrap()),
+                },
+            ]
+        );
+
+        // the maker trade keeps the maker info in v2 records
+        let records: Vec<TradeRecord> = trades.into_iter().map(TradeRecord::from).collect();
+        assert_eq!(
+            records[0].created_at_in_ms.timestamp_millis(),
+            1676300000456
+        );
+        assert_eq!(records[0].fee_currency.as_deref(), Some("btc"));
+        let info = records[0].info.as_ref().unwrap();
+        assert_eq!(info.side(), Some(TradeSide::Bid));
+        assert_eq!(info.info().unwrap().order_id, 2931740000);
+        assert_eq!(records[1].order_id, Some(2931740001));
+        assert_eq!(records[1].info, None);
+    }
+}
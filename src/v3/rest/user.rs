@@ -0,0 +1,101 @@
+use serde::{Deserialize, Serialize};
+
+use crate::v2::rest::api_impl::*;
+use crate::v2::rest::RespVIPLevel;
+
+// ========
+// Requests
+// ========
+
+/// GET /api/v3/info
+///
+/// Get the user info, a compact profile of v3 API.
+#[derive(Serialize, Clone, Eq, PartialEq, Default, Debug)]
+pub struct GetUserInfo {}
+impl_api!(GetUserInfo => RespUserInfo : auth GET, "/api/v3/info");
+
+// =========
+// Responses
+// =========
+
+/// User info of v3 API. Fields not modeled here are ignored, see [`crate::v2::rest::RespProfile`] for the full
+/// profile.
+#[derive(Serialize, Deserialize, Clone, Eq, PartialEq, Default, Debug)]
+#[serde(default)]
+pub struct RespUserInfo {
+    /// email (string): masked user email.
+    pub email: String,
+    /// level (integer): member level.
+    pub level: Option<u8>,
+    /// current_vip_level (object): current VIP level and its fees.
+    pub current_vip_level: Option<RespVIPLevel>,
+    /// withdrawable (boolean): can user make a withdrawal?
+    pub withdrawable: Option<bool>,
+    /// current_two_factor_type (string): `'app'`, `'sms'`, or `None` if two-factor authentication is not set.
+    pub current_two_factor_type: Option<String>,
+}
+
+impl RespUserInfo {
+    /// `true` only if the server says the user can make a withdrawal.
+    pub fn is_withdrawable(&self) -> bool {
+        self.withdrawable == Some(true)
+    }
+
+    /// Current VIP level, `None` if not given.
+    pub fn vip_level(&self) -> Option<u8> {
+        self.current_vip_level.as_ref().map(|vip| vip.level)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::test_util::*;
+    use rust_decimal_macros::dec;
+    use surf::Client as HTTPClient;
+    use surf_vcr::VcrMode;
+
+    async fn create_client(cassette: &'static str) -> HTTPClient {
+        let mut path_builder = test_resource_path();
+        path_builder.push("v3");
+        path_builder.push("rest");
+        path_builder.push("user");
+        path_builder.push(cassette);
+        create_test_recording_client(VcrMode::Replay, path_builder.as_path().to_str().unwrap())
+            .await
+    }
+
+    #[async_std::test]
+    async fn get_user_info() {
+        let resp = create_client("get_user_info.yaml")
+            .await
+            .send(GetUserInfo {}.to_request(&TEST_CREDENTIALS))
+            .await
+            .expect("Error while sending request");
+        let info = GetUserInfo::read_response(resp.into())
+            .await
+            .expect("failed to parse result");
+        assert_eq!(
+            info,
+            RespUserInfo {
+                email: "te***@example.com".into(),
+                level: Some(2),
+                current_vip_level: Some(RespVIPLevel {
+                    level: 1,
+                    minimum_trading_volume: dec!(3000000),
+                    minimum_staking_volume: dec!(0),
+                    maker_fee: dec!(0.00045),
+                    taker_fee: dec!(0.0015),
+                }),
+                withdrawable: Some(true),
+                current_two_factor_type: Some("app".into()),
+            }
+        );
+        assert!(info.is_withdrawable());
+        assert_eq!(info.vip_level(), Some(1));
+
+        let info: RespUserInfo = serde_json::from_str(r#"{"email":"te***@example.com"}"#).unwrap();
+        assert!(!info.is_withdrawable());
+        assert_eq!(info.vip_level(), None);
+    }
+}
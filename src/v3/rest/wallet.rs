@@ -0,0 +1,177 @@
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+
+use crate::common::*;
+use crate::v2::rest::api_impl::*;
+use crate::v3::rest::WalletType;
+
+// ========
+// Requests
+// ========
+
+/// GET /api/v3/wallet/{wallet}/accounts
+///
+/// Get the balances of the wallet.
+#[derive(Serialize, Clone, Eq, PartialEq, Debug)]
+pub struct GetWalletAccounts {
+    /// Wallet to query, in the path.
+    #[serde(skip)]
+    pub wallet: WalletType,
+    /// Unique currency id, set to return the account of the currency only.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub currency: Option<Currency>,
+}
+impl_api!(GetWalletAccounts => Vec<RespWalletAccount> : auth GET, dynamic params {
+    format!("/api/v3/wallet/{}/accounts", params.wallet)
+});
+
+impl GetWalletAccounts {
+    /// All the accounts of the wallet.
+    pub fn of(wallet: WalletType) -> Self {
+        Self {
+            wallet,
+            currency: None,
+        }
+    }
+}
+
+// =========
+// Responses
+// =========
+
+/// Account balance of a wallet. Fields not modeled here are ignored, and missing ones are left default.
+#[derive(Serialize, Deserialize, Clone, Eq, PartialEq, Default, Debug)]
+#[serde(default)]
+pub struct RespWalletAccount {
+    /// currency (string): currency id, e.g. twd, btc, ...
+    pub currency: Currency,
+    /// balance (string): available balance
+    pub balance: Decimal,
+    /// locked (string): locked funds
+    pub locked: Decimal,
+    /// staked (string, optional): staked funds
+    pub staked: Option<Decimal>,
+    /// principal (string, optional): borrowed principal, M-wallet only
+    pub principal: Option<Decimal>,
+    /// interest (string, optional): accrued interest, M-wallet only
+    pub interest: Option<Decimal>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::test_util::*;
+    use crate::v2::rest::{ApiHost, AuthGetDebug, Endpoint};
+    use crate::Credentials;
+    use rust_decimal_macros::dec;
+    use serde_json::{json, Value as JsonValue};
+    use surf::Client as HTTPClient;
+    use surf_vcr::VcrMode;
+
+    async fn create_client(cassette: &'static str) -> HTTPClient {
+        let mut path_builder = test_resource_path();
+        path_builder.push("v3");
+        path_builder.push("rest");
+        path_builder.push("wallet");
+        path_builder.push(cassette);
+        create_test_recording_client(VcrMode::Replay, path_builder.as_path().to_str().unwrap())
+            .await
+    }
+
+    #[test]
+    fn wallet_accounts_request() {
+        let credentials = Credentials::new("api key".into(), "api secret".into());
+        let params = GetWalletAccounts {
+            currency: Some(Currency::BTC),
+            ..GetWalletAccounts::of(WalletType::Spot)
+        };
+        assert_eq!(params.path(), "/api/v3/wallet/spot/accounts");
+
+        // signed as v2 requests, with the path of v3
+        let (url, payload, _) = params
+            .signed_parts_with(&ApiHost::default(), &credentials, 1637671372000)
+            .unwrap();
+        assert_eq!(
+            url.as_str(),
+            "https://max-api.maicoin.com/api/v3/wallet/spot/accounts?currency=btc&nonce=1637671372000"
+        );
+        let payload: JsonValue = serde_json::from_slice(&base64::decode(payload).unwrap()).unwrap();
+        assert_eq!(
+            payload,
+            json!({"path": "/api/v3/wallet/spot/accounts", "currency": "btc", "nonce": 1637671372000u64})
+        );
+
+        let req = GetWalletAccounts::of(WalletType::M).to_request(&credentials);
+        assert_eq!(req.url().path(), "/api/v3/wallet/m/accounts");
+    }
+
+    #[async_std::test]
+    async fn wallet_accounts_response() {
+        let mut resp = http_types::Response::new(200);
+        resp.set_body(
+            r#"[
+                {"currency":"usdt","balance":"1000.0","locked":"10.5","staked":null,"principal":"100.0","interest":"0.01"},
+                {"currency":"btc","balance":"0.5","locked":"0.0"}
+            ]"#,
+        );
+        let accounts = GetWalletAccounts::read_response(resp).await.unwrap();
+        assert_eq!(accounts.len(), 2);
+        assert_eq!(accounts[0].currency, Currency::USDT);
+        assert_eq!(accounts[0].locked, dec!(10.5));
+        assert_eq!(accounts[0].principal, Some(dec!(100.0)));
+        assert_eq!(accounts[1].balance, dec!(0.5));
+        assert_eq!(accounts[1].interest, None);
+    }
+
+    #[async_std::test]
+    async fn get_wallet_accounts() {
+        let params = GetWalletAccounts::of(WalletType::Spot);
+        let resp = create_client("get_wallet_accounts_spot.yaml")
+            .await
+            .send(params.to_request(&TEST_CREDENTIALS))
+            .await
+            .expect("Error while sending request");
+        let accounts = GetWalletAccounts::read_response(resp.into())
+            .await
+            .expect("failed to parse result");
+        assert_eq!(
+            accounts,
+            vec![
+                RespWalletAccount {
+                    currency: Currency::TWD,
+                    balance: dec!(12000.0),
+                    locked: dec!(500.0),
+                    ..Default::default()
+                },
+                RespWalletAccount {
+                    currency: Currency::USDT,
+                    balance: dec!(25.5),
+                    locked: dec!(0.0),
+                    staked: Some(dec!(10.0)),
+                    ..Default::default()
+                },
+            ]
+        );
+
+        let params = GetWalletAccounts::of(WalletType::M);
+        let resp = create_client("get_wallet_accounts_m.yaml")
+            .await
+            .send(params.to_request(&TEST_CREDENTIALS))
+            .await
+            .expect("Error while sending request");
+        let accounts = GetWalletAccounts::read_response(resp.into())
+            .await
+            .expect("failed to parse result");
+        assert_eq!(
+            accounts,
+            vec![RespWalletAccount {
+                currency: Currency::USDT,
+                balance: dec!(1000.0),
+                locked: dec!(10.5),
+                staked: None,
+                principal: Some(dec!(300.0)),
+                interest: Some(dec!(0.0125)),
+            }]
+        );
+    }
+}
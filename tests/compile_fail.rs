@@ -0,0 +1,8 @@
+//! Misuses of the API which should be rejected at compile time.
+#![cfg(not(target_arch = "wasm32"))]
+
+#[test]
+fn compile_fail() {
+    let t = trybuild::TestCases::new();
+    t.compile_fail("tests/ui/*.rs");
+}
@@ -0,0 +1,100 @@
+//! Downstream usage of the `test-util` feature: mock the HTTP client with canned responses, or replay recorded
+//! sessions with the VCR client.
+#![cfg(feature = "test-util")]
+
+use std::path::PathBuf;
+
+use maicoin_max::error::Error;
+use maicoin_max::util::test_util::*;
+use maicoin_max::v2::rest::*;
+use maicoin_max::Credentials;
+
+// Reply canned responses, regardless of the requests.
+struct CannedExecutor {
+    status: u16,
+    body: &'static str,
+}
+
+impl HttpExecutor for CannedExecutor {
+    fn execute(&self, _req: http_types::Request) -> ExecuteFuture<'_> {
+        let resp = canned_response(self.status, self.body);
+        Box::pin(async move { Ok(resp) })
+    }
+}
+
+fn canned(body: &'static str) -> CannedExecutor {
+    CannedExecutor { status: 200, body }
+}
+
+#[async_std::test]
+async fn parse_fixtures() {
+    let credentials = Credentials::new("api key".into(), "api secret".into());
+
+    let params = GetOrder {
+        id: Some(1545763894),
+        client_oid: None,
+    };
+    let order = execute_auth(&canned(fixtures::ORDER), &params, &credentials)
+        .await
+        .unwrap();
+    assert_eq!(order.id, Some(1545763894));
+
+    let params = GetMyTradesOfOrder {
+        id: order.id,
+        client_oid: None,
+    };
+    let trades = execute_auth(&canned(fixtures::TRADES), &params, &credentials)
+        .await
+        .unwrap();
+    assert_eq!(trades.len(), 1);
+    assert_eq!(trades[0].market, "dotusdt");
+
+    let params = GetTickersOfMarket {
+        market: "btctwd".into(),
+    };
+    let ticker = execute(&canned(fixtures::TICKER), &params).await.unwrap();
+    assert!(ticker.buy < ticker.sell);
+
+    let params = GetDepth {
+        market: "btctwd".into(),
+        limit: Some(2),
+        sort_by_price: true,
+    };
+    let depth = execute(&canned(fixtures::DEPTH), &params).await.unwrap();
+    assert_eq!((depth.asks.len(), depth.bids.len()), (2, 2));
+}
+
+#[async_std::test]
+async fn canned_error() {
+    let executor = CannedExecutor {
+        status: 404,
+        body: fixtures::ERROR_ORDER_NOT_FOUND,
+    };
+    let credentials = Credentials::new("api key".into(), "api secret".into());
+    let params = DeleteOrder {
+        id: Some(1),
+        client_oid: None,
+    };
+    let err: Error = execute_auth(&executor, &params, &credentials)
+        .await
+        .unwrap_err();
+    assert!(err.is_order_not_found());
+}
+
+#[async_std::test]
+async fn replay_session() {
+    let cassette: PathBuf = [
+        env!("CARGO_MANIFEST_DIR"),
+        "resource",
+        "test",
+        "rest",
+        "public",
+        "misc",
+        "get_timestamp.yaml",
+    ]
+    .iter()
+    .collect();
+    let client = create_test_recording_client(VcrMode::Replay, cassette.to_str().unwrap()).await;
+    let ts = GetTimestamp {}.fetch(&client).await.unwrap();
+    assert_eq!(ts.0, 1636258261);
+}
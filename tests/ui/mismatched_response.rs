@@ -0,0 +1,15 @@
+// The response reader of `GetOrder` can not parse the response as orders of `GetOrders`.
+use maicoin_max::v2::rest::{Endpoint, GetOrder, RespOrder};
+use maicoin_max::Credentials;
+
+async fn parse(resp: http_types::Response) {
+    let credentials = Credentials::new("api key".into(), "api secret".into());
+    let params = GetOrder {
+        id: Some(1),
+        client_oid: None,
+    };
+    let (_req, reader) = params.prepare_typed(Some(&credentials)).unwrap().into_parts();
+    let _orders: Vec<RespOrder> = reader.read(resp).await.unwrap();
+}
+
+fn main() {}
@@ -0,0 +1,80 @@
+//! Smoke tests for wasm32-unknown-unknown, run with `wasm-pack test --node` (or `cargo test --target
+//! wasm32-unknown-unknown --test wasm` with `wasm-bindgen-cli` installed).
+#![cfg(all(target_arch = "wasm32", target_os = "unknown"))]
+
+use chrono::{TimeZone, Utc};
+use rust_decimal_macros::dec;
+use serde_json::json;
+use wasm_bindgen_test::wasm_bindgen_test;
+
+use maicoin_max::v2::rest::{ApiHost, AuthGetDebug, GetOrders};
+use maicoin_max::v2::ws::ServerPushEvent;
+use maicoin_max::Credentials;
+
+fn get_orders() -> GetOrders {
+    GetOrders {
+        market: "maxtwd".into(),
+        state: Vec::new(),
+        order_by: None,
+        group_id: None,
+        pagination: None,
+        page_params: None,
+        offset: None,
+    }
+}
+
+#[wasm_bindgen_test]
+fn sign_payload() {
+    let credentials = Credentials::new("api key".into(), "api secret".into());
+    let (url, payload, signature) = get_orders()
+        .signed_parts_with(&ApiHost::default(), &credentials, 1637671372000)
+        .unwrap();
+    assert_eq!(
+        url.as_str(),
+        "https://max-api.maicoin.com/api/v2/orders?market=maxtwd&nonce=1637671372000"
+    );
+    assert_eq!(
+        payload,
+        "eyJtYXJrZXQiOiJtYXh0d2QiLCJub25jZSI6MTYzNzY3MTM3MjAwMCwicGF0aCI6Ii9hcGkvdjIvb3JkZXJzIn0="
+    );
+    assert_eq!(
+        signature,
+        "f7a8450ee917c90816d499c1909aef1ffeb2e076b1ec861fabc29c62c12127d7"
+    );
+
+    // nonce is taken from the clock of JS runtime
+    let (url, _, _) = get_orders().signed_parts(&credentials).unwrap();
+    let (_, nonce) = url
+        .query_pairs()
+        .find(|(key, _)| key == "nonce")
+        .expect("nonce must be signed");
+    assert!(nonce.parse::<u64>().unwrap() > 1637671372000);
+}
+
+#[wasm_bindgen_test]
+fn parse_ticker_feed() {
+    let raw = json!({
+        "c": "ticker",
+        "e": "snapshot",
+        "M": "btctwd",
+        "tk": {
+            "O": "280007.1",
+            "H": "280017.2",
+            "L": "280005.3",
+            "C": "280004.5",
+            "v": "71.01"
+        },
+        "T": 123456789
+    })
+    .to_string();
+    match serde_json::from_str(&raw).expect("failed to parse ticker feed") {
+        ServerPushEvent::PubTickerFeed(feed) => {
+            assert!(feed.is_snapshot);
+            assert_eq!(feed.market, "btctwd");
+            assert_eq!(feed.tick.open, dec!(280007.1));
+            assert_eq!(feed.tick.volume, dec!(71.01));
+            assert_eq!(feed.time, Utc.timestamp_millis_opt(123456789).unwrap());
+        }
+        event => panic!("unexpected event: {:?}", event),
+    }
+}
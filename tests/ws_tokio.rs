@@ -0,0 +1,72 @@
+//! The websocket data types work on tokio runtime: drive the subscription flow of `examples/ws_client_tokio.rs` against
+//! a local mock server built with `tokio-tungstenite`.
+#![cfg(not(target_arch = "wasm32"))]
+
+use futures::{SinkExt, StreamExt};
+use serde_json::json;
+use tokio::net::TcpListener;
+use tokio_tungstenite::tungstenite::Message;
+
+use maicoin_max::v2::ws::{ServerPushEvent, SubRequest, WsHost};
+
+#[tokio::test]
+async fn subscribe_ticker() {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    let server = tokio::spawn(async move {
+        let (tcp, _) = listener.accept().await.unwrap();
+        let mut ws = tokio_tungstenite::accept_async(tcp).await.unwrap();
+        let sub = match ws.next().await {
+            Some(Ok(Message::Text(raw))) => serde_json::from_str::<SubRequest>(&raw).unwrap(),
+            other => panic!("unexpected message: {:?}", other),
+        };
+        let replies = [
+            json!({
+                "e": "subscribed",
+                "s": [{"channel": "ticker", "market": "maxtwd"}],
+                "i": sub.id(),
+                "T": 1637998469525u64
+            }),
+            json!({
+                "c": "ticker",
+                "e": "snapshot",
+                "M": "maxtwd",
+                "tk": {"O": "11.4", "H": "11.9", "L": "11.2", "C": "11.6", "v": "1234.5"},
+                "T": 1637998469526u64
+            }),
+        ];
+        for reply in replies.iter() {
+            ws.send(Message::text(reply.to_string())).await.unwrap();
+        }
+        ws.close(None).await.unwrap();
+        sub
+    });
+
+    let host = WsHost::parse(&format!("ws://{}/ws", addr)).unwrap();
+    let mut stream = tokio_tungstenite::connect_async(host.as_str())
+        .await
+        .expect("failed to connect mock server")
+        .0;
+    let (mut sub, id) = SubRequest::new_sub(String::new()).with_generated_id();
+    sub.subset().insert_ticker("maxtwd");
+    stream
+        .send(Message::text(serde_json::to_string(&sub).unwrap()))
+        .await
+        .unwrap();
+
+    let mut events = Vec::new();
+    while let Some(Ok(msg)) = stream.next().await {
+        if let Message::Text(raw) = msg {
+            events.push(serde_json::from_str::<ServerPushEvent>(&raw).unwrap());
+        }
+    }
+    assert_eq!(server.await.unwrap(), sub);
+
+    match &events[..] {
+        [ServerPushEvent::SubResp(resp), ServerPushEvent::PubTickerFeed(ticker)] => {
+            assert_eq!(resp.id, id);
+            assert_eq!(ticker.market, "maxtwd");
+        }
+        other => panic!("unexpected events: {:?}", other),
+    }
+}